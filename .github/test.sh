@@ -7,12 +7,14 @@ cargo test --no-run
 cargo test
 cargo test --features derive
 cargo test --features unstable__schema
+cargo test --features derive,serde
 cargo test --test test_rc --features rc
 cargo test --test test_hash_map --test test_btree_map --features de_strict_order
 
 cargo test --no-default-features
 cargo test --no-default-features --features derive
 cargo test --no-default-features --features unstable__schema
+cargo test --no-default-features --features derive,serde
 cargo test --no-default-features --test test_rc --features rc
 cargo test --no-default-features --features hashbrown
 popd
@@ -1,14 +1,17 @@
 use std::collections::BTreeMap;
 
 use once_cell::sync::Lazy;
-use syn::{meta::ParseNestedMeta, Attribute, WherePredicate};
+use proc_macro2::Span;
+use quote::quote;
+use syn::{meta::ParseNestedMeta, Attribute, Path, WherePredicate};
 
 use self::bounds::BOUNDS_FIELD_PARSE_MAP;
 
 use super::{
     get_one_attribute,
     parsing::{attr_get_by_symbol_keys, meta_get_by_symbol_keys, parse_lit_into},
-    BoundType, Symbol, BORSH, BOUND, DESERIALIZE_WITH, SERIALIZE_WITH, SKIP,
+    BoundType, Symbol, BORSH, BOUND, DEFAULT, DESERIALIZE_WITH, DISCRIMINANT_FROM, FLATTEN, LENGTH,
+    LENGTH_WIDTH, SERDE, SERIALIZE_WITH, SKIP, VALIDATE, VARINT,
 };
 
 #[cfg(feature = "schema")]
@@ -25,7 +28,14 @@ enum Variants {
     Bounds(bounds::Bounds),
     SerializeWith(syn::ExprPath),
     DeserializeWith(syn::ExprPath),
+    DiscriminantFrom(syn::Ident),
     Skip(()),
+    VarInt(()),
+    LengthWidth(u8),
+    Default(Option<syn::ExprPath>),
+    Validate(syn::ExprPath),
+    Flatten(()),
+    Serde(()),
     #[cfg(feature = "schema")]
     Schema(schema::Attributes),
 }
@@ -52,6 +62,11 @@ static BORSH_FIELD_PARSE_MAP: Lazy<BTreeMap<Symbol, Box<ParseFn>>> = Lazy::new(|
             .map(Variants::DeserializeWith)
     });
 
+    let f_discriminant_from: Box<ParseFn> = Box::new(|attr_name, meta_item_name, meta| {
+        parse_lit_into::<syn::Ident>(attr_name, meta_item_name, meta)
+            .map(Variants::DiscriminantFrom)
+    });
+
     #[cfg(feature = "schema")]
     let f_schema: Box<ParseFn> = Box::new(|_attr_name, _meta_item_name, meta| {
         let map_result = meta_get_by_symbol_keys(SCHEMA, meta, &SCHEMA_FIELD_PARSE_MAP)?;
@@ -61,10 +76,64 @@ static BORSH_FIELD_PARSE_MAP: Lazy<BTreeMap<Symbol, Box<ParseFn>>> = Lazy::new(|
 
     let f_skip: Box<ParseFn> =
         Box::new(|_attr_name, _meta_item_name, _meta| Ok(Variants::Skip(())));
+    let f_varint: Box<ParseFn> =
+        Box::new(|_attr_name, _meta_item_name, _meta| Ok(Variants::VarInt(())));
+    let f_flatten: Box<ParseFn> =
+        Box::new(|_attr_name, _meta_item_name, _meta| Ok(Variants::Flatten(())));
+    let f_serde: Box<ParseFn> =
+        Box::new(|_attr_name, _meta_item_name, _meta| Ok(Variants::Serde(())));
+
+    let f_length_width: Box<ParseFn> = Box::new(|_attr_name, _meta_item_name, meta| {
+        let value: syn::LitInt = meta.value()?.parse()?;
+        match value.base10_parse::<u8>() {
+            Ok(width @ (1 | 2 | 4 | 8)) => Ok(Variants::LengthWidth(width)),
+            _ => Err(syn::Error::new(
+                value.span(),
+                "`length_width` accepts only 1, 2, 4 or 8",
+            )),
+        }
+    });
+
+    let f_length: Box<ParseFn> = Box::new(|attr_name, meta_item_name, meta| {
+        let ty = parse_lit_into::<syn::Ident>(attr_name, meta_item_name, meta)?;
+        let width = match ty.to_string().as_str() {
+            "u8" => 1,
+            "u16" => 2,
+            "u32" => 4,
+            "u64" => 8,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    ty,
+                    "`length` accepts only \"u8\", \"u16\", \"u32\" or \"u64\"",
+                ))
+            }
+        };
+        Ok(Variants::LengthWidth(width))
+    });
+
+    let f_default: Box<ParseFn> = Box::new(|attr_name, meta_item_name, meta| {
+        if !meta.input.peek(syn::Token![=]) {
+            return Ok(Variants::Default(None));
+        }
+        parse_lit_into::<syn::ExprPath>(attr_name, meta_item_name, meta)
+            .map(|path| Variants::Default(Some(path)))
+    });
+
+    let f_validate: Box<ParseFn> = Box::new(|attr_name, meta_item_name, meta| {
+        parse_lit_into::<syn::ExprPath>(attr_name, meta_item_name, meta).map(Variants::Validate)
+    });
     m.insert(BOUND, f_bounds);
     m.insert(SERIALIZE_WITH, f_serialize_with);
     m.insert(DESERIALIZE_WITH, f_deserialize_with);
+    m.insert(DISCRIMINANT_FROM, f_discriminant_from);
     m.insert(SKIP, f_skip);
+    m.insert(VARINT, f_varint);
+    m.insert(LENGTH_WIDTH, f_length_width);
+    m.insert(LENGTH, f_length);
+    m.insert(DEFAULT, f_default);
+    m.insert(VALIDATE, f_validate);
+    m.insert(FLATTEN, f_flatten);
+    m.insert(SERDE, f_serde);
     #[cfg(feature = "schema")]
     m.insert(SCHEMA, f_schema);
     m
@@ -75,7 +144,22 @@ pub(crate) struct Attributes {
     pub bounds: Option<bounds::Bounds>,
     pub serialize_with: Option<syn::ExprPath>,
     pub deserialize_with: Option<syn::ExprPath>,
+    /// sibling field, holding tag of the variant of annotated field of untagged enum type
+    pub discriminant_from: Option<syn::Ident>,
     pub skip: bool,
+    /// (de)serialize integer field as LEB128 varint
+    pub varint: bool,
+    /// width of length prefix of collection field in bytes, from `length_width` or `length`
+    pub length_width: Option<u8>,
+    /// value of trailing field, missing from the input: `Some(None)` for `Default::default()`,
+    /// `Some(Some(path))` for `path()`
+    pub default: Option<Option<syn::ExprPath>>,
+    /// function, which checks deserialized value of the field
+    pub validate: Option<syn::ExprPath>,
+    /// inline named fields of the field's struct type into the schema of the parent
+    pub flatten: bool,
+    /// (de)serialize field with `serde` impls of its type, in borsh format
+    pub serde: bool,
     #[cfg(feature = "schema")]
     pub schema: Option<schema::Attributes>,
 }
@@ -85,7 +169,14 @@ impl From<BTreeMap<Symbol, Variants>> for Attributes {
         let bounds = map.remove(&BOUND);
         let serialize_with = map.remove(&SERIALIZE_WITH);
         let deserialize_with = map.remove(&DESERIALIZE_WITH);
+        let discriminant_from = map.remove(&DISCRIMINANT_FROM);
         let skip = map.remove(&SKIP);
+        let varint = map.remove(&VARINT);
+        let length_width = map.remove(&LENGTH_WIDTH).or_else(|| map.remove(&LENGTH));
+        let default = map.remove(&DEFAULT);
+        let validate = map.remove(&VALIDATE);
+        let flatten = map.remove(&FLATTEN);
+        let serde = map.remove(&SERDE);
         let bounds = bounds.map(|variant| match variant {
             Variants::Bounds(bounds) => bounds,
             _ => unreachable!("only one enum variant is expected to correspond to given map key"),
@@ -101,11 +192,46 @@ impl From<BTreeMap<Symbol, Variants>> for Attributes {
             _ => unreachable!("only one enum variant is expected to correspond to given map key"),
         });
 
+        let discriminant_from = discriminant_from.map(|variant| match variant {
+            Variants::DiscriminantFrom(discriminant_from) => discriminant_from,
+            _ => unreachable!("only one enum variant is expected to correspond to given map key"),
+        });
+
         let skip = skip.map(|variant| match variant {
             Variants::Skip(skip) => skip,
             _ => unreachable!("only one enum variant is expected to correspond to given map key"),
         });
 
+        let varint = varint.map(|variant| match variant {
+            Variants::VarInt(varint) => varint,
+            _ => unreachable!("only one enum variant is expected to correspond to given map key"),
+        });
+
+        let length_width = length_width.map(|variant| match variant {
+            Variants::LengthWidth(length_width) => length_width,
+            _ => unreachable!("only one enum variant is expected to correspond to given map key"),
+        });
+
+        let default = default.map(|variant| match variant {
+            Variants::Default(default) => default,
+            _ => unreachable!("only one enum variant is expected to correspond to given map key"),
+        });
+
+        let validate = validate.map(|variant| match variant {
+            Variants::Validate(validate) => validate,
+            _ => unreachable!("only one enum variant is expected to correspond to given map key"),
+        });
+
+        let flatten = flatten.map(|variant| match variant {
+            Variants::Flatten(flatten) => flatten,
+            _ => unreachable!("only one enum variant is expected to correspond to given map key"),
+        });
+
+        let serde = serde.map(|variant| match variant {
+            Variants::Serde(serde) => serde,
+            _ => unreachable!("only one enum variant is expected to correspond to given map key"),
+        });
+
         #[cfg(feature = "schema")]
         let schema = {
             let schema = map.remove(&SCHEMA);
@@ -120,7 +246,14 @@ impl From<BTreeMap<Symbol, Variants>> for Attributes {
             bounds,
             serialize_with,
             deserialize_with,
+            discriminant_from,
             skip: skip.is_some(),
+            varint: varint.is_some(),
+            length_width,
+            default,
+            validate,
+            flatten: flatten.is_some(),
+            serde: serde.is_some(),
             #[cfg(feature = "schema")]
             schema,
         }
@@ -146,6 +279,107 @@ impl Attributes {
             ));
         }
 
+        if self.discriminant_from.is_some() && (self.skip || self.deserialize_with.is_some()) {
+            return Err(syn::Error::new_spanned(
+                attr,
+                format!(
+                    "`{}` cannot be used at the same time as `{}` or `{}`",
+                    DISCRIMINANT_FROM.0, SKIP.0, DESERIALIZE_WITH.0
+                ),
+            ));
+        }
+
+        if self.varint
+            && (self.skip
+                || self.serialize_with.is_some()
+                || self.deserialize_with.is_some()
+                || self.discriminant_from.is_some())
+        {
+            return Err(syn::Error::new_spanned(
+                attr,
+                format!(
+                    "`{}` cannot be used at the same time as `{}`, `{}`, `{}` or `{}`",
+                    VARINT.0, SKIP.0, SERIALIZE_WITH.0, DESERIALIZE_WITH.0, DISCRIMINANT_FROM.0
+                ),
+            ));
+        }
+
+        if self.length_width.is_some()
+            && (self.skip
+                || self.varint
+                || self.serialize_with.is_some()
+                || self.deserialize_with.is_some()
+                || self.discriminant_from.is_some())
+        {
+            return Err(syn::Error::new_spanned(
+                attr,
+                format!(
+                    "`{}` cannot be used at the same time as `{}`, `{}`, `{}`, `{}` or `{}`",
+                    LENGTH_WIDTH.0,
+                    SKIP.0,
+                    VARINT.0,
+                    SERIALIZE_WITH.0,
+                    DESERIALIZE_WITH.0,
+                    DISCRIMINANT_FROM.0
+                ),
+            ));
+        }
+
+        if self.default.is_some() && (self.skip || self.discriminant_from.is_some()) {
+            return Err(syn::Error::new_spanned(
+                attr,
+                format!(
+                    "`{}` cannot be used at the same time as `{}` or `{}`",
+                    DEFAULT.0, SKIP.0, DISCRIMINANT_FROM.0
+                ),
+            ));
+        }
+
+        if self.validate.is_some() && self.skip {
+            return Err(syn::Error::new_spanned(
+                attr,
+                format!(
+                    "`{}` cannot be used at the same time as `{}`",
+                    VALIDATE.0, SKIP.0
+                ),
+            ));
+        }
+
+        if self.flatten
+            && (self.skip || self.varint || self.length_width.is_some() || self.default.is_some())
+        {
+            return Err(syn::Error::new_spanned(
+                attr,
+                format!(
+                    "`{}` cannot be used at the same time as `{}`, `{}`, `{}` or `{}`",
+                    FLATTEN.0, SKIP.0, VARINT.0, LENGTH_WIDTH.0, DEFAULT.0
+                ),
+            ));
+        }
+
+        if self.serde
+            && (self.skip
+                || self.varint
+                || self.length_width.is_some()
+                || self.serialize_with.is_some()
+                || self.deserialize_with.is_some()
+                || self.discriminant_from.is_some())
+        {
+            return Err(syn::Error::new_spanned(
+                attr,
+                format!(
+                    "`{}` cannot be used at the same time as `{}`, `{}`, `{}`, `{}`, `{}` or `{}`",
+                    SERDE.0,
+                    SKIP.0,
+                    VARINT.0,
+                    LENGTH_WIDTH.0,
+                    SERIALIZE_WITH.0,
+                    DESERIALIZE_WITH.0,
+                    DISCRIMINANT_FROM.0
+                ),
+            ));
+        }
+
         #[cfg(feature = "schema")]
         self.check_schema(attr)?;
 
@@ -155,7 +389,17 @@ impl Attributes {
         let borsh = get_one_attribute(attrs)?;
 
         let result: Self = if let Some(attr) = borsh {
-            let result: Self = attr_get_by_symbol_keys(BORSH, attr, &BORSH_FIELD_PARSE_MAP)?.into();
+            let map = attr_get_by_symbol_keys(BORSH, attr, &BORSH_FIELD_PARSE_MAP)?;
+            if map.contains_key(&LENGTH_WIDTH) && map.contains_key(&LENGTH) {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    format!(
+                        "`{}` cannot be used at the same time as `{}`",
+                        LENGTH_WIDTH.0, LENGTH.0
+                    ),
+                ));
+            }
+            let result: Self = map.into();
             result.check(attr)?;
             result
         } else {
@@ -164,6 +408,55 @@ impl Attributes {
 
         Ok(result)
     }
+    /// unsigned integer type of length prefix, set with `length_width` or `length`
+    pub(crate) fn length_type(&self) -> Option<syn::Ident> {
+        self.length_width
+            .map(|width| syn::Ident::new(&format!("u{}", u32::from(width) * 8), Span::call_site()))
+    }
+
+    /// `serialize_with` function, either explicit or implied by `varint`, `length_width` or `serde`
+    pub(crate) fn serialize_with_path(&self, cratename: &Path) -> Option<syn::ExprPath> {
+        if self.varint {
+            return Some(syn::parse2(quote! { #cratename::varint::serialize }).unwrap());
+        }
+        if self.serde {
+            return Some(syn::parse2(quote! { #cratename::serde::serialize }).unwrap());
+        }
+        if let Some(length_type) = self.length_type() {
+            return Some(
+                syn::parse2(quote! { #cratename::length_prefix::serialize::<#length_type, _, _> })
+                    .unwrap(),
+            );
+        }
+        self.serialize_with.clone()
+    }
+
+    /// function, implied by `length_width` or `serde`, which is called
+    /// with `(reader, limits)` to deserialize the field
+    pub(crate) fn deserialize_with_limits_path(&self, cratename: &Path) -> Option<syn::ExprPath> {
+        if self.serde {
+            return Some(syn::parse2(quote! { #cratename::serde::deserialize_with_limits }).unwrap());
+        }
+        if let Some(length_type) = self.length_type() {
+            return Some(
+                syn::parse2(quote! {
+                    #cratename::length_prefix::deserialize_with_limits::<#length_type, _, _>
+                })
+                .unwrap(),
+            );
+        }
+        None
+    }
+
+    /// `deserialize_with` function, either explicit or implied by `varint`,
+    /// which is called with `(reader)` only, as it doesn't take `Limits`
+    pub(crate) fn deserialize_with_path(&self, cratename: &Path) -> Option<syn::ExprPath> {
+        if self.varint {
+            return Some(syn::parse2(quote! { #cratename::varint::deserialize }).unwrap());
+        }
+        self.deserialize_with.clone()
+    }
+
     pub(crate) fn needs_bounds_derive(&self, ty: BoundType) -> bool {
         let predicates = self.get_bounds(ty);
         predicates.is_none()
@@ -180,6 +473,34 @@ impl Attributes {
         let predicates = self.get_bounds(ty);
         predicates.unwrap_or(vec![])
     }
+
+    /// function, returning value of `default` field, missing from the input
+    pub(crate) fn default_path(&self) -> Option<syn::ExprPath> {
+        self.default.as_ref().map(|path| {
+            path.clone()
+                .unwrap_or_else(|| syn::parse2(quote! { core::default::Default::default }).unwrap())
+        })
+    }
+}
+
+/// checks, that fields with `default` are followed only by other `default` or skipped fields
+pub(crate) fn check_trailing_defaults(fields: &syn::Fields) -> Result<(), syn::Error> {
+    let mut seen_default = false;
+    for field in fields {
+        let parsed = Attributes::parse(&field.attrs)?;
+        if parsed.default.is_some() {
+            seen_default = true;
+        } else if seen_default && !parsed.skip {
+            return Err(syn::Error::new_spanned(
+                field,
+                format!(
+                    "fields following a field with `{}` must have `{}` too",
+                    DEFAULT.0, DEFAULT.0
+                ),
+            ));
+        }
+    }
+    Ok(())
 }
 
 #[cfg(feature = "schema")]
@@ -196,6 +517,16 @@ impl Attributes {
                 ));
             }
 
+            if self.flatten && schema.with_funcs.is_some() {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    format!(
+                        "`{}` cannot be used at the same time as `{}({})`",
+                        FLATTEN.0, SCHEMA.0, WITH_FUNCS.1
+                    ),
+                ));
+            }
+
             if self.skip && schema.with_funcs.is_some() {
                 return Err(syn::Error::new_spanned(
                     attr,
@@ -253,7 +584,7 @@ mod tests {
         local_insta_assert_snapshot,
     };
 
-    use super::{bounds, Attributes};
+    use super::{bounds, check_trailing_defaults, Attributes};
 
     #[test]
     fn test_reject_multiple_borsh_attrs() {
@@ -465,6 +796,184 @@ mod tests {
         let result = Attributes::parse(&first_field.attrs).unwrap();
         assert!(!result.skip);
     }
+
+    #[test]
+    fn test_default_parsing() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            struct A {
+                x: u64,
+                #[borsh(default)]
+                y: u32,
+                #[borsh(default = "third_party_impl::default_z")]
+                z: u32,
+            }
+        })
+        .unwrap();
+
+        let fields = item_struct.fields.into_iter().collect::<Vec<_>>();
+        let attrs = Attributes::parse(&fields[0].attrs).unwrap();
+        assert!(attrs.default.is_none());
+        let attrs = Attributes::parse(&fields[1].attrs).unwrap();
+        assert_eq!(
+            debug_print_tokenizable(attrs.default_path()),
+            "core :: default :: Default :: default\n"
+        );
+        let attrs = Attributes::parse(&fields[2].attrs).unwrap();
+        assert_eq!(
+            debug_print_tokenizable(attrs.default_path()),
+            "third_party_impl :: default_z\n"
+        );
+    }
+
+    #[test]
+    fn test_default_skip_conflict() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            struct A {
+                #[borsh(default, skip)]
+                x: u64,
+            }
+        })
+        .unwrap();
+
+        let first_field = &item_struct.fields.into_iter().collect::<Vec<_>>()[0];
+        let err = match Attributes::parse(&first_field.attrs) {
+            Ok(..) => unreachable!("expecting error here"),
+            Err(err) => err,
+        };
+        local_insta_assert_debug_snapshot!(err);
+    }
+
+    #[test]
+    fn test_validate_skip_conflict() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            struct A {
+                #[borsh(validate = "check_x", skip)]
+                x: u64,
+            }
+        })
+        .unwrap();
+
+        let first_field = &item_struct.fields.into_iter().collect::<Vec<_>>()[0];
+        let err = match Attributes::parse(&first_field.attrs) {
+            Ok(..) => unreachable!("expecting error here"),
+            Err(err) => err,
+        };
+        local_insta_assert_debug_snapshot!(err);
+    }
+
+    #[test]
+    fn test_flatten_varint_conflict() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            struct A {
+                #[borsh(flatten, varint)]
+                x: Header,
+            }
+        })
+        .unwrap();
+
+        let first_field = &item_struct.fields.into_iter().collect::<Vec<_>>()[0];
+        let err = match Attributes::parse(&first_field.attrs) {
+            Ok(..) => unreachable!("expecting error here"),
+            Err(err) => err,
+        };
+        local_insta_assert_debug_snapshot!(err);
+    }
+
+    #[test]
+    fn test_default_not_trailing() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            struct A {
+                x: u64,
+                #[borsh(default)]
+                y: u32,
+                #[borsh(skip)]
+                z: u8,
+                w: u16,
+            }
+        })
+        .unwrap();
+
+        let err = match check_trailing_defaults(&item_struct.fields) {
+            Ok(..) => unreachable!("expecting error here"),
+            Err(err) => err,
+        };
+        local_insta_assert_debug_snapshot!(err);
+    }
+
+    #[test]
+    fn test_length_width_parsing() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            struct A {
+                #[borsh(length_width = 2)]
+                x: Vec<u8>,
+                #[borsh(length = "u8")]
+                y: String,
+            }
+        })
+        .unwrap();
+
+        let fields = item_struct.fields.into_iter().collect::<Vec<_>>();
+        let attrs = Attributes::parse(&fields[0].attrs).unwrap();
+        assert_eq!(attrs.length_width, Some(2));
+        assert_eq!(attrs.length_type().unwrap().to_string(), "u16");
+        let attrs = Attributes::parse(&fields[1].attrs).unwrap();
+        assert_eq!(attrs.length_width, Some(1));
+        assert_eq!(attrs.length_type().unwrap().to_string(), "u8");
+    }
+
+    #[test]
+    fn test_length_width_wrong_value() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            struct A {
+                #[borsh(length_width = 3)]
+                x: Vec<u8>,
+            }
+        })
+        .unwrap();
+
+        let first_field = &item_struct.fields.into_iter().collect::<Vec<_>>()[0];
+        let err = match Attributes::parse(&first_field.attrs) {
+            Ok(..) => unreachable!("expecting error here"),
+            Err(err) => err,
+        };
+        local_insta_assert_debug_snapshot!(err);
+    }
+
+    #[test]
+    fn test_length_wrong_type() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            struct A {
+                #[borsh(length = "i16")]
+                x: Vec<u8>,
+            }
+        })
+        .unwrap();
+
+        let first_field = &item_struct.fields.into_iter().collect::<Vec<_>>()[0];
+        let err = match Attributes::parse(&first_field.attrs) {
+            Ok(..) => unreachable!("expecting error here"),
+            Err(err) => err,
+        };
+        local_insta_assert_debug_snapshot!(err);
+    }
+
+    #[test]
+    fn test_length_width_and_length_conflict() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            struct A {
+                #[borsh(length_width = 1, length = "u8")]
+                x: Vec<u8>,
+            }
+        })
+        .unwrap();
+
+        let first_field = &item_struct.fields.into_iter().collect::<Vec<_>>()[0];
+        let err = match Attributes::parse(&first_field.attrs) {
+            Ok(..) => unreachable!("expecting error here"),
+            Err(err) => err,
+        };
+        local_insta_assert_debug_snapshot!(err);
+    }
 }
 
 #[cfg(feature = "schema")]
@@ -1,18 +1,50 @@
-use crate::internals::attributes::{BORSH, CRATE, INIT, USE_DISCRIMINANT};
+use crate::internals::attributes::{
+    field, Symbol, BORSH, CRATE, INIT, MIGRATE_FROM, TAG_WIDTH, TRANSPARENT, UNSAFE_UNION_BYTES,
+    UNSAFE_UNION_DISCRIMINANT, UNTAGGED, USE_DISCRIMINANT, VALIDATE, VERSION,
+};
 use quote::ToTokens;
-use syn::{spanned::Spanned, Attribute, DeriveInput, Error, Expr, ItemEnum, Path};
+use syn::{
+    meta::ParseNestedMeta, parse::ParseStream, spanned::Spanned, Attribute, DeriveInput, Error,
+    Expr, ExprPath, ItemEnum, ItemUnion, LitInt, LitStr, Path, Token, Type,
+};
 
 use super::{get_one_attribute, parsing};
 
+/// item-level attributes, supported by `borsh`
+const ITEM_ATTRIBUTES: &[Symbol] = &[
+    CRATE,
+    USE_DISCRIMINANT,
+    INIT,
+    UNSAFE_UNION_BYTES,
+    UNSAFE_UNION_DISCRIMINANT,
+    UNTAGGED,
+    TAG_WIDTH,
+    VERSION,
+    MIGRATE_FROM,
+    VALIDATE,
+    TRANSPARENT,
+];
+
 pub fn check_attributes(derive_input: &DeriveInput) -> Result<(), Error> {
     let borsh = get_one_attribute(&derive_input.attrs)?;
+    let mut transparent = None;
+    let mut versioned = false;
 
     if let Some(attr) = borsh {
         attr.parse_nested_meta(|meta| {
-            if meta.path != USE_DISCRIMINANT && meta.path != INIT && meta.path != CRATE {
+            if !ITEM_ATTRIBUTES.iter().any(|symbol| meta.path == *symbol) {
+                let names = ITEM_ATTRIBUTES
+                    .iter()
+                    .map(|symbol| format!("`{}`", symbol.0))
+                    .collect::<Vec<_>>();
+                let (last, rest) = names.split_last().unwrap();
                 return Err(syn::Error::new(
                     meta.path.span(),
-                    "`crate`, `use_discriminant` or `init` are the only supported attributes for `borsh`",
+                    format!(
+                        "{} or {} are the only supported attributes for `borsh`",
+                        rest.join(", "),
+                        last
+                    ),
                 ));
             }
             if meta.path == USE_DISCRIMINANT {
@@ -23,21 +55,89 @@ pub fn check_attributes(derive_input: &DeriveInput) -> Result<(), Error> {
                         "borsh(use_discriminant=<bool>) does not support structs",
                     ));
                 }
-            } else if meta.path == INIT || meta.path == CRATE {
+            } else if meta.path == UNSAFE_UNION_BYTES || meta.path == UNSAFE_UNION_DISCRIMINANT {
+                skip_value(&meta)?;
+                if !matches!(derive_input.data, syn::Data::Union(..)) {
+                    return Err(syn::Error::new(
+                        meta.path.span(),
+                        "borsh(unsafe_union_bytes) and borsh(unsafe_union_discriminant=...) only support unions",
+                    ));
+                }
+            } else if meta.path == UNTAGGED || meta.path == TAG_WIDTH {
+                skip_value(&meta)?;
+                if !matches!(derive_input.data, syn::Data::Enum(..)) {
+                    return Err(syn::Error::new(
+                        meta.path.span(),
+                        "borsh(untagged) and borsh(tag_width=...) only support enums",
+                    ));
+                }
+            } else if meta.path == VERSION || meta.path == MIGRATE_FROM {
+                skip_value(&meta)?;
+                versioned = true;
+                if !matches!(derive_input.data, syn::Data::Struct(..)) {
+                    return Err(syn::Error::new(
+                        meta.path.span(),
+                        "borsh(version=...) and borsh(migrate_from(...)) only support structs",
+                    ));
+                }
+            } else if meta.path == TRANSPARENT {
+                if meta.input.peek(Token![=]) {
+                    let _alias: LitStr = meta.value()?.parse()?;
+                }
+                transparent = Some(meta.path.span());
+                if !matches!(derive_input.data, syn::Data::Struct(..)) {
+                    return Err(syn::Error::new(
+                        meta.path.span(),
+                        "borsh(transparent) only supports structs",
+                    ));
+                }
+            } else if meta.path == INIT || meta.path == CRATE || meta.path == VALIDATE {
                 let _expr: Expr = meta.value()?.parse()?;
             }
 
             Ok(())
         })?;
     }
+    if let (Some(span), syn::Data::Struct(data)) = (transparent, &derive_input.data) {
+        if versioned {
+            return Err(syn::Error::new(
+                span,
+                "`transparent` cannot be used at the same time as `version`",
+            ));
+        }
+        let mut not_skipped = 0;
+        for field in &data.fields {
+            if !field::Attributes::parse(&field.attrs)?.skip {
+                not_skipped += 1;
+            }
+        }
+        if not_skipped != 1 {
+            return Err(syn::Error::new(
+                derive_input.ident.span(),
+                "`transparent` structs have to contain exactly one field, which isn't skipped",
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// consumes value of an item-level attribute, which is not of interest to the caller
+fn skip_value(meta: &ParseNestedMeta) -> Result<(), Error> {
+    if meta.input.peek(Token![=]) {
+        let _value_expr: Expr = meta.value()?.parse()?;
+    } else if meta.input.peek(syn::token::Paren) {
+        let _value_group: proc_macro2::Group = meta.input.parse()?;
+    }
     Ok(())
 }
 
 pub(crate) fn contains_use_discriminant(input: &ItemEnum) -> Result<bool, syn::Error> {
-    if input.variants.len() > 256 {
+    let tag_width = get_tag_width(input)?;
+    let max_variants = 1u128 << (u32::from(tag_width) * 8);
+    if input.variants.len() as u128 > max_variants {
         return Err(syn::Error::new(
             input.span(),
-            "up to 256 enum variants are supported",
+            format!("up to {} enum variants are supported", max_variants),
         ));
     }
 
@@ -61,8 +161,8 @@ pub(crate) fn contains_use_discriminant(input: &ItemEnum) -> Result<bool, syn::E
                         ));
                     }
                 };
-            } else if meta.path == INIT || meta.path == CRATE {
-                let _value_expr: Expr = meta.value()?.parse()?;
+            } else {
+                skip_value(&meta)?;
             }
             Ok(())
         })?;
@@ -80,6 +180,54 @@ pub(crate) fn contains_use_discriminant(input: &ItemEnum) -> Result<bool, syn::E
     Ok(use_discriminant.unwrap_or(false))
 }
 
+pub(crate) fn contains_untagged(input: &ItemEnum) -> Result<bool, Error> {
+    let mut res = false;
+    let attr = input.attrs.iter().find(|attr| attr.path() == BORSH);
+    if let Some(attr) = attr {
+        attr.parse_nested_meta(|meta| {
+            if meta.path == UNTAGGED {
+                res = true;
+            } else {
+                skip_value(&meta)?;
+            }
+            Ok(())
+        })?;
+    }
+    Ok(res)
+}
+
+/// width in bytes of the tag of an enum, `1` unless specified with `#[borsh(tag_width = ...)]`
+pub(crate) fn get_tag_width(input: &ItemEnum) -> Result<u8, Error> {
+    let mut res = None;
+    let attr = input.attrs.iter().find(|attr| attr.path() == BORSH);
+    if let Some(attr) = attr {
+        attr.parse_nested_meta(|meta| {
+            if meta.path == TAG_WIDTH {
+                let value: syn::LitInt = meta.value()?.parse()?;
+                match value.base10_parse::<u8>() {
+                    Ok(width @ (1 | 2 | 4 | 8)) => res = Some(width),
+                    _ => {
+                        return Err(syn::Error::new(
+                            value.span(),
+                            "`tag_width` accepts only 1, 2, 4 or 8",
+                        ))
+                    }
+                }
+            } else {
+                skip_value(&meta)?;
+            }
+            Ok(())
+        })?;
+    }
+    if res.is_some() && contains_untagged(input)? {
+        return Err(syn::Error::new(
+            input.ident.span(),
+            "`tag_width` cannot be used at the same time as `untagged`",
+        ));
+    }
+    Ok(res.unwrap_or(1))
+}
+
 pub(crate) fn contains_initialize_with(attrs: &[Attribute]) -> Result<Option<Path>, Error> {
     let mut res = None;
     let attr = attrs.iter().find(|attr| attr.path() == BORSH);
@@ -88,8 +236,8 @@ pub(crate) fn contains_initialize_with(attrs: &[Attribute]) -> Result<Option<Pat
             if meta.path == INIT {
                 let value_expr: Path = meta.value()?.parse()?;
                 res = Some(value_expr);
-            } else if meta.path == USE_DISCRIMINANT || meta.path == CRATE {
-                let _value_expr: Expr = meta.value()?.parse()?;
+            } else {
+                skip_value(&meta)?;
             }
 
             Ok(())
@@ -107,8 +255,28 @@ pub(crate) fn get_crate(attrs: &[Attribute]) -> Result<Option<Path>, Error> {
             if meta.path == CRATE {
                 let value_expr: Path = parsing::parse_lit_into(BORSH, CRATE, &meta)?;
                 res = Some(value_expr);
-            } else if meta.path == USE_DISCRIMINANT || meta.path == INIT {
-                let _value_expr: Expr = meta.value()?.parse()?;
+            } else {
+                skip_value(&meta)?;
+            }
+
+            Ok(())
+        })?;
+    }
+
+    Ok(res)
+}
+
+/// function, which checks the whole deserialized item, set with `#[borsh(validate = "...")]`
+pub(crate) fn get_validate(attrs: &[Attribute]) -> Result<Option<ExprPath>, Error> {
+    let mut res = None;
+    let attr = attrs.iter().find(|attr| attr.path() == BORSH);
+    if let Some(attr) = attr {
+        attr.parse_nested_meta(|meta| {
+            if meta.path == VALIDATE {
+                let value_expr: ExprPath = parsing::parse_lit_into(BORSH, VALIDATE, &meta)?;
+                res = Some(value_expr);
+            } else {
+                skip_value(&meta)?;
             }
 
             Ok(())
@@ -118,6 +286,182 @@ pub(crate) fn get_crate(attrs: &[Attribute]) -> Result<Option<Path>, Error> {
     Ok(res)
 }
 
+/// `#[borsh(transparent)]` struct, whose schema is the one of its only not skipped field
+#[cfg(feature = "schema")]
+pub(crate) struct Transparent {
+    /// `#[borsh(transparent = "...")]`: name, which is defined as an alias of the field,
+    /// instead of reusing the declaration of the field as is
+    pub alias: Option<LitStr>,
+}
+
+#[cfg(feature = "schema")]
+pub(crate) fn get_transparent(attrs: &[Attribute]) -> Result<Option<Transparent>, Error> {
+    let mut res = None;
+    let attr = attrs.iter().find(|attr| attr.path() == BORSH);
+    if let Some(attr) = attr {
+        attr.parse_nested_meta(|meta| {
+            if meta.path == TRANSPARENT {
+                let alias = if meta.input.peek(Token![=]) {
+                    Some(meta.value()?.parse::<LitStr>()?)
+                } else {
+                    None
+                };
+                res = Some(Transparent { alias });
+            } else {
+                skip_value(&meta)?;
+            }
+            Ok(())
+        })?;
+    }
+    Ok(res)
+}
+
+/// version tag of a struct, selected with `#[borsh(version = ..., migrate_from(...))]`
+pub(crate) struct Versions {
+    /// `u8` tag, written before the fields
+    pub current: u8,
+    /// older tags with the types, which payloads following them are deserialized as,
+    /// before being converted with `From`
+    pub migrate_from: Vec<(u8, Type)>,
+}
+
+fn parse_version(value: &LitInt) -> Result<u8, Error> {
+    value.base10_parse::<u8>().map_err(|_| {
+        syn::Error::new(
+            value.span(),
+            "versions are `u8` tags and accept only integers from 0 to 255",
+        )
+    })
+}
+
+/// parses single `<version> = <type>` entry of `migrate_from(...)`
+fn parse_migration(input: ParseStream) -> Result<(LitInt, Type), Error> {
+    let version: LitInt = input.parse()?;
+    input.parse::<Token![=]>()?;
+    let ty: Type = input.parse()?;
+    Ok((version, ty))
+}
+
+pub(crate) fn get_versions(attrs: &[Attribute]) -> Result<Option<Versions>, Error> {
+    let mut current = None;
+    let mut migrate_from = None;
+    let attr = attrs.iter().find(|attr| attr.path() == BORSH);
+    if let Some(attr) = attr {
+        attr.parse_nested_meta(|meta| {
+            if meta.path == VERSION {
+                let value: LitInt = meta.value()?.parse()?;
+                current = Some(parse_version(&value)?);
+            } else if meta.path == MIGRATE_FROM {
+                let content;
+                syn::parenthesized!(content in meta.input);
+                let entries = content.parse_terminated(parse_migration, Token![,])?;
+                migrate_from = Some((meta.path.clone(), entries));
+            } else {
+                skip_value(&meta)?;
+            }
+            Ok(())
+        })?;
+    }
+    let current = match (current, &migrate_from) {
+        (None, None) => return Ok(None),
+        (None, Some((path, _))) => {
+            return Err(syn::Error::new(
+                path.span(),
+                "`migrate_from` cannot be used without `version`",
+            ));
+        }
+        (Some(current), _) => current,
+    };
+    let mut versions = Versions {
+        current,
+        migrate_from: vec![],
+    };
+    for (value, ty) in migrate_from.into_iter().flat_map(|(_, entries)| entries) {
+        let version = parse_version(&value)?;
+        if version >= current {
+            return Err(syn::Error::new(
+                value.span(),
+                "versions in `migrate_from` have to be lower than `version`",
+            ));
+        }
+        if versions
+            .migrate_from
+            .iter()
+            .any(|(other, _)| *other == version)
+        {
+            return Err(syn::Error::new(
+                value.span(),
+                format!(
+                    "version {} is listed in `migrate_from` more than once",
+                    version
+                ),
+            ));
+        }
+        versions.migrate_from.push((version, ty));
+    }
+    Ok(Some(versions))
+}
+
+/// representation of a union in borsh, selected with an item-level attribute
+pub(crate) enum UnionRepr {
+    /// `#[borsh(unsafe_union_bytes)]`: raw bytes of the union
+    Bytes,
+    /// `#[borsh(unsafe_union_discriminant = "...")]`: `u8` index of the active field, returned by
+    /// the function, followed by the field
+    Discriminant(Path),
+}
+
+pub(crate) fn union_repr(input: &ItemUnion) -> Result<UnionRepr, Error> {
+    let mut bytes = false;
+    let mut discriminant = None;
+    let attr = input.attrs.iter().find(|attr| attr.path() == BORSH);
+    if let Some(attr) = attr {
+        attr.parse_nested_meta(|meta| {
+            if meta.path == UNSAFE_UNION_BYTES {
+                bytes = true;
+            } else if meta.path == UNSAFE_UNION_DISCRIMINANT {
+                let value_expr: Path =
+                    parsing::parse_lit_into(BORSH, UNSAFE_UNION_DISCRIMINANT, &meta)?;
+                discriminant = Some(value_expr);
+            } else {
+                skip_value(&meta)?;
+            }
+            Ok(())
+        })?;
+    }
+    match (bytes, discriminant) {
+        (true, None) => {
+            // fields of such unions are never (de)serialized individually
+            for field in &input.fields.named {
+                if let Some(attr) = field.attrs.iter().find(|attr| attr.path() == BORSH) {
+                    return Err(syn::Error::new_spanned(
+                        attr,
+                        "`borsh` attributes are not supported on fields of `#[borsh(unsafe_union_bytes)]` unions",
+                    ));
+                }
+            }
+            Ok(UnionRepr::Bytes)
+        }
+        (false, Some(discriminant)) => {
+            if input.fields.named.len() > 256 {
+                return Err(syn::Error::new(
+                    input.span(),
+                    "up to 256 union fields are supported with `unsafe_union_discriminant`",
+                ));
+            }
+            Ok(UnionRepr::Discriminant(discriminant))
+        }
+        (true, Some(_)) => Err(syn::Error::new(
+            input.ident.span(),
+            "`unsafe_union_bytes` and `unsafe_union_discriminant` cannot be used at the same time",
+        )),
+        (false, None) => Err(syn::Error::new(
+            input.ident.span(),
+            "unions require either `#[borsh(unsafe_union_bytes)]` or `#[borsh(unsafe_union_discriminant = \"...\")]`",
+        )),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::internals::test_helpers::local_insta_assert_debug_snapshot;
@@ -408,4 +752,200 @@ mod tests {
             "reexporter :: borsh"
         );
     }
+
+    #[test]
+    fn test_tag_width() {
+        let item_enum: ItemEnum = syn::parse2(quote! {
+            #[borsh(tag_width = 4)]
+            enum A {
+                X,
+                Y,
+            }
+        })
+        .unwrap();
+        assert_eq!(get_tag_width(&item_enum).unwrap(), 4);
+    }
+
+    #[test]
+    fn test_tag_width_wrong_value() {
+        let item_enum: ItemEnum = syn::parse2(quote! {
+            #[borsh(tag_width = 3)]
+            enum A {
+                X,
+                Y,
+            }
+        })
+        .unwrap();
+        let err = get_tag_width(&item_enum).unwrap_err();
+        local_insta_assert_debug_snapshot!(err);
+    }
+
+    #[test]
+    fn test_tag_width_untagged() {
+        let item_enum: ItemEnum = syn::parse2(quote! {
+            #[borsh(untagged, tag_width = 2)]
+            enum A {
+                X,
+                Y,
+            }
+        })
+        .unwrap();
+        let err = get_tag_width(&item_enum).unwrap_err();
+        local_insta_assert_debug_snapshot!(err);
+    }
+
+    #[test]
+    fn test_versions() {
+        let item_struct: DeriveInput = syn::parse2(quote! {
+            #[borsh(version = 3, migrate_from(1 = V1, 2 = crate::V2<u8>), init = init)]
+            struct A {
+                x: u64,
+            }
+        })
+        .unwrap();
+        assert!(check_attributes(&item_struct).is_ok());
+        let versions = get_versions(&item_struct.attrs).unwrap().unwrap();
+        assert_eq!(versions.current, 3);
+        let migrate_from = versions
+            .migrate_from
+            .iter()
+            .map(|(version, ty)| (*version, ty.to_token_stream().to_string()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            migrate_from,
+            vec![(1, "V1".to_string()), (2, "crate :: V2 < u8 >".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_check_attrs_version_on_enum() {
+        let item_enum: DeriveInput = syn::parse2(quote! {
+            #[borsh(version = 1)]
+            enum A {
+                X,
+                Y,
+            }
+        })
+        .unwrap();
+        let actual = check_attributes(&item_enum);
+        local_insta_assert_debug_snapshot!(actual.unwrap_err());
+    }
+
+    #[test]
+    fn test_versions_migrate_from_without_version() {
+        let item_struct: DeriveInput = syn::parse2(quote! {
+            #[borsh(migrate_from(1 = V1))]
+            struct A {
+                x: u64,
+            }
+        })
+        .unwrap();
+        let err = get_versions(&item_struct.attrs).err().unwrap();
+        local_insta_assert_debug_snapshot!(err);
+    }
+
+    #[test]
+    fn test_versions_not_lower() {
+        let item_struct: DeriveInput = syn::parse2(quote! {
+            #[borsh(version = 2, migrate_from(1 = V1, 2 = V2))]
+            struct A {
+                x: u64,
+            }
+        })
+        .unwrap();
+        let err = get_versions(&item_struct.attrs).err().unwrap();
+        local_insta_assert_debug_snapshot!(err);
+    }
+
+    #[test]
+    fn test_versions_duplicated() {
+        let item_struct: DeriveInput = syn::parse2(quote! {
+            #[borsh(version = 2, migrate_from(1 = V1, 1 = V2))]
+            struct A {
+                x: u64,
+            }
+        })
+        .unwrap();
+        let err = get_versions(&item_struct.attrs).err().unwrap();
+        local_insta_assert_debug_snapshot!(err);
+    }
+
+    #[test]
+    fn test_versions_out_of_range() {
+        let item_struct: DeriveInput = syn::parse2(quote! {
+            #[borsh(version = 256)]
+            struct A {
+                x: u64,
+            }
+        })
+        .unwrap();
+        let err = get_versions(&item_struct.attrs).err().unwrap();
+        local_insta_assert_debug_snapshot!(err);
+    }
+
+    #[test]
+    fn test_validate() {
+        let item_enum: DeriveInput = syn::parse2(quote! {
+            #[borsh(validate = "check_a", init = init)]
+            enum A {
+                X,
+                Y,
+            }
+        })
+        .unwrap();
+        assert!(check_attributes(&item_enum).is_ok());
+        let validate = get_validate(&item_enum.attrs).unwrap().unwrap();
+        assert_eq!(validate.to_token_stream().to_string(), "check_a");
+    }
+
+    #[cfg(feature = "schema")]
+    #[test]
+    fn test_transparent() {
+        let item_struct: DeriveInput = syn::parse2(quote! {
+            #[borsh(transparent = "AccountId")]
+            struct A(String, #[borsh(skip)] PhantomData<u8>);
+        })
+        .unwrap();
+        assert!(check_attributes(&item_struct).is_ok());
+        let transparent = get_transparent(&item_struct.attrs).unwrap().unwrap();
+        assert_eq!(transparent.alias.unwrap().value(), "AccountId");
+    }
+
+    #[test]
+    fn test_check_attrs_transparent_two_fields() {
+        let item_struct: DeriveInput = syn::parse2(quote! {
+            #[borsh(transparent)]
+            struct A {
+                x: u64,
+                y: u64,
+            }
+        })
+        .unwrap();
+        let actual = check_attributes(&item_struct);
+        local_insta_assert_debug_snapshot!(actual.unwrap_err());
+    }
+
+    #[test]
+    fn test_check_attrs_transparent_on_enum() {
+        let item_enum: DeriveInput = syn::parse2(quote! {
+            #[borsh(transparent)]
+            enum A {
+                X(u64),
+            }
+        })
+        .unwrap();
+        let actual = check_attributes(&item_enum);
+        local_insta_assert_debug_snapshot!(actual.unwrap_err());
+    }
+
+    #[test]
+    fn test_check_attrs_transparent_versioned() {
+        let item_struct: DeriveInput = syn::parse2(quote! {
+            #[borsh(transparent, version = 1)]
+            struct A(u64);
+        })
+        .unwrap();
+        let actual = check_attributes(&item_struct);
+        local_insta_assert_debug_snapshot!(actual.unwrap_err());
+    }
 }
@@ -29,6 +29,40 @@ pub const SERIALIZE_WITH: Symbol = Symbol("serialize_with", "serialize_with = ..
 pub const DESERIALIZE_WITH: Symbol = Symbol("deserialize_with", "deserialize_with = ...");
 /// crate - sub-borsh nested meta, item-level only, `BorshSerialize`, `BorshDeserialize`, `BorshSchema` contexts
 pub const CRATE: Symbol = Symbol("crate", "crate = ...");
+/// unsafe_union_bytes - sub-borsh nested meta, item-level only, unions only, `BorshSerialize` and `BorshDeserialize` contexts
+pub const UNSAFE_UNION_BYTES: Symbol = Symbol("unsafe_union_bytes", "unsafe_union_bytes");
+/// unsafe_union_discriminant - sub-borsh nested meta, item-level only, unions only, `BorshSerialize` and `BorshDeserialize` contexts
+pub const UNSAFE_UNION_DISCRIMINANT: Symbol = Symbol(
+    "unsafe_union_discriminant",
+    "unsafe_union_discriminant = ...",
+);
+/// untagged - sub-borsh nested meta, item-level only, enums only, `BorshSerialize`, `BorshDeserialize`, `BorshSchema` contexts
+pub const UNTAGGED: Symbol = Symbol("untagged", "untagged");
+/// tag_width - sub-borsh nested meta, item-level only, enums only, `BorshSerialize`, `BorshDeserialize`, `BorshSchema` contexts
+pub const TAG_WIDTH: Symbol = Symbol("tag_width", "tag_width = ...");
+/// version - sub-borsh nested meta, item-level only, structs only, `BorshSerialize`, `BorshDeserialize`, `BorshSchema` contexts
+pub const VERSION: Symbol = Symbol("version", "version = ...");
+/// migrate_from - sub-borsh nested meta, item-level only, structs only, `BorshDeserialize`, `BorshSchema` contexts
+pub const MIGRATE_FROM: Symbol = Symbol("migrate_from", "migrate_from(...)");
+/// discriminant_from - sub-borsh nested meta, field-level only, `BorshDeserialize` context
+pub const DISCRIMINANT_FROM: Symbol = Symbol("discriminant_from", "discriminant_from = ...");
+/// varint - sub-borsh nested meta, field-level only, `BorshSerialize`, `BorshDeserialize`, `BorshSchema` contexts
+pub const VARINT: Symbol = Symbol("varint", "varint");
+/// length_width - sub-borsh nested meta, field-level only, `BorshSerialize`, `BorshDeserialize`, `BorshSchema` contexts
+pub const LENGTH_WIDTH: Symbol = Symbol("length_width", "length_width = ...");
+/// length - sub-borsh nested meta, field-level only, alternative form of `length_width`
+pub const LENGTH: Symbol = Symbol("length", "length = ...");
+/// default - sub-borsh nested meta, field-level only, `BorshDeserialize`, `BorshSchema` contexts
+pub const DEFAULT: Symbol = Symbol("default", "default = ...");
+
+/// validate - sub-borsh nested meta, item-level and field-level, `BorshDeserialize` context
+pub const VALIDATE: Symbol = Symbol("validate", "validate = ...");
+/// flatten - sub-borsh nested meta, field-level only, `BorshSchema` context
+pub const FLATTEN: Symbol = Symbol("flatten", "flatten");
+/// transparent - sub-borsh nested meta, item-level only, structs only, `BorshSchema` context
+pub const TRANSPARENT: Symbol = Symbol("transparent", "transparent");
+/// serde - sub-borsh nested meta, field-level only, `BorshSerialize`, `BorshDeserialize` contexts
+pub const SERDE: Symbol = Symbol("serde", "serde");
 
 #[cfg(feature = "schema")]
 pub mod schema_keys {
@@ -58,7 +92,7 @@ impl PartialEq<Symbol> for Path {
     }
 }
 
-impl<'a> PartialEq<Symbol> for &'a Path {
+impl PartialEq<Symbol> for &Path {
     fn eq(&self, word: &Symbol) -> bool {
         self.is_ident(word.0)
     }
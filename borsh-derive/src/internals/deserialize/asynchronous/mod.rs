@@ -0,0 +1,145 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{Ident, Path};
+
+use super::{field_default_output, field_path, GenericsOutput};
+use crate::internals::attributes::{field, BoundType};
+
+pub mod enums;
+pub mod structs;
+
+fn process_field(
+    field: &syn::Field,
+    field_idx: usize,
+    cratename: &Path,
+    path_prefix: &TokenStream2,
+    body: &mut TokenStream2,
+    generics: &mut GenericsOutput,
+) -> syn::Result<()> {
+    let parsed = field::Attributes::parse(&field.attrs)?;
+    if let Some(sibling) = parsed.discriminant_from {
+        return Err(syn::Error::new_spanned(
+            sibling,
+            "`discriminant_from` is not supported by BorshDeserializeAsync",
+        ));
+    }
+
+    if let Some(deserialize_with) = parsed.deserialize_with {
+        return Err(syn::Error::new_spanned(
+            deserialize_with,
+            "`deserialize_with` is not supported by BorshDeserializeAsync",
+        ));
+    }
+    if parsed.varint {
+        return Err(syn::Error::new_spanned(
+            field,
+            "`varint` is not supported by BorshDeserializeAsync",
+        ));
+    }
+    if parsed.length_width.is_some() {
+        return Err(syn::Error::new_spanned(
+            field,
+            "`length_width` is not supported by BorshDeserializeAsync",
+        ));
+    }
+    if parsed.serde {
+        return Err(syn::Error::new_spanned(
+            field,
+            "`serde` is not supported by BorshDeserializeAsync",
+        ));
+    }
+    if parsed.default.is_some() {
+        return Err(syn::Error::new_spanned(
+            field,
+            "`default` is not supported by BorshDeserializeAsync",
+        ));
+    }
+    generics
+        .overrides
+        .extend(parsed.collect_bounds(BoundType::Deserialize));
+    let needs_bounds_derive = parsed.needs_bounds_derive(BoundType::Deserialize);
+
+    let field_name = field.ident.as_ref();
+    let delta = if parsed.skip {
+        if needs_bounds_derive {
+            generics.default_visitor.visit_field(field);
+        }
+        field_default_output(field_name)
+    } else {
+        if needs_bounds_derive {
+            generics.deserialize_visitor.visit_field(field);
+        }
+        let path = field_path(field, field_idx, cratename, path_prefix);
+        field_output(field, cratename, &parsed, path)
+    };
+    body.extend(delta);
+    Ok(())
+}
+
+/// function which computes derive output [proc_macro2::TokenStream]
+/// of code, which deserializes single field inside of `async` block
+fn field_output(
+    field: &syn::Field,
+    cratename: &Path,
+    parsed: &field::Attributes,
+    field_path: TokenStream2,
+) -> TokenStream2 {
+    let value = quote! {
+        #cratename::BorshDeserializeAsync::deserialize_async_with_limits(reader, limits)
+            .await
+            .map_err(|err| #cratename::error::prepend_path(err, &[#field_path]))?
+    };
+    let value = super::validated_value(value, &field.ty, parsed, cratename, field_path);
+    if let Some(field_name) = &field.ident {
+        quote! {
+            #field_name: #value,
+        }
+    } else {
+        quote! {
+            #value,
+        }
+    }
+}
+
+/// function which computes derive output [proc_macro2::TokenStream]
+/// of `BorshDeserializeAsync` impl, which deserializes the item with `body`,
+/// evaluated inside of `async` block with `reader` and `limits` in scope
+fn impl_output(
+    name: &Ident,
+    impl_generics: syn::ImplGenerics,
+    ty_generics: syn::TypeGenerics,
+    where_clause: &syn::WhereClause,
+    cratename: &Path,
+    body: TokenStream2,
+) -> TokenStream2 {
+    quote! {
+        impl #impl_generics #cratename::de::BorshDeserializeAsync for #name #ty_generics #where_clause {
+            fn deserialize_async<'__a, R: #cratename::async_io::AsyncRead + ?Sized>(
+                reader: &'__a mut R,
+            ) -> #cratename::async_io::BoxFuture<'__a, ::core::result::Result<Self, #cratename::io::Error>>
+            where
+                Self: '__a,
+            {
+                #cratename::__private::maybestd::boxed::Box::pin(async move {
+                    <Self as #cratename::de::BorshDeserializeAsync>::deserialize_async_with_limits(
+                        reader,
+                        &mut #cratename::de::Limits::unlimited(),
+                    )
+                    .await
+                })
+            }
+
+            fn deserialize_async_with_limits<'__a, R: #cratename::async_io::AsyncRead + ?Sized>(
+                reader: &'__a mut R,
+                limits: &'__a mut #cratename::de::Limits,
+            ) -> #cratename::async_io::BoxFuture<'__a, ::core::result::Result<Self, #cratename::io::Error>>
+            where
+                Self: '__a,
+            {
+                #cratename::__private::maybestd::boxed::Box::pin(async move {
+                    #body
+                })
+            }
+        }
+    }
+}
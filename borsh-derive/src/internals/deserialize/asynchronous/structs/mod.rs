@@ -0,0 +1,257 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{Fields, ItemStruct, Path};
+
+use crate::internals::{attributes::item, deserialize, generics};
+
+pub fn process(input: &ItemStruct, cratename: Path) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let generics = generics::without_defaults(&input.generics);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let mut where_clause = generics::default_where(where_clause);
+    let mut body = TokenStream2::new();
+    let mut generics_output = deserialize::GenericsOutput::new(&generics);
+    let path_prefix = deserialize::type_path(name, &cratename);
+    let validate = deserialize::validate_output(&input.attrs, &cratename, &path_prefix)?;
+    if item::get_versions(&input.attrs)?.is_some() {
+        return Err(syn::Error::new(
+            name.span(),
+            "`borsh(version)` is not supported by BorshDeserializeAsync",
+        ));
+    }
+
+    let return_value = match &input.fields {
+        Fields::Named(fields) => {
+            for (field_idx, field) in fields.named.iter().enumerate() {
+                super::process_field(
+                    field,
+                    field_idx,
+                    &cratename,
+                    &path_prefix,
+                    &mut body,
+                    &mut generics_output,
+                )?;
+            }
+            quote! {
+                Self { #body }
+            }
+        }
+        Fields::Unnamed(fields) => {
+            for (field_idx, field) in fields.unnamed.iter().enumerate() {
+                super::process_field(
+                    field,
+                    field_idx,
+                    &cratename,
+                    &path_prefix,
+                    &mut body,
+                    &mut generics_output,
+                )?;
+            }
+            quote! {
+                Self( #body )
+            }
+        }
+        Fields::Unit => {
+            quote! {
+                Self {}
+            }
+        }
+    };
+    let de_trait: Path = syn::parse2(quote! { #cratename::de::BorshDeserializeAsync }).unwrap();
+    generics_output.extend_with_trait(&mut where_clause, &de_trait);
+
+    let (binding, init) = if let Some(method_ident) = item::contains_initialize_with(&input.attrs)?
+    {
+        (
+            quote! { mut return_value },
+            quote! {
+                return_value.#method_ident();
+            },
+        )
+    } else {
+        (quote! { return_value }, quote! {})
+    };
+
+    Ok(super::impl_output(
+        name,
+        impl_generics,
+        ty_generics,
+        &where_clause,
+        &cratename,
+        quote! {
+            limits.enter()?;
+            let #binding = #return_value;
+            limits.exit();
+            #validate
+            #init
+            Ok(return_value)
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::internals::test_helpers::{
+        default_cratename, local_insta_assert_debug_snapshot, local_insta_assert_snapshot,
+        pretty_print_syn_str,
+    };
+
+    use super::*;
+
+    #[test]
+    fn simple_struct() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            struct A {
+                x: u64,
+                y: String,
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_struct, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn generic_tuple_struct_borsh_skip() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            struct TupleA<T, U>(T, #[borsh(skip)] U);
+        })
+        .unwrap();
+
+        let actual = process(&item_struct, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn borsh_init_func() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            #[borsh(init=initialization_method)]
+            struct A {
+                x: u64,
+                y: String,
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_struct, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn reject_deserialize_with() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            struct A<K, V> {
+                #[borsh(deserialize_with = "third_party_impl::deserialize_third_party")]
+                x: ThirdParty<K, V>,
+                y: u64,
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_struct, default_cratename());
+
+        let err = match actual {
+            Ok(..) => unreachable!("expecting error here"),
+            Err(err) => err,
+        };
+        local_insta_assert_debug_snapshot!(err);
+    }
+
+    #[test]
+    fn reject_varint() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            struct A {
+                #[borsh(varint)]
+                x: u64,
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_struct, default_cratename());
+
+        let err = match actual {
+            Ok(..) => unreachable!("expecting error here"),
+            Err(err) => err,
+        };
+        local_insta_assert_debug_snapshot!(err);
+    }
+
+    #[test]
+    fn reject_length_width() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            struct A {
+                #[borsh(length_width = 1)]
+                x: String,
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_struct, default_cratename());
+
+        let err = match actual {
+            Ok(..) => unreachable!("expecting error here"),
+            Err(err) => err,
+        };
+        local_insta_assert_debug_snapshot!(err);
+    }
+
+    #[test]
+    fn reject_default() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            struct A {
+                x: u64,
+                #[borsh(default)]
+                y: u64,
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_struct, default_cratename());
+
+        let err = match actual {
+            Ok(..) => unreachable!("expecting error here"),
+            Err(err) => err,
+        };
+        local_insta_assert_debug_snapshot!(err);
+    }
+
+    #[test]
+    fn reject_version() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            #[borsh(version = 1)]
+            struct A {
+                x: u64,
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_struct, default_cratename());
+
+        let err = match actual {
+            Ok(..) => unreachable!("expecting error here"),
+            Err(err) => err,
+        };
+        local_insta_assert_debug_snapshot!(err);
+    }
+
+    #[test]
+    fn check_validate_attr() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            #[borsh(validate = "A::check")]
+            struct A {
+                #[borsh(validate = "check_x")]
+                x: u64,
+                y: String,
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_struct, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+}
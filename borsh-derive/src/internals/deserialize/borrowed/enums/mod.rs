@@ -0,0 +1,219 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{Fields, Ident, ItemEnum, Path, Variant};
+
+use crate::internals::{
+    attributes::{field, item},
+    deserialize,
+    enum_discriminant::Discriminants,
+    generics,
+};
+
+pub fn process(input: &ItemEnum, cratename: Path) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let generics = generics::without_defaults(&input.generics);
+    let (_, ty_generics, where_clause) = generics.split_for_impl();
+    let (impl_generics, de) = super::impl_generics_with_de(&generics);
+    let (impl_generics, _, _) = impl_generics.split_for_impl();
+    let mut where_clause = generics::default_where(where_clause);
+    let mut variant_arms = TokenStream2::new();
+    let use_discriminant = item::contains_use_discriminant(input)?;
+    let discriminants = Discriminants::new(&input.variants, item::get_tag_width(input)?);
+    let tag_type = discriminants.tag_type();
+    if item::contains_untagged(input)? {
+        return Err(syn::Error::new(
+            name.span(),
+            "`borsh(untagged)` is not supported by BorshDeserializeBorrowed",
+        ));
+    }
+    let mut generics_output = deserialize::GenericsOutput::new(&generics);
+
+    for (variant_idx, variant) in input.variants.iter().enumerate() {
+        let variant_body = process_variant(name, variant, &cratename, &mut generics_output)?;
+        let variant_ident = &variant.ident;
+
+        let discriminant_value = discriminants.get(variant_ident, use_discriminant, variant_idx)?;
+        variant_arms.extend(quote! {
+            if variant_tag == #discriminant_value { #name::#variant_ident #variant_body } else
+        });
+    }
+    let init = if let Some(method_ident) = item::contains_initialize_with(&input.attrs)? {
+        quote! {
+            return_value.#method_ident();
+        }
+    } else {
+        quote! {}
+    };
+    let de_trait: Path =
+        syn::parse2(quote! { #cratename::de::BorshDeserializeBorrowed<#de> }).unwrap();
+    generics_output.extend_with_trait(&mut where_clause, &de_trait);
+    let type_path = deserialize::type_path(name, &cratename);
+    let validate = deserialize::validate_output(&input.attrs, &cratename, &type_path)?;
+
+    Ok(quote! {
+        impl #impl_generics #de_trait for #name #ty_generics #where_clause {
+            fn deserialize_borrowed(buf: &mut &#de [u8]) -> ::core::result::Result<Self, #cratename::io::Error> {
+                <Self as #de_trait>::deserialize_borrowed_with_limits(
+                    buf,
+                    &mut #cratename::de::Limits::unlimited(),
+                )
+            }
+
+            fn deserialize_borrowed_with_limits(
+                buf: &mut &#de [u8],
+                limits: &mut #cratename::de::Limits,
+            ) -> ::core::result::Result<Self, #cratename::io::Error> {
+                let variant_tag = <#tag_type as #cratename::de::BorshDeserialize>::deserialize(buf)
+                    .map_err(|err| #cratename::error::prepend_path(err, &[#type_path]))?;
+                limits.enter()?;
+                let mut return_value =
+                    #variant_arms {
+                    return Err(#cratename::error::prepend_path(
+                        #cratename::io::Error::new(
+                            #cratename::io::ErrorKind::InvalidData,
+                            #cratename::__private::maybestd::format!("Unexpected variant tag: {:?}", variant_tag),
+                        ),
+                        &[#type_path],
+                    ))
+                };
+                limits.exit();
+                #validate
+                #init
+                Ok(return_value)
+            }
+        }
+    })
+}
+
+fn process_variant(
+    name: &Ident,
+    variant: &Variant,
+    cratename: &Path,
+    generics: &mut deserialize::GenericsOutput,
+) -> syn::Result<TokenStream2> {
+    let mut body = TokenStream2::new();
+    let path_prefix = deserialize::variant_path(name, &variant.ident, cratename);
+    field::check_trailing_defaults(&variant.fields)?;
+    match &variant.fields {
+        Fields::Named(fields) => {
+            for (field_idx, field) in fields.named.iter().enumerate() {
+                super::process_field(
+                    field,
+                    field_idx,
+                    cratename,
+                    &path_prefix,
+                    &mut body,
+                    generics,
+                )?;
+            }
+            body = quote! { { #body }};
+        }
+        Fields::Unnamed(fields) => {
+            for (field_idx, field) in fields.unnamed.iter().enumerate() {
+                super::process_field(
+                    field,
+                    field_idx,
+                    cratename,
+                    &path_prefix,
+                    &mut body,
+                    generics,
+                )?;
+            }
+            body = quote! { ( #body )};
+        }
+        Fields::Unit => {}
+    }
+    Ok(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::internals::test_helpers::{
+        default_cratename, local_insta_assert_snapshot, pretty_print_syn_str,
+    };
+
+    use super::*;
+
+    #[test]
+    fn borrowed_fields_enum() {
+        let item_enum: ItemEnum = syn::parse2(quote! {
+            enum A<'a> {
+                B {
+                    x: &'a str,
+                    y: u32,
+                },
+                C(Cow<'a, [u8]>),
+                D,
+            }
+        })
+        .unwrap();
+        let actual = process(&item_enum, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn generic_borsh_skip_struct_field() {
+        let item_enum: ItemEnum = syn::parse2(quote! {
+            enum A<'a, K, V, U> {
+                B {
+                    #[borsh(skip)]
+                    x: HashMap<K, V>,
+                    y: &'a str,
+                },
+                C(K, Vec<U>),
+            }
+        })
+        .unwrap();
+        let actual = process(&item_enum, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn borsh_discriminant_true() {
+        let item_enum: ItemEnum = syn::parse2(quote! {
+            #[borsh(use_discriminant = true)]
+            enum X<'a> {
+                A(&'a str),
+                B = 20,
+                C,
+            }
+        })
+        .unwrap();
+        let actual = process(&item_enum, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn borsh_init_func() {
+        let item_enum: ItemEnum = syn::parse2(quote! {
+            #[borsh(init = initialization_method)]
+            enum A<'a> {
+                B(&'a [u8]),
+                C,
+            }
+        })
+        .unwrap();
+        let actual = process(&item_enum, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn borsh_tag_width() {
+        let item_enum: ItemEnum = syn::parse2(quote! {
+            #[borsh(tag_width = 2, use_discriminant = true)]
+            enum Opcode {
+                Nop = 0,
+                Push(u64) = 0x100,
+                Call { target: u32 },
+            }
+        })
+        .unwrap();
+        let actual = process(&item_enum, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+}
@@ -0,0 +1,114 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{GenericParam, Generics, Lifetime, LifetimeParam, Path};
+
+use super::{field_default_output, field_path, GenericsOutput};
+use crate::internals::attributes::{field, BoundType};
+
+pub mod enums;
+pub mod structs;
+
+/// name of the lifetime of the input buffer, added to generics of the impl
+const DE_LIFETIME: &str = "'de";
+
+/// adds `'de` lifetime param to `generics`, which outlives all other lifetime params
+/// of the item; if the item already declares a lifetime param named `'de`, the bounds
+/// are added to it instead
+fn impl_generics_with_de(generics: &Generics) -> (Generics, Lifetime) {
+    let de = Lifetime::new(DE_LIFETIME, proc_macro2::Span::call_site());
+    let mut generics = generics.clone();
+    let others: Vec<Lifetime> = generics
+        .lifetimes()
+        .map(|param| param.lifetime.clone())
+        .filter(|lifetime| *lifetime != de)
+        .collect();
+    if let Some(de_param) = generics.lifetimes_mut().find(|param| param.lifetime == de) {
+        de_param.bounds.extend(others);
+        return (generics, de);
+    }
+    let mut de_param = LifetimeParam::new(de.clone());
+    de_param.bounds.extend(others);
+    generics.params.insert(0, GenericParam::Lifetime(de_param));
+    (generics, de)
+}
+
+fn process_field(
+    field: &syn::Field,
+    field_idx: usize,
+    cratename: &Path,
+    path_prefix: &TokenStream2,
+    body: &mut TokenStream2,
+    generics: &mut GenericsOutput,
+) -> syn::Result<()> {
+    let parsed = field::Attributes::parse(&field.attrs)?;
+    if let Some(sibling) = parsed.discriminant_from {
+        return Err(syn::Error::new_spanned(
+            sibling,
+            "`discriminant_from` is not supported by BorshDeserializeBorrowed",
+        ));
+    }
+
+    generics
+        .overrides
+        .extend(parsed.collect_bounds(BoundType::Deserialize));
+    let needs_bounds_derive = parsed.needs_bounds_derive(BoundType::Deserialize);
+
+    let field_name = field.ident.as_ref();
+    let delta = if parsed.skip {
+        if needs_bounds_derive {
+            generics.default_visitor.visit_field(field);
+        }
+        field_default_output(field_name)
+    } else {
+        if needs_bounds_derive {
+            generics.deserialize_visitor.visit_field(field);
+            if matches!(parsed.default, Some(None)) {
+                generics.default_visitor.visit_field(field);
+            }
+        }
+        let path = field_path(field, field_idx, cratename, path_prefix);
+        field_output(field, cratename, &parsed, path)
+    };
+    body.extend(delta);
+    Ok(())
+}
+
+/// function which computes derive output [proc_macro2::TokenStream]
+/// of code, which deserializes single field, borrowing from input buffer
+///
+/// `deserialize_with` functions are called with `&mut &'de [u8]` as reader and without
+/// `limits`, `default` function is called instead, if the buffer is empty
+fn field_output(
+    field: &syn::Field,
+    cratename: &Path,
+    parsed: &field::Attributes,
+    field_path: TokenStream2,
+) -> TokenStream2 {
+    let read = if let Some(path) = parsed.deserialize_with_limits_path(cratename) {
+        quote! { #path(buf, limits) }
+    } else if let Some(path) = parsed.deserialize_with_path(cratename) {
+        quote! { #path(buf) }
+    } else {
+        quote! {
+            #cratename::de::BorshDeserializeBorrowed::deserialize_borrowed_with_limits(buf, limits)
+        }
+    };
+    let mut value = quote! {
+        #read.map_err(|err| #cratename::error::prepend_path(err, &[#field_path]))?
+    };
+    if let Some(default) = parsed.default_path() {
+        value = quote! {
+            if buf.is_empty() { #default() } else { #value }
+        };
+    }
+    let value = super::validated_value(value, &field.ty, parsed, cratename, field_path);
+    if let Some(field_name) = &field.ident {
+        quote! {
+            #field_name: #value,
+        }
+    } else {
+        quote! {
+            #value,
+        }
+    }
+}
@@ -0,0 +1,259 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{Fields, ItemStruct, Path};
+
+use crate::internals::{
+    attributes::{field, item},
+    deserialize, generics,
+};
+
+pub fn process(input: &ItemStruct, cratename: Path) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let generics = generics::without_defaults(&input.generics);
+    let (_, ty_generics, where_clause) = generics.split_for_impl();
+    let (impl_generics, de) = super::impl_generics_with_de(&generics);
+    let (impl_generics, _, _) = impl_generics.split_for_impl();
+    let mut where_clause = generics::default_where(where_clause);
+    let mut body = TokenStream2::new();
+    let mut generics_output = deserialize::GenericsOutput::new(&generics);
+    let path_prefix = deserialize::type_path(name, &cratename);
+    let validate = deserialize::validate_output(&input.attrs, &cratename, &path_prefix)?;
+    field::check_trailing_defaults(&input.fields)?;
+    if item::get_versions(&input.attrs)?.is_some() {
+        return Err(syn::Error::new(
+            name.span(),
+            "`borsh(version)` is not supported by BorshDeserializeBorrowed",
+        ));
+    }
+
+    let return_value = match &input.fields {
+        Fields::Named(fields) => {
+            for (field_idx, field) in fields.named.iter().enumerate() {
+                super::process_field(
+                    field,
+                    field_idx,
+                    &cratename,
+                    &path_prefix,
+                    &mut body,
+                    &mut generics_output,
+                )?;
+            }
+            quote! {
+                Self { #body }
+            }
+        }
+        Fields::Unnamed(fields) => {
+            for (field_idx, field) in fields.unnamed.iter().enumerate() {
+                super::process_field(
+                    field,
+                    field_idx,
+                    &cratename,
+                    &path_prefix,
+                    &mut body,
+                    &mut generics_output,
+                )?;
+            }
+            quote! {
+                Self( #body )
+            }
+        }
+        Fields::Unit => {
+            quote! {
+                Self {}
+            }
+        }
+    };
+    let de_trait: Path =
+        syn::parse2(quote! { #cratename::de::BorshDeserializeBorrowed<#de> }).unwrap();
+    generics_output.extend_with_trait(&mut where_clause, &de_trait);
+
+    let (binding, init) = if let Some(method_ident) = item::contains_initialize_with(&input.attrs)?
+    {
+        (
+            quote! { mut return_value },
+            quote! {
+                return_value.#method_ident();
+            },
+        )
+    } else {
+        (quote! { return_value }, quote! {})
+    };
+
+    Ok(quote! {
+        impl #impl_generics #de_trait for #name #ty_generics #where_clause {
+            fn deserialize_borrowed(buf: &mut &#de [u8]) -> ::core::result::Result<Self, #cratename::io::Error> {
+                <Self as #de_trait>::deserialize_borrowed_with_limits(
+                    buf,
+                    &mut #cratename::de::Limits::unlimited(),
+                )
+            }
+
+            fn deserialize_borrowed_with_limits(
+                buf: &mut &#de [u8],
+                limits: &mut #cratename::de::Limits,
+            ) -> ::core::result::Result<Self, #cratename::io::Error> {
+                limits.enter()?;
+                let #binding = #return_value;
+                limits.exit();
+                #validate
+                #init
+                Ok(return_value)
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::internals::test_helpers::{
+        default_cratename, local_insta_assert_debug_snapshot, local_insta_assert_snapshot,
+        pretty_print_syn_str,
+    };
+
+    use super::*;
+
+    #[test]
+    fn borrowed_fields_struct() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            struct A<'a> {
+                x: u64,
+                y: &'a str,
+                z: Cow<'a, [u8]>,
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_struct, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn explicit_de_lifetime() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            struct A<'a, 'de> {
+                x: &'a str,
+                y: &'de [u8],
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_struct, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn generic_tuple_struct_borsh_skip() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            struct TupleA<'a, T, U>(&'a str, T, #[borsh(skip)] U);
+        })
+        .unwrap();
+
+        let actual = process(&item_struct, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn borsh_init_func() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            #[borsh(init=initialization_method)]
+            struct A<'a> {
+                x: u64,
+                y: &'a str,
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_struct, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn check_deserialize_with_attr() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            struct A<'a, K: Ord, V> {
+                #[borsh(deserialize_with = "third_party_impl::deserialize_third_party")]
+                x: ThirdParty<K, V>,
+                y: &'a [u8],
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_struct, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn check_varint_attr() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            struct A<'a> {
+                #[borsh(varint)]
+                x: i64,
+                y: &'a str,
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_struct, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn check_default_attr() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            struct A<'a> {
+                x: &'a str,
+                #[borsh(default)]
+                y: u32,
+                #[borsh(default = "third_party_impl::default_z")]
+                z: &'a [u8],
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_struct, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn reject_version() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            #[borsh(version = 1)]
+            struct A {
+                x: u64,
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_struct, default_cratename());
+
+        let err = match actual {
+            Ok(..) => unreachable!("expecting error here"),
+            Err(err) => err,
+        };
+        local_insta_assert_debug_snapshot!(err);
+    }
+
+    #[test]
+    fn check_validate_attr() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            #[borsh(validate = "A::check")]
+            struct A {
+                #[borsh(validate = "check_x")]
+                x: u64,
+                y: String,
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_struct, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+}
@@ -1,8 +1,13 @@
 use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
-use syn::{Fields, ItemEnum, Path, Variant};
+use syn::{Fields, Ident, ItemEnum, Path, Variant};
 
-use crate::internals::{attributes::item, deserialize, enum_discriminant::Discriminants, generics};
+use crate::internals::{
+    attributes::{field, item},
+    deserialize,
+    enum_discriminant::Discriminants,
+    generics,
+};
 
 pub fn process(input: &ItemEnum, cratename: Path) -> syn::Result<TokenStream2> {
     let name = &input.ident;
@@ -11,11 +16,11 @@ pub fn process(input: &ItemEnum, cratename: Path) -> syn::Result<TokenStream2> {
     let mut where_clause = generics::default_where(where_clause);
     let mut variant_arms = TokenStream2::new();
     let use_discriminant = item::contains_use_discriminant(input)?;
-    let discriminants = Discriminants::new(&input.variants);
+    let discriminants = Discriminants::new(&input.variants, item::get_tag_width(input)?);
     let mut generics_output = deserialize::GenericsOutput::new(&generics);
 
     for (variant_idx, variant) in input.variants.iter().enumerate() {
-        let variant_body = process_variant(variant, &cratename, &mut generics_output)?;
+        let variant_body = process_variant(name, variant, &cratename, &mut generics_output)?;
         let variant_ident = &variant.ident;
 
         let discriminant_value = discriminants.get(variant_ident, use_discriminant, variant_idx)?;
@@ -31,50 +36,143 @@ pub fn process(input: &ItemEnum, cratename: Path) -> syn::Result<TokenStream2> {
         quote! {}
     };
     generics_output.extend(&mut where_clause, &cratename);
+    let type_path = deserialize::type_path(name, &cratename);
+    let validate = deserialize::validate_output(&input.attrs, &cratename, &type_path)?;
+    let variant_body = quote! {
+        limits.enter()?;
+        let mut return_value =
+            #variant_arms {
+            return Err(#cratename::error::prepend_path(
+                #cratename::io::Error::new(
+                    #cratename::io::ErrorKind::InvalidData,
+                    #cratename::__private::maybestd::format!("Unexpected variant tag: {:?}", variant_tag),
+                ),
+                &[#type_path],
+            ))
+        };
+        limits.exit();
+        #validate
+        #init
+        Ok(return_value)
+    };
+    let deserialize_impl = if item::contains_untagged(input)? {
+        // tag of untagged enums is only known to the caller of `EnumExt`,
+        // so they don't implement `BorshDeserialize`
+        quote! {}
+    } else if discriminants.tag_width() == 1 {
+        quote! {
+            impl #impl_generics #cratename::de::BorshDeserialize for #name #ty_generics #where_clause {
+                fn deserialize_reader<R: #cratename::io::Read>(reader: &mut R) -> ::core::result::Result<Self, #cratename::io::Error> {
+                    <Self as #cratename::de::BorshDeserialize>::deserialize_reader_with_limits(
+                        reader,
+                        &mut #cratename::de::Limits::unlimited(),
+                    )
+                }
 
-    Ok(quote! {
-        impl #impl_generics #cratename::de::BorshDeserialize for #name #ty_generics #where_clause {
-            fn deserialize_reader<R: #cratename::io::Read>(reader: &mut R) -> ::core::result::Result<Self, #cratename::io::Error> {
-                let tag = <u8 as #cratename::de::BorshDeserialize>::deserialize_reader(reader)?;
-                <Self as #cratename::de::EnumExt>::deserialize_variant(reader, tag)
+                fn deserialize_reader_with_limits<R: #cratename::io::Read>(
+                    reader: &mut R,
+                    limits: &mut #cratename::de::Limits,
+                ) -> ::core::result::Result<Self, #cratename::io::Error> {
+                    let tag = <u8 as #cratename::de::BorshDeserialize>::deserialize_reader(reader)
+                        .map_err(|err| #cratename::error::prepend_path(err, &[#type_path]))?;
+                    <Self as #cratename::de::EnumExt>::deserialize_variant_with_limits(reader, tag, limits)
+                }
             }
         }
+    } else {
+        // `EnumExt` only accepts `u8` tags, so wider tags are read right here
+        let tag_type = discriminants.tag_type();
+        quote! {
+            impl #impl_generics #cratename::de::BorshDeserialize for #name #ty_generics #where_clause {
+                fn deserialize_reader<R: #cratename::io::Read>(reader: &mut R) -> ::core::result::Result<Self, #cratename::io::Error> {
+                    <Self as #cratename::de::BorshDeserialize>::deserialize_reader_with_limits(
+                        reader,
+                        &mut #cratename::de::Limits::unlimited(),
+                    )
+                }
+
+                fn deserialize_reader_with_limits<R: #cratename::io::Read>(
+                    reader: &mut R,
+                    limits: &mut #cratename::de::Limits,
+                ) -> ::core::result::Result<Self, #cratename::io::Error> {
+                    let variant_tag = <#tag_type as #cratename::de::BorshDeserialize>::deserialize_reader(reader)
+                        .map_err(|err| #cratename::error::prepend_path(err, &[#type_path]))?;
+                    #variant_body
+                }
+            }
+        }
+    };
+    // `u8` tag of `EnumExt` is widened to the tag of the enum, so only variants
+    // with discriminants up to `u8::MAX` can be deserialized with it
+    let variant_tag = if discriminants.tag_width() == 1 {
+        quote! {}
+    } else {
+        let tag_type = discriminants.tag_type();
+        quote! {
+            let variant_tag = <#tag_type as ::core::convert::From<u8>>::from(variant_tag);
+        }
+    };
+
+    Ok(quote! {
+        #deserialize_impl
 
         impl #impl_generics #cratename::de::EnumExt for #name #ty_generics #where_clause {
             fn deserialize_variant<R: #cratename::io::Read>(
                 reader: &mut R,
                 variant_tag: u8,
             ) -> ::core::result::Result<Self, #cratename::io::Error> {
-                let mut return_value =
-                    #variant_arms {
-                    return Err(#cratename::io::Error::new(
-                        #cratename::io::ErrorKind::InvalidData,
-                        #cratename::__private::maybestd::format!("Unexpected variant tag: {:?}", variant_tag),
-                    ))
-                };
-                #init
-                Ok(return_value)
+                <Self as #cratename::de::EnumExt>::deserialize_variant_with_limits(
+                    reader,
+                    variant_tag,
+                    &mut #cratename::de::Limits::unlimited(),
+                )
+            }
+
+            fn deserialize_variant_with_limits<R: #cratename::io::Read>(
+                reader: &mut R,
+                variant_tag: u8,
+                limits: &mut #cratename::de::Limits,
+            ) -> ::core::result::Result<Self, #cratename::io::Error> {
+                #variant_tag
+                #variant_body
             }
         }
     })
 }
 
 fn process_variant(
+    name: &Ident,
     variant: &Variant,
     cratename: &Path,
     generics: &mut deserialize::GenericsOutput,
 ) -> syn::Result<TokenStream2> {
     let mut body = TokenStream2::new();
+    let path_prefix = deserialize::variant_path(name, &variant.ident, cratename);
+    field::check_trailing_defaults(&variant.fields)?;
     match &variant.fields {
         Fields::Named(fields) => {
-            for field in &fields.named {
-                deserialize::process_field(field, cratename, &mut body, generics)?;
+            for (field_idx, field) in fields.named.iter().enumerate() {
+                deserialize::process_field(
+                    field,
+                    field_idx,
+                    cratename,
+                    &path_prefix,
+                    &mut body,
+                    generics,
+                )?;
             }
             body = quote! { { #body }};
         }
         Fields::Unnamed(fields) => {
-            for field in fields.unnamed.iter() {
-                deserialize::process_field(field, cratename, &mut body, generics)?;
+            for (field_idx, field) in fields.unnamed.iter().enumerate() {
+                deserialize::process_field(
+                    field,
+                    field_idx,
+                    cratename,
+                    &path_prefix,
+                    &mut body,
+                    generics,
+                )?;
             }
             body = quote! { ( #body )};
         }
@@ -86,7 +184,8 @@ fn process_variant(
 #[cfg(test)]
 mod tests {
     use crate::internals::test_helpers::{
-        default_cratename, local_insta_assert_snapshot, pretty_print_syn_str,
+        default_cratename, local_insta_assert_debug_snapshot, local_insta_assert_snapshot,
+        pretty_print_syn_str,
     };
 
     use super::*;
@@ -328,4 +427,69 @@ mod tests {
         let actual = process(&item_enum, default_cratename()).unwrap();
         local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
     }
+
+    #[test]
+    fn borsh_untagged() {
+        let item_enum: ItemEnum = syn::parse2(quote! {
+            #[borsh(untagged)]
+            enum Payload {
+                Ping,
+                Transfer { to: u64, amount: u64 },
+                Memo(String),
+            }
+        })
+        .unwrap();
+        let actual = process(&item_enum, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn discriminant_from_in_variant() {
+        let item_enum: ItemEnum = syn::parse2(quote! {
+            enum Message {
+                Framed {
+                    kind: u8,
+                    #[borsh(discriminant_from = "kind")]
+                    payload: Payload,
+                },
+            }
+        })
+        .unwrap();
+        let actual = process(&item_enum, default_cratename());
+
+        local_insta_assert_debug_snapshot!(actual.unwrap_err());
+    }
+
+    #[test]
+    fn borsh_tag_width() {
+        let item_enum: ItemEnum = syn::parse2(quote! {
+            #[borsh(tag_width = 2, use_discriminant = true)]
+            enum Opcode {
+                Nop = 0,
+                Push(u64) = 0x100,
+                Call { target: u32 },
+            }
+        })
+        .unwrap();
+        let actual = process(&item_enum, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn check_validate_attr() {
+        let item_enum: ItemEnum = syn::parse2(quote! {
+            #[borsh(validate = "A::check")]
+            enum A {
+                B(#[borsh(validate = "check_x")] u64),
+                C,
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_enum, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
 }
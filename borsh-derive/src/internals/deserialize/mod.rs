@@ -1,12 +1,14 @@
 use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
-use syn::{ExprPath, Generics, Ident, Path};
+use syn::{ext::IdentExt, Generics, Ident, Path};
 
 use super::{
-    attributes::{field, BoundType},
+    attributes::{field, item, BoundType},
     generics,
 };
 
+pub mod asynchronous;
+pub mod borrowed;
 pub mod enums;
 pub mod structs;
 pub mod unions;
@@ -27,9 +29,12 @@ impl GenericsOutput {
     }
     fn extend(self, where_clause: &mut syn::WhereClause, cratename: &Path) {
         let de_trait: Path = syn::parse2(quote! { #cratename::de::BorshDeserialize }).unwrap();
+        self.extend_with_trait(where_clause, &de_trait);
+    }
+    fn extend_with_trait(self, where_clause: &mut syn::WhereClause, de_trait: &Path) {
         let default_trait: Path = syn::parse2(quote! { core::default::Default }).unwrap();
         let de_predicates =
-            generics::compute_predicates(self.deserialize_visitor.process_for_bounds(), &de_trait);
+            generics::compute_predicates(self.deserialize_visitor.process_for_bounds(), de_trait);
         let default_predicates =
             generics::compute_predicates(self.default_visitor.process_for_bounds(), &default_trait);
         where_clause.predicates.extend(de_predicates);
@@ -40,11 +45,19 @@ impl GenericsOutput {
 
 fn process_field(
     field: &syn::Field,
+    field_idx: usize,
     cratename: &Path,
+    path_prefix: &TokenStream2,
     body: &mut TokenStream2,
     generics: &mut GenericsOutput,
 ) -> syn::Result<()> {
     let parsed = field::Attributes::parse(&field.attrs)?;
+    if let Some(sibling) = parsed.discriminant_from {
+        return Err(syn::Error::new_spanned(
+            sibling,
+            "`discriminant_from` is only supported on fields of structs with named fields",
+        ));
+    }
 
     generics
         .overrides
@@ -60,8 +73,12 @@ fn process_field(
     } else {
         if needs_bounds_derive {
             generics.deserialize_visitor.visit_field(field);
+            if matches!(parsed.default, Some(None)) {
+                generics.default_visitor.visit_field(field);
+            }
         }
-        field_output(field_name, cratename, parsed.deserialize_with)
+        let path = field_path(field, field_idx, cratename, path_prefix);
+        field_output(field, cratename, &parsed, path)
     };
     body.extend(delta);
     Ok(())
@@ -70,24 +87,158 @@ fn process_field(
 /// function which computes derive output [proc_macro2::TokenStream]
 /// of code, which deserializes single field
 fn field_output(
-    field_name: Option<&Ident>,
+    field: &syn::Field,
     cratename: &Path,
-    deserialize_with: Option<ExprPath>,
+    parsed: &field::Attributes,
+    field_path: TokenStream2,
 ) -> TokenStream2 {
-    let default_path: ExprPath =
-        syn::parse2(quote! { #cratename::BorshDeserialize::deserialize_reader }).unwrap();
-    let path: ExprPath = deserialize_with.unwrap_or(default_path);
-    if let Some(field_name) = field_name {
+    let value = field_value(&field.ty, cratename, parsed, field_path);
+    if let Some(field_name) = &field.ident {
         quote! {
-            #field_name: #path(reader)?,
+            #field_name: #value,
         }
     } else {
         quote! {
-            #path(reader)?,
+            #value,
         }
     }
 }
 
+/// function which computes derive output [proc_macro2::TokenStream]
+/// of expression, which deserializes single field
+fn field_value(
+    ty: &syn::Type,
+    cratename: &Path,
+    parsed: &field::Attributes,
+    field_path: TokenStream2,
+) -> TokenStream2 {
+    let read = if let Some(path) = parsed.deserialize_with_limits_path(cratename) {
+        quote! { #path(reader, limits) }
+    } else if let Some(path) = parsed.deserialize_with_path(cratename) {
+        quote! { #path(reader) }
+    } else {
+        quote! {
+            #cratename::BorshDeserialize::deserialize_reader_with_limits(reader, limits)
+        }
+    };
+    let read = match parsed.default_path() {
+        Some(default) => quote! {
+            #cratename::trailing::deserialize_or_else(reader, #default, |reader| #read)
+        },
+        None => read,
+    };
+    let value = quote! {
+        #read.map_err(|err| #cratename::error::prepend_path(err, &[#field_path]))?
+    };
+    validated_value(value, ty, parsed, cratename, field_path)
+}
+
+/// function which computes derive output [proc_macro2::TokenStream]
+/// of expression, which checks deserialized `value` of a field with its
+/// `#[borsh(validate = ...)]` function, if there's one
+fn validated_value(
+    value: TokenStream2,
+    ty: &syn::Type,
+    parsed: &field::Attributes,
+    cratename: &Path,
+    field_path: TokenStream2,
+) -> TokenStream2 {
+    match &parsed.validate {
+        Some(validate) => quote! {{
+            let value: #ty = #value;
+            #validate(&value).map_err(|err| {
+                #cratename::error::prepend_path(
+                    #cratename::error::validation_error(err),
+                    &[#field_path],
+                )
+            })?;
+            value
+        }},
+        None => value,
+    }
+}
+
+/// function which computes derive output [proc_macro2::TokenStream]
+/// of code, which checks deserialized `return_value` of an item with its
+/// `#[borsh(validate = ...)]` function, if there's one
+fn validate_output(
+    attrs: &[syn::Attribute],
+    cratename: &Path,
+    type_path: &TokenStream2,
+) -> syn::Result<TokenStream2> {
+    Ok(match item::get_validate(attrs)? {
+        Some(validate) => quote! {
+            #validate(&return_value).map_err(|err| {
+                #cratename::error::prepend_path(
+                    #cratename::error::validation_error(err),
+                    &[#type_path],
+                )
+            })?;
+        },
+        None => quote! {},
+    })
+}
+
+/// function which computes derive output [proc_macro2::TokenStream]
+/// of expression, which deserializes single field of untagged enum type
+/// with the tag, converted to `u8` from value of `sibling` binding
+fn field_variant_value(
+    ty: &syn::Type,
+    sibling: &Ident,
+    cratename: &Path,
+    field_path: TokenStream2,
+) -> TokenStream2 {
+    quote! {{
+        let variant_tag = <u8 as ::core::convert::TryFrom<_>>::try_from(
+            ::core::clone::Clone::clone(&#sibling),
+        )
+        .map_err(|_| {
+            #cratename::error::prepend_path(
+                #cratename::io::Error::new(
+                    #cratename::io::ErrorKind::InvalidData,
+                    "Variant tag is out of range",
+                ),
+                &[#field_path],
+            )
+        })?;
+        <#ty as #cratename::de::EnumExt>::deserialize_variant_with_limits(reader, variant_tag, limits)
+            .map_err(|err| #cratename::error::prepend_path(err, &[#field_path]))?
+    }}
+}
+
+/// function which computes [proc_macro2::TokenStream] of
+/// `borsh::error::PathSegment`-s of item's name, prepended to paths of errors
+fn type_path(name: &Ident, cratename: &Path) -> TokenStream2 {
+    let name = name.unraw().to_string();
+    quote! { #cratename::error::PathSegment::Type(#name), }
+}
+
+/// function which computes [proc_macro2::TokenStream] of
+/// `borsh::error::PathSegment`-s of enum variant, prepended to paths of errors
+fn variant_path(name: &Ident, variant: &Ident, cratename: &Path) -> TokenStream2 {
+    let type_path = type_path(name, cratename);
+    let variant = variant.unraw().to_string();
+    quote! { #type_path #cratename::error::PathSegment::Variant(#variant), }
+}
+
+/// function which computes [proc_macro2::TokenStream] of
+/// `borsh::error::PathSegment`-s, identifying single field in errors
+fn field_path(
+    field: &syn::Field,
+    field_idx: usize,
+    cratename: &Path,
+    path_prefix: &TokenStream2,
+) -> TokenStream2 {
+    let segment = match &field.ident {
+        Some(ident) => {
+            let name = ident.unraw().to_string();
+            quote! { #cratename::error::PathSegment::Field(#name) }
+        }
+        None => quote! { #cratename::error::PathSegment::Position(#field_idx) },
+    };
+    quote! { #path_prefix #segment }
+}
+
 /// function which computes derive output [proc_macro2::TokenStream]
 /// of code, which deserializes single skipped field
 fn field_default_output(field_name: Option<&Ident>) -> TokenStream2 {
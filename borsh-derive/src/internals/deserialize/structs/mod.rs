@@ -1,8 +1,11 @@
-use proc_macro2::TokenStream as TokenStream2;
+use proc_macro2::{Span, TokenStream as TokenStream2};
 use quote::quote;
-use syn::{Fields, ItemStruct, Path};
+use syn::{ext::IdentExt, Fields, FieldsNamed, Ident, ItemStruct, Path};
 
-use crate::internals::{attributes::item, deserialize, generics};
+use crate::internals::{
+    attributes::{field, item, BoundType},
+    deserialize, generics,
+};
 
 pub fn process(input: &ItemStruct, cratename: Path) -> syn::Result<TokenStream2> {
     let name = &input.ident;
@@ -11,19 +14,39 @@ pub fn process(input: &ItemStruct, cratename: Path) -> syn::Result<TokenStream2>
     let mut where_clause = generics::default_where(where_clause);
     let mut body = TokenStream2::new();
     let mut generics_output = deserialize::GenericsOutput::new(&generics);
+    let path_prefix = deserialize::type_path(name, &cratename);
+    let validate = deserialize::validate_output(&input.attrs, &cratename, &path_prefix)?;
+    field::check_trailing_defaults(&input.fields)?;
 
     let return_value = match &input.fields {
+        Fields::Named(fields) if has_discriminant_from(fields)? => {
+            process_with_bindings(fields, &cratename, &path_prefix, &mut generics_output)?
+        }
         Fields::Named(fields) => {
-            for field in &fields.named {
-                deserialize::process_field(field, &cratename, &mut body, &mut generics_output)?;
+            for (field_idx, field) in fields.named.iter().enumerate() {
+                deserialize::process_field(
+                    field,
+                    field_idx,
+                    &cratename,
+                    &path_prefix,
+                    &mut body,
+                    &mut generics_output,
+                )?;
             }
             quote! {
                 Self { #body }
             }
         }
         Fields::Unnamed(fields) => {
-            for (_field_idx, field) in fields.unnamed.iter().enumerate() {
-                deserialize::process_field(field, &cratename, &mut body, &mut generics_output)?;
+            for (field_idx, field) in fields.unnamed.iter().enumerate() {
+                deserialize::process_field(
+                    field,
+                    field_idx,
+                    &cratename,
+                    &path_prefix,
+                    &mut body,
+                    &mut generics_output,
+                )?;
             }
             quote! {
                 Self( #body )
@@ -36,32 +59,176 @@ pub fn process(input: &ItemStruct, cratename: Path) -> syn::Result<TokenStream2>
         }
     };
     generics_output.extend(&mut where_clause, &cratename);
+    let return_value = match item::get_versions(&input.attrs)? {
+        Some(versions) => versioned_value(&versions, return_value, &cratename, &path_prefix),
+        None => return_value,
+    };
+
+    let (binding, init) = if let Some(method_ident) = item::contains_initialize_with(&input.attrs)?
+    {
+        (
+            quote! { mut return_value },
+            quote! {
+                return_value.#method_ident();
+            },
+        )
+    } else {
+        (quote! { return_value }, quote! {})
+    };
+
+    Ok(quote! {
+        impl #impl_generics #cratename::de::BorshDeserialize for #name #ty_generics #where_clause {
+            fn deserialize_reader<R: #cratename::io::Read>(reader: &mut R) -> ::core::result::Result<Self, #cratename::io::Error> {
+                <Self as #cratename::de::BorshDeserialize>::deserialize_reader_with_limits(
+                    reader,
+                    &mut #cratename::de::Limits::unlimited(),
+                )
+            }
 
-    if let Some(method_ident) = item::contains_initialize_with(&input.attrs)? {
-        Ok(quote! {
-            impl #impl_generics #cratename::de::BorshDeserialize for #name #ty_generics #where_clause {
-                fn deserialize_reader<R: #cratename::io::Read>(reader: &mut R) -> ::core::result::Result<Self, #cratename::io::Error> {
-                    let mut return_value = #return_value;
-                    return_value.#method_ident();
-                    Ok(return_value)
+            fn deserialize_reader_with_limits<R: #cratename::io::Read>(
+                reader: &mut R,
+                limits: &mut #cratename::de::Limits,
+            ) -> ::core::result::Result<Self, #cratename::io::Error> {
+                limits.enter()?;
+                let #binding = #return_value;
+                limits.exit();
+                #validate
+                #init
+                Ok(return_value)
+            }
+        }
+    })
+}
+
+/// function which computes derive output [proc_macro2::TokenStream]
+/// of expression, which reads the version tag of a struct and then either `current_value`,
+/// or a value of an older type from `migrate_from(...)`, converted with `From`
+fn versioned_value(
+    versions: &item::Versions,
+    current_value: TokenStream2,
+    cratename: &Path,
+    path_prefix: &TokenStream2,
+) -> TokenStream2 {
+    let current = versions.current;
+    let migrations = versions.migrate_from.iter().map(|(version, ty)| {
+        quote! {
+            #version => <Self as ::core::convert::From<#ty>>::from(
+                <#ty as #cratename::de::BorshDeserialize>::deserialize_reader_with_limits(reader, limits)
+                    .map_err(|err| #cratename::error::prepend_path(err, &[#path_prefix]))?,
+            ),
+        }
+    });
+    quote! {
+        match <u8 as #cratename::de::BorshDeserialize>::deserialize_reader(reader)
+            .map_err(|err| #cratename::error::prepend_path(err, &[#path_prefix]))?
+        {
+            #current => #current_value,
+            #(#migrations)*
+            version => {
+                return Err(#cratename::error::prepend_path(
+                    #cratename::io::Error::new(
+                        #cratename::io::ErrorKind::InvalidData,
+                        #cratename::__private::maybestd::format!("Unsupported version: {}", version),
+                    ),
+                    &[#path_prefix],
+                ))
+            }
+        }
+    }
+}
+
+fn has_discriminant_from(fields: &FieldsNamed) -> syn::Result<bool> {
+    for field in &fields.named {
+        if field::Attributes::parse(&field.attrs)?
+            .discriminant_from
+            .is_some()
+        {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// function which computes derive output [proc_macro2::TokenStream]
+/// of code, which deserializes fields into local bindings, so that fields with
+/// `#[borsh(discriminant_from = ...)]` can refer to preceding fields
+fn process_with_bindings(
+    fields: &FieldsNamed,
+    cratename: &Path,
+    path_prefix: &TokenStream2,
+    generics: &mut deserialize::GenericsOutput,
+) -> syn::Result<TokenStream2> {
+    let mut bindings = TokenStream2::new();
+    let mut body = TokenStream2::new();
+    let mut preceding: Vec<&Ident> = vec![];
+    for (field_idx, field) in fields.named.iter().enumerate() {
+        let parsed = field::Attributes::parse(&field.attrs)?;
+        generics
+            .overrides
+            .extend(parsed.collect_bounds(BoundType::Deserialize));
+        let needs_bounds_derive = parsed.needs_bounds_derive(BoundType::Deserialize);
+
+        let field_name = field.ident.as_ref().unwrap();
+        let path = deserialize::field_path(field, field_idx, cratename, path_prefix);
+        let value = if parsed.skip {
+            if needs_bounds_derive {
+                generics.default_visitor.visit_field(field);
+            }
+            quote! { core::default::Default::default() }
+        } else {
+            if needs_bounds_derive {
+                generics.deserialize_visitor.visit_field(field);
+                if matches!(parsed.default, Some(None)) {
+                    generics.default_visitor.visit_field(field);
                 }
             }
-        })
-    } else {
-        Ok(quote! {
-            impl #impl_generics #cratename::de::BorshDeserialize for #name #ty_generics #where_clause {
-                fn deserialize_reader<R: #cratename::io::Read>(reader: &mut R) -> ::core::result::Result<Self, #cratename::io::Error> {
-                    Ok(#return_value)
+            match &parsed.discriminant_from {
+                Some(sibling) => {
+                    if !preceding.contains(&sibling) {
+                        return Err(syn::Error::new_spanned(
+                            sibling,
+                            "`discriminant_from` has to name one of preceding fields",
+                        ));
+                    }
+                    let sibling = binding(sibling);
+                    let value = deserialize::field_variant_value(
+                        &field.ty,
+                        &sibling,
+                        cratename,
+                        path.clone(),
+                    );
+                    deserialize::validated_value(value, &field.ty, &parsed, cratename, path)
                 }
+                None => deserialize::field_value(&field.ty, cratename, &parsed, path),
             }
-        })
+        };
+        let binding = binding(field_name);
+        let ty = &field.ty;
+        bindings.extend(quote! {
+            let #binding: #ty = #value;
+        });
+        body.extend(quote! {
+            #field_name: #binding,
+        });
+        preceding.push(field_name);
     }
+    Ok(quote! {{
+        #bindings
+        Self { #body }
+    }})
+}
+
+/// local binding of the value of field `name`, which doesn't clash with
+/// names, used by derived code or by the field types
+fn binding(name: &Ident) -> Ident {
+    Ident::new(&format!("field_{}", name.unraw()), Span::mixed_site())
 }
 
 #[cfg(test)]
 mod tests {
     use crate::internals::test_helpers::{
-        default_cratename, local_insta_assert_snapshot, pretty_print_syn_str,
+        default_cratename, local_insta_assert_debug_snapshot, local_insta_assert_snapshot,
+        pretty_print_syn_str,
     };
 
     use super::*;
@@ -262,4 +429,168 @@ mod tests {
         let actual = process(&item_enum, default_cratename()).unwrap();
         local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
     }
+
+    #[test]
+    fn discriminant_from() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            struct Message<T> {
+                kind: u8,
+                #[borsh(skip)]
+                cached: Option<T>,
+                #[borsh(discriminant_from = "kind")]
+                payload: Payload,
+                r#type: String,
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_struct, default_cratename()).unwrap();
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn discriminant_from_following_field() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            struct Message {
+                #[borsh(discriminant_from = "kind")]
+                payload: Payload,
+                kind: u8,
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_struct, default_cratename());
+        local_insta_assert_debug_snapshot!(actual.unwrap_err());
+    }
+
+    #[test]
+    fn discriminant_from_tuple_struct() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            struct Message(u8, #[borsh(discriminant_from = "kind")] Payload);
+        })
+        .unwrap();
+
+        let actual = process(&item_struct, default_cratename());
+        local_insta_assert_debug_snapshot!(actual.unwrap_err());
+    }
+
+    #[test]
+    fn check_varint_attr() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            struct A {
+                #[borsh(varint)]
+                x: u64,
+                y: u64,
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_struct, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn check_serde_attr() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            struct A {
+                #[borsh(serde)]
+                x: Point,
+                y: u64,
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_struct, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn check_length_width_attr() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            struct A {
+                #[borsh(length_width = 1)]
+                x: String,
+                #[borsh(length = "u16")]
+                y: Vec<u64>,
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_struct, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn check_default_attr() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            struct A<K, V> {
+                x: u64,
+                #[borsh(default)]
+                y: K,
+                #[borsh(default = "third_party_impl::default_z", length_width = 1)]
+                z: Vec<V>,
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_struct, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn check_default_attr_not_trailing() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            struct A {
+                #[borsh(default)]
+                x: u64,
+                y: u64,
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_struct, default_cratename());
+
+        let err = match actual {
+            Ok(..) => unreachable!("expecting error here"),
+            Err(err) => err,
+        };
+        local_insta_assert_debug_snapshot!(err);
+    }
+
+    #[test]
+    fn versioned() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            #[borsh(version = 3, migrate_from(1 = AV1, 2 = AV2), init = init)]
+            struct A {
+                x: u64,
+                y: String,
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_struct, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn check_validate_attr() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            #[borsh(validate = "A::check", init = init)]
+            struct A {
+                #[borsh(validate = "check_x")]
+                x: u64,
+                y: String,
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_struct, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
 }
@@ -1,6 +1,241 @@
 use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use std::convert::TryFrom;
 use syn::{ItemUnion, Path};
 
-pub fn process(_input: &ItemUnion, _cratename: Path) -> syn::Result<TokenStream2> {
-    unimplemented!()
+use crate::internals::{
+    attributes::{
+        field,
+        item::{self, UnionRepr},
+        BoundType,
+    },
+    deserialize, generics,
+};
+
+pub fn process(input: &ItemUnion, cratename: Path) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let generics = generics::without_defaults(&input.generics);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let mut where_clause = generics::default_where(where_clause);
+    let type_path = deserialize::type_path(name, &cratename);
+    let validate = deserialize::validate_output(&input.attrs, &cratename, &type_path)?;
+
+    let return_value = match item::union_repr(input)? {
+        UnionRepr::Bytes => {
+            where_clause
+                .predicates
+                .push(syn::parse2(quote! { Self: core::marker::Copy }).unwrap());
+            quote! {
+                unsafe { #cratename::__private::union_bytes::deserialize::<Self, R>(reader) }
+                    .map_err(|err| #cratename::error::prepend_path(err, &[#type_path]))?
+            }
+        }
+        UnionRepr::Discriminant(_) => {
+            let mut generics_output = deserialize::GenericsOutput::new(&generics);
+            let mut field_arms = TokenStream2::new();
+            for (field_idx, field) in input.fields.named.iter().enumerate() {
+                let field_body = process_field(
+                    field,
+                    field_idx,
+                    &cratename,
+                    &type_path,
+                    &mut generics_output,
+                )?;
+                let field_idx = u8::try_from(field_idx).expect("up to 256 union fields");
+                field_arms.extend(quote! {
+                    if field_idx == #field_idx { Self { #field_body } } else
+                });
+            }
+            generics_output.extend(&mut where_clause, &cratename);
+            quote! {{
+                let field_idx = <u8 as #cratename::de::BorshDeserialize>::deserialize_reader(reader)
+                    .map_err(|err| #cratename::error::prepend_path(err, &[#type_path]))?;
+                #field_arms {
+                    return Err(#cratename::error::prepend_path(
+                        #cratename::io::Error::new(
+                            #cratename::io::ErrorKind::InvalidData,
+                            #cratename::__private::maybestd::format!("Unexpected union field index: {:?}", field_idx),
+                        ),
+                        &[#type_path],
+                    ))
+                }
+            }}
+        }
+    };
+
+    let (binding, init) = if let Some(method_ident) = item::contains_initialize_with(&input.attrs)?
+    {
+        (
+            quote! { mut return_value },
+            quote! {
+                return_value.#method_ident();
+            },
+        )
+    } else {
+        (quote! { return_value }, quote! {})
+    };
+
+    Ok(quote! {
+        impl #impl_generics #cratename::de::BorshDeserialize for #name #ty_generics #where_clause {
+            fn deserialize_reader<R: #cratename::io::Read>(reader: &mut R) -> ::core::result::Result<Self, #cratename::io::Error> {
+                <Self as #cratename::de::BorshDeserialize>::deserialize_reader_with_limits(
+                    reader,
+                    &mut #cratename::de::Limits::unlimited(),
+                )
+            }
+
+            fn deserialize_reader_with_limits<R: #cratename::io::Read>(
+                reader: &mut R,
+                limits: &mut #cratename::de::Limits,
+            ) -> ::core::result::Result<Self, #cratename::io::Error> {
+                limits.enter()?;
+                let #binding = #return_value;
+                limits.exit();
+                #validate
+                #init
+                Ok(return_value)
+            }
+        }
+    })
+}
+
+/// function which computes derive output [proc_macro2::TokenStream]
+/// of code, which deserializes single union field
+fn process_field(
+    field: &syn::Field,
+    field_idx: usize,
+    cratename: &Path,
+    path_prefix: &TokenStream2,
+    generics: &mut deserialize::GenericsOutput,
+) -> syn::Result<TokenStream2> {
+    let parsed = field::Attributes::parse(&field.attrs)?;
+    if parsed.skip {
+        return Err(syn::Error::new_spanned(
+            field,
+            "`borsh(skip)` is not supported on union fields",
+        ));
+    }
+    if let Some(sibling) = parsed.discriminant_from {
+        return Err(syn::Error::new_spanned(
+            sibling,
+            "`discriminant_from` is only supported on fields of structs with named fields",
+        ));
+    }
+    generics
+        .overrides
+        .extend(parsed.collect_bounds(BoundType::Deserialize));
+    if parsed.needs_bounds_derive(BoundType::Deserialize) {
+        generics.deserialize_visitor.visit_field(field);
+    }
+
+    let path = deserialize::field_path(field, field_idx, cratename, path_prefix);
+    Ok(deserialize::field_output(field, cratename, &parsed, path))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::internals::test_helpers::{
+        default_cratename, local_insta_assert_debug_snapshot, local_insta_assert_snapshot,
+        pretty_print_syn_str,
+    };
+
+    use super::*;
+
+    #[test]
+    fn union_bytes() {
+        let item_union: ItemUnion = syn::parse2(quote! {
+            #[borsh(unsafe_union_bytes)]
+            union A {
+                x: u32,
+                y: [u8; 4],
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_union, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn union_bytes_init() {
+        let item_union: ItemUnion = syn::parse2(quote! {
+            #[borsh(unsafe_union_bytes, init = initialization_method)]
+            union A {
+                x: u32,
+                y: [u8; 4],
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_union, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn union_discriminant() {
+        let item_union: ItemUnion = syn::parse2(quote! {
+            #[borsh(unsafe_union_discriminant = "active_field")]
+            union A {
+                x: u64,
+                #[borsh(deserialize_with = "third_party_impl::deserialize_third_party")]
+                y: ThirdParty,
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_union, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn union_discriminant_generics() {
+        let item_union: ItemUnion = syn::parse2(quote! {
+            #[borsh(unsafe_union_discriminant = "active_field")]
+            union A<T: Copy, U: Copy> {
+                x: T,
+                #[borsh(bound(deserialize = "U: Copy + borsh::de::BorshDeserialize"))]
+                y: ManuallyDrop<U>,
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_union, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn union_without_repr() {
+        let item_union: ItemUnion = syn::parse2(quote! {
+            union A {
+                x: u32,
+                y: f32,
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_union, default_cratename());
+
+        local_insta_assert_debug_snapshot!(actual.unwrap_err());
+    }
+
+    #[test]
+    fn union_discriminant_skip() {
+        let item_union: ItemUnion = syn::parse2(quote! {
+            #[borsh(unsafe_union_discriminant = "active_field")]
+            union A {
+                x: u32,
+                #[borsh(skip)]
+                y: f32,
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_union, default_cratename());
+
+        local_insta_assert_debug_snapshot!(actual.unwrap_err());
+    }
 }
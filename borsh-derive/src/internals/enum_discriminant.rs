@@ -1,15 +1,17 @@
 use std::collections::HashMap;
-use std::convert::TryFrom;
 
-use proc_macro2::{Ident, TokenStream};
-use quote::quote;
+use proc_macro2::{Ident, Literal, Span, TokenStream};
+use quote::{quote, ToTokens};
 use syn::{punctuated::Punctuated, token::Comma, Variant};
 
-pub struct Discriminants(HashMap<Ident, TokenStream>);
+pub struct Discriminants {
+    values: HashMap<Ident, TokenStream>,
+    tag_width: u8,
+}
 impl Discriminants {
     /// Calculates the discriminant that will be assigned by the compiler.
     /// See: https://doc.rust-lang.org/reference/items/enumerations.html#assigning-discriminant-values
-    pub fn new(variants: &Punctuated<Variant, Comma>) -> Self {
+    pub fn new(variants: &Punctuated<Variant, Comma>, tag_width: u8) -> Self {
         let mut map = HashMap::new();
         let mut next_discriminant_if_not_specified = quote! {0};
 
@@ -23,7 +25,20 @@ impl Discriminants {
             map.insert(variant.ident.clone(), this_discriminant);
         }
 
-        Self(map)
+        Self {
+            values: map,
+            tag_width,
+        }
+    }
+
+    pub fn tag_width(&self) -> u8 {
+        self.tag_width
+    }
+
+    /// unsigned integer type of the tag, which is `tag_width` bytes wide
+    pub fn tag_type(&self) -> Ident {
+        let bits = u32::from(self.tag_width) * 8;
+        Ident::new(&format!("u{}", bits), Span::call_site())
     }
 
     pub fn get(
@@ -32,17 +47,24 @@ impl Discriminants {
         use_discriminant: bool,
         variant_idx: usize,
     ) -> syn::Result<TokenStream> {
-        let variant_idx = u8::try_from(variant_idx).map_err(|err| {
-            syn::Error::new(
-                variant_ident.span(),
-                format!("up to 256 enum variants are supported: {}", err),
-            )
-        })?;
         let result = if use_discriminant {
-            let discriminant_value = self.0.get(variant_ident).unwrap();
+            let discriminant_value = self.values.get(variant_ident).unwrap();
             quote! { #discriminant_value }
         } else {
-            quote! { #variant_idx }
+            let max_variants = 1u128 << (u32::from(self.tag_width) * 8);
+            if variant_idx as u128 >= max_variants {
+                return Err(syn::Error::new(
+                    variant_ident.span(),
+                    format!("up to {} enum variants are supported", max_variants),
+                ));
+            }
+            let variant_idx = variant_idx as u64;
+            match self.tag_width {
+                1 => Literal::u8_suffixed(variant_idx as u8).into_token_stream(),
+                2 => Literal::u16_suffixed(variant_idx as u16).into_token_stream(),
+                4 => Literal::u32_suffixed(variant_idx as u32).into_token_stream(),
+                _ => Literal::u64_suffixed(variant_idx).into_token_stream(),
+            }
         };
         Ok(result)
     }
@@ -1,4 +1,4 @@
-use proc_macro2::{Span, TokenStream as TokenStream2};
+use proc_macro2::{Literal, Span, TokenStream as TokenStream2};
 use quote::{quote, ToTokens};
 use std::collections::HashSet;
 use syn::{Fields, Generics, Ident, ItemEnum, ItemStruct, Path, Variant, Visibility};
@@ -36,7 +36,12 @@ pub fn process(input: &ItemEnum, cratename: Path) -> syn::Result<TokenStream2> {
     let mut where_clause = generics::default_where(where_clause);
     let mut generics_output = schema::GenericsOutput::new(&generics);
     let use_discriminant = item::contains_use_discriminant(input)?;
-    let discriminants = Discriminants::new(&input.variants);
+    let discriminants = Discriminants::new(&input.variants, item::get_tag_width(input)?);
+    let tag_width = Literal::u8_unsuffixed(if item::contains_untagged(input)? {
+        0
+    } else {
+        discriminants.tag_width()
+    });
 
     // Generate functions that return the schema for variants.
     let mut discriminant_variables = vec![];
@@ -69,7 +74,7 @@ pub fn process(input: &ItemEnum, cratename: Path) -> syn::Result<TokenStream2> {
             #add_recursive_defs
             #(#discriminant_variables)*
             let definition = #cratename::schema::Definition::Enum {
-                tag_width: 1,
+                tag_width: #tag_width,
                 variants: #cratename::__private::maybestd::vec![#(#variants_defs),*],
             };
             #cratename::schema::add_definition(Self::declaration(), definition, definitions);
@@ -93,7 +98,7 @@ struct VariantOutput {
     inner_struct: TokenStream2,
     /// call to `add_definitions_recursively`.
     add_definitions_recursively_call: TokenStream2,
-    /// declaration of `u8` variable, holding the value for discriminant of a variant
+    /// declaration of variable of tag type, holding the value for discriminant of a variant
     discriminant_variable_assignment: TokenStream2,
     /// entry with a variant's declaration, element in vector of whole enum's definition
     variant_entry: TokenStream2,
@@ -116,8 +121,9 @@ fn process_discriminant(
     let discriminant_variable_name = format!("discriminant_{}", info.variant_idx);
     let discriminant_variable = Ident::new(&discriminant_variable_name, Span::call_site());
 
+    let tag_type = info.discriminants.tag_type();
     let discriminant_variable_assignment = quote! {
-        let #discriminant_variable: u8 = #discriminant_value;
+        let #discriminant_variable: #tag_type = #discriminant_value;
     };
     Ok((discriminant_variable, discriminant_variable_assignment))
 }
@@ -527,4 +533,36 @@ mod tests {
 
         local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
     }
+
+    #[test]
+    fn borsh_untagged() {
+        let item_enum: ItemEnum = syn::parse2(quote! {
+            #[borsh(untagged)]
+            enum Payload {
+                Ping,
+                Memo(String),
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_enum, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn borsh_tag_width() {
+        let item_enum: ItemEnum = syn::parse2(quote! {
+            #[borsh(tag_width = 2, use_discriminant = true)]
+            enum Opcode {
+                Nop = 0,
+                Push(u64) = 0x100,
+                Call { target: u32 },
+            }
+        })
+        .unwrap();
+        let actual = process(&item_enum, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
 }
@@ -1,8 +1,11 @@
 use proc_macro2::TokenStream as TokenStream2;
 use quote::{quote, ToTokens};
-use syn::{ExprPath, Fields, Ident, ItemStruct, Path, Type};
+use syn::{ExprPath, Fields, FieldsNamed, Ident, ItemStruct, Path, Type};
 
-use crate::internals::{attributes::field, generics, schema};
+use crate::internals::{
+    attributes::{field, item},
+    generics, schema,
+};
 
 /// function which computes derive output [proc_macro2::TokenStream]
 /// of code, which computes declaration of a single field, which is later added to
@@ -49,6 +52,9 @@ fn field_definitions_output(
 }
 
 pub fn process(input: &ItemStruct, cratename: Path) -> syn::Result<TokenStream2> {
+    if let Some(transparent) = item::get_transparent(&input.attrs)? {
+        return process_transparent(input, transparent, cratename);
+    }
     let name = &input.ident;
     let struct_name = name.to_token_stream().to_string();
     let generics = generics::without_defaults(&input.generics);
@@ -58,17 +64,33 @@ pub fn process(input: &ItemStruct, cratename: Path) -> syn::Result<TokenStream2>
     let (struct_fields, add_definitions_recursively) =
         process_fields(&cratename, &input.fields, &mut generics_output)?;
 
-    let add_definitions_recursively = quote! {
-        fn add_definitions_recursively(definitions: &mut #cratename::__private::maybestd::collections::BTreeMap<#cratename::schema::Declaration, #cratename::schema::Definition>) {
-            #struct_fields
-            let definition = #cratename::schema::Definition::Struct { fields };
-
-            let no_recursion_flag = definitions.get(&Self::declaration()).is_none();
-            #cratename::schema::add_definition(Self::declaration(), definition, definitions);
-            if no_recursion_flag {
-                #add_definitions_recursively
-            }
+    let add_definitions_recursively = match item::get_versions(&input.attrs)? {
+        Some(versions) => {
+            let payload_declaration = schema::declaration(
+                &format!("{}V{}", struct_name, versions.current),
+                cratename.clone(),
+                generics_output.params_visitor.clone().process_for_bounds(),
+            );
+            versioned_definitions_output(
+                &versions,
+                &cratename,
+                struct_fields,
+                payload_declaration,
+                add_definitions_recursively,
+            )
         }
+        None => quote! {
+            fn add_definitions_recursively(definitions: &mut #cratename::__private::maybestd::collections::BTreeMap<#cratename::schema::Declaration, #cratename::schema::Definition>) {
+                #struct_fields
+                let definition = #cratename::schema::Definition::Struct { fields };
+
+                let no_recursion_flag = definitions.get(&Self::declaration()).is_none();
+                #cratename::schema::add_definition(Self::declaration(), definition, definitions);
+                if no_recursion_flag {
+                    #add_definitions_recursively
+                }
+            }
+        },
     };
 
     let (predicates, declaration) = generics_output.result(&struct_name, &cratename);
@@ -83,6 +105,122 @@ pub fn process(input: &ItemStruct, cratename: Path) -> syn::Result<TokenStream2>
     })
 }
 
+/// function which computes derive output [proc_macro2::TokenStream]
+/// of `BorshSchema` impl of a `#[borsh(transparent)]` struct, which reuses the declaration
+/// of its only not skipped field, or defines the alias of it
+fn process_transparent(
+    input: &ItemStruct,
+    transparent: item::Transparent,
+    cratename: Path,
+) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let generics = generics::without_defaults(&input.generics);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let mut where_clause = generics::default_where(where_clause);
+    let mut generics_output = schema::GenericsOutput::new(&generics);
+    schema::visit_struct_fields(&input.fields, &mut generics_output.params_visitor)?;
+
+    let mut inner = None;
+    for field in &input.fields {
+        let parsed = field::Attributes::parse(&field.attrs)?;
+        if parsed.flatten {
+            return Err(syn::Error::new_spanned(
+                field,
+                "`flatten` is not supported on fields of `transparent` structs",
+            ));
+        }
+        if !parsed.skip {
+            inner = Some((field, parsed));
+        }
+    }
+    let (field, parsed) = inner.ok_or_else(|| {
+        syn::Error::new(
+            name.span(),
+            "`transparent` structs have to contain exactly one field, which isn't skipped",
+        )
+    })?;
+    let field_type = schema_field_type(&field.ty, &parsed, &cratename);
+    let field_declaration =
+        field_declaration_output(None, &field_type, &cratename, parsed.schema_declaration());
+    let field_definitions =
+        field_definitions_output(&field_type, &cratename, parsed.schema_definitions());
+
+    let alias = transparent.alias.map(|alias| alias.value());
+    let (predicates, alias_declaration) =
+        generics_output.result(alias.as_deref().unwrap_or(&name.to_string()), &cratename);
+    where_clause.predicates.extend(predicates);
+    let (declaration, add_alias_definition) = match alias {
+        Some(_) => (
+            alias_declaration,
+            quote! {
+                #cratename::schema::add_alias_definition(Self::declaration(), &#field_declaration, definitions);
+            },
+        ),
+        None => (field_declaration, TokenStream2::new()),
+    };
+    Ok(quote! {
+        impl #impl_generics #cratename::BorshSchema for #name #ty_generics #where_clause {
+            fn declaration() -> #cratename::schema::Declaration {
+                #declaration
+            }
+            fn add_definitions_recursively(definitions: &mut #cratename::__private::maybestd::collections::BTreeMap<#cratename::schema::Declaration, #cratename::schema::Definition>) {
+                #field_definitions
+                #add_alias_definition
+            }
+        }
+    })
+}
+
+/// function which computes derive output [proc_macro2::TokenStream]
+/// of `add_definitions_recursively` of a struct with `#[borsh(version = ...)]`, which is
+/// defined as an enum with versions for tags, and its current fields as a separate struct
+fn versioned_definitions_output(
+    versions: &item::Versions,
+    cratename: &Path,
+    struct_fields: TokenStream2,
+    payload_declaration: TokenStream2,
+    add_definitions_recursively: TokenStream2,
+) -> TokenStream2 {
+    let mut migrate_from = versions.migrate_from.iter().collect::<Vec<_>>();
+    migrate_from.sort_by_key(|(version, _)| *version);
+    let mut variants = vec![];
+    let mut add_migrations_recursively = TokenStream2::new();
+    for (version, ty) in migrate_from {
+        let variant_name = format!("V{}", version);
+        variants.push(quote! {
+            (#version as i64, #variant_name.to_string(), <#ty as #cratename::BorshSchema>::declaration())
+        });
+        add_migrations_recursively.extend(quote! {
+            <#ty as #cratename::BorshSchema>::add_definitions_recursively(definitions);
+        });
+    }
+    let current = versions.current;
+    let variant_name = format!("V{}", current);
+    variants.push(quote! {
+        (#current as i64, #variant_name.to_string(), payload_declaration.clone())
+    });
+
+    quote! {
+        fn add_definitions_recursively(definitions: &mut #cratename::__private::maybestd::collections::BTreeMap<#cratename::schema::Declaration, #cratename::schema::Definition>) {
+            #struct_fields
+            let definition = #cratename::schema::Definition::Struct { fields };
+            let payload_declaration = { #payload_declaration };
+
+            let no_recursion_flag = definitions.get(&payload_declaration).is_none();
+            #cratename::schema::add_definition(payload_declaration.clone(), definition, definitions);
+            let definition = #cratename::schema::Definition::Enum {
+                tag_width: 1,
+                variants: #cratename::__private::maybestd::vec![#(#variants),*],
+            };
+            #cratename::schema::add_definition(Self::declaration(), definition, definitions);
+            if no_recursion_flag {
+                #add_migrations_recursively
+                #add_definitions_recursively
+            }
+        }
+    }
+}
+
 fn process_fields(
     cratename: &Path,
     fields: &Fields,
@@ -95,6 +233,27 @@ fn process_fields(
     let mut fields_vec = vec![];
     schema::visit_struct_fields(fields, &mut generics.params_visitor)?;
     match fields {
+        Fields::Named(fields) if has_flatten(fields)? => {
+            let mut flattened_definitions = TokenStream2::new();
+            for field in &fields.named {
+                process_flatten_mode_field(
+                    field,
+                    cratename,
+                    &mut fields_vec,
+                    &mut flattened_definitions,
+                    &mut add_definitions_recursively,
+                )?;
+            }
+            struct_fields = quote! {
+                let mut flattened = #cratename::__private::maybestd::collections::BTreeMap::new();
+                #flattened_definitions
+                let fields = #cratename::schema::flatten_fields(
+                    #cratename::__private::maybestd::vec![#(#fields_vec),*],
+                    flattened,
+                    definitions,
+                );
+            };
+        }
         Fields::Named(fields) => {
             for field in &fields.named {
                 process_field(
@@ -135,6 +294,84 @@ fn process_fields(
     }
     Ok((struct_fields, add_definitions_recursively))
 }
+fn has_flatten(fields: &FieldsNamed) -> syn::Result<bool> {
+    for field in &fields.named {
+        if field::Attributes::parse(&field.attrs)?.flatten {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// function which computes derive output [proc_macro2::TokenStream]
+/// of entries of fields of a struct with `#[borsh(flatten)]` fields, which are passed
+/// to `borsh::schema::flatten_fields`: `(name, declaration, false)` for regular fields
+/// and `(name, declaration, true)` for flattened ones, whose definitions are added
+/// to separate `flattened` map beforehand
+fn process_flatten_mode_field(
+    field: &syn::Field,
+    cratename: &Path,
+    fields_vec: &mut Vec<TokenStream2>,
+    flattened_definitions: &mut TokenStream2,
+    add_definitions_recursively: &mut TokenStream2,
+) -> syn::Result<()> {
+    let parsed = field::Attributes::parse(&field.attrs)?;
+    if parsed.flatten {
+        let ty = &field.ty;
+        if !matches!(ty, Type::Path(_)) {
+            return Err(syn::Error::new_spanned(
+                ty,
+                "`flatten` is only supported on fields of struct types",
+            ));
+        }
+        let declaration = field_declaration_output(field.ident.as_ref(), ty, cratename, None);
+        fields_vec.push(quote! {
+            {
+                let (name, declaration) = #declaration;
+                (name, declaration, true)
+            }
+        });
+        flattened_definitions.extend(quote! {
+            <#ty as #cratename::BorshSchema>::add_definitions_recursively(&mut flattened);
+        });
+        return Ok(());
+    }
+    let mut declarations = vec![];
+    process_field(
+        field,
+        cratename,
+        &mut declarations,
+        add_definitions_recursively,
+    )?;
+    for declaration in declarations {
+        fields_vec.push(quote! {
+            {
+                let (name, declaration) = #declaration;
+                (name, declaration, false)
+            }
+        });
+    }
+    Ok(())
+}
+
+/// type, which describes a field in schema
+fn schema_field_type(ty: &Type, parsed: &field::Attributes, cratename: &Path) -> Type {
+    // `varint` and `length_width` fields are described by schema of their encoding wrappers,
+    // `default` fields, which may be missing from the input, are wrapped into `Trailing`
+    let mut field_type: Type = if parsed.varint {
+        syn::parse2(quote! { <#ty as #cratename::varint::VarInt>::Encoded }).unwrap()
+    } else if let Some(length_type) = parsed.length_type() {
+        syn::parse2(quote! { #cratename::length_prefix::LengthPrefixed<#length_type, #ty> })
+            .unwrap()
+    } else {
+        ty.clone()
+    };
+    if parsed.default.is_some() {
+        field_type = syn::parse2(quote! { #cratename::trailing::Trailing<#field_type> }).unwrap();
+    }
+    field_type
+}
+
 fn process_field(
     field: &syn::Field,
     cratename: &Path,
@@ -142,17 +379,23 @@ fn process_field(
     add_definitions_recursively: &mut TokenStream2,
 ) -> syn::Result<()> {
     let parsed = field::Attributes::parse(&field.attrs)?;
+    if parsed.flatten {
+        return Err(syn::Error::new_spanned(
+            field,
+            "`flatten` is only supported on named fields",
+        ));
+    }
     if !parsed.skip {
         let field_name = field.ident.as_ref();
-        let field_type = &field.ty;
+        let field_type = schema_field_type(&field.ty, &parsed, cratename);
         fields_vec.push(field_declaration_output(
             field_name,
-            field_type,
+            &field_type,
             cratename,
             parsed.schema_declaration(),
         ));
         add_definitions_recursively.extend(field_definitions_output(
-            field_type,
+            &field_type,
             cratename,
             parsed.schema_definitions(),
         ));
@@ -525,4 +768,143 @@ mod tests {
 
         local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
     }
+
+    #[test]
+    fn varint_attr() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            struct A {
+                #[borsh(varint)]
+                x: u64,
+                y: u64,
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_struct, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn length_width_attr() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            struct A {
+                #[borsh(length_width = 1)]
+                x: String,
+                #[borsh(length = "u16")]
+                y: Vec<u64>,
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_struct, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn default_attr() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            struct A {
+                x: u64,
+                #[borsh(default, varint)]
+                y: u64,
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_struct, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn versioned() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            #[borsh(version = 3, migrate_from(1 = AV1, 2 = AV2))]
+            struct A {
+                x: u64,
+                y: String,
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_struct, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn flatten_attr() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            struct A<T> {
+                x: u64,
+                #[borsh(flatten)]
+                header: Header<T>,
+                y: String,
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_struct, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn flatten_tuple_field() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            struct A {
+                x: u64,
+                #[borsh(flatten)]
+                inner: (u8, u16),
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_struct, default_cratename());
+
+        local_insta_assert_debug_snapshot!(actual.unwrap_err());
+    }
+
+    #[test]
+    fn flatten_unnamed_field() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            struct A(u64, #[borsh(flatten)] Header);
+        })
+        .unwrap();
+
+        let actual = process(&item_struct, default_cratename());
+
+        local_insta_assert_debug_snapshot!(actual.unwrap_err());
+    }
+
+    #[test]
+    fn transparent() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            #[borsh(transparent)]
+            struct A<T>(Vec<T>, #[borsh(skip)] PhantomData<u8>);
+        })
+        .unwrap();
+
+        let actual = process(&item_struct, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn transparent_alias() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            #[borsh(transparent = "AccountId")]
+            struct A {
+                #[borsh(length_width = 1)]
+                id: String,
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_struct, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
 }
@@ -0,0 +1,236 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{Fields, Ident, ItemEnum, Path, Variant};
+
+use crate::internals::{
+    attributes::{field, item, BoundType},
+    enum_discriminant::Discriminants,
+    generics, serialize,
+};
+
+pub fn process(input: &ItemEnum, cratename: Path) -> syn::Result<TokenStream2> {
+    let enum_ident = &input.ident;
+    let generics = generics::without_defaults(&input.generics);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let mut where_clause = generics::default_where(where_clause);
+    let mut generics_output = serialize::GenericsOutput::new(&generics);
+    let mut all_variants_idx_body = TokenStream2::new();
+    let mut fields_body = TokenStream2::new();
+    let use_discriminant = item::contains_use_discriminant(input)?;
+    let discriminants = Discriminants::new(&input.variants, item::get_tag_width(input)?);
+    let tag_type = discriminants.tag_type();
+
+    for (variant_idx, variant) in input.variants.iter().enumerate() {
+        let variant_ident = &variant.ident;
+        let discriminant_value = discriminants.get(variant_ident, use_discriminant, variant_idx)?;
+        let variant_output = process_variant(
+            variant,
+            enum_ident,
+            &discriminant_value,
+            &cratename,
+            &mut generics_output,
+        )?;
+        all_variants_idx_body.extend(variant_output.variant_idx_body);
+        let (variant_header, variant_body) = (variant_output.header, variant_output.body);
+        fields_body.extend(quote!(
+            #enum_ident::#variant_ident #variant_header => {
+                #variant_body
+            }
+        ))
+    }
+    let ser_trait: Path = syn::parse2(quote! { #cratename::ser::BorshSerializeAsync }).unwrap();
+    generics_output.extend_with_trait(&mut where_clause, &ser_trait);
+    let write_tag = if item::contains_untagged(input)? {
+        quote! {}
+    } else {
+        quote! {
+            let variant_idx: #tag_type = match self {
+                #all_variants_idx_body
+            };
+            #cratename::async_io::AsyncWrite::write_all(writer, &variant_idx.to_le_bytes()).await?;
+        }
+    };
+
+    Ok(super::impl_output(
+        enum_ident,
+        impl_generics,
+        ty_generics,
+        &where_clause,
+        &cratename,
+        quote! {
+            #write_tag
+
+            match self {
+                #fields_body
+            }
+        },
+    ))
+}
+
+struct VariantOutput {
+    header: TokenStream2,
+    body: TokenStream2,
+    variant_idx_body: TokenStream2,
+}
+
+impl VariantOutput {
+    fn new() -> Self {
+        Self {
+            body: TokenStream2::new(),
+            header: TokenStream2::new(),
+            variant_idx_body: TokenStream2::new(),
+        }
+    }
+}
+
+fn process_variant(
+    variant: &Variant,
+    enum_ident: &Ident,
+    discriminant_value: &TokenStream2,
+    cratename: &Path,
+    generics: &mut serialize::GenericsOutput,
+) -> syn::Result<VariantOutput> {
+    let variant_ident = &variant.ident;
+    let mut variant_output = VariantOutput::new();
+    match &variant.fields {
+        Fields::Named(fields) => {
+            for field in &fields.named {
+                let field_id = serialize::FieldId::Enum(field.ident.clone().unwrap());
+                process_field(field, field_id, cratename, generics, &mut variant_output)?;
+            }
+            let header = variant_output.header;
+            // `..` pattern matching works even if all fields were specified
+            variant_output.header = quote! { { #header.. }};
+            variant_output.variant_idx_body = quote!(
+                #enum_ident::#variant_ident {..} => #discriminant_value,
+            );
+        }
+        Fields::Unnamed(fields) => {
+            for (field_idx, field) in fields.unnamed.iter().enumerate() {
+                let field_id = serialize::FieldId::new_enum_unnamed(field_idx)?;
+                process_field(field, field_id, cratename, generics, &mut variant_output)?;
+            }
+            let header = variant_output.header;
+            variant_output.header = quote! { ( #header )};
+            variant_output.variant_idx_body = quote!(
+                #enum_ident::#variant_ident(..) => #discriminant_value,
+            );
+        }
+        Fields::Unit => {
+            variant_output.variant_idx_body = quote!(
+                #enum_ident::#variant_ident => #discriminant_value,
+            );
+        }
+    };
+    Ok(variant_output)
+}
+
+fn process_field(
+    field: &syn::Field,
+    field_id: serialize::FieldId,
+    cratename: &Path,
+    generics: &mut serialize::GenericsOutput,
+    output: &mut VariantOutput,
+) -> syn::Result<()> {
+    let parsed = field::Attributes::parse(&field.attrs)?;
+    super::check_field_attributes(field, &parsed)?;
+
+    let needs_bounds_derive = parsed.needs_bounds_derive(BoundType::Serialize);
+    generics
+        .overrides
+        .extend(parsed.collect_bounds(BoundType::Serialize));
+
+    let field_variant_header = field_id.enum_variant_header(parsed.skip);
+    if let Some(field_variant_header) = field_variant_header {
+        output.header.extend(field_variant_header);
+    }
+
+    if !parsed.skip {
+        output
+            .body
+            .extend(super::field_output(&field_id, cratename));
+        if needs_bounds_derive {
+            generics.serialize_visitor.visit_field(field);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::internals::test_helpers::{
+        default_cratename, local_insta_assert_snapshot, pretty_print_syn_str,
+    };
+
+    use super::*;
+
+    #[test]
+    fn borsh_skip_tuple_variant_field() {
+        let item_enum: ItemEnum = syn::parse2(quote! {
+            enum AATTB {
+                B(#[borsh(skip)] i32, #[borsh(skip)] u32),
+
+                NegatedVariant {
+                    beta: u8,
+                }
+            }
+        })
+        .unwrap();
+        let actual = process(&item_enum, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn generic_borsh_skip_struct_field() {
+        let item_enum: ItemEnum = syn::parse2(quote! {
+            enum A<K, V, U> {
+                B {
+                    #[borsh(skip)]
+                    x: HashMap<K, V>,
+                    y: String,
+                },
+                C(K, Vec<U>),
+            }
+        })
+        .unwrap();
+        let actual = process(&item_enum, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn borsh_discriminant_true() {
+        let item_enum: ItemEnum = syn::parse2(quote! {
+            #[borsh(use_discriminant = true)]
+            enum X {
+                A,
+                B = 20,
+                C,
+                D,
+                E = 10,
+                F,
+            }
+        })
+        .unwrap();
+        let actual = process(&item_enum, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn borsh_tag_width() {
+        let item_enum: ItemEnum = syn::parse2(quote! {
+            #[borsh(tag_width = 2, use_discriminant = true)]
+            enum Opcode {
+                Nop = 0,
+                Push(u64) = 0x100,
+                Call { target: u32 },
+            }
+        })
+        .unwrap();
+        let actual = process(&item_enum, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+}
@@ -0,0 +1,73 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{Ident, Path};
+
+use super::FieldId;
+use crate::internals::attributes::field;
+
+pub mod enums;
+pub mod structs;
+
+/// rejects `#[borsh(serialize_with = ...)]`, `#[borsh(varint)]`, `#[borsh(length_width = ...)]`
+/// and `#[borsh(serde)]`,
+/// as their functions are called with a blocking writer
+fn check_field_attributes(field: &syn::Field, parsed: &field::Attributes) -> syn::Result<()> {
+    if let Some(serialize_with) = &parsed.serialize_with {
+        return Err(syn::Error::new_spanned(
+            serialize_with,
+            "`serialize_with` is not supported by BorshSerializeAsync",
+        ));
+    }
+    if parsed.varint {
+        return Err(syn::Error::new_spanned(
+            field,
+            "`varint` is not supported by BorshSerializeAsync",
+        ));
+    }
+    if parsed.length_width.is_some() {
+        return Err(syn::Error::new_spanned(
+            field,
+            "`length_width` is not supported by BorshSerializeAsync",
+        ));
+    }
+    if parsed.serde {
+        return Err(syn::Error::new_spanned(
+            field,
+            "`serde` is not supported by BorshSerializeAsync",
+        ));
+    }
+    Ok(())
+}
+
+/// function which computes derive output [proc_macro2::TokenStream]
+/// of code, which serializes single field inside of `async` block
+fn field_output(field_id: &FieldId, cratename: &Path) -> TokenStream2 {
+    let arg = field_id.serialize_arg();
+    quote! { #cratename::BorshSerializeAsync::serialize_async(#arg, writer).await?; }
+}
+
+/// function which computes derive output [proc_macro2::TokenStream]
+/// of `BorshSerializeAsync` impl, which serializes the item with `body`,
+/// evaluated inside of `async` block with `writer` in scope
+fn impl_output(
+    name: &Ident,
+    impl_generics: syn::ImplGenerics,
+    ty_generics: syn::TypeGenerics,
+    where_clause: &syn::WhereClause,
+    cratename: &Path,
+    body: TokenStream2,
+) -> TokenStream2 {
+    quote! {
+        impl #impl_generics #cratename::ser::BorshSerializeAsync for #name #ty_generics #where_clause {
+            fn serialize_async<'__a, W: #cratename::async_io::AsyncWrite + ?Sized>(
+                &'__a self,
+                writer: &'__a mut W,
+            ) -> #cratename::async_io::BoxFuture<'__a, ::core::result::Result<(), #cratename::io::Error>> {
+                #cratename::__private::maybestd::boxed::Box::pin(async move {
+                    #body
+                    Ok(())
+                })
+            }
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{Fields, ItemStruct, Path};
+
+use crate::internals::{
+    attributes::{field, item, BoundType},
+    generics, serialize,
+};
+
+pub fn process(input: &ItemStruct, cratename: Path) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let generics = generics::without_defaults(&input.generics);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let mut where_clause = generics::default_where(where_clause);
+    let mut body = TokenStream2::new();
+    let mut generics_output = serialize::GenericsOutput::new(&generics);
+    if item::get_versions(&input.attrs)?.is_some() {
+        return Err(syn::Error::new(
+            name.span(),
+            "`borsh(version)` is not supported by BorshSerializeAsync",
+        ));
+    }
+    match &input.fields {
+        Fields::Named(fields) => {
+            for field in &fields.named {
+                let field_id = serialize::FieldId::Struct(field.ident.clone().unwrap());
+
+                process_field(field, field_id, &cratename, &mut generics_output, &mut body)?;
+            }
+        }
+        Fields::Unnamed(fields) => {
+            for (field_idx, field) in fields.unnamed.iter().enumerate() {
+                let field_id = serialize::FieldId::new_struct_unnamed(field_idx)?;
+
+                process_field(field, field_id, &cratename, &mut generics_output, &mut body)?;
+            }
+        }
+        Fields::Unit => {}
+    }
+    let ser_trait: Path = syn::parse2(quote! { #cratename::ser::BorshSerializeAsync }).unwrap();
+    generics_output.extend_with_trait(&mut where_clause, &ser_trait);
+
+    Ok(super::impl_output(
+        name,
+        impl_generics,
+        ty_generics,
+        &where_clause,
+        &cratename,
+        body,
+    ))
+}
+
+fn process_field(
+    field: &syn::Field,
+    field_id: serialize::FieldId,
+    cratename: &Path,
+    generics: &mut serialize::GenericsOutput,
+    body: &mut TokenStream2,
+) -> syn::Result<()> {
+    let parsed = field::Attributes::parse(&field.attrs)?;
+    super::check_field_attributes(field, &parsed)?;
+    let needs_bounds_derive = parsed.needs_bounds_derive(BoundType::Serialize);
+
+    generics
+        .overrides
+        .extend(parsed.collect_bounds(BoundType::Serialize));
+    if !parsed.skip {
+        body.extend(super::field_output(&field_id, cratename));
+
+        if needs_bounds_derive {
+            generics.serialize_visitor.visit_field(field);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::internals::test_helpers::{
+        default_cratename, local_insta_assert_debug_snapshot, local_insta_assert_snapshot,
+        pretty_print_syn_str,
+    };
+
+    use super::*;
+
+    #[test]
+    fn simple_struct() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            struct A {
+                x: u64,
+                y: String,
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_struct, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn generic_tuple_struct_borsh_skip() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            struct TupleA<T, U>(T, #[borsh(skip)] U);
+        })
+        .unwrap();
+
+        let actual = process(&item_struct, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn generic_serialize_bound() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            struct C<T: Debug, U> {
+                a: String,
+                #[borsh(bound(serialize =
+                    "T: borsh::ser::BorshSerializeAsync + PartialOrd,
+                     U: borsh::ser::BorshSerializeAsync"
+                ))]
+                b: HashMap<T, U>,
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_struct, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn reject_serialize_with() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            struct A<K, V> {
+                #[borsh(serialize_with = "third_party_impl::serialize_third_party")]
+                x: ThirdParty<K, V>,
+                y: u64,
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_struct, default_cratename());
+
+        let err = match actual {
+            Ok(..) => unreachable!("expecting error here"),
+            Err(err) => err,
+        };
+        local_insta_assert_debug_snapshot!(err);
+    }
+
+    #[test]
+    fn reject_varint() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            struct A {
+                #[borsh(varint)]
+                x: u64,
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_struct, default_cratename());
+
+        let err = match actual {
+            Ok(..) => unreachable!("expecting error here"),
+            Err(err) => err,
+        };
+        local_insta_assert_debug_snapshot!(err);
+    }
+
+    #[test]
+    fn reject_serde() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            struct A {
+                #[borsh(serde)]
+                x: Point,
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_struct, default_cratename());
+
+        let err = match actual {
+            Ok(..) => unreachable!("expecting error here"),
+            Err(err) => err,
+        };
+        local_insta_assert_debug_snapshot!(err);
+    }
+
+    #[test]
+    fn reject_version() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            #[borsh(version = 1)]
+            struct A {
+                x: u64,
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_struct, default_cratename());
+
+        let err = match actual {
+            Ok(..) => unreachable!("expecting error here"),
+            Err(err) => err,
+        };
+        local_insta_assert_debug_snapshot!(err);
+    }
+}
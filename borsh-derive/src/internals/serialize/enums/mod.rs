@@ -17,7 +17,8 @@ pub fn process(input: &ItemEnum, cratename: Path) -> syn::Result<TokenStream2> {
     let mut all_variants_idx_body = TokenStream2::new();
     let mut fields_body = TokenStream2::new();
     let use_discriminant = item::contains_use_discriminant(input)?;
-    let discriminants = Discriminants::new(&input.variants);
+    let discriminants = Discriminants::new(&input.variants, item::get_tag_width(input)?);
+    let tag_type = discriminants.tag_type();
 
     for (variant_idx, variant) in input.variants.iter().enumerate() {
         let variant_ident = &variant.ident;
@@ -38,14 +39,22 @@ pub fn process(input: &ItemEnum, cratename: Path) -> syn::Result<TokenStream2> {
         ))
     }
     generics_output.extend(&mut where_clause, &cratename);
+    // tag of untagged enums is not written, it's known from elsewhere on deserialization
+    let write_tag = if item::contains_untagged(input)? {
+        quote! {}
+    } else {
+        quote! {
+            let variant_idx: #tag_type = match self {
+                #all_variants_idx_body
+            };
+            writer.write_all(&variant_idx.to_le_bytes())?;
+        }
+    };
 
     Ok(quote! {
         impl #impl_generics #cratename::ser::BorshSerialize for #enum_ident #ty_generics #where_clause {
             fn serialize<W: #cratename::io::Write>(&self, writer: &mut W) -> ::core::result::Result<(), #cratename::io::Error> {
-                let variant_idx: u8 = match self {
-                    #all_variants_idx_body
-                };
-                writer.write_all(&variant_idx.to_le_bytes())?;
+                #write_tag
 
                 match self {
                     #fields_body
@@ -134,7 +143,7 @@ fn process_field(
     }
 
     if !parsed.skip {
-        let delta = field_id.serialize_output(cratename, parsed.serialize_with);
+        let delta = field_id.serialize_output(cratename, parsed.serialize_with_path(cratename));
         output.body.extend(delta);
         if needs_bounds_derive {
             generics.serialize_visitor.visit_field(field);
@@ -425,4 +434,36 @@ mod tests {
 
         local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
     }
+
+    #[test]
+    fn borsh_untagged() {
+        let item_enum: ItemEnum = syn::parse2(quote! {
+            #[borsh(untagged)]
+            enum Payload {
+                Ping,
+                Transfer { to: u64, amount: u64 },
+                Memo(String),
+            }
+        })
+        .unwrap();
+        let actual = process(&item_enum, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn borsh_tag_width() {
+        let item_enum: ItemEnum = syn::parse2(quote! {
+            #[borsh(tag_width = 2, use_discriminant = true)]
+            enum Opcode {
+                Nop = 0,
+                Push(u64) = 0x100,
+                Call { target: u32 },
+            }
+        })
+        .unwrap();
+        let actual = process(&item_enum, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
 }
@@ -5,6 +5,7 @@ use syn::{Expr, ExprPath, Generics, Ident, Index, Path};
 
 use super::generics;
 
+pub mod asynchronous;
 pub mod enums;
 pub mod structs;
 pub mod unions;
@@ -23,8 +24,11 @@ impl GenericsOutput {
     }
     fn extend(self, where_clause: &mut syn::WhereClause, cratename: &Path) {
         let trait_path: Path = syn::parse2(quote! { #cratename::ser::BorshSerialize }).unwrap();
+        self.extend_with_trait(where_clause, &trait_path);
+    }
+    fn extend_with_trait(self, where_clause: &mut syn::WhereClause, trait_path: &Path) {
         let predicates =
-            generics::compute_predicates(self.serialize_visitor.process_for_bounds(), &trait_path);
+            generics::compute_predicates(self.serialize_visitor.process_for_bounds(), trait_path);
         where_clause.predicates.extend(predicates);
         where_clause.predicates.extend(self.overrides);
     }
@@ -3,7 +3,7 @@ use quote::quote;
 use syn::{Fields, ItemStruct, Path};
 
 use crate::internals::{
-    attributes::{field, BoundType},
+    attributes::{field, item, BoundType},
     generics, serialize,
 };
 
@@ -14,6 +14,12 @@ pub fn process(input: &ItemStruct, cratename: Path) -> syn::Result<TokenStream2>
     let mut where_clause = generics::default_where(where_clause);
     let mut body = TokenStream2::new();
     let mut generics_output = serialize::GenericsOutput::new(&generics);
+    if let Some(versions) = item::get_versions(&input.attrs)? {
+        let version = versions.current;
+        body.extend(quote! {
+            #cratename::BorshSerialize::serialize(&#version, writer)?;
+        });
+    }
     match &input.fields {
         Fields::Named(fields) => {
             for field in &fields.named {
@@ -57,7 +63,7 @@ fn process_field(
         .overrides
         .extend(parsed.collect_bounds(BoundType::Serialize));
     if !parsed.skip {
-        let delta = field_id.serialize_output(cratename, parsed.serialize_with);
+        let delta = field_id.serialize_output(cratename, parsed.serialize_with_path(cratename));
         body.extend(delta);
 
         if needs_bounds_derive {
@@ -299,4 +305,107 @@ mod tests {
         };
         local_insta_assert_debug_snapshot!(err);
     }
+
+    #[test]
+    fn check_varint_attr() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            struct A {
+                #[borsh(varint)]
+                x: u64,
+                y: u64,
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_struct, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn check_varint_serialize_with_conflict() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            struct A {
+                #[borsh(varint, serialize_with = "third_party_impl::serialize_third_party")]
+                x: u64,
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_struct, default_cratename());
+
+        let err = match actual {
+            Ok(..) => unreachable!("expecting error here"),
+            Err(err) => err,
+        };
+        local_insta_assert_debug_snapshot!(err);
+    }
+
+    #[test]
+    fn check_serde_attr() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            struct A {
+                #[borsh(serde)]
+                x: Point,
+                y: u64,
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_struct, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn check_serde_varint_conflict() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            struct A {
+                #[borsh(serde, varint)]
+                x: u64,
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_struct, default_cratename());
+
+        let err = match actual {
+            Ok(..) => unreachable!("expecting error here"),
+            Err(err) => err,
+        };
+        local_insta_assert_debug_snapshot!(err);
+    }
+
+    #[test]
+    fn check_length_width_attr() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            struct A {
+                #[borsh(length_width = 1)]
+                x: String,
+                #[borsh(length = "u16")]
+                y: Vec<u64>,
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_struct, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn versioned() {
+        let item_struct: ItemStruct = syn::parse2(quote! {
+            #[borsh(version = 3, migrate_from(1 = AV1, 2 = AV2))]
+            struct A {
+                x: u64,
+                y: String,
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_struct, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
 }
@@ -1,6 +1,231 @@
 use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use std::convert::TryFrom;
 use syn::{ItemUnion, Path};
 
-pub fn process(_input: &ItemUnion, _cratename: Path) -> syn::Result<TokenStream2> {
-    unimplemented!()
+use crate::internals::{
+    attributes::{
+        field,
+        item::{self, UnionRepr},
+        BoundType,
+    },
+    generics, serialize,
+};
+
+pub fn process(input: &ItemUnion, cratename: Path) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let generics = generics::without_defaults(&input.generics);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let mut where_clause = generics::default_where(where_clause);
+
+    let body = match item::union_repr(input)? {
+        UnionRepr::Bytes => {
+            where_clause
+                .predicates
+                .push(syn::parse2(quote! { Self: core::marker::Copy }).unwrap());
+            quote! {
+                unsafe { #cratename::__private::union_bytes::serialize(self, writer) }
+            }
+        }
+        UnionRepr::Discriminant(discriminant) => {
+            let mut generics_output = serialize::GenericsOutput::new(&generics);
+            let mut arms = TokenStream2::new();
+            for (field_idx, field) in input.fields.named.iter().enumerate() {
+                let field_idx = u8::try_from(field_idx).map_err(|_| {
+                    syn::Error::new_spanned(
+                        field,
+                        "up to 256 union fields are supported with `unsafe_union_discriminant`",
+                    )
+                })?;
+                let delta = process_field(field, field_idx, &cratename, &mut generics_output)?;
+                arms.extend(delta);
+            }
+            generics_output.extend(&mut where_clause, &cratename);
+            quote! {
+                let field_idx: u8 = #discriminant(self);
+                match field_idx {
+                    #arms
+                    _ => {
+                        return Err(#cratename::io::Error::new(
+                            #cratename::io::ErrorKind::InvalidData,
+                            #cratename::__private::maybestd::format!(
+                                "Invalid union field index: {}",
+                                field_idx
+                            ),
+                        ))
+                    }
+                }
+                Ok(())
+            }
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics #cratename::ser::BorshSerialize for #name #ty_generics #where_clause {
+            fn serialize<W: #cratename::io::Write>(&self, writer: &mut W) -> ::core::result::Result<(), #cratename::io::Error> {
+                #body
+            }
+        }
+    })
+}
+
+/// function which computes derive output [proc_macro2::TokenStream]
+/// of match arm, which serializes single union field with its index
+fn process_field(
+    field: &syn::Field,
+    field_idx: u8,
+    cratename: &Path,
+    generics: &mut serialize::GenericsOutput,
+) -> syn::Result<TokenStream2> {
+    let parsed = field::Attributes::parse(&field.attrs)?;
+    if parsed.skip {
+        return Err(syn::Error::new_spanned(
+            field,
+            "`borsh(skip)` is not supported on union fields",
+        ));
+    }
+    generics
+        .overrides
+        .extend(parsed.collect_bounds(BoundType::Serialize));
+    if parsed.needs_bounds_derive(BoundType::Serialize) {
+        generics.serialize_visitor.visit_field(field);
+    }
+
+    let field_name = field.ident.as_ref().unwrap();
+    let arg = quote! { unsafe { &self.#field_name } };
+    let serialize = match parsed.serialize_with_path(cratename) {
+        Some(func) => quote! { #func(#arg, writer)?; },
+        None => quote! { #cratename::BorshSerialize::serialize(#arg, writer)?; },
+    };
+    Ok(quote! {
+        #field_idx => {
+            #cratename::BorshSerialize::serialize(&field_idx, writer)?;
+            #serialize
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::internals::test_helpers::{
+        default_cratename, local_insta_assert_debug_snapshot, local_insta_assert_snapshot,
+        pretty_print_syn_str,
+    };
+
+    use super::*;
+
+    #[test]
+    fn union_bytes() {
+        let item_union: ItemUnion = syn::parse2(quote! {
+            #[borsh(unsafe_union_bytes)]
+            union A {
+                x: u32,
+                y: [u8; 4],
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_union, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn union_discriminant() {
+        let item_union: ItemUnion = syn::parse2(quote! {
+            #[borsh(unsafe_union_discriminant = "active_field")]
+            union A {
+                x: u64,
+                #[borsh(serialize_with = "third_party_impl::serialize_third_party")]
+                y: ThirdParty,
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_union, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn union_discriminant_generics() {
+        let item_union: ItemUnion = syn::parse2(quote! {
+            #[borsh(unsafe_union_discriminant = "active_field")]
+            union A<T: Copy, U: Copy> {
+                x: T,
+                #[borsh(bound(serialize = "U: Copy + borsh::ser::BorshSerialize"))]
+                y: ManuallyDrop<U>,
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_union, default_cratename()).unwrap();
+
+        local_insta_assert_snapshot!(pretty_print_syn_str(&actual).unwrap());
+    }
+
+    #[test]
+    fn union_without_repr() {
+        let item_union: ItemUnion = syn::parse2(quote! {
+            union A {
+                x: u32,
+                y: f32,
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_union, default_cratename());
+
+        local_insta_assert_debug_snapshot!(actual.unwrap_err());
+    }
+
+    #[test]
+    fn union_bytes_and_discriminant() {
+        let item_union: ItemUnion = syn::parse2(quote! {
+            #[borsh(unsafe_union_bytes, unsafe_union_discriminant = "active_field")]
+            union A {
+                x: u32,
+                y: f32,
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_union, default_cratename());
+
+        local_insta_assert_debug_snapshot!(actual.unwrap_err());
+    }
+
+    #[test]
+    fn union_bytes_field_attribute() {
+        let item_union: ItemUnion = syn::parse2(quote! {
+            #[borsh(unsafe_union_bytes)]
+            union A {
+                x: u32,
+                #[borsh(skip)]
+                y: f32,
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_union, default_cratename());
+
+        local_insta_assert_debug_snapshot!(actual.unwrap_err());
+    }
+
+    #[test]
+    fn union_discriminant_skip() {
+        let item_union: ItemUnion = syn::parse2(quote! {
+            #[borsh(unsafe_union_discriminant = "active_field")]
+            union A {
+                x: u32,
+                #[borsh(skip)]
+                y: f32,
+            }
+        })
+        .unwrap();
+
+        let actual = process(&item_union, default_cratename());
+
+        local_insta_assert_debug_snapshot!(actual.unwrap_err());
+    }
 }
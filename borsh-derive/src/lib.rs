@@ -74,7 +74,7 @@ Attribute takes literal string value, which is the [Path](syn::Path) to `borsh`
 Attribute is optional.
 
 1. If the attribute is not provided, [crate_name](proc_macro_crate::crate_name) is used to find a version of `borsh`
-in `[dependencies]` of the relevant `Cargo.toml`. If there is no match, a compilation error, similar to the following, is raised:
+   in `[dependencies]` of the relevant `Cargo.toml`. If there is no match, a compilation error, similar to the following, is raised:
 
 ```bash
  1  error: proc-macro derive panicked
@@ -176,6 +176,8 @@ enum X {
 }
 ```
 
+unless the tag is made wider with `#[borsh(tag_width = ...)]`.
+
 ### 3. `#[borsh(skip)]` (field level attribute)
 
 `#[borsh(skip)]` makes derive skip serializing annotated field.
@@ -286,6 +288,149 @@ struct B<K, V> {
 
 `#[borsh(serialize_with = ...)]` is not allowed to be used simultaneously with `#[borsh(skip)]`.
 
+### 6. `#[borsh(unsafe_union_bytes)]` or `#[borsh(unsafe_union_discriminant = ...)]` (item level attribute)
+
+These attributes are only applicable to unions, and one of them is required to derive for a union.
+
+`#[borsh(unsafe_union_bytes)]` serializes the union as its raw `size_of::<Self>()` bytes. The union must be `Copy`,
+and all of its bytes must be initialized for every value, e.g. a `#[repr(C)]` union without padding.
+Derive can't check it, so the `unsafe_` prefix marks the attribute as an explicit opt-in into this contract,
+broken by e.g. a field with padding bytes.
+Field level attributes are not allowed on such unions.
+
+```ignore
+#[derive(BorshSerialize, Clone, Copy)]
+#[borsh(unsafe_union_bytes)]
+#[repr(C)]
+union A {
+    x: u32,
+    y: [u8; 4],
+}
+```
+
+`#[borsh(unsafe_union_discriminant = ...)]` takes literal string value, which is a syn's [Path](syn::Path)
+of a `fn(&Self) -> u8` function, returning index of the active field.
+The index is serialized as `u8`, followed by the active field.
+`#[borsh(skip)]` is not allowed on fields of such unions.
+
+###### safety
+
+The derived impl reads the field at the returned index, so the function must only return
+the index of a field, which is initialized in the value. Returning the index of any other field
+is undefined behavior. Derive can't check it, so, as with `unsafe_union_bytes`,
+the `unsafe_` prefix marks the attribute as an explicit opt-in into this contract.
+
+```ignore
+fn active_field(value: &B) -> u8 {
+    unsafe { (value.tag & 1) as u8 }
+}
+
+#[derive(BorshSerialize)]
+#[borsh(unsafe_union_discriminant = "active_field")]
+union B {
+    tag: u64,
+    bytes: [u8; 8],
+}
+```
+
+### 7. `#[borsh(untagged)]` (item level attribute)
+
+This attribute is only applicable to enums.
+
+`#[borsh(untagged)]` makes derive serialize only the fields of the variant, without the tag,
+e.g. for protocols, where the tag lives in a header elsewhere.
+
+```ignore
+#[derive(BorshSerialize)]
+#[borsh(untagged)]
+enum Payload {
+    Ping,
+    Transfer { to: u64, amount: u64 },
+}
+```
+
+### 8. `#[borsh(tag_width = ...)]` (item level attribute)
+
+This attribute is only applicable to enums, and can't be used together with `#[borsh(untagged)]`.
+
+`#[borsh(tag_width = ...)]` sets width of the tag of an enum in bytes: 1 (default), 2, 4 or 8.
+The tag is serialized as little-endian unsigned integer of that width, which allows for more than 256 variants,
+and, with `#[borsh(use_discriminant = true)]`, for discriminant values beyond `u8` range.
+
+```ignore
+#[derive(BorshSerialize)]
+#[borsh(tag_width = 2, use_discriminant = true)]
+#[repr(u16)]
+enum Opcode {
+    Nop = 0,
+    Push(u64) = 0x100,
+    Call { target: u32 },
+}
+```
+
+### 9. `#[borsh(varint)]` (field level attribute)
+
+`#[borsh(varint)]` serializes an `u32`, `u64` or `i64` field as LEB128 variable-length integer
+(zigzag-encoded for `i64`) with `borsh::varint::serialize`, so that small values take a single byte.
+It can't be used together with `#[borsh(skip)]`, `#[borsh(serialize_with = ...)]`,
+`#[borsh(deserialize_with = ...)]` or `#[borsh(discriminant_from = ...)]`.
+
+```ignore
+#[derive(BorshSerialize)]
+struct Counters {
+    #[borsh(varint)]
+    hits: u64,
+}
+```
+
+### 10. `#[borsh(length_width = ...)]` or `#[borsh(length = ...)]` (field level attribute)
+
+`#[borsh(length_width = ...)]` serializes length prefix of a collection field (`Vec`, `String`, maps, sets, etc.)
+as little-endian unsigned integer of 1, 2, 4 or 8 bytes instead of the standard `u32`, with `borsh::length_prefix::serialize`.
+`#[borsh(length = "u8")]` is the same as `#[borsh(length_width = 1)]`; `"u16"`, `"u32"` and `"u64"` are accepted too.
+Serialization fails, if length of the collection doesn't fit into the prefix.
+
+```ignore
+#[derive(BorshSerialize)]
+struct Packet {
+    #[borsh(length_width = 1)]
+    name: String,
+    #[borsh(length = "u16")]
+    payload: Vec<u8>,
+}
+```
+
+### 11. `#[borsh(version = ...)]` (item level attribute)
+
+This attribute is only applicable to structs.
+
+`#[borsh(version = ...)]` writes the specified version as `u8` tag before the fields of a struct.
+Versions, listed in `#[borsh(migrate_from(...))]`, only affect deserialization.
+
+```ignore
+#[derive(BorshSerialize)]
+#[borsh(version = 2)]
+struct Account {
+    balance: u64,
+    owner: String,
+}
+```
+
+### 12. `#[borsh(serde)]` (field level attribute)
+
+`#[borsh(serde)]` serializes a field with its `serde::Serialize` impl in borsh format,
+with `borsh::serde::serialize`, which requires **serde** feature of `borsh`.
+Output matches that of `BorshSerialize` of an equivalent type.
+Bounds of generic parameters of such fields have to be specified with `#[borsh(bound(serialize = ...))]`.
+
+```ignore
+#[derive(BorshSerialize)]
+struct Order {
+    #[borsh(serde)]
+    price: rust_decimal::Decimal,
+    amount: u64,
+}
+```
 
 */
 #[proc_macro_derive(BorshSerialize, attributes(borsh))]
@@ -361,7 +506,7 @@ Attribute takes literal string value, which is the [Path](syn::Path) to `borsh`
 Attribute is optional.
 
 1. If the attribute is not provided, [crate_name](proc_macro_crate::crate_name) is used to find a version of `borsh`
-in `[dependencies]` of the relevant `Cargo.toml`. If there is no match, a compilation error, similar to the following, is raised:
+   in `[dependencies]` of the relevant `Cargo.toml`. If there is no match, a compilation error, similar to the following, is raised:
 
 ```bash
  1  error: proc-macro derive panicked
@@ -497,6 +642,8 @@ enum X {
 }
 ```
 
+unless the tag is made wider with `#[borsh(tag_width = ...)]`.
+
 
 ### 4. `#[borsh(skip)]` (field level attribute)
 
@@ -622,10 +769,254 @@ struct B<K: Hash + Eq, V> {
 }
 ```
 
+###### interaction with limits
+
+Functions are called with the reader only, so limits of `borsh::from_slice_with_options`
+aren't applied inside of them: a function, which deserializes untrusted input,
+has to bound its own allocations. Fields with `#[borsh(varint)]` don't take limits either,
+as they never allocate.
+
 ###### interaction with `#[borsh(skip)]`
 
 `#[borsh(deserialize_with = ...)]` is not allowed to be used simultaneously with `#[borsh(skip)]`.
 
+### 7. `#[borsh(unsafe_union_bytes)]` or `#[borsh(unsafe_union_discriminant = ...)]` (item level attribute)
+
+These attributes are only applicable to unions, and one of them is required to derive for a union.
+
+`#[borsh(unsafe_union_bytes)]` deserializes the union from its raw `size_of::<Self>()` bytes.
+The union must be `Copy`, and field level attributes are not allowed on it.
+By using the attribute, author of the union asserts, that any `size_of::<Self>()` bytes are a valid value of it,
+which doesn't hold e.g. for unions with `bool`, `char` or reference fields.
+
+```ignore
+#[derive(BorshDeserialize, Clone, Copy)]
+#[borsh(unsafe_union_bytes)]
+#[repr(C)]
+union A {
+    x: u32,
+    y: [u8; 4],
+}
+```
+
+`#[borsh(unsafe_union_discriminant = ...)]` reads `u8` index of the active field, followed by the field.
+The function, which is the value of the attribute, is only used by `BorshSerialize`,
+but has to be specified for both derives.
+`#[borsh(skip)]` is not allowed on fields of such unions.
+
+```ignore
+#[derive(BorshDeserialize)]
+#[borsh(unsafe_union_discriminant = "active_field")]
+union B {
+    tag: u64,
+    bytes: [u8; 8],
+}
+```
+
+### 8. `#[borsh(untagged)]` (item level attribute)
+
+This attribute is only applicable to enums.
+
+Untagged enums are serialized without the tag, so they can only be deserialized with the tag,
+known from elsewhere: with `borsh::de::EnumExt::deserialize_variant`, or as a field
+with `#[borsh(discriminant_from = ...)]`.
+Thus derive only implements `borsh::de::EnumExt` for such enums and not `borsh::de::BorshDeserialize`,
+so e.g. `borsh::from_slice::<Payload>` or a `Vec<Payload>` field don't compile.
+
+```ignore
+#[derive(BorshDeserialize)]
+#[borsh(untagged)]
+enum Payload {
+    Ping,
+    Transfer { to: u64, amount: u64 },
+}
+```
+
+### 9. `#[borsh(discriminant_from = ...)]` (field level attribute)
+
+###### syntax
+
+Attribute takes literal string value, which is the name of one of preceding fields of the struct.
+
+###### usage
+
+The annotated field, which has to be of untagged enum type, is deserialized with `borsh::de::EnumExt`,
+using value of the named field, converted with `u8::try_from`, as the tag.
+It's only supported on fields of structs with named fields.
+
+```ignore
+#[derive(BorshDeserialize)]
+struct Message {
+    kind: u8,
+    len: u32,
+    #[borsh(discriminant_from = "kind")]
+    payload: Payload,
+}
+```
+
+###### interaction with `#[borsh(skip)]` and `#[borsh(deserialize_with = ...)]`
+
+`#[borsh(discriminant_from = ...)]` is not allowed to be used simultaneously with `#[borsh(skip)]`
+or `#[borsh(deserialize_with = ...)]`.
+
+### 10. `#[borsh(tag_width = ...)]` (item level attribute)
+
+This attribute is only applicable to enums, and can't be used together with `#[borsh(untagged)]`.
+
+`#[borsh(tag_width = ...)]` sets width of the tag of an enum in bytes: 1 (default), 2, 4 or 8.
+The tag is deserialized as little-endian unsigned integer of that width, which allows for more than 256 variants,
+and, with `#[borsh(use_discriminant = true)]`, for discriminant values beyond `u8` range.
+
+```ignore
+#[derive(BorshDeserialize)]
+#[borsh(tag_width = 2, use_discriminant = true)]
+#[repr(u16)]
+enum Opcode {
+    Nop = 0,
+    Push(u64) = 0x100,
+    Call { target: u32 },
+}
+```
+
+`borsh::de::EnumExt` takes `u8` tag, which is widened to the tag of the enum, so only variants
+with discriminants up to `u8::MAX` can be deserialized with it, e.g. with `#[borsh(discriminant_from = ...)]`.
+
+### 11. `#[borsh(varint)]` (field level attribute)
+
+`#[borsh(varint)]` deserializes an `u32`, `u64` or `i64` field from LEB128 variable-length integer
+(zigzag-encoded for `i64`) with `borsh::varint::deserialize`. Only the shortest encoding of a value is accepted.
+It can't be used together with `#[borsh(skip)]`, `#[borsh(serialize_with = ...)]`,
+`#[borsh(deserialize_with = ...)]` or `#[borsh(discriminant_from = ...)]`.
+
+```ignore
+#[derive(BorshDeserialize)]
+struct Counters {
+    #[borsh(varint)]
+    hits: u64,
+}
+```
+
+### 12. `#[borsh(length_width = ...)]` or `#[borsh(length = ...)]` (field level attribute)
+
+`#[borsh(length_width = ...)]` deserializes length prefix of a collection field (`Vec`, `String`, maps, sets, etc.)
+as little-endian unsigned integer of 1, 2, 4 or 8 bytes instead of the standard `u32`,
+with `borsh::length_prefix::deserialize_with_limits`.
+`#[borsh(length = "u8")]` is the same as `#[borsh(length_width = 1)]`; `"u16"`, `"u32"` and `"u64"` are accepted too.
+
+```ignore
+#[derive(BorshDeserialize)]
+struct Packet {
+    #[borsh(length_width = 1)]
+    name: String,
+    #[borsh(length = "u16")]
+    payload: Vec<u8>,
+}
+```
+
+### 13. `#[borsh(default)]` or `#[borsh(default = ...)]` (field level attribute)
+
+If the input ends right before a field with `#[borsh(default)]`, the field is set to `Default::default()`
+instead of failing with "Unexpected length of input", so that fields can be appended to stored structs.
+`#[borsh(default = "path")]` sets it to the result of `path()`, which doesn't require the type to implement `Default`.
+Such fields can only be followed by other `#[borsh(default)]` or `#[borsh(skip)]` fields.
+It can't be used together with `#[borsh(skip)]` or `#[borsh(discriminant_from = ...)]`.
+
+```ignore
+#[derive(BorshDeserialize)]
+struct Account {
+    balance: u64,
+    #[borsh(default)]
+    nonce: u32,
+    #[borsh(default = "default_limit")]
+    limit: u64,
+}
+
+fn default_limit() -> u64 {
+    1000
+}
+```
+
+### 14. `#[borsh(version = ...)]` and `#[borsh(migrate_from(...))]` (item level attributes)
+
+These attributes are only applicable to structs.
+
+`#[borsh(version = ...)]` reads the `u8` version tag, written by [`BorshSerialize`](macro@crate::BorshSerialize),
+before the fields of a struct. `#[borsh(migrate_from(<version> = <type>, ...))]` lists older versions,
+whose payloads are deserialized as the specified types, and then converted to the struct with `From`.
+Versions in `migrate_from` have to be lower than the current one; any other tag fails with "Unsupported version".
+`#[borsh(init = ...)]` is called on migrated values too.
+
+```ignore
+#[derive(BorshDeserialize)]
+struct AccountV1 {
+    balance: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+#[borsh(version = 2, migrate_from(1 = AccountV1))]
+struct Account {
+    balance: u64,
+    owner: String,
+}
+
+impl From<AccountV1> for Account {
+    fn from(old: AccountV1) -> Self {
+        Self { balance: old.balance, owner: String::new() }
+    }
+}
+```
+
+### 15. `#[borsh(validate = ...)]` (item level and field level attribute)
+
+`#[borsh(validate = "path")]` calls `path(&value)` with the deserialized field or item, returning `Result<(), E>`,
+where `E: Display`. An error fails deserialization with `ErrorKind::InvalidData` and the message of `E`,
+attributed to the field or item in `borsh::error::DeserializeError` path.
+The item is validated before `#[borsh(init = ...)]` method is called on it.
+It can't be used together with `#[borsh(skip)]`.
+
+```ignore
+#[derive(BorshDeserialize)]
+#[borsh(validate = "Range::check")]
+struct Range {
+    #[borsh(validate = "non_empty")]
+    name: String,
+    start: u64,
+    end: u64,
+}
+
+impl Range {
+    fn check(&self) -> Result<(), &'static str> {
+        if self.start > self.end {
+            return Err("start is after end");
+        }
+        Ok(())
+    }
+}
+
+fn non_empty(value: &str) -> Result<(), &'static str> {
+    if value.is_empty() {
+        return Err("must not be empty");
+    }
+    Ok(())
+}
+```
+
+### 16. `#[borsh(serde)]` (field level attribute)
+
+`#[borsh(serde)]` deserializes a field with its `serde::Deserialize` impl from borsh format,
+with `borsh::serde::deserialize`, which requires **serde** feature of `borsh`.
+The field's type has to implement `serde::de::DeserializeOwned`.
+Bounds of generic parameters of such fields have to be specified with `#[borsh(bound(deserialize = ...))]`.
+
+```ignore
+#[derive(BorshDeserialize)]
+struct Order {
+    #[borsh(serde)]
+    price: rust_decimal::Decimal,
+    amount: u64,
+}
+```
+
 */
 #[proc_macro_derive(BorshDeserialize, attributes(borsh))]
 pub fn borsh_deserialize(input: TokenStream) -> TokenStream {
@@ -652,6 +1043,196 @@ pub fn borsh_deserialize(input: TokenStream) -> TokenStream {
     })
 }
 
+/**
+# derive proc-macro for `borsh::de::BorshDeserializeBorrowed` trait
+
+Derived impl deserializes the item from a `&'de [u8]` buffer, allowing fields
+to borrow from it, e.g. `&'a str`, `&'a [u8]`, `Cow<'a, str>` or `Cow<'a, [u8]>`.
+A `'de` lifetime param, which outlives all lifetime params of the item, is added to the impl,
+unless the item already declares a lifetime param named `'de`.
+
+```ignore
+/// impl<'de: 'a, 'a, U> borsh::de::BorshDeserializeBorrowed<'de> for A<'a, U>
+/// where
+///     U: borsh::de::BorshDeserializeBorrowed<'de>,
+#[derive(BorshDeserializeBorrowed)]
+struct A<'a, U> {
+    x: &'a str,
+    y: U,
+}
+```
+
+## Attributes
+
+Same attributes as for [`BorshDeserialize`](macro@crate::BorshDeserialize) are supported:
+`#[borsh(crate = ...)]`, `#[borsh(use_discriminant = ...)]`, `#[borsh(init = ...)]`,
+`#[borsh(skip)]`, `#[borsh(bound(deserialize = ...))]`, `#[borsh(deserialize_with = ...)]`,
+`#[borsh(varint)]`, `#[borsh(length_width = ...)]`, `#[borsh(default)]`, `#[borsh(validate = ...)]`
+and `#[borsh(serde)]`.
+
+Functions, specified with `#[borsh(deserialize_with = ...)]`, are called with
+`&mut &'de [u8]` as reader, and thus can only produce owned values. As with
+`BorshDeserialize`, they are not passed the limits of `borsh::from_slice_borrowed_with_options`.
+
+Unions, enums with `#[borsh(untagged)]`, structs with `#[borsh(version = ...)]`
+and fields with `#[borsh(discriminant_from = ...)]` are not supported.
+*/
+#[proc_macro_derive(BorshDeserializeBorrowed, attributes(borsh))]
+pub fn borsh_deserialize_borrowed(input: TokenStream) -> TokenStream {
+    let cratename = match check_attrs_get_cratename(&input) {
+        Ok(cratename) => cratename,
+        Err(err) => {
+            return err.to_compile_error().into();
+        }
+    };
+
+    let res = if let Ok(input) = syn::parse::<ItemStruct>(input.clone()) {
+        deserialize::borrowed::structs::process(&input, cratename)
+    } else if let Ok(input) = syn::parse::<ItemEnum>(input.clone()) {
+        deserialize::borrowed::enums::process(&input, cratename)
+    } else if syn::parse::<ItemUnion>(input).is_ok() {
+        Err(syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "Borsh borrowed deserialization does not support unions.",
+        ))
+    } else {
+        // Derive macros can only be defined on structs, enums, and unions.
+        unreachable!()
+    };
+    TokenStream::from(match res {
+        Ok(res) => res,
+        Err(err) => err.to_compile_error(),
+    })
+}
+
+/**
+# derive proc-macro for `borsh::ser::BorshSerializeAsync` trait
+
+Derived impl writes the same bytes as [`BorshSerialize`](macro@crate::BorshSerialize) one,
+to a `borsh::async_io::AsyncWrite` writer.
+
+## Bounds
+
+Generally, `BorshSerializeAsync` adds `borsh::ser::BorshSerializeAsync` bound to any type parameter
+found in item's fields.
+
+```ignore
+/// impl<U, V> borsh::ser::BorshSerializeAsync for A<U, V>
+/// where
+///     U: borsh::ser::BorshSerializeAsync,
+///     V: borsh::ser::BorshSerializeAsync,
+#[derive(BorshSerializeAsync)]
+struct A<U, V> {
+    x: U,
+    y: V,
+}
+```
+
+## Attributes
+
+Same attributes as for [`BorshSerialize`](macro@crate::BorshSerialize) are supported:
+`#[borsh(crate = ...)]`, `#[borsh(use_discriminant = ...)]`, `#[borsh(skip)]`
+and `#[borsh(bound(serialize = ...))]`.
+
+`#[borsh(serialize_with = ...)]`, `#[borsh(varint)]`, `#[borsh(length_width = ...)]`
+and `#[borsh(serde)]` are rejected,
+as their functions take a blocking writer.
+
+Unions and structs with `#[borsh(version = ...)]` are not supported.
+*/
+#[proc_macro_derive(BorshSerializeAsync, attributes(borsh))]
+pub fn borsh_serialize_async(input: TokenStream) -> TokenStream {
+    let cratename = match check_attrs_get_cratename(&input) {
+        Ok(cratename) => cratename,
+        Err(err) => {
+            return err.to_compile_error().into();
+        }
+    };
+
+    let res = if let Ok(input) = syn::parse::<ItemStruct>(input.clone()) {
+        serialize::asynchronous::structs::process(&input, cratename)
+    } else if let Ok(input) = syn::parse::<ItemEnum>(input.clone()) {
+        serialize::asynchronous::enums::process(&input, cratename)
+    } else if syn::parse::<ItemUnion>(input).is_ok() {
+        Err(syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "Borsh async serialization does not support unions.",
+        ))
+    } else {
+        // Derive macros can only be defined on structs, enums, and unions.
+        unreachable!()
+    };
+    TokenStream::from(match res {
+        Ok(res) => res,
+        Err(err) => err.to_compile_error(),
+    })
+}
+
+/**
+# derive proc-macro for `borsh::de::BorshDeserializeAsync` trait
+
+Derived impl reads the same bytes as [`BorshDeserialize`](macro@crate::BorshDeserialize) one,
+from a `borsh::async_io::AsyncRead` reader.
+
+## Bounds
+
+Generally, `BorshDeserializeAsync` adds `borsh::de::BorshDeserializeAsync` bound to any type parameter
+found in item's fields and `core::default::Default` bound to any type parameter found
+in item's skipped fields.
+
+```ignore
+/// impl<U, V> borsh::de::BorshDeserializeAsync for A<U, V>
+/// where
+///     U: borsh::de::BorshDeserializeAsync,
+///     V: core::default::Default,
+#[derive(BorshDeserializeAsync)]
+struct A<U, V> {
+    x: U,
+    #[borsh(skip)]
+    y: V,
+}
+```
+
+## Attributes
+
+Same attributes as for [`BorshDeserialize`](macro@crate::BorshDeserialize) are supported:
+`#[borsh(crate = ...)]`, `#[borsh(use_discriminant = ...)]`, `#[borsh(init = ...)]`,
+`#[borsh(skip)]`, `#[borsh(bound(deserialize = ...))]` and `#[borsh(validate = ...)]`.
+
+`#[borsh(deserialize_with = ...)]`, `#[borsh(varint)]`, `#[borsh(length_width = ...)]`,
+`#[borsh(default)]` and `#[borsh(serde)]` are rejected, as their functions take a blocking reader.
+
+Unions, enums with `#[borsh(untagged)]`, structs with `#[borsh(version = ...)]`
+and fields with `#[borsh(discriminant_from = ...)]` are not supported.
+*/
+#[proc_macro_derive(BorshDeserializeAsync, attributes(borsh))]
+pub fn borsh_deserialize_async(input: TokenStream) -> TokenStream {
+    let cratename = match check_attrs_get_cratename(&input) {
+        Ok(cratename) => cratename,
+        Err(err) => {
+            return err.to_compile_error().into();
+        }
+    };
+
+    let res = if let Ok(input) = syn::parse::<ItemStruct>(input.clone()) {
+        deserialize::asynchronous::structs::process(&input, cratename)
+    } else if let Ok(input) = syn::parse::<ItemEnum>(input.clone()) {
+        deserialize::asynchronous::enums::process(&input, cratename)
+    } else if syn::parse::<ItemUnion>(input).is_ok() {
+        Err(syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "Borsh async deserialization does not support unions.",
+        ))
+    } else {
+        // Derive macros can only be defined on structs, enums, and unions.
+        unreachable!()
+    };
+    TokenStream::from(match res {
+        Ok(res) => res,
+        Err(err) => err.to_compile_error(),
+    })
+}
+
 /**
 # derive proc-macro for `borsh::BorshSchema` trait
 
@@ -697,7 +1278,7 @@ Attribute takes literal string value, which is the [Path](syn::Path) to `borsh`
 Attribute is optional.
 
 1. If the attribute is not provided, [crate_name](proc_macro_crate::crate_name) is used to find a version of `borsh`
-in `[dependencies]` of the relevant `Cargo.toml`. If there is no match, a compilation error, similar to the following, is raised:
+   in `[dependencies]` of the relevant `Cargo.toml`. If there is no match, a compilation error, similar to the following, is raised:
 
 ```bash
  1  error: proc-macro derive panicked
@@ -799,6 +1380,8 @@ enum X {
 }
 ```
 
+unless the tag is made wider with `#[borsh(tag_width = ...)]`.
+
 ### 3. `#[borsh(skip)]` (field level attribute)
 
 `#[borsh(skip)]` makes derive skip including schema from annotated field into schema's implementation.
@@ -824,7 +1407,7 @@ Attribute takes literal string value, which is a comma-separated list of `Parame
 It may be used in order to:
 
 1. fix complex cases, when derive hasn't figured out the right bounds on type parameters and
-declaration parameters automatically.
+   declaration parameters automatically.
 2. remove parameters, which do not take part in serialization/deserialization, from bounded ones and from declaration parameters.
 
 `ParameterOverride` describes an entry like `order_param => override_type`,
@@ -953,6 +1536,114 @@ struct B<K, V> {
 
 `#[borsh(schema(with_funcs(declaration = ..., definitions = ...)))]` is not allowed to be used simultaneously with `#[borsh(skip)]`.
 
+### 6. `#[borsh(untagged)]` (item level attribute)
+
+This attribute is only applicable to enums.
+
+Definition of an enum with `#[borsh(untagged)]` has `tag_width: 0`.
+
+### 7. `#[borsh(tag_width = ...)]` (item level attribute)
+
+This attribute is only applicable to enums.
+
+Definition of an enum with `#[borsh(tag_width = ...)]` has the specified `tag_width`.
+
+### 8. `#[borsh(varint)]` (field level attribute)
+
+Field with `#[borsh(varint)]` is declared as its `borsh::varint` wrapper type,
+e.g. `VarU64` for `u64`, which is defined as `Sequence { length_width: 0, length_range: 1..=10, elements: "u8" }`.
+
+### 9. `#[borsh(length_width = ...)]` or `#[borsh(length = ...)]` (field level attribute)
+
+Field with `#[borsh(length_width = ...)]` is declared as `borsh::length_prefix::LengthPrefixed<L, T>`,
+e.g. `LengthPrefixed<u8, String>`, which is defined as the sequence of `T` with the specified `length_width`
+and `length_range`, capped at maximum value of the prefix.
+
+### 10. `#[borsh(default)]` or `#[borsh(default = ...)]` (field level attribute)
+
+Field with `#[borsh(default)]`, which may be missing at the end of the input, is declared as
+`borsh::trailing::Trailing<T>`, which is defined as `Definition::Trailing` of `T`,
+so that `check_compatibility` doesn't consider appending such fields a breaking change.
+
+### 11. `#[borsh(version = ...)]` and `#[borsh(migrate_from(...))]` (item level attributes)
+
+These attributes are only applicable to structs.
+
+Definition of a struct with `#[borsh(version = ...)]` is an enum with `tag_width: 1` and a variant per version,
+named `"V<version>"`. Variants of older versions are declared as their types from `migrate_from(...)`,
+and the variant of the current version as a struct with the fields, declared as `"<Name>V<version>"`,
+e.g. `"AccountV2"`.
+
+### 12. `#[borsh(flatten)]` (field level attribute)
+
+Fields of a struct or an enum variant with named fields may be flattened: named fields of the field's
+type are inlined into the `Fields::NamedFields` of the parent in place of the field itself.
+The byte encoding isn't affected, as nested structs are serialized as concatenation of their fields anyway.
+
+```ignore
+use borsh::BorshSchema;
+
+#[derive(BorshSchema)]
+struct Header {
+    id: u32,
+    flags: u8,
+}
+
+#[derive(BorshSchema)]
+struct Message {
+    kind: u8,
+    #[borsh(flatten)]
+    header: Header,
+    body: Vec<u8>,
+}
+```
+
+Definition of `Message` has fields `kind`, `id`, `flags` and `body`.
+Definition of `Header` isn't added to the container, unless it's referenced elsewhere.
+
+Type of the flattened field should be a struct with named fields (or without fields): tuples, arrays
+and references are rejected by the derive, while other types are kept as regular fields, which doesn't
+change the encoding. Names of inlined fields, which clash with other fields of the parent, are kept as is,
+and are reported by `BorshSchemaContainer::validate`.
+
+`flatten` cannot be combined with `skip`, `varint`, `length_width`, `default` or `schema(with_funcs(...))`.
+
+### 13. `#[borsh(transparent)]` or `#[borsh(transparent = ...)]` (item level attribute)
+
+This attribute is only applicable to structs with exactly one field, which isn't skipped,
+and cannot be combined with `version`.
+
+Such a struct is serialized exactly as its field, and with `#[borsh(transparent)]` its schema
+reuses the declaration of the field, so that no separate definition is added for the struct:
+
+```ignore
+use borsh::BorshSchema;
+
+#[derive(BorshSchema)]
+#[borsh(transparent)]
+struct AccountId(String);
+
+assert_eq!(AccountId::declaration(), String::declaration());
+```
+
+With `#[borsh(transparent = "Alias")]` the struct is declared as `"Alias"` (with parameters, if it has any),
+which is defined as `Definition::Alias` of the declaration of the field.
+
+```ignore
+use borsh::BorshSchema;
+
+#[derive(BorshSchema)]
+#[borsh(transparent = "Balance")]
+struct Balance {
+    amount: u128,
+}
+```
+
+Definition of `"Balance"` is `Definition::Alias("u128".to_string())`.
+
+The type of the field must not refer to the struct itself, as there's no separate definition
+to terminate the recursion on.
+
 */
 #[cfg(feature = "schema")]
 #[proc_macro_derive(BorshSchema, attributes(borsh))]
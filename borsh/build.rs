@@ -1,7 +1,10 @@
 use cfg_aliases::cfg_aliases;
 
 fn main() {
+    println!("cargo:rustc-check-cfg=cfg(hash_collections)");
+    println!("cargo:rustc-check-cfg=cfg(async_io)");
     cfg_aliases! {
         hash_collections: { any(feature = "hashbrown", feature = "std") },
+        async_io: { any(feature = "tokio", feature = "futures-io") },
     }
 }
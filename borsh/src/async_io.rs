@@ -0,0 +1,161 @@
+//! Asynchronous counterparts of [`io::Read`](crate::io::Read) and [`io::Write`](crate::io::Write),
+//! used by [`BorshSerializeAsync`](crate::BorshSerializeAsync) and
+//! [`BorshDeserializeAsync`](crate::BorshDeserializeAsync).
+//!
+//! With **tokio** feature, [`AsyncRead`](crate::async_io::AsyncRead) and
+//! [`AsyncWrite`](crate::async_io::AsyncWrite) are implemented for all
+//! [`tokio::io::AsyncRead`] and [`tokio::io::AsyncWrite`] types respectively.
+//! With **futures-io** feature, [`futures_io::AsyncRead`] and [`futures_io::AsyncWrite`] types
+//! can be used by wrapping them into [`FuturesIo`](crate::async_io::FuturesIo).
+
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{ready, Context, Poll};
+
+use crate::__private::maybestd::boxed::Box;
+use crate::io::{Error, ErrorKind, Result};
+
+/// Boxed future, returned by methods of async borsh traits.
+///
+/// Futures are boxed, as async traits can't return `impl Future` on the supported
+/// Rust versions, and so that recursive types can be (de)serialized.
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Source of bytes for [`BorshDeserializeAsync`](crate::BorshDeserializeAsync).
+pub trait AsyncRead: Unpin + Send {
+    /// Attempts to read bytes into `buf`, returning the number of bytes read.
+    ///
+    /// `Ok(0)` means end of input, unless `buf` is empty.
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8])
+        -> Poll<Result<usize>>;
+
+    /// Reads exactly as many bytes as needed to fill `buf`.
+    ///
+    /// Fails with [`ErrorKind::UnexpectedEof`], if input ends before that.
+    fn read_exact<'a>(&'a mut self, buf: &'a mut [u8]) -> ReadExact<'a, Self> {
+        ReadExact {
+            reader: self,
+            buf,
+            filled: 0,
+        }
+    }
+}
+
+/// Sink of bytes for [`BorshSerializeAsync`](crate::BorshSerializeAsync).
+pub trait AsyncWrite: Unpin + Send {
+    /// Attempts to write bytes from `buf`, returning the number of bytes written.
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize>>;
+
+    /// Writes the whole `buf`.
+    fn write_all<'a>(&'a mut self, buf: &'a [u8]) -> WriteAll<'a, Self> {
+        WriteAll { writer: self, buf }
+    }
+}
+
+/// Future, returned by [`AsyncRead::read_exact`].
+#[must_use = "futures do nothing unless polled"]
+pub struct ReadExact<'a, R: ?Sized> {
+    reader: &'a mut R,
+    buf: &'a mut [u8],
+    filled: usize,
+}
+
+impl<R: AsyncRead + ?Sized> Future for ReadExact<'_, R> {
+    type Output = Result<()>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let this = self.get_mut();
+        while this.filled < this.buf.len() {
+            match ready!(Pin::new(&mut *this.reader).poll_read(cx, &mut this.buf[this.filled..])) {
+                Ok(0) => return Poll::Ready(Err(ErrorKind::UnexpectedEof.into())),
+                Ok(read) => this.filled += read,
+                Err(err) if err.kind() == ErrorKind::Interrupted => {}
+                Err(err) => return Poll::Ready(Err(err)),
+            }
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// Future, returned by [`AsyncWrite::write_all`].
+#[must_use = "futures do nothing unless polled"]
+pub struct WriteAll<'a, W: ?Sized> {
+    writer: &'a mut W,
+    buf: &'a [u8],
+}
+
+impl<W: AsyncWrite + ?Sized> Future for WriteAll<'_, W> {
+    type Output = Result<()>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let this = self.get_mut();
+        while !this.buf.is_empty() {
+            match ready!(Pin::new(&mut *this.writer).poll_write(cx, this.buf)) {
+                Ok(0) => {
+                    return Poll::Ready(Err(Error::new(
+                        ErrorKind::WriteZero,
+                        "failed to write whole buffer",
+                    )))
+                }
+                Ok(written) => this.buf = &this.buf[written..],
+                Err(err) if err.kind() == ErrorKind::Interrupted => {}
+                Err(err) => return Poll::Ready(Err(err)),
+            }
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<R: tokio::io::AsyncRead + Unpin + Send + ?Sized> AsyncRead for R {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<Result<usize>> {
+        let mut buf = tokio::io::ReadBuf::new(buf);
+        ready!(tokio::io::AsyncRead::poll_read(self, cx, &mut buf))?;
+        Poll::Ready(Ok(buf.filled().len()))
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<W: tokio::io::AsyncWrite + Unpin + Send + ?Sized> AsyncWrite for W {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize>> {
+        tokio::io::AsyncWrite::poll_write(self, cx, buf)
+    }
+}
+
+/// Adapter, which implements [`AsyncRead`] and [`AsyncWrite`] for
+/// [`futures_io::AsyncRead`] and [`futures_io::AsyncWrite`] types.
+///
+/// ```
+/// # futures::executor::block_on(async {
+/// use borsh::{async_io::FuturesIo, BorshDeserializeAsync};
+///
+/// let mut reader = FuturesIo(futures::io::Cursor::new(vec![1, 0, 0, 0, 42]));
+/// let value = <Vec<u8>>::deserialize_async(&mut reader).await.unwrap();
+/// assert_eq!(value, vec![42]);
+/// # });
+/// ```
+#[cfg(feature = "futures-io")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FuturesIo<T>(pub T);
+
+#[cfg(feature = "futures-io")]
+impl<R: futures_io::AsyncRead + Unpin + Send> AsyncRead for FuturesIo<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<Result<usize>> {
+        Pin::new(&mut self.get_mut().0).poll_read(cx, buf)
+    }
+}
+
+#[cfg(feature = "futures-io")]
+impl<W: futures_io::AsyncWrite + Unpin + Send> AsyncWrite for FuturesIo<W> {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize>> {
+        Pin::new(&mut self.get_mut().0).poll_write(cx, buf)
+    }
+}
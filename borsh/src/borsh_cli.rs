@@ -0,0 +1,352 @@
+//! Inspect borsh payloads with a `BorshSchemaContainer`, serialized into a file.
+//!
+//! Usage: `borsh-cli <command> <schema file> [<args>]`, where `-` for a file stands for stdin:
+//! * `decode <schema file> <payload file>` - prints the payload as JSON;
+//! * `encode <schema file> <json file> [<output file>]` - encodes JSON into borsh,
+//!   writes to stdout if no output file is given;
+//! * `hexdump <schema file> <payload file>` - prints the payload as an annotated hexdump;
+//! * `schema <schema file>` - prints the definitions as a tree, starting from the root type;
+//! * `validate <schema file>` - checks that the schema is valid;
+//! * `max-size <schema file>` - prints the maximum size of serialized values.
+
+use borsh::schema::{BorshSchemaContainer, Declaration, Definition, Fields};
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::process;
+
+const USAGE: &str = "\
+Usage: borsh-cli <command> <schema file> [<args>]
+
+Commands:
+    decode <schema file> <payload file>               print the payload as JSON
+    encode <schema file> <json file> [<output file>]  encode JSON into borsh
+    hexdump <schema file> <payload file>              print the payload as an annotated hexdump
+    schema <schema file>                              print the schema as a tree
+    validate <schema file>                            check that the schema is valid
+    max-size <schema file>                            print the maximum serialized size
+
+Use `-` in place of a file to read it from stdin.";
+
+fn main() {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    if let Err(err) = run(&args, &mut io::stdin(), &mut io::stdout()) {
+        eprintln!("{}", err);
+        process::exit(1);
+    }
+}
+
+/// Runs the command, given by `args`, which reads `-` files from `input`
+/// and prints its results into `output`.
+fn run(args: &[String], input: &mut dyn Read, output: &mut dyn Write) -> Result<(), String> {
+    let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+    let (command, schema_file, rest) = match args.as_slice() {
+        [command, schema_file, rest @ ..] => (*command, *schema_file, rest),
+        _ => return Err(USAGE.to_string()),
+    };
+    let schema: BorshSchemaContainer = borsh::from_slice(&read(schema_file, input)?)
+        .map_err(|err| format!("Failed to deserialize BorshSchemaContainer: {}", err))?;
+
+    match (command, rest) {
+        ("decode", [payload_file]) => {
+            let json = schema
+                .borsh_to_json(&read(payload_file, input)?)
+                .map_err(|err| format!("Failed to decode the payload: {}", err))?;
+            let json = serde_json::to_string_pretty(&json).expect("JSON values are serializable");
+            write_output(output, format!("{}\n", json).as_bytes())?;
+        }
+        ("encode", [json_file, output_file @ ..]) if output_file.len() <= 1 => {
+            let json = serde_json::from_slice(&read(json_file, input)?)
+                .map_err(|err| format!("Failed to parse JSON: {}", err))?;
+            let data = schema
+                .json_to_borsh(&json)
+                .map_err(|err| format!("Failed to encode JSON: {}", err))?;
+            match output_file.first() {
+                Some(output_file) => File::create(output_file)
+                    .and_then(|mut file| file.write_all(&data))
+                    .map_err(|err| format!("Failed to write `{}`: {}", output_file, err))?,
+                None => write_output(output, &data)?,
+            }
+        }
+        ("hexdump", [payload_file]) => {
+            let hexdump = schema.hexdump(&read(payload_file, input)?).to_string();
+            write_output(output, hexdump.as_bytes())?;
+        }
+        ("schema", []) => write_output(output, schema_tree(&schema).as_bytes())?,
+        ("validate", []) => {
+            schema
+                .validate()
+                .map_err(|err| format!("Invalid schema: {}", err))?;
+            write_output(output, b"ok\n")?;
+        }
+        ("max-size", []) => {
+            let size = schema
+                .max_serialized_size()
+                .map_err(|err| format!("Failed to compute the maximum size: {}", err))?;
+            write_output(output, format!("{}\n", size).as_bytes())?;
+        }
+        _ => return Err(USAGE.to_string()),
+    }
+    Ok(())
+}
+
+/// Reads file at `path`, or `input` if the path is `-`.
+fn read(path: &str, input: &mut dyn Read) -> Result<Vec<u8>, String> {
+    let mut data = Vec::new();
+    let result = if path == "-" {
+        input.read_to_end(&mut data).map(|_| ())
+    } else {
+        fs::read(path).map(|file| data = file)
+    };
+    result.map_err(|err| format!("Failed to read `{}`: {}", path, err))?;
+    Ok(data)
+}
+
+/// Writes `data` to `output`, which may be closed early, e.g. when stdout is piped into `head`.
+fn write_output(output: &mut dyn Write, data: &[u8]) -> Result<(), String> {
+    match output.write_all(data) {
+        Err(err) if err.kind() != io::ErrorKind::BrokenPipe => {
+            Err(format!("Failed to write the output: {}", err))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Definitions of the schema as a tree, starting from the root type.
+fn schema_tree(schema: &BorshSchemaContainer) -> String {
+    let mut tree = String::new();
+    let mut expanded = BTreeSet::new();
+    write_tree(
+        &mut tree,
+        schema,
+        "",
+        schema.declaration(),
+        0,
+        &mut expanded,
+    );
+    tree
+}
+
+/// Writes `declaration` with its definition on a line, followed by the types it consists of,
+/// indented, unless they were already written above.
+fn write_tree(
+    out: &mut String,
+    schema: &BorshSchemaContainer,
+    label: &str,
+    declaration: &str,
+    depth: usize,
+    expanded: &mut BTreeSet<Declaration>,
+) {
+    let indent = "  ".repeat(depth);
+    let definition = match schema.get_definition(declaration) {
+        Some(definition) => definition,
+        None => {
+            let _ = writeln!(
+                out,
+                "{}{}{}: missing definition",
+                indent, label, declaration
+            );
+            return;
+        }
+    };
+    let (summary, children) = describe(definition);
+    if !children.is_empty() && !expanded.insert(declaration.to_string()) {
+        let _ = writeln!(out, "{}{}{}: see above", indent, label, declaration);
+        return;
+    }
+    let _ = writeln!(out, "{}{}{}: {}", indent, label, declaration, summary);
+    for (label, child) in children {
+        let label = format!("{}: ", label);
+        write_tree(out, schema, &label, child, depth + 1, expanded);
+    }
+}
+
+/// Summary of a definition and labelled declarations it refers to.
+fn describe(definition: &Definition) -> (String, Vec<(String, &Declaration)>) {
+    match definition {
+        Definition::Primitive(1) => ("primitive, 1 byte".to_string(), vec![]),
+        Definition::Primitive(size) => (format!("primitive, {} bytes", size), vec![]),
+        Definition::Sequence {
+            length_width,
+            length_range,
+            elements,
+        } => {
+            let summary = if *length_width == Definition::ARRAY_LENGTH_WIDTH
+                && length_range.start() == length_range.end()
+            {
+                format!("sequence of {} elements", length_range.start())
+            } else if *length_width == Definition::ARRAY_LENGTH_WIDTH {
+                format!("sequence of {:?} elements, not prefixed", length_range)
+            } else {
+                format!(
+                    "sequence of {:?} elements, prefixed with {}-byte length",
+                    length_range, length_width
+                )
+            };
+            (summary, vec![("[]".to_string(), elements)])
+        }
+        Definition::Tuple { elements } => (
+            "tuple".to_string(),
+            elements
+                .iter()
+                .enumerate()
+                .map(|(index, element)| (index.to_string(), element))
+                .collect(),
+        ),
+        Definition::Enum {
+            tag_width,
+            variants,
+        } => {
+            let summary = if *tag_width == 0 {
+                "untagged enum".to_string()
+            } else {
+                format!("enum, {}-byte tag", tag_width)
+            };
+            let variants = variants
+                .iter()
+                .map(|(discriminant, name, variant)| {
+                    (format!("{} = {}", name, discriminant), variant)
+                })
+                .collect();
+            (summary, variants)
+        }
+        Definition::Struct { fields } => match fields {
+            Fields::NamedFields(fields) => (
+                "struct".to_string(),
+                fields
+                    .iter()
+                    .map(|(name, field)| (name.clone(), field))
+                    .collect(),
+            ),
+            Fields::UnnamedFields(fields) => (
+                "struct".to_string(),
+                fields
+                    .iter()
+                    .enumerate()
+                    .map(|(index, field)| (index.to_string(), field))
+                    .collect(),
+            ),
+            Fields::Empty => ("struct without fields".to_string(), vec![]),
+        },
+        Definition::Trailing(present) => (
+            "may be missing at the end of the input".to_string(),
+            vec![("value".to_string(), present)],
+        ),
+        Definition::Alias(aliased) => ("alias".to_string(), vec![("of".to_string(), aliased)]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use borsh::schema::BorshSchemaContainer;
+    use std::collections::BTreeMap;
+    use std::path::PathBuf;
+
+    /// Writes `data` into a temporary file, unique for the test `name`.
+    fn temp_file(name: &str, data: &[u8]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("borsh-cli-{}-{}", process::id(), name));
+        fs::write(&path, data).unwrap();
+        path
+    }
+
+    fn schema_file(name: &str, schema: &BorshSchemaContainer) -> String {
+        let path = temp_file(name, &borsh::to_vec(schema).unwrap());
+        path.to_str().unwrap().to_string()
+    }
+
+    /// Runs the command with `input` as stdin and returns what it printed.
+    fn run_with(args: &[&str], mut input: &[u8]) -> Result<Vec<u8>, String> {
+        let args = args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        let mut output = Vec::new();
+        run(&args, &mut input, &mut output)?;
+        Ok(output)
+    }
+
+    #[test]
+    fn test_decode_encode() {
+        let schema = BorshSchemaContainer::for_type::<(u16, Option<u32>)>();
+        let schema = schema_file("decode-encode", &schema);
+        let payload = borsh::to_vec(&(7u16, Some(9u32))).unwrap();
+
+        let json = run_with(&["decode", &schema, "-"], &payload).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&json).unwrap();
+        let expected = BorshSchemaContainer::for_type::<(u16, Option<u32>)>()
+            .borsh_to_json(&payload)
+            .unwrap();
+        assert_eq!(json, expected);
+
+        let encoded = run_with(&["encode", &schema, "-"], json.to_string().as_bytes()).unwrap();
+        assert_eq!(encoded, payload);
+
+        let output = std::env::temp_dir().join(format!("borsh-cli-{}-encoded", process::id()));
+        let output = output.to_str().unwrap();
+        let printed = run_with(
+            &["encode", &schema, "-", output],
+            json.to_string().as_bytes(),
+        );
+        assert_eq!(printed.unwrap(), b"");
+        assert_eq!(fs::read(output).unwrap(), payload);
+
+        let err = run_with(&["decode", &schema, "-"], &payload[..3]).unwrap_err();
+        assert!(err.starts_with("Failed to decode the payload: "), "{}", err);
+        assert!(!err.contains("Error"), "{}", err);
+    }
+
+    #[test]
+    fn test_schema_tree() {
+        let schema = BorshSchemaContainer::for_type::<(u16, Option<u32>)>();
+        let tree = schema_tree(&schema);
+        let schema = schema_file("tree", &schema);
+        assert_eq!(
+            run_with(&["schema", &schema], b"").unwrap(),
+            tree.as_bytes()
+        );
+        assert_eq!(
+            tree,
+            "\
+(u16, Option<u32>): tuple
+  0: u16: primitive, 2 bytes
+  1: Option<u32>: enum, 1-byte tag
+    None = 0: (): primitive, 0 bytes
+    Some = 1: u32: primitive, 4 bytes
+"
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        let schema = BorshSchemaContainer::for_type::<(u16, Option<u32>)>();
+        let schema = schema_file("validate-ok", &schema);
+        assert_eq!(run_with(&["validate", &schema], b"").unwrap(), b"ok\n");
+
+        let mut definitions = BTreeMap::new();
+        definitions.insert(
+            "Pair".to_string(),
+            Definition::Tuple {
+                elements: vec!["u8".to_string(), "Missing".to_string()],
+            },
+        );
+        let invalid = BorshSchemaContainer::new("Pair".to_string(), definitions);
+        let invalid = schema_file("validate-err", &invalid);
+        let err = run_with(&["validate", &invalid], b"").unwrap_err();
+        assert!(err.starts_with("Invalid schema: "), "{}", err);
+        assert!(!err.contains("MissingDefinition"), "{}", err);
+    }
+
+    #[test]
+    fn test_max_size() {
+        let schema = BorshSchemaContainer::for_type::<(u16, Option<u32>)>();
+        let schema = schema_file("max-size", &schema);
+        assert_eq!(run_with(&["max-size", &schema], b"").unwrap(), b"7\n");
+    }
+
+    #[test]
+    fn test_usage() {
+        assert_eq!(run_with(&[], b""), Err(USAGE.to_string()));
+        let schema = BorshSchemaContainer::for_type::<u8>();
+        let schema = schema_file("usage", &schema);
+        assert_eq!(run_with(&["unknown", &schema], b""), Err(USAGE.to_string()));
+    }
+}
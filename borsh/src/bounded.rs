@@ -0,0 +1,300 @@
+//! Collections with bounded length.
+//!
+//! [`BoundedVec<T, MIN, MAX>`], [`BoundedString<MAX>`] and [`BoundedBytes<MAX>`] are encoded
+//! exactly as `Vec<T>`, `String` and `Vec<u8>`, but deserialization rejects lengths out of
+//! the bounds before allocating anything, and their [`BorshSchema`](crate::BorshSchema) declares
+//! the bounds as `length_range` of the sequence, so that
+//! [`max_serialized_size`](crate::max_serialized_size) of types, built from them, is finite.
+//!
+//! ```
+//! use borsh::bounded::{BoundedBytes, BoundedVec};
+//! use core::convert::TryFrom;
+//!
+//! type Chunks = BoundedVec<BoundedBytes<64>, 1, 4>;
+//!
+//! let chunks = Chunks::try_from(vec![BoundedBytes::try_from(vec![1, 2]).unwrap()]).unwrap();
+//! let encoded = borsh::to_vec(&chunks).unwrap();
+//! assert_eq!(borsh::from_slice::<Chunks>(&encoded).unwrap(), chunks);
+//!
+//! let empty = borsh::to_vec(&Vec::<Vec<u8>>::new()).unwrap();
+//! assert!(borsh::from_slice::<Chunks>(&empty).is_err());
+//! ```
+
+use core::convert::TryFrom;
+use core::fmt;
+use core::ops::Deref;
+
+use crate::__private::maybestd::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use crate::de::{BorshDeserializeBorrowed, Limits};
+use crate::io::{Error, ErrorKind, Read, Result, Write};
+use crate::length_prefix::deserialize_with_len;
+use crate::{BorshDeserialize, BorshSerialize};
+
+/// Error of a collection length, which is out of bounds of a bounded collection.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct LengthOutOfBounds {
+    pub len: usize,
+    pub min: usize,
+    pub max: usize,
+}
+
+impl fmt::Display for LengthOutOfBounds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Length {} is out of bounds {}..={}",
+            self.len, self.min, self.max
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for LengthOutOfBounds {}
+
+fn check_len(len: usize, min: usize, max: usize) -> core::result::Result<(), LengthOutOfBounds> {
+    if (min..=max).contains(&len) {
+        Ok(())
+    } else {
+        Err(LengthOutOfBounds { len, min, max })
+    }
+}
+
+/// Reads `u32` length prefix, rejecting it, if it's out of `min..=max`.
+fn read_len<R: Read>(reader: &mut R, min: usize, max: usize) -> Result<u32> {
+    let len = u32::deserialize_reader(reader)?;
+    check_len(len as usize, min, max)
+        .map_err(|err| Error::new(ErrorKind::InvalidData, err.to_string()))?;
+    Ok(len)
+}
+
+/// `Vec<T>` with at least `MIN` and at most `MAX` elements.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct BoundedVec<T, const MIN: usize, const MAX: usize>(Vec<T>);
+
+impl<T, const MIN: usize, const MAX: usize> BoundedVec<T, MIN, MAX> {
+    pub fn as_slice(&self) -> &[T] {
+        &self.0
+    }
+
+    pub fn into_inner(self) -> Vec<T> {
+        self.0
+    }
+}
+
+impl<T, const MIN: usize, const MAX: usize> TryFrom<Vec<T>> for BoundedVec<T, MIN, MAX> {
+    type Error = LengthOutOfBounds;
+
+    fn try_from(vec: Vec<T>) -> core::result::Result<Self, Self::Error> {
+        check_len(vec.len(), MIN, MAX)?;
+        Ok(Self(vec))
+    }
+}
+
+impl<T, const MIN: usize, const MAX: usize> Deref for BoundedVec<T, MIN, MAX> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.0
+    }
+}
+
+impl<T: BorshSerialize, const MIN: usize, const MAX: usize> BorshSerialize
+    for BoundedVec<T, MIN, MAX>
+{
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        self.0.serialize(writer)
+    }
+}
+
+impl<T: BorshDeserialize, const MIN: usize, const MAX: usize> BorshDeserialize
+    for BoundedVec<T, MIN, MAX>
+{
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        Self::deserialize_reader_with_limits(reader, &mut Limits::unlimited())
+    }
+
+    fn deserialize_reader_with_limits<R: Read>(
+        reader: &mut R,
+        limits: &mut Limits,
+    ) -> Result<Self> {
+        let len = read_len(reader, MIN, MAX)?;
+        deserialize_with_len(len, reader, limits).map(Self)
+    }
+}
+
+/// `String` of at most `MAX` bytes.
+#[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct BoundedString<const MAX: usize>(String);
+
+impl<const MAX: usize> BoundedString<MAX> {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl<const MAX: usize> TryFrom<String> for BoundedString<MAX> {
+    type Error = LengthOutOfBounds;
+
+    fn try_from(string: String) -> core::result::Result<Self, Self::Error> {
+        check_len(string.len(), 0, MAX)?;
+        Ok(Self(string))
+    }
+}
+
+impl<const MAX: usize> Deref for BoundedString<MAX> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl<const MAX: usize> BorshSerialize for BoundedString<MAX> {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        self.0.serialize(writer)
+    }
+}
+
+impl<const MAX: usize> BorshDeserialize for BoundedString<MAX> {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        Self::deserialize_reader_with_limits(reader, &mut Limits::unlimited())
+    }
+
+    fn deserialize_reader_with_limits<R: Read>(
+        reader: &mut R,
+        limits: &mut Limits,
+    ) -> Result<Self> {
+        let len = read_len(reader, 0, MAX)?;
+        deserialize_with_len(len, reader, limits).map(Self)
+    }
+}
+
+/// `Vec<u8>` of at most `MAX` bytes.
+#[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct BoundedBytes<const MAX: usize>(Vec<u8>);
+
+impl<const MAX: usize> BoundedBytes<MAX> {
+    pub fn as_slice(&self) -> &[u8] {
+        &self.0
+    }
+
+    pub fn into_inner(self) -> Vec<u8> {
+        self.0
+    }
+}
+
+impl<const MAX: usize> TryFrom<Vec<u8>> for BoundedBytes<MAX> {
+    type Error = LengthOutOfBounds;
+
+    fn try_from(bytes: Vec<u8>) -> core::result::Result<Self, Self::Error> {
+        check_len(bytes.len(), 0, MAX)?;
+        Ok(Self(bytes))
+    }
+}
+
+impl<const MAX: usize> Deref for BoundedBytes<MAX> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl<const MAX: usize> BorshSerialize for BoundedBytes<MAX> {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        self.0.serialize(writer)
+    }
+}
+
+impl<const MAX: usize> BorshDeserialize for BoundedBytes<MAX> {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        Self::deserialize_reader_with_limits(reader, &mut Limits::unlimited())
+    }
+
+    fn deserialize_reader_with_limits<R: Read>(
+        reader: &mut R,
+        limits: &mut Limits,
+    ) -> Result<Self> {
+        let len = read_len(reader, 0, MAX)?;
+        deserialize_with_len(len, reader, limits).map(Self)
+    }
+}
+
+impl<'de, T: BorshDeserialize, const MIN: usize, const MAX: usize> BorshDeserializeBorrowed<'de>
+    for BoundedVec<T, MIN, MAX>
+{
+    fn deserialize_borrowed(buf: &mut &'de [u8]) -> Result<Self> {
+        Self::deserialize(buf)
+    }
+}
+
+impl<'de, const MAX: usize> BorshDeserializeBorrowed<'de> for BoundedString<MAX> {
+    fn deserialize_borrowed(buf: &mut &'de [u8]) -> Result<Self> {
+        Self::deserialize(buf)
+    }
+}
+
+impl<'de, const MAX: usize> BorshDeserializeBorrowed<'de> for BoundedBytes<MAX> {
+    fn deserialize_borrowed(buf: &mut &'de [u8]) -> Result<Self> {
+        Self::deserialize(buf)
+    }
+}
+
+#[cfg(feature = "unstable__schema")]
+mod schema {
+    use super::{BoundedBytes, BoundedString, BoundedVec};
+    use crate::__private::maybestd::{collections::BTreeMap, format};
+    use crate::schema::{add_definition, BorshSchema, Declaration, Definition};
+
+    fn add_sequence<T: BorshSchema>(
+        declaration: Declaration,
+        min: usize,
+        max: usize,
+        definitions: &mut BTreeMap<Declaration, Definition>,
+    ) {
+        let definition = Definition::Sequence {
+            length_width: Definition::DEFAULT_LENGTH_WIDTH,
+            length_range: min as u64..=max as u64,
+            elements: T::declaration(),
+        };
+        add_definition(declaration, definition, definitions);
+        T::add_definitions_recursively(definitions);
+    }
+
+    impl<T: BorshSchema, const MIN: usize, const MAX: usize> BorshSchema for BoundedVec<T, MIN, MAX> {
+        fn add_definitions_recursively(definitions: &mut BTreeMap<Declaration, Definition>) {
+            add_sequence::<T>(Self::declaration(), MIN, MAX, definitions);
+        }
+
+        fn declaration() -> Declaration {
+            format!("BoundedVec<{}, {}, {}>", T::declaration(), MIN, MAX)
+        }
+    }
+
+    impl<const MAX: usize> BorshSchema for BoundedString<MAX> {
+        fn add_definitions_recursively(definitions: &mut BTreeMap<Declaration, Definition>) {
+            add_sequence::<u8>(Self::declaration(), 0, MAX, definitions);
+        }
+
+        fn declaration() -> Declaration {
+            format!("BoundedString<{}>", MAX)
+        }
+    }
+
+    impl<const MAX: usize> BorshSchema for BoundedBytes<MAX> {
+        fn add_definitions_recursively(definitions: &mut BTreeMap<Declaration, Definition>) {
+            add_sequence::<u8>(Self::declaration(), 0, MAX, definitions);
+        }
+
+        fn declaration() -> Declaration {
+            format!("BoundedBytes<{}>", MAX)
+        }
+    }
+}
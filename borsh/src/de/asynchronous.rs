@@ -0,0 +1,986 @@
+use core::convert::TryFrom;
+use core::marker::PhantomData;
+use core::mem::size_of;
+
+use crate::__private::maybestd::{
+    borrow::{Borrow, Cow, ToOwned},
+    boxed::Box,
+    collections::{BTreeMap, BTreeSet, LinkedList, VecDeque},
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use crate::async_io::{AsyncRead, BoxFuture};
+use crate::error::{check_zst, prepend_path, PathSegment};
+use crate::io::{Error, ErrorKind, Result};
+
+#[cfg(feature = "rc")]
+use crate::__private::maybestd::sync::Arc;
+
+use super::{
+    hint, unexpected_eof_to_unexpected_length_of_input, BorshDeserialize, Limits,
+    ERROR_UNEXPECTED_LENGTH_OF_INPUT,
+};
+
+#[cfg(feature = "de_strict_order")]
+const ERROR_WRONG_ORDER_OF_KEYS: &str = "keys were not serialized in ascending order";
+
+/// A data-structure that can be de-serialized from binary format by NBOR, reading
+/// from an [`AsyncRead`] source.
+///
+/// Accepts the same bytes as [`BorshDeserialize`], and reads exactly as many bytes
+/// as the value takes, so that values can be decoded one by one from a stream.
+/// Futures are boxed, see [`BoxFuture`], and need to be `Send`, which is why the
+/// trait requires `Send`.
+///
+/// ```
+/// use borsh::{BorshDeserializeAsync, BorshSerializeAsync};
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let (mut client, mut server) = tokio::io::duplex(64);
+/// (42u32, "hello").serialize_async(&mut client).await.unwrap();
+/// let value = <(u32, String)>::deserialize_async(&mut server).await.unwrap();
+/// assert_eq!(value, (42, "hello".to_string()));
+/// # });
+/// ```
+pub trait BorshDeserializeAsync: Sized + Send {
+    fn deserialize_async<'a, R: AsyncRead + ?Sized>(
+        reader: &'a mut R,
+    ) -> BoxFuture<'a, Result<Self>>
+    where
+        Self: 'a;
+
+    /// Deserializes this instance from a reader, enforcing `limits`.
+    ///
+    /// See [`BorshDeserialize::deserialize_reader_with_limits`].
+    #[inline]
+    fn deserialize_async_with_limits<'a, R: AsyncRead + ?Sized>(
+        reader: &'a mut R,
+        limits: &'a mut Limits,
+    ) -> BoxFuture<'a, Result<Self>>
+    where
+        Self: 'a,
+    {
+        let _ = limits;
+        Self::deserialize_async(reader)
+    }
+
+    #[inline]
+    #[doc(hidden)]
+    fn vec_from_reader_async<'a, R: AsyncRead + ?Sized>(
+        len: u32,
+        reader: &'a mut R,
+    ) -> BoxFuture<'a, Result<Option<Vec<Self>>>>
+    where
+        Self: 'a,
+    {
+        let _ = len;
+        let _ = reader;
+        Box::pin(async { Ok(None) })
+    }
+
+    #[inline]
+    #[doc(hidden)]
+    fn array_from_reader_async<'a, R: AsyncRead + ?Sized, const N: usize>(
+        reader: &'a mut R,
+    ) -> BoxFuture<'a, Result<Option<[Self; N]>>>
+    where
+        Self: 'a,
+    {
+        let _ = reader;
+        Box::pin(async { Ok(None) })
+    }
+}
+
+/// Reads `len` bytes from the reader, see `read_bytes` for the blocking version.
+async fn read_bytes<R: AsyncRead + ?Sized>(len: u32, reader: &mut R) -> Result<Vec<u8>> {
+    let len = usize::try_from(len).map_err(|_| ErrorKind::InvalidData)?;
+    // Avoid OOM by limiting the size of allocation, as the blocking version does.
+    let mut vec = vec![0u8; len.min(1024 * 1024)];
+    let mut pos = 0;
+    while pos < len {
+        if pos == vec.len() {
+            vec.resize(vec.len().saturating_mul(2).min(len), 0)
+        }
+        reader
+            .read_exact(&mut vec.as_mut_slice()[pos..])
+            .await
+            .map_err(unexpected_eof_to_unexpected_length_of_input)?;
+        pos = vec.len();
+    }
+    Ok(vec)
+}
+
+/// Reads a value of `u32` length prefix.
+async fn read_len<R: AsyncRead + ?Sized>(reader: &mut R) -> Result<u32> {
+    let mut buf = [0u8; 4];
+    reader
+        .read_exact(&mut buf)
+        .await
+        .map_err(unexpected_eof_to_unexpected_length_of_input)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+/// Checks that keys, read with `key`, are in ascending order.
+#[cfg(feature = "de_strict_order")]
+fn check_order<T, K: Ord + ?Sized>(items: &[T], key: impl Fn(&T) -> &K) -> Result<()> {
+    for pair in items.windows(2) {
+        if key(&pair[0]) >= key(&pair[1]) {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                ERROR_WRONG_ORDER_OF_KEYS,
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Implements the trait for types of fixed size with [`BorshDeserialize`], which
+/// deserializes them from a buffer on stack.
+macro_rules! impl_via_buffer {
+    ($($type:ty => $size:expr),+ $(,)?) => {
+        $(
+        impl BorshDeserializeAsync for $type {
+            #[inline]
+            fn deserialize_async<'a, R: AsyncRead + ?Sized>(
+                reader: &'a mut R,
+            ) -> BoxFuture<'a, Result<Self>>
+            where
+                Self: 'a,
+            {
+                Box::pin(async move {
+                    let mut buf = [0u8; $size];
+                    reader
+                        .read_exact(&mut buf)
+                        .await
+                        .map_err(unexpected_eof_to_unexpected_length_of_input)?;
+                    <$type as BorshDeserialize>::deserialize(&mut &buf[..])
+                })
+            }
+        }
+        )+
+    };
+}
+
+impl_via_buffer!(
+    i8 => 1,
+    i16 => 2,
+    i32 => 4,
+    i64 => 8,
+    i128 => 16,
+    u16 => 2,
+    u32 => 4,
+    u64 => 8,
+    u128 => 16,
+    isize => 8,
+    usize => 8,
+    core::num::NonZeroI8 => 1,
+    core::num::NonZeroI16 => 2,
+    core::num::NonZeroI32 => 4,
+    core::num::NonZeroI64 => 8,
+    core::num::NonZeroI128 => 16,
+    core::num::NonZeroU8 => 1,
+    core::num::NonZeroU16 => 2,
+    core::num::NonZeroU32 => 4,
+    core::num::NonZeroU64 => 8,
+    core::num::NonZeroU128 => 16,
+    core::num::NonZeroUsize => 8,
+    f32 => 4,
+    f64 => 8,
+    bool => 1,
+    std::net::SocketAddrV4 => 6,
+    std::net::SocketAddrV6 => 18,
+    std::net::Ipv4Addr => 4,
+    std::net::Ipv6Addr => 16,
+);
+
+#[cfg(feature = "bson")]
+impl_via_buffer!(bson::oid::ObjectId => 12);
+
+impl BorshDeserializeAsync for u8 {
+    #[inline]
+    fn deserialize_async<'a, R: AsyncRead + ?Sized>(
+        reader: &'a mut R,
+    ) -> BoxFuture<'a, Result<Self>>
+    where
+        Self: 'a,
+    {
+        Box::pin(async move {
+            let mut buf = [0u8; 1];
+            reader
+                .read_exact(&mut buf)
+                .await
+                .map_err(unexpected_eof_to_unexpected_length_of_input)?;
+            Ok(buf[0])
+        })
+    }
+
+    #[inline]
+    #[doc(hidden)]
+    fn vec_from_reader_async<'a, R: AsyncRead + ?Sized>(
+        len: u32,
+        reader: &'a mut R,
+    ) -> BoxFuture<'a, Result<Option<Vec<Self>>>>
+    where
+        Self: 'a,
+    {
+        Box::pin(async move { read_bytes(len, reader).await.map(Some) })
+    }
+
+    #[inline]
+    #[doc(hidden)]
+    fn array_from_reader_async<'a, R: AsyncRead + ?Sized, const N: usize>(
+        reader: &'a mut R,
+    ) -> BoxFuture<'a, Result<Option<[Self; N]>>>
+    where
+        Self: 'a,
+    {
+        Box::pin(async move {
+            let mut arr = [0u8; N];
+            reader
+                .read_exact(&mut arr)
+                .await
+                .map_err(unexpected_eof_to_unexpected_length_of_input)?;
+            Ok(Some(arr))
+        })
+    }
+}
+
+impl BorshDeserializeAsync for std::net::SocketAddr {
+    #[inline]
+    fn deserialize_async<'a, R: AsyncRead + ?Sized>(
+        reader: &'a mut R,
+    ) -> BoxFuture<'a, Result<Self>>
+    where
+        Self: 'a,
+    {
+        Box::pin(async move {
+            let kind = u8::deserialize_async(reader).await?;
+            match kind {
+                0 => std::net::SocketAddrV4::deserialize_async(reader)
+                    .await
+                    .map(std::net::SocketAddr::V4),
+                1 => std::net::SocketAddrV6::deserialize_async(reader)
+                    .await
+                    .map(std::net::SocketAddr::V6),
+                value => Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!("Invalid SocketAddr variant: {}", value),
+                )),
+            }
+        })
+    }
+}
+
+impl<T> BorshDeserializeAsync for Option<T>
+where
+    T: BorshDeserializeAsync,
+{
+    #[inline]
+    fn deserialize_async<'a, R: AsyncRead + ?Sized>(
+        reader: &'a mut R,
+    ) -> BoxFuture<'a, Result<Self>>
+    where
+        Self: 'a,
+    {
+        Box::pin(async move {
+            Self::deserialize_async_with_limits(reader, &mut Limits::unlimited()).await
+        })
+    }
+
+    #[inline]
+    fn deserialize_async_with_limits<'a, R: AsyncRead + ?Sized>(
+        reader: &'a mut R,
+        limits: &'a mut Limits,
+    ) -> BoxFuture<'a, Result<Self>>
+    where
+        Self: 'a,
+    {
+        Box::pin(async move {
+            let flag = u8::deserialize_async(reader).await?;
+            if flag == 0 {
+                Ok(None)
+            } else if flag == 1 {
+                Ok(Some(
+                    T::deserialize_async_with_limits(reader, limits).await?,
+                ))
+            } else {
+                let msg = format!(
+                    "Invalid Option representation: {}. The first byte must be 0 or 1",
+                    flag
+                );
+
+                Err(Error::new(ErrorKind::InvalidData, msg))
+            }
+        })
+    }
+}
+
+impl<T, E> BorshDeserializeAsync for core::result::Result<T, E>
+where
+    T: BorshDeserializeAsync,
+    E: BorshDeserializeAsync,
+{
+    #[inline]
+    fn deserialize_async<'a, R: AsyncRead + ?Sized>(
+        reader: &'a mut R,
+    ) -> BoxFuture<'a, Result<Self>>
+    where
+        Self: 'a,
+    {
+        Box::pin(async move {
+            Self::deserialize_async_with_limits(reader, &mut Limits::unlimited()).await
+        })
+    }
+
+    #[inline]
+    fn deserialize_async_with_limits<'a, R: AsyncRead + ?Sized>(
+        reader: &'a mut R,
+        limits: &'a mut Limits,
+    ) -> BoxFuture<'a, Result<Self>>
+    where
+        Self: 'a,
+    {
+        Box::pin(async move {
+            let flag = u8::deserialize_async(reader).await?;
+            if flag == 0 {
+                Ok(Err(E::deserialize_async_with_limits(reader, limits).await?))
+            } else if flag == 1 {
+                Ok(Ok(T::deserialize_async_with_limits(reader, limits).await?))
+            } else {
+                let msg = format!(
+                    "Invalid Result representation: {}. The first byte must be 0 or 1",
+                    flag
+                );
+
+                Err(Error::new(ErrorKind::InvalidData, msg))
+            }
+        })
+    }
+}
+
+impl BorshDeserializeAsync for String {
+    #[inline]
+    fn deserialize_async<'a, R: AsyncRead + ?Sized>(
+        reader: &'a mut R,
+    ) -> BoxFuture<'a, Result<Self>>
+    where
+        Self: 'a,
+    {
+        Box::pin(async move {
+            Self::deserialize_async_with_limits(reader, &mut Limits::unlimited()).await
+        })
+    }
+
+    #[inline]
+    fn deserialize_async_with_limits<'a, R: AsyncRead + ?Sized>(
+        reader: &'a mut R,
+        limits: &'a mut Limits,
+    ) -> BoxFuture<'a, Result<Self>>
+    where
+        Self: 'a,
+    {
+        Box::pin(async move {
+            let len = read_len(reader).await?;
+            limits.reserve_string(len)?;
+            String::from_utf8(read_bytes(len, reader).await?).map_err(|err| {
+                let msg = err.to_string();
+                Error::new(ErrorKind::InvalidData, msg)
+            })
+        })
+    }
+}
+
+impl<T> BorshDeserializeAsync for Vec<T>
+where
+    T: BorshDeserializeAsync,
+{
+    #[inline]
+    fn deserialize_async<'a, R: AsyncRead + ?Sized>(
+        reader: &'a mut R,
+    ) -> BoxFuture<'a, Result<Self>>
+    where
+        Self: 'a,
+    {
+        Box::pin(async move {
+            Self::deserialize_async_with_limits(reader, &mut Limits::unlimited()).await
+        })
+    }
+
+    #[inline]
+    fn deserialize_async_with_limits<'a, R: AsyncRead + ?Sized>(
+        reader: &'a mut R,
+        limits: &'a mut Limits,
+    ) -> BoxFuture<'a, Result<Self>>
+    where
+        Self: 'a,
+    {
+        Box::pin(async move {
+            check_zst::<T>()?;
+
+            let len = read_len(reader).await?;
+            limits.reserve_collection::<T>(len)?;
+            if len == 0 {
+                Ok(Vec::new())
+            } else if let Some(vec_bytes) = T::vec_from_reader_async(len, reader).await? {
+                Ok(vec_bytes)
+            } else {
+                limits.enter()?;
+                let mut result = Vec::with_capacity(hint::cautious::<T>(len));
+                for idx in 0..len {
+                    result.push(
+                        T::deserialize_async_with_limits(reader, limits)
+                            .await
+                            .map_err(|err| {
+                                prepend_path(err, &[PathSegment::Index(idx as usize)])
+                            })?,
+                    );
+                }
+                limits.exit();
+                Ok(result)
+            }
+        })
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl BorshDeserializeAsync for bytes::Bytes {
+    #[inline]
+    fn deserialize_async<'a, R: AsyncRead + ?Sized>(
+        reader: &'a mut R,
+    ) -> BoxFuture<'a, Result<Self>>
+    where
+        Self: 'a,
+    {
+        Box::pin(async move {
+            Self::deserialize_async_with_limits(reader, &mut Limits::unlimited()).await
+        })
+    }
+
+    #[inline]
+    fn deserialize_async_with_limits<'a, R: AsyncRead + ?Sized>(
+        reader: &'a mut R,
+        limits: &'a mut Limits,
+    ) -> BoxFuture<'a, Result<Self>>
+    where
+        Self: 'a,
+    {
+        Box::pin(async move {
+            let vec = <Vec<u8>>::deserialize_async_with_limits(reader, limits).await?;
+            Ok(vec.into())
+        })
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl BorshDeserializeAsync for bytes::BytesMut {
+    #[inline]
+    fn deserialize_async<'a, R: AsyncRead + ?Sized>(
+        reader: &'a mut R,
+    ) -> BoxFuture<'a, Result<Self>>
+    where
+        Self: 'a,
+    {
+        Box::pin(async move {
+            Self::deserialize_async_with_limits(reader, &mut Limits::unlimited()).await
+        })
+    }
+
+    #[inline]
+    fn deserialize_async_with_limits<'a, R: AsyncRead + ?Sized>(
+        reader: &'a mut R,
+        limits: &'a mut Limits,
+    ) -> BoxFuture<'a, Result<Self>>
+    where
+        Self: 'a,
+    {
+        Box::pin(async move {
+            let vec = <Vec<u8>>::deserialize_async_with_limits(reader, limits).await?;
+            Ok(vec.as_slice().into())
+        })
+    }
+}
+
+impl<T> BorshDeserializeAsync for Cow<'_, T>
+where
+    T: ToOwned + Sync + ?Sized,
+    T::Owned: BorshDeserializeAsync,
+{
+    #[inline]
+    fn deserialize_async<'a, R: AsyncRead + ?Sized>(
+        reader: &'a mut R,
+    ) -> BoxFuture<'a, Result<Self>>
+    where
+        Self: 'a,
+    {
+        Box::pin(async move {
+            Self::deserialize_async_with_limits(reader, &mut Limits::unlimited()).await
+        })
+    }
+
+    #[inline]
+    fn deserialize_async_with_limits<'a, R: AsyncRead + ?Sized>(
+        reader: &'a mut R,
+        limits: &'a mut Limits,
+    ) -> BoxFuture<'a, Result<Self>>
+    where
+        Self: 'a,
+    {
+        Box::pin(async move {
+            Ok(Cow::Owned(
+                T::Owned::deserialize_async_with_limits(reader, limits).await?,
+            ))
+        })
+    }
+}
+
+impl<T> BorshDeserializeAsync for VecDeque<T>
+where
+    T: BorshDeserializeAsync,
+{
+    #[inline]
+    fn deserialize_async<'a, R: AsyncRead + ?Sized>(
+        reader: &'a mut R,
+    ) -> BoxFuture<'a, Result<Self>>
+    where
+        Self: 'a,
+    {
+        Box::pin(async move {
+            Self::deserialize_async_with_limits(reader, &mut Limits::unlimited()).await
+        })
+    }
+
+    #[inline]
+    fn deserialize_async_with_limits<'a, R: AsyncRead + ?Sized>(
+        reader: &'a mut R,
+        limits: &'a mut Limits,
+    ) -> BoxFuture<'a, Result<Self>>
+    where
+        Self: 'a,
+    {
+        Box::pin(async move {
+            let vec = <Vec<T>>::deserialize_async_with_limits(reader, limits).await?;
+            Ok(vec.into())
+        })
+    }
+}
+
+impl<T> BorshDeserializeAsync for LinkedList<T>
+where
+    T: BorshDeserializeAsync,
+{
+    #[inline]
+    fn deserialize_async<'a, R: AsyncRead + ?Sized>(
+        reader: &'a mut R,
+    ) -> BoxFuture<'a, Result<Self>>
+    where
+        Self: 'a,
+    {
+        Box::pin(async move {
+            Self::deserialize_async_with_limits(reader, &mut Limits::unlimited()).await
+        })
+    }
+
+    #[inline]
+    fn deserialize_async_with_limits<'a, R: AsyncRead + ?Sized>(
+        reader: &'a mut R,
+        limits: &'a mut Limits,
+    ) -> BoxFuture<'a, Result<Self>>
+    where
+        Self: 'a,
+    {
+        Box::pin(async move {
+            let vec = <Vec<T>>::deserialize_async_with_limits(reader, limits).await?;
+            Ok(vec.into_iter().collect::<LinkedList<T>>())
+        })
+    }
+}
+
+mod hashes {
+    use core::hash::{BuildHasher, Hash};
+
+    use super::BorshDeserializeAsync;
+    use crate::__private::maybestd::{
+        boxed::Box,
+        collections::{HashMap, HashSet},
+        vec::Vec,
+    };
+    use crate::async_io::{AsyncRead, BoxFuture};
+    use crate::de::Limits;
+    use crate::error::check_zst;
+    use crate::io::Result;
+
+    impl<T, H> BorshDeserializeAsync for HashSet<T, H>
+    where
+        T: BorshDeserializeAsync + Eq + Hash + Ord,
+        H: BuildHasher + Default + Send,
+    {
+        #[inline]
+        fn deserialize_async<'a, R: AsyncRead + ?Sized>(
+            reader: &'a mut R,
+        ) -> BoxFuture<'a, Result<Self>>
+        where
+            Self: 'a,
+        {
+            Box::pin(async move {
+                Self::deserialize_async_with_limits(reader, &mut Limits::unlimited()).await
+            })
+        }
+
+        #[inline]
+        fn deserialize_async_with_limits<'a, R: AsyncRead + ?Sized>(
+            reader: &'a mut R,
+            limits: &'a mut Limits,
+        ) -> BoxFuture<'a, Result<Self>>
+        where
+            Self: 'a,
+        {
+            Box::pin(async move {
+                let vec = <Vec<T>>::deserialize_async_with_limits(reader, limits).await?;
+
+                #[cfg(feature = "de_strict_order")]
+                super::check_order(&vec, |item| item)?;
+
+                Ok(vec.into_iter().collect::<HashSet<T, H>>())
+            })
+        }
+    }
+
+    impl<K, V, H> BorshDeserializeAsync for HashMap<K, V, H>
+    where
+        K: BorshDeserializeAsync + Eq + Hash + Ord,
+        V: BorshDeserializeAsync,
+        H: BuildHasher + Default + Send,
+    {
+        #[inline]
+        fn deserialize_async<'a, R: AsyncRead + ?Sized>(
+            reader: &'a mut R,
+        ) -> BoxFuture<'a, Result<Self>>
+        where
+            Self: 'a,
+        {
+            Box::pin(async move {
+                Self::deserialize_async_with_limits(reader, &mut Limits::unlimited()).await
+            })
+        }
+
+        #[inline]
+        fn deserialize_async_with_limits<'a, R: AsyncRead + ?Sized>(
+            reader: &'a mut R,
+            limits: &'a mut Limits,
+        ) -> BoxFuture<'a, Result<Self>>
+        where
+            Self: 'a,
+        {
+            Box::pin(async move {
+                check_zst::<K>()?;
+                let vec = <Vec<(K, V)>>::deserialize_async_with_limits(reader, limits).await?;
+
+                #[cfg(feature = "de_strict_order")]
+                super::check_order(&vec, |(key, _)| key)?;
+
+                Ok(vec.into_iter().collect::<HashMap<K, V, H>>())
+            })
+        }
+    }
+}
+
+impl<T> BorshDeserializeAsync for BTreeSet<T>
+where
+    T: BorshDeserializeAsync + Ord,
+{
+    #[inline]
+    fn deserialize_async<'a, R: AsyncRead + ?Sized>(
+        reader: &'a mut R,
+    ) -> BoxFuture<'a, Result<Self>>
+    where
+        Self: 'a,
+    {
+        Box::pin(async move {
+            Self::deserialize_async_with_limits(reader, &mut Limits::unlimited()).await
+        })
+    }
+
+    #[inline]
+    fn deserialize_async_with_limits<'a, R: AsyncRead + ?Sized>(
+        reader: &'a mut R,
+        limits: &'a mut Limits,
+    ) -> BoxFuture<'a, Result<Self>>
+    where
+        Self: 'a,
+    {
+        Box::pin(async move {
+            let vec = <Vec<T>>::deserialize_async_with_limits(reader, limits).await?;
+
+            #[cfg(feature = "de_strict_order")]
+            check_order(&vec, |item| item)?;
+
+            Ok(vec.into_iter().collect::<BTreeSet<T>>())
+        })
+    }
+}
+
+impl<K, V> BorshDeserializeAsync for BTreeMap<K, V>
+where
+    K: BorshDeserializeAsync + Ord,
+    V: BorshDeserializeAsync,
+{
+    #[inline]
+    fn deserialize_async<'a, R: AsyncRead + ?Sized>(
+        reader: &'a mut R,
+    ) -> BoxFuture<'a, Result<Self>>
+    where
+        Self: 'a,
+    {
+        Box::pin(async move {
+            Self::deserialize_async_with_limits(reader, &mut Limits::unlimited()).await
+        })
+    }
+
+    #[inline]
+    fn deserialize_async_with_limits<'a, R: AsyncRead + ?Sized>(
+        reader: &'a mut R,
+        limits: &'a mut Limits,
+    ) -> BoxFuture<'a, Result<Self>>
+    where
+        Self: 'a,
+    {
+        Box::pin(async move {
+            check_zst::<K>()?;
+            let vec = <Vec<(K, V)>>::deserialize_async_with_limits(reader, limits).await?;
+
+            #[cfg(feature = "de_strict_order")]
+            check_order(&vec, |(key, _)| key)?;
+
+            Ok(vec.into_iter().collect::<BTreeMap<K, V>>())
+        })
+    }
+}
+
+impl<T, U> BorshDeserializeAsync for Box<T>
+where
+    U: Into<Box<T>> + Borrow<T>,
+    T: ToOwned<Owned = U> + Send + ?Sized,
+    T::Owned: BorshDeserializeAsync,
+{
+    fn deserialize_async<'a, R: AsyncRead + ?Sized>(
+        reader: &'a mut R,
+    ) -> BoxFuture<'a, Result<Self>>
+    where
+        Self: 'a,
+    {
+        Box::pin(async move {
+            Self::deserialize_async_with_limits(reader, &mut Limits::unlimited()).await
+        })
+    }
+
+    fn deserialize_async_with_limits<'a, R: AsyncRead + ?Sized>(
+        reader: &'a mut R,
+        limits: &'a mut Limits,
+    ) -> BoxFuture<'a, Result<Self>>
+    where
+        Self: 'a,
+    {
+        Box::pin(async move {
+            limits.enter()?;
+            limits.reserve_bytes(size_of::<U>())?;
+            let result = T::Owned::deserialize_async_with_limits(reader, limits)
+                .await?
+                .into();
+            limits.exit();
+            Ok(result)
+        })
+    }
+}
+
+impl<T, const N: usize> BorshDeserializeAsync for [T; N]
+where
+    T: BorshDeserializeAsync,
+{
+    #[inline]
+    fn deserialize_async<'a, R: AsyncRead + ?Sized>(
+        reader: &'a mut R,
+    ) -> BoxFuture<'a, Result<Self>>
+    where
+        Self: 'a,
+    {
+        Box::pin(async move {
+            Self::deserialize_async_with_limits(reader, &mut Limits::unlimited()).await
+        })
+    }
+
+    #[inline]
+    fn deserialize_async_with_limits<'a, R: AsyncRead + ?Sized>(
+        reader: &'a mut R,
+        limits: &'a mut Limits,
+    ) -> BoxFuture<'a, Result<Self>>
+    where
+        Self: 'a,
+    {
+        Box::pin(async move {
+            if let Some(arr) = T::array_from_reader_async(reader).await? {
+                return Ok(arr);
+            }
+            let mut result = Vec::with_capacity(N);
+            for idx in 0..N {
+                result.push(
+                    T::deserialize_async_with_limits(reader, limits)
+                        .await
+                        .map_err(|err| prepend_path(err, &[PathSegment::Index(idx)]))?,
+                );
+            }
+            <[T; N]>::try_from(result)
+                .map_err(|_| Error::new(ErrorKind::InvalidData, ERROR_UNEXPECTED_LENGTH_OF_INPUT))
+        })
+    }
+}
+
+macro_rules! impl_tuple {
+    (@unit $name:ty) => {
+        impl BorshDeserializeAsync for $name {
+            #[inline]
+            fn deserialize_async<'a, R: AsyncRead + ?Sized>(
+                _reader: &'a mut R,
+            ) -> BoxFuture<'a, Result<Self>>
+            where
+                Self: 'a,
+            {
+                Box::pin(async { Ok(<$name>::default()) })
+            }
+        }
+    };
+
+    ($($name:ident)+) => {
+      impl<$($name),+> BorshDeserializeAsync for ($($name,)+)
+      where $($name: BorshDeserializeAsync,)+
+      {
+        #[inline]
+        fn deserialize_async<'a, R: AsyncRead + ?Sized>(
+            reader: &'a mut R,
+        ) -> BoxFuture<'a, Result<Self>>
+        where
+            Self: 'a,
+        {
+            Box::pin(async move {
+                Self::deserialize_async_with_limits(reader, &mut Limits::unlimited()).await
+            })
+        }
+
+        #[inline]
+        fn deserialize_async_with_limits<'a, R: AsyncRead + ?Sized>(
+            reader: &'a mut R,
+            limits: &'a mut Limits,
+        ) -> BoxFuture<'a, Result<Self>>
+        where
+            Self: 'a,
+        {
+            Box::pin(async move {
+                Ok(($($name::deserialize_async_with_limits(reader, limits).await?,)+))
+            })
+        }
+      }
+    };
+}
+
+impl_tuple!(@unit ());
+impl_tuple!(@unit core::ops::RangeFull);
+
+impl_tuple!(T0);
+impl_tuple!(T0 T1);
+impl_tuple!(T0 T1 T2);
+impl_tuple!(T0 T1 T2 T3);
+impl_tuple!(T0 T1 T2 T3 T4);
+impl_tuple!(T0 T1 T2 T3 T4 T5);
+impl_tuple!(T0 T1 T2 T3 T4 T5 T6);
+impl_tuple!(T0 T1 T2 T3 T4 T5 T6 T7);
+impl_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8);
+impl_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9);
+impl_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10);
+impl_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11);
+impl_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11 T12);
+impl_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11 T12 T13);
+impl_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11 T12 T13 T14);
+impl_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11 T12 T13 T14 T15);
+impl_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11 T12 T13 T14 T15 T16);
+impl_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11 T12 T13 T14 T15 T16 T17);
+impl_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11 T12 T13 T14 T15 T16 T17 T18);
+impl_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11 T12 T13 T14 T15 T16 T17 T18 T19);
+
+macro_rules! impl_range {
+    ($type:ident, $make:expr, $($side:ident),*) => {
+        impl<T: BorshDeserializeAsync> BorshDeserializeAsync for core::ops::$type<T> {
+            #[inline]
+            fn deserialize_async<'a, R: AsyncRead + ?Sized>(
+                reader: &'a mut R,
+            ) -> BoxFuture<'a, Result<Self>>
+            where
+                Self: 'a,
+            {
+                Box::pin(async move {
+                    Self::deserialize_async_with_limits(reader, &mut Limits::unlimited()).await
+                })
+            }
+
+            #[inline]
+            fn deserialize_async_with_limits<'a, R: AsyncRead + ?Sized>(
+                reader: &'a mut R,
+                limits: &'a mut Limits,
+            ) -> BoxFuture<'a, Result<Self>>
+            where
+                Self: 'a,
+            {
+                Box::pin(async move {
+                    let ($($side,)*) = <_>::deserialize_async_with_limits(reader, limits).await?;
+                    Ok($make)
+                })
+            }
+        }
+    };
+}
+
+impl_range!(Range, start..end, start, end);
+impl_range!(RangeInclusive, start..=end, start, end);
+impl_range!(RangeFrom, start.., start);
+impl_range!(RangeTo, ..end, end);
+impl_range!(RangeToInclusive, ..=end, end);
+
+#[cfg(feature = "rc")]
+impl<T: Send + Sync + ?Sized> BorshDeserializeAsync for Arc<T>
+where
+    Box<T>: BorshDeserializeAsync,
+{
+    fn deserialize_async<'a, R: AsyncRead + ?Sized>(
+        reader: &'a mut R,
+    ) -> BoxFuture<'a, Result<Self>>
+    where
+        Self: 'a,
+    {
+        Box::pin(async move {
+            Self::deserialize_async_with_limits(reader, &mut Limits::unlimited()).await
+        })
+    }
+
+    fn deserialize_async_with_limits<'a, R: AsyncRead + ?Sized>(
+        reader: &'a mut R,
+        limits: &'a mut Limits,
+    ) -> BoxFuture<'a, Result<Self>>
+    where
+        Self: 'a,
+    {
+        Box::pin(async move {
+            Ok(Box::<T>::deserialize_async_with_limits(reader, limits)
+                .await?
+                .into())
+        })
+    }
+}
+
+impl<T: Send + ?Sized> BorshDeserializeAsync for PhantomData<T> {
+    fn deserialize_async<'a, R: AsyncRead + ?Sized>(_: &'a mut R) -> BoxFuture<'a, Result<Self>>
+    where
+        Self: 'a,
+    {
+        Box::pin(async { Ok(PhantomData) })
+    }
+}
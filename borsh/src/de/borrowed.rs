@@ -0,0 +1,355 @@
+use core::{marker::PhantomData, mem::size_of};
+
+use crate::__private::maybestd::{
+    borrow::Cow,
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use crate::error::{check_zst, prepend_path, set_offset, PathSegment};
+use crate::io::{Error, ErrorKind, Result};
+
+use super::{
+    hint, not_all_bytes_read, ArrayDropGuard, BorshDeserialize, DeserializeOptions, Limits,
+    ERROR_UNEXPECTED_LENGTH_OF_INPUT,
+};
+
+/// A data-structure that can be de-serialized from a slice of bytes, borrowing
+/// data from the slice where possible instead of copying it.
+///
+/// Unlike [`BorshDeserialize`], impls of this trait are allowed to return values
+/// which hold references into the input, e.g. `&'de str` or `&'de [u8]`, which makes it
+/// possible to decode large blobs without allocating for every string and byte field.
+pub trait BorshDeserializeBorrowed<'de>: Sized {
+    /// Deserializes this instance from a given slice of bytes.
+    /// Updates the buffer to point at the remaining bytes.
+    fn deserialize_borrowed(buf: &mut &'de [u8]) -> Result<Self>;
+
+    /// Deserializes this instance from a given slice of bytes, enforcing `limits`.
+    ///
+    /// Default implementation ignores `limits`, see
+    /// [`BorshDeserialize::deserialize_reader_with_limits`]. Borrowed `&[u8]` and `&str`
+    /// don't allocate, so they are only subject to the limits of their containers.
+    #[inline]
+    fn deserialize_borrowed_with_limits(buf: &mut &'de [u8], limits: &mut Limits) -> Result<Self> {
+        let _ = limits;
+        Self::deserialize_borrowed(buf)
+    }
+
+    /// Deserialize this instance from a slice of bytes, borrowing from it.
+    fn try_from_slice_borrowed(v: &'de [u8]) -> Result<Self> {
+        let mut v_mut = v;
+        let result = Self::deserialize_borrowed(&mut v_mut)
+            .map_err(|err| set_offset(err, v.len() - v_mut.len()))?;
+        if !v_mut.is_empty() {
+            return Err(not_all_bytes_read(v.len() - v_mut.len()));
+        }
+        Ok(result)
+    }
+}
+
+/// Splits off `len` bytes from the front of `buf`.
+fn take_bytes<'de>(buf: &mut &'de [u8], len: usize) -> Result<&'de [u8]> {
+    if buf.len() < len {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            ERROR_UNEXPECTED_LENGTH_OF_INPUT,
+        ));
+    }
+    let (head, tail) = buf.split_at(len);
+    *buf = tail;
+    Ok(head)
+}
+
+/// Splits off a `u32` length-prefixed byte slice from the front of `buf`.
+fn take_prefixed_bytes<'de>(buf: &mut &'de [u8]) -> Result<&'de [u8]> {
+    let len = u32::deserialize(buf)?;
+    take_bytes(buf, len as usize)
+}
+
+macro_rules! impl_for_owned {
+    ($($type:ty),+) => {
+        $(
+        impl<'de> BorshDeserializeBorrowed<'de> for $type {
+            #[inline]
+            fn deserialize_borrowed(buf: &mut &'de [u8]) -> Result<Self> {
+                <$type as BorshDeserialize>::deserialize(buf)
+            }
+
+            #[inline]
+            fn deserialize_borrowed_with_limits(buf: &mut &'de [u8], limits: &mut Limits) -> Result<Self> {
+                <$type as BorshDeserialize>::deserialize_reader_with_limits(buf, limits)
+            }
+        }
+        )+
+    };
+}
+
+impl_for_owned!(u8, u16, u32, u64, u128, usize);
+impl_for_owned!(i8, i16, i32, i64, i128, isize);
+impl_for_owned!(f32, f64, bool, String, (), core::ops::RangeFull);
+impl_for_owned!(
+    core::num::NonZeroI8,
+    core::num::NonZeroI16,
+    core::num::NonZeroI32,
+    core::num::NonZeroI64,
+    core::num::NonZeroI128,
+    core::num::NonZeroU8,
+    core::num::NonZeroU16,
+    core::num::NonZeroU32,
+    core::num::NonZeroU64,
+    core::num::NonZeroU128,
+    core::num::NonZeroUsize
+);
+
+impl<'de: 'a, 'a> BorshDeserializeBorrowed<'de> for &'a [u8] {
+    #[inline]
+    fn deserialize_borrowed(buf: &mut &'de [u8]) -> Result<Self> {
+        take_prefixed_bytes(buf)
+    }
+}
+
+impl<'de: 'a, 'a> BorshDeserializeBorrowed<'de> for &'a str {
+    #[inline]
+    fn deserialize_borrowed(buf: &mut &'de [u8]) -> Result<Self> {
+        core::str::from_utf8(take_prefixed_bytes(buf)?).map_err(|err| {
+            let msg = err.to_string();
+            Error::new(ErrorKind::InvalidData, msg)
+        })
+    }
+}
+
+impl<'de: 'a, 'a> BorshDeserializeBorrowed<'de> for Cow<'a, [u8]> {
+    #[inline]
+    fn deserialize_borrowed(buf: &mut &'de [u8]) -> Result<Self> {
+        Ok(Cow::Borrowed(<&'a [u8]>::deserialize_borrowed(buf)?))
+    }
+}
+
+impl<'de: 'a, 'a> BorshDeserializeBorrowed<'de> for Cow<'a, str> {
+    #[inline]
+    fn deserialize_borrowed(buf: &mut &'de [u8]) -> Result<Self> {
+        Ok(Cow::Borrowed(<&'a str>::deserialize_borrowed(buf)?))
+    }
+}
+
+impl<'de, T> BorshDeserializeBorrowed<'de> for Option<T>
+where
+    T: BorshDeserializeBorrowed<'de>,
+{
+    #[inline]
+    fn deserialize_borrowed(buf: &mut &'de [u8]) -> Result<Self> {
+        Self::deserialize_borrowed_with_limits(buf, &mut Limits::unlimited())
+    }
+
+    #[inline]
+    fn deserialize_borrowed_with_limits(buf: &mut &'de [u8], limits: &mut Limits) -> Result<Self> {
+        let flag = u8::deserialize(buf)?;
+        if flag == 0 {
+            Ok(None)
+        } else if flag == 1 {
+            Ok(Some(T::deserialize_borrowed_with_limits(buf, limits)?))
+        } else {
+            let msg = format!(
+                "Invalid Option representation: {}. The first byte must be 0 or 1",
+                flag
+            );
+
+            Err(Error::new(ErrorKind::InvalidData, msg))
+        }
+    }
+}
+
+impl<'de, T, E> BorshDeserializeBorrowed<'de> for core::result::Result<T, E>
+where
+    T: BorshDeserializeBorrowed<'de>,
+    E: BorshDeserializeBorrowed<'de>,
+{
+    #[inline]
+    fn deserialize_borrowed(buf: &mut &'de [u8]) -> Result<Self> {
+        Self::deserialize_borrowed_with_limits(buf, &mut Limits::unlimited())
+    }
+
+    #[inline]
+    fn deserialize_borrowed_with_limits(buf: &mut &'de [u8], limits: &mut Limits) -> Result<Self> {
+        let flag = u8::deserialize(buf)?;
+        if flag == 0 {
+            Ok(Err(E::deserialize_borrowed_with_limits(buf, limits)?))
+        } else if flag == 1 {
+            Ok(Ok(T::deserialize_borrowed_with_limits(buf, limits)?))
+        } else {
+            let msg = format!(
+                "Invalid Result representation: {}. The first byte must be 0 or 1",
+                flag
+            );
+
+            Err(Error::new(ErrorKind::InvalidData, msg))
+        }
+    }
+}
+
+impl<'de, T> BorshDeserializeBorrowed<'de> for Vec<T>
+where
+    T: BorshDeserializeBorrowed<'de>,
+{
+    #[inline]
+    fn deserialize_borrowed(buf: &mut &'de [u8]) -> Result<Self> {
+        Self::deserialize_borrowed_with_limits(buf, &mut Limits::unlimited())
+    }
+
+    #[inline]
+    fn deserialize_borrowed_with_limits(buf: &mut &'de [u8], limits: &mut Limits) -> Result<Self> {
+        check_zst::<T>()?;
+
+        let len = u32::deserialize(buf)?;
+        limits.reserve_collection::<T>(len)?;
+        limits.enter()?;
+        let mut result = Vec::with_capacity(hint::cautious::<T>(len));
+        for idx in 0..len {
+            result.push(
+                T::deserialize_borrowed_with_limits(buf, limits)
+                    .map_err(|err| prepend_path(err, &[PathSegment::Index(idx as usize)]))?,
+            );
+        }
+        limits.exit();
+        Ok(result)
+    }
+}
+
+impl<'de, T> BorshDeserializeBorrowed<'de> for Box<T>
+where
+    T: BorshDeserializeBorrowed<'de>,
+{
+    #[inline]
+    fn deserialize_borrowed(buf: &mut &'de [u8]) -> Result<Self> {
+        Ok(Box::new(T::deserialize_borrowed(buf)?))
+    }
+
+    #[inline]
+    fn deserialize_borrowed_with_limits(buf: &mut &'de [u8], limits: &mut Limits) -> Result<Self> {
+        limits.enter()?;
+        limits.reserve_bytes(size_of::<T>())?;
+        let result = Box::new(T::deserialize_borrowed_with_limits(buf, limits)?);
+        limits.exit();
+        Ok(result)
+    }
+}
+
+impl<'de, T, const N: usize> BorshDeserializeBorrowed<'de> for [T; N]
+where
+    T: BorshDeserializeBorrowed<'de>,
+{
+    #[inline]
+    fn deserialize_borrowed(buf: &mut &'de [u8]) -> Result<Self> {
+        Self::deserialize_borrowed_with_limits(buf, &mut Limits::unlimited())
+    }
+
+    #[inline]
+    fn deserialize_borrowed_with_limits(buf: &mut &'de [u8], limits: &mut Limits) -> Result<Self> {
+        let mut result = ArrayDropGuard::new();
+        result.fill_buffer(|idx| {
+            T::deserialize_borrowed_with_limits(buf, limits)
+                .map_err(|err| prepend_path(err, &[PathSegment::Index(idx)]))
+        })?;
+        // SAFETY: All `N` elements have been initialized in `fill_buffer`.
+        Ok(unsafe { result.transmute_to_array() })
+    }
+}
+
+impl<'de, T: ?Sized> BorshDeserializeBorrowed<'de> for PhantomData<T> {
+    #[inline]
+    fn deserialize_borrowed(_: &mut &'de [u8]) -> Result<Self> {
+        Ok(PhantomData)
+    }
+}
+
+macro_rules! impl_tuple {
+    ($($name:ident)+) => {
+      impl<'de, $($name),+> BorshDeserializeBorrowed<'de> for ($($name,)+)
+      where $($name: BorshDeserializeBorrowed<'de>,)+
+      {
+        #[inline]
+        fn deserialize_borrowed(buf: &mut &'de [u8]) -> Result<Self> {
+
+            Ok(($($name::deserialize_borrowed(buf)?,)+))
+        }
+
+        #[inline]
+        fn deserialize_borrowed_with_limits(buf: &mut &'de [u8], limits: &mut Limits) -> Result<Self> {
+
+            Ok(($($name::deserialize_borrowed_with_limits(buf, limits)?,)+))
+        }
+      }
+    };
+}
+
+impl_tuple!(T0);
+impl_tuple!(T0 T1);
+impl_tuple!(T0 T1 T2);
+impl_tuple!(T0 T1 T2 T3);
+impl_tuple!(T0 T1 T2 T3 T4);
+impl_tuple!(T0 T1 T2 T3 T4 T5);
+impl_tuple!(T0 T1 T2 T3 T4 T5 T6);
+impl_tuple!(T0 T1 T2 T3 T4 T5 T6 T7);
+impl_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8);
+impl_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9);
+impl_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10);
+impl_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11);
+impl_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11 T12);
+impl_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11 T12 T13);
+impl_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11 T12 T13 T14);
+impl_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11 T12 T13 T14 T15);
+impl_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11 T12 T13 T14 T15 T16);
+impl_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11 T12 T13 T14 T15 T16 T17);
+impl_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11 T12 T13 T14 T15 T16 T17 T18);
+impl_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11 T12 T13 T14 T15 T16 T17 T18 T19);
+
+/// Deserializes an object from a slice of bytes, borrowing from it where possible.
+/// # Example
+/// ```
+/// use borsh::{from_slice_borrowed, to_vec};
+///
+/// let encoded = to_vec(&("hello", 42u32)).unwrap();
+/// let (greeting, answer): (&str, u32) = from_slice_borrowed(&encoded).unwrap();
+/// assert_eq!(greeting, "hello");
+/// assert_eq!(answer, 42);
+/// ```
+/// # Errors
+/// If the data is invalid, this function will return an error.
+/// # Note
+/// This function will return an error if the data is not fully read.
+pub fn from_slice_borrowed<'de, T: BorshDeserializeBorrowed<'de>>(v: &'de [u8]) -> Result<T> {
+    T::try_from_slice_borrowed(v)
+}
+
+/// Deserializes an object from a slice of bytes, borrowing from it where possible,
+/// and enforcing limits from `options`.
+///
+/// See [`from_slice_with_options`](crate::from_slice_with_options).
+/// # Example
+/// ```
+/// use borsh::{de::DeserializeOptions, from_slice_borrowed_with_options, to_vec};
+///
+/// let options = DeserializeOptions::new().max_collection_len(2);
+/// let encoded = to_vec(&vec!["a", "b", "c"]).unwrap();
+/// let err = from_slice_borrowed_with_options::<Vec<&str>>(&encoded, &options).unwrap_err();
+/// assert_eq!(err.to_string(), "Collection length 3 exceeds limit of 2");
+/// ```
+/// # Errors
+/// If the data is invalid or exceeds any of the limits, this function will return an error.
+/// # Note
+/// This function will return an error if the data is not fully read.
+pub fn from_slice_borrowed_with_options<'de, T: BorshDeserializeBorrowed<'de>>(
+    v: &'de [u8],
+    options: &DeserializeOptions,
+) -> Result<T> {
+    let mut v_mut = v;
+    let mut limits = Limits::new(*options);
+    let result = T::deserialize_borrowed_with_limits(&mut v_mut, &mut limits)
+        .map_err(|err| set_offset(err, v.len() - v_mut.len()))?;
+    if !v_mut.is_empty() {
+        return Err(not_all_bytes_read(v.len() - v_mut.len()));
+    }
+    Ok(result)
+}
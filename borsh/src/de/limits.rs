@@ -0,0 +1,215 @@
+use core::mem::size_of;
+
+use crate::__private::maybestd::format;
+use crate::io::{Error, ErrorKind, Result};
+
+const ERROR_MAX_DEPTH_EXCEEDED: &str = "Maximum depth of nested values exceeded";
+const ERROR_MAX_ALLOCATION_EXCEEDED: &str = "Maximum total allocation exceeded";
+
+/// Limits, enforced when deserializing untrusted input with
+/// [`from_slice_with_options`](crate::from_slice_with_options) or
+/// [`from_reader_with_options`](crate::from_reader_with_options).
+///
+/// All limits are unset by default.
+///
+/// ```
+/// use borsh::{de::DeserializeOptions, from_slice_with_options, to_vec};
+///
+/// let options = DeserializeOptions::new()
+///     .max_depth(16)
+///     .max_allocation(1024)
+///     .max_collection_len(100)
+///     .max_string_len(64);
+///
+/// let encoded = to_vec(&vec![vec![0u8; 10]; 10]).unwrap();
+/// assert!(from_slice_with_options::<Vec<Vec<u8>>>(&encoded, &options).is_ok());
+///
+/// let encoded = to_vec(&vec![0u32; 1000]).unwrap();
+/// assert!(from_slice_with_options::<Vec<u32>>(&encoded, &options).is_err());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DeserializeOptions {
+    max_depth: usize,
+    max_allocation: usize,
+    max_collection_len: u32,
+    max_string_len: u32,
+}
+
+impl DeserializeOptions {
+    /// Options without any limits.
+    pub const fn new() -> Self {
+        Self {
+            max_depth: usize::MAX,
+            max_allocation: usize::MAX,
+            max_collection_len: u32::MAX,
+            max_string_len: u32::MAX,
+        }
+    }
+
+    /// Maximum nesting depth of derived structs and enums, boxes and collections.
+    pub const fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Maximum number of bytes, allocated on heap for all deserialized collections,
+    /// strings and boxes in total.
+    ///
+    /// Space for a collection is accounted for as soon as its length is read,
+    /// based on the declared number of elements.
+    pub const fn max_allocation(mut self, max_allocation: usize) -> Self {
+        self.max_allocation = max_allocation;
+        self
+    }
+
+    /// Maximum number of elements in any single collection.
+    pub const fn max_collection_len(mut self, max_collection_len: u32) -> Self {
+        self.max_collection_len = max_collection_len;
+        self
+    }
+
+    /// Maximum length of any single string in bytes.
+    pub const fn max_string_len(mut self, max_string_len: u32) -> Self {
+        self.max_string_len = max_string_len;
+        self
+    }
+}
+
+impl Default for DeserializeOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// State of [`DeserializeOptions`] enforcement during a single deserialization.
+///
+/// It is passed to [`BorshDeserialize::deserialize_reader_with_limits`](crate::BorshDeserialize::deserialize_reader_with_limits),
+/// and is meant to be used by manual impls of the trait, which allocate or nest values.
+#[derive(Clone, Debug)]
+pub struct Limits {
+    options: DeserializeOptions,
+    depth: usize,
+    allocated: usize,
+}
+
+impl Limits {
+    /// Limits, which never fail.
+    pub const fn unlimited() -> Self {
+        Self::new(DeserializeOptions::new())
+    }
+
+    /// Fresh state of enforcing `options`, for a single deserialization
+    /// at zero depth with nothing allocated yet.
+    pub const fn new(options: DeserializeOptions) -> Self {
+        Self {
+            options,
+            depth: 0,
+            allocated: 0,
+        }
+    }
+
+    /// Current nesting depth.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Total number of bytes, accounted for so far.
+    pub fn allocated(&self) -> usize {
+        self.allocated
+    }
+
+    /// Enters a nested value, should be paired with [`Limits::exit`].
+    #[inline]
+    pub fn enter(&mut self) -> Result<()> {
+        if self.depth >= self.options.max_depth {
+            return Err(Error::new(ErrorKind::InvalidData, ERROR_MAX_DEPTH_EXCEEDED));
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    /// Exits a nested value, entered with [`Limits::enter`].
+    #[inline]
+    pub fn exit(&mut self) {
+        self.depth = self.depth.saturating_sub(1);
+    }
+
+    /// Accounts for a collection of `len` elements of type `T`.
+    #[inline]
+    pub fn reserve_collection<T>(&mut self, len: u32) -> Result<()> {
+        if len > self.options.max_collection_len {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "Collection length {} exceeds limit of {}",
+                    len, self.options.max_collection_len
+                ),
+            ));
+        }
+        self.reserve_bytes((len as usize).saturating_mul(size_of::<T>()))
+    }
+
+    /// Accounts for a string of `len` bytes.
+    #[inline]
+    pub fn reserve_string(&mut self, len: u32) -> Result<()> {
+        if len > self.options.max_string_len {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "String length {} exceeds limit of {}",
+                    len, self.options.max_string_len
+                ),
+            ));
+        }
+        self.reserve_bytes(len as usize)
+    }
+
+    /// Accounts for `bytes` of heap allocation.
+    #[inline]
+    pub fn reserve_bytes(&mut self, bytes: usize) -> Result<()> {
+        match self.allocated.checked_add(bytes) {
+            Some(allocated) if allocated <= self.options.max_allocation => {
+                self.allocated = allocated;
+                Ok(())
+            }
+            _ => Err(Error::new(
+                ErrorKind::InvalidData,
+                ERROR_MAX_ALLOCATION_EXCEEDED,
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_depth() {
+        let mut limits = Limits::new(DeserializeOptions::new().max_depth(2));
+        limits.enter().unwrap();
+        limits.enter().unwrap();
+        assert!(limits.enter().is_err());
+        limits.exit();
+        limits.enter().unwrap();
+        assert_eq!(limits.depth(), 2);
+    }
+
+    #[test]
+    fn test_allocation() {
+        let mut limits = Limits::new(
+            DeserializeOptions::new()
+                .max_allocation(100)
+                .max_collection_len(20)
+                .max_string_len(10),
+        );
+        limits.reserve_collection::<u32>(20).unwrap();
+        assert!(limits.reserve_collection::<u8>(21).is_err());
+        assert!(limits.reserve_string(11).is_err());
+        limits.reserve_string(10).unwrap();
+        assert_eq!(limits.allocated(), 90);
+        assert!(limits.reserve_bytes(11).is_err());
+        limits.reserve_bytes(10).unwrap();
+        assert!(Limits::unlimited().reserve_bytes(usize::MAX).is_ok());
+    }
+}
@@ -21,9 +21,20 @@ use crate::io::{Error, ErrorKind, Read, Result};
 
 #[cfg(feature = "rc")]
 use crate::__private::maybestd::{rc::Rc, sync::Arc};
-use crate::error::check_zst;
+use crate::error::{check_zst, prepend_path, set_offset, PathSegment};
 
+#[cfg(async_io)]
+mod asynchronous;
+mod borrowed;
 mod hint;
+mod limits;
+
+#[cfg(async_io)]
+pub use asynchronous::BorshDeserializeAsync;
+pub use borrowed::{
+    from_slice_borrowed, from_slice_borrowed_with_options, BorshDeserializeBorrowed,
+};
+pub use limits::{DeserializeOptions, Limits};
 
 const ERROR_NOT_ALL_BYTES_READ: &str = "Not all bytes read";
 const ERROR_UNEXPECTED_LENGTH_OF_INPUT: &str = "Unexpected length of input";
@@ -34,6 +45,42 @@ const ERROR_INVALID_ZERO_VALUE: &str = "Expected a non-zero value";
 #[cfg(feature = "de_strict_order")]
 const ERROR_WRONG_ORDER_OF_KEYS: &str = "keys were not serialized in ascending order";
 
+/// Error for input, which still has unread bytes at `offset` after the value was decoded.
+pub(crate) fn not_all_bytes_read(offset: usize) -> Error {
+    set_offset(
+        Error::new(ErrorKind::InvalidData, ERROR_NOT_ALL_BYTES_READ),
+        offset,
+    )
+}
+
+/// Reader adaptor, which counts consumed bytes to report offset of a failure.
+struct CountingReader<'a, R: ?Sized> {
+    inner: &'a mut R,
+    count: usize,
+}
+
+impl<'a, R: Read + ?Sized> CountingReader<'a, R> {
+    fn new(inner: &'a mut R) -> Self {
+        Self { inner, count: 0 }
+    }
+}
+
+impl<R: Read + ?Sized> Read for CountingReader<'_, R> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.count += read;
+        Ok(read)
+    }
+
+    #[inline]
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result<()> {
+        self.inner.read_exact(buf)?;
+        self.count += buf.len();
+        Ok(())
+    }
+}
+
 /// A data-structure that can be de-serialized from binary format by NBOR.
 pub trait BorshDeserialize: Sized {
     /// Deserializes this instance from a given slice of bytes.
@@ -44,22 +91,41 @@ pub trait BorshDeserialize: Sized {
 
     fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self>;
 
+    /// Deserializes this instance from a reader, enforcing `limits`.
+    ///
+    /// Default implementation ignores `limits`, which is fine for types, which neither
+    /// allocate nor contain other values. Impls for containers and recursive types should
+    /// account for their allocations and nesting with [`Limits`], and pass it on to
+    /// this method of their elements.
+    #[inline]
+    fn deserialize_reader_with_limits<R: Read>(
+        reader: &mut R,
+        limits: &mut Limits,
+    ) -> Result<Self> {
+        let _ = limits;
+        Self::deserialize_reader(reader)
+    }
+
     /// Deserialize this instance from a slice of bytes.
     fn try_from_slice(v: &[u8]) -> Result<Self> {
         let mut v_mut = v;
-        let result = Self::deserialize(&mut v_mut)?;
+        let result =
+            Self::deserialize(&mut v_mut).map_err(|err| set_offset(err, v.len() - v_mut.len()))?;
         if !v_mut.is_empty() {
-            return Err(Error::new(ErrorKind::InvalidData, ERROR_NOT_ALL_BYTES_READ));
+            return Err(not_all_bytes_read(v.len() - v_mut.len()));
         }
         Ok(result)
     }
 
     fn try_from_reader<R: Read>(reader: &mut R) -> Result<Self> {
-        let result = Self::deserialize_reader(reader)?;
+        let mut counting = CountingReader::new(reader);
+        let result = Self::deserialize_reader(&mut counting)
+            .map_err(|err| set_offset(err, counting.count))?;
+        let consumed = counting.count;
         let mut buf = [0u8; 1];
         match reader.read_exact(&mut buf) {
             Err(f) if f.kind() == ErrorKind::UnexpectedEof => Ok(result),
-            _ => Err(Error::new(ErrorKind::InvalidData, ERROR_NOT_ALL_BYTES_READ)),
+            _ => Err(not_all_bytes_read(consumed)),
         }
     }
 
@@ -80,7 +146,10 @@ pub trait BorshDeserialize: Sized {
 }
 
 /// Additional methods offered on enums which is used by `[derive(BorshDeserialize)]`.
-pub trait EnumExt: BorshDeserialize {
+///
+/// Enums with `#[borsh(untagged)]` only implement this trait and not [`BorshDeserialize`],
+/// as their tag has to be known from elsewhere.
+pub trait EnumExt: Sized {
     /// Deserialises given variant of an enum from the reader.
     ///
     /// This may be used to perform validation or filtering based on what
@@ -135,9 +204,22 @@ pub trait EnumExt: BorshDeserialize {
     /// assert!(from_slice::<OneOrZero>(&data[..]).is_err());
     /// ```
     fn deserialize_variant<R: Read>(reader: &mut R, tag: u8) -> Result<Self>;
+
+    /// Deserialises given variant of an enum from the reader, enforcing `limits`.
+    ///
+    /// See [`BorshDeserialize::deserialize_reader_with_limits`].
+    #[inline]
+    fn deserialize_variant_with_limits<R: Read>(
+        reader: &mut R,
+        tag: u8,
+        limits: &mut Limits,
+    ) -> Result<Self> {
+        let _ = limits;
+        Self::deserialize_variant(reader, tag)
+    }
 }
 
-fn unexpected_eof_to_unexpected_length_of_input(e: Error) -> Error {
+pub(crate) fn unexpected_eof_to_unexpected_length_of_input(e: Error) -> Error {
     if e.kind() == ErrorKind::UnexpectedEof {
         Error::new(ErrorKind::InvalidData, ERROR_UNEXPECTED_LENGTH_OF_INPUT)
     } else {
@@ -158,31 +240,7 @@ impl BorshDeserialize for u8 {
     #[inline]
     #[doc(hidden)]
     fn vec_from_reader<R: Read>(len: u32, reader: &mut R) -> Result<Option<Vec<Self>>> {
-        let len: usize = len.try_into().map_err(|_| ErrorKind::InvalidData)?;
-        // Avoid OOM by limiting the size of allocation.  This makes the read
-        // less efficient (since we need to loop and reallocate) but it protects
-        // us from someone sending us [0xff, 0xff, 0xff, 0xff] and forcing us to
-        // allocate 4GiB of memory.
-        let mut vec = vec![0u8; len.min(1024 * 1024)];
-        let mut pos = 0;
-        while pos < len {
-            if pos == vec.len() {
-                vec.resize(vec.len().saturating_mul(2).min(len), 0)
-            }
-            // TODO(mina86): Convert this to read_buf once that stabilises.
-            match reader.read(&mut vec.as_mut_slice()[pos..])? {
-                0 => {
-                    return Err(Error::new(
-                        ErrorKind::InvalidData,
-                        ERROR_UNEXPECTED_LENGTH_OF_INPUT,
-                    ))
-                }
-                read => {
-                    pos += read;
-                }
-            }
-        }
-        Ok(Some(vec))
+        read_bytes(len, reader).map(Some)
     }
 
     #[inline]
@@ -196,6 +254,35 @@ impl BorshDeserialize for u8 {
     }
 }
 
+/// Reads `len` bytes from the reader.
+fn read_bytes<R: Read>(len: u32, reader: &mut R) -> Result<Vec<u8>> {
+    let len: usize = len.try_into().map_err(|_| ErrorKind::InvalidData)?;
+    // Avoid OOM by limiting the size of allocation.  This makes the read
+    // less efficient (since we need to loop and reallocate) but it protects
+    // us from someone sending us [0xff, 0xff, 0xff, 0xff] and forcing us to
+    // allocate 4GiB of memory.
+    let mut vec = vec![0u8; len.min(1024 * 1024)];
+    let mut pos = 0;
+    while pos < len {
+        if pos == vec.len() {
+            vec.resize(vec.len().saturating_mul(2).min(len), 0)
+        }
+        // TODO(mina86): Convert this to read_buf once that stabilises.
+        match reader.read(&mut vec.as_mut_slice()[pos..])? {
+            0 => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    ERROR_UNEXPECTED_LENGTH_OF_INPUT,
+                ))
+            }
+            read => {
+                pos += read;
+            }
+        }
+    }
+    Ok(vec)
+}
+
 macro_rules! impl_for_integer {
     ($type: ident) => {
         impl BorshDeserialize for $type {
@@ -321,11 +408,19 @@ where
 {
     #[inline]
     fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        Self::deserialize_reader_with_limits(reader, &mut Limits::unlimited())
+    }
+
+    #[inline]
+    fn deserialize_reader_with_limits<R: Read>(
+        reader: &mut R,
+        limits: &mut Limits,
+    ) -> Result<Self> {
         let flag: u8 = BorshDeserialize::deserialize_reader(reader)?;
         if flag == 0 {
             Ok(None)
         } else if flag == 1 {
-            Ok(Some(T::deserialize_reader(reader)?))
+            Ok(Some(T::deserialize_reader_with_limits(reader, limits)?))
         } else {
             let msg = format!(
                 "Invalid Option representation: {}. The first byte must be 0 or 1",
@@ -344,11 +439,19 @@ where
 {
     #[inline]
     fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        Self::deserialize_reader_with_limits(reader, &mut Limits::unlimited())
+    }
+
+    #[inline]
+    fn deserialize_reader_with_limits<R: Read>(
+        reader: &mut R,
+        limits: &mut Limits,
+    ) -> Result<Self> {
         let flag: u8 = BorshDeserialize::deserialize_reader(reader)?;
         if flag == 0 {
-            Ok(Err(E::deserialize_reader(reader)?))
+            Ok(Err(E::deserialize_reader_with_limits(reader, limits)?))
         } else if flag == 1 {
-            Ok(Ok(T::deserialize_reader(reader)?))
+            Ok(Ok(T::deserialize_reader_with_limits(reader, limits)?))
         } else {
             let msg = format!(
                 "Invalid Result representation: {}. The first byte must be 0 or 1",
@@ -363,7 +466,17 @@ where
 impl BorshDeserialize for String {
     #[inline]
     fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
-        String::from_utf8(Vec::<u8>::deserialize_reader(reader)?).map_err(|err| {
+        Self::deserialize_reader_with_limits(reader, &mut Limits::unlimited())
+    }
+
+    #[inline]
+    fn deserialize_reader_with_limits<R: Read>(
+        reader: &mut R,
+        limits: &mut Limits,
+    ) -> Result<Self> {
+        let len = u32::deserialize_reader(reader)?;
+        limits.reserve_string(len)?;
+        String::from_utf8(read_bytes(len, reader)?).map_err(|err| {
             let msg = err.to_string();
             Error::new(ErrorKind::InvalidData, msg)
         })
@@ -376,19 +489,33 @@ where
 {
     #[inline]
     fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        Self::deserialize_reader_with_limits(reader, &mut Limits::unlimited())
+    }
+
+    #[inline]
+    fn deserialize_reader_with_limits<R: Read>(
+        reader: &mut R,
+        limits: &mut Limits,
+    ) -> Result<Self> {
         check_zst::<T>()?;
 
         let len = u32::deserialize_reader(reader)?;
+        limits.reserve_collection::<T>(len)?;
         if len == 0 {
             Ok(Vec::new())
         } else if let Some(vec_bytes) = T::vec_from_reader(len, reader)? {
             Ok(vec_bytes)
         } else {
+            limits.enter()?;
             // TODO(16): return capacity allocation when we can safely do that.
             let mut result = Vec::with_capacity(hint::cautious::<T>(len));
-            for _ in 0..len {
-                result.push(T::deserialize_reader(reader)?);
+            for idx in 0..len {
+                result.push(
+                    T::deserialize_reader_with_limits(reader, limits)
+                        .map_err(|err| prepend_path(err, &[PathSegment::Index(idx as usize)]))?,
+                );
             }
+            limits.exit();
             Ok(result)
         }
     }
@@ -398,7 +525,15 @@ where
 impl BorshDeserialize for bytes::Bytes {
     #[inline]
     fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
-        let vec = <Vec<u8>>::deserialize_reader(reader)?;
+        Self::deserialize_reader_with_limits(reader, &mut Limits::unlimited())
+    }
+
+    #[inline]
+    fn deserialize_reader_with_limits<R: Read>(
+        reader: &mut R,
+        limits: &mut Limits,
+    ) -> Result<Self> {
+        let vec = <Vec<u8>>::deserialize_reader_with_limits(reader, limits)?;
         Ok(vec.into())
     }
 }
@@ -407,7 +542,16 @@ impl BorshDeserialize for bytes::Bytes {
 impl BorshDeserialize for bytes::BytesMut {
     #[inline]
     fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        Self::deserialize_reader_with_limits(reader, &mut Limits::unlimited())
+    }
+
+    #[inline]
+    fn deserialize_reader_with_limits<R: Read>(
+        reader: &mut R,
+        limits: &mut Limits,
+    ) -> Result<Self> {
         let len = u32::deserialize_reader(reader)?;
+        limits.reserve_collection::<u8>(len)?;
         let mut out = BytesMut::with_capacity(hint::cautious::<u8>(len));
         for _ in 0..len {
             out.put_u8(u8::deserialize_reader(reader)?);
@@ -433,7 +577,17 @@ where
 {
     #[inline]
     fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
-        Ok(Cow::Owned(BorshDeserialize::deserialize_reader(reader)?))
+        Self::deserialize_reader_with_limits(reader, &mut Limits::unlimited())
+    }
+
+    #[inline]
+    fn deserialize_reader_with_limits<R: Read>(
+        reader: &mut R,
+        limits: &mut Limits,
+    ) -> Result<Self> {
+        Ok(Cow::Owned(
+            BorshDeserialize::deserialize_reader_with_limits(reader, limits)?,
+        ))
     }
 }
 
@@ -443,7 +597,15 @@ where
 {
     #[inline]
     fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
-        let vec = <Vec<T>>::deserialize_reader(reader)?;
+        Self::deserialize_reader_with_limits(reader, &mut Limits::unlimited())
+    }
+
+    #[inline]
+    fn deserialize_reader_with_limits<R: Read>(
+        reader: &mut R,
+        limits: &mut Limits,
+    ) -> Result<Self> {
+        let vec = <Vec<T>>::deserialize_reader_with_limits(reader, limits)?;
         Ok(vec.into())
     }
 }
@@ -454,14 +616,22 @@ where
 {
     #[inline]
     fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
-        let vec = <Vec<T>>::deserialize_reader(reader)?;
+        Self::deserialize_reader_with_limits(reader, &mut Limits::unlimited())
+    }
+
+    #[inline]
+    fn deserialize_reader_with_limits<R: Read>(
+        reader: &mut R,
+        limits: &mut Limits,
+    ) -> Result<Self> {
+        let vec = <Vec<T>>::deserialize_reader_with_limits(reader, limits)?;
         Ok(vec.into_iter().collect::<LinkedList<T>>())
     }
 }
 
-/// Module is available if borsh is built with `features = ["std"]` or `features = ["hashbrown"]`.
 #[cfg(hash_collections)]
 pub mod hashes {
+    //! Module is available if borsh is built with `features = ["std"]` or `features = ["hashbrown"]`.
     //!
     //! Module defines [BorshDeserialize](crate::de::BorshDeserialize) implementation for
     //! [HashMap](std::collections::HashMap)/[HashSet](std::collections::HashSet).
@@ -470,6 +640,7 @@ pub mod hashes {
     use crate::BorshDeserialize;
     use crate::__private::maybestd::collections::{HashMap, HashSet};
     use crate::__private::maybestd::vec::Vec;
+    use crate::de::Limits;
     use crate::io::{Read, Result};
 
     #[cfg(feature = "de_strict_order")]
@@ -485,11 +656,19 @@ pub mod hashes {
     {
         #[inline]
         fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+            Self::deserialize_reader_with_limits(reader, &mut Limits::unlimited())
+        }
+
+        #[inline]
+        fn deserialize_reader_with_limits<R: Read>(
+            reader: &mut R,
+            limits: &mut Limits,
+        ) -> Result<Self> {
             // NOTE: deserialize-as-you-go approach as once was in HashSet is better in the sense
             // that it allows to fail early, and not allocate memory for all the elements
             // which may fail `cmp()` checks
             // NOTE: deserialize first to `Vec<T>` is faster
-            let vec = <Vec<T>>::deserialize_reader(reader)?;
+            let vec = <Vec<T>>::deserialize_reader_with_limits(reader, limits)?;
 
             #[cfg(feature = "de_strict_order")]
             // TODO: replace with `is_sorted` api when stabilizes https://github.com/rust-lang/rust/issues/53485
@@ -519,12 +698,20 @@ pub mod hashes {
     {
         #[inline]
         fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+            Self::deserialize_reader_with_limits(reader, &mut Limits::unlimited())
+        }
+
+        #[inline]
+        fn deserialize_reader_with_limits<R: Read>(
+            reader: &mut R,
+            limits: &mut Limits,
+        ) -> Result<Self> {
             check_zst::<K>()?;
             // NOTE: deserialize-as-you-go approach as once was in HashSet is better in the sense
             // that it allows to fail early, and not allocate memory for all the entries
             // which may fail `cmp()` checks
             // NOTE: deserialize first to `Vec<(K, V)>` is faster
-            let vec = <Vec<(K, V)>>::deserialize_reader(reader)?;
+            let vec = <Vec<(K, V)>>::deserialize_reader_with_limits(reader, limits)?;
 
             #[cfg(feature = "de_strict_order")]
             // TODO: replace with `is_sorted` api when stabilizes https://github.com/rust-lang/rust/issues/53485
@@ -553,11 +740,19 @@ where
 {
     #[inline]
     fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        Self::deserialize_reader_with_limits(reader, &mut Limits::unlimited())
+    }
+
+    #[inline]
+    fn deserialize_reader_with_limits<R: Read>(
+        reader: &mut R,
+        limits: &mut Limits,
+    ) -> Result<Self> {
         // NOTE: deserialize-as-you-go approach as once was in HashSet is better in the sense
         // that it allows to fail early, and not allocate memory for all the elements
         // which may fail `cmp()` checks
         // NOTE: deserialize first to `Vec<T>` is faster
-        let vec = <Vec<T>>::deserialize_reader(reader)?;
+        let vec = <Vec<T>>::deserialize_reader_with_limits(reader, limits)?;
 
         #[cfg(feature = "de_strict_order")]
         // TODO: replace with `is_sorted` api when stabilizes https://github.com/rust-lang/rust/issues/53485
@@ -587,12 +782,20 @@ where
 {
     #[inline]
     fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        Self::deserialize_reader_with_limits(reader, &mut Limits::unlimited())
+    }
+
+    #[inline]
+    fn deserialize_reader_with_limits<R: Read>(
+        reader: &mut R,
+        limits: &mut Limits,
+    ) -> Result<Self> {
         check_zst::<K>()?;
         // NOTE: deserialize-as-you-go approach as once was in HashSet is better in the sense
         // that it allows to fail early, and not allocate memory for all the entries
         // which may fail `cmp()` checks
         // NOTE: deserialize first to `Vec<(K, V)>` is faster
-        let vec = <Vec<(K, V)>>::deserialize_reader(reader)?;
+        let vec = <Vec<(K, V)>>::deserialize_reader_with_limits(reader, limits)?;
 
         #[cfg(feature = "de_strict_order")]
         // TODO: replace with `is_sorted` api when stabilizes https://github.com/rust-lang/rust/issues/53485
@@ -683,7 +886,62 @@ where
     T::Owned: BorshDeserialize,
 {
     fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
-        Ok(T::Owned::deserialize_reader(reader)?.into())
+        Self::deserialize_reader_with_limits(reader, &mut Limits::unlimited())
+    }
+
+    fn deserialize_reader_with_limits<R: Read>(
+        reader: &mut R,
+        limits: &mut Limits,
+    ) -> Result<Self> {
+        limits.enter()?;
+        limits.reserve_bytes(size_of::<U>())?;
+        let result = T::Owned::deserialize_reader_with_limits(reader, limits)?.into();
+        limits.exit();
+        Ok(result)
+    }
+}
+
+/// Array, which is filled element by element, dropping the initialized elements,
+/// if it isn't filled up, e.g. due to an error.
+struct ArrayDropGuard<T, const N: usize> {
+    buffer: [MaybeUninit<T>; N],
+    init_count: usize,
+}
+impl<T, const N: usize> Drop for ArrayDropGuard<T, N> {
+    fn drop(&mut self) {
+        let init_range = &mut self.buffer[..self.init_count];
+        // SAFETY: Elements up to self.init_count have been initialized. Assumes this value
+        //         is only incremented in `fill_buffer`, which writes the element before
+        //         increasing the init_count.
+        unsafe {
+            core::ptr::drop_in_place(init_range as *mut _ as *mut [T]);
+        };
+    }
+}
+impl<T, const N: usize> ArrayDropGuard<T, N> {
+    fn new() -> Self {
+        ArrayDropGuard {
+            buffer: unsafe { MaybeUninit::uninit().assume_init() },
+            init_count: 0,
+        }
+    }
+    unsafe fn transmute_to_array(mut self) -> [T; N] {
+        debug_assert_eq!(self.init_count, N);
+        // Set init_count to 0 so that the values do not get dropped twice.
+        self.init_count = 0;
+        // SAFETY: This cast is required because `mem::transmute` does not work with
+        //         const generics https://github.com/rust-lang/rust/issues/61956. This
+        //         array is guaranteed to be initialized by this point.
+        core::ptr::read(&self.buffer as *const _ as *const [T; N])
+    }
+    fn fill_buffer(&mut self, mut f: impl FnMut(usize) -> Result<T>) -> Result<()> {
+        // TODO: replace with `core::array::try_from_fn` when stabilized to avoid manually
+        // dropping uninitialized values through the guard drop.
+        for (idx, elem) in self.buffer.iter_mut().enumerate() {
+            elem.write(f(idx)?);
+            self.init_count += 1;
+        }
+        Ok(())
     }
 }
 
@@ -693,51 +951,23 @@ where
 {
     #[inline]
     fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
-        struct ArrayDropGuard<T, const N: usize> {
-            buffer: [MaybeUninit<T>; N],
-            init_count: usize,
-        }
-        impl<T, const N: usize> Drop for ArrayDropGuard<T, N> {
-            fn drop(&mut self) {
-                let init_range = &mut self.buffer[..self.init_count];
-                // SAFETY: Elements up to self.init_count have been initialized. Assumes this value
-                //         is only incremented in `fill_buffer`, which writes the element before
-                //         increasing the init_count.
-                unsafe {
-                    core::ptr::drop_in_place(init_range as *mut _ as *mut [T]);
-                };
-            }
-        }
-        impl<T, const N: usize> ArrayDropGuard<T, N> {
-            unsafe fn transmute_to_array(mut self) -> [T; N] {
-                debug_assert_eq!(self.init_count, N);
-                // Set init_count to 0 so that the values do not get dropped twice.
-                self.init_count = 0;
-                // SAFETY: This cast is required because `mem::transmute` does not work with
-                //         const generics https://github.com/rust-lang/rust/issues/61956. This
-                //         array is guaranteed to be initialized by this point.
-                core::ptr::read(&self.buffer as *const _ as *const [T; N])
-            }
-            fn fill_buffer(&mut self, mut f: impl FnMut() -> Result<T>) -> Result<()> {
-                // TODO: replace with `core::array::try_from_fn` when stabilized to avoid manually
-                // dropping uninitialized values through the guard drop.
-                for elem in self.buffer.iter_mut() {
-                    elem.write(f()?);
-                    self.init_count += 1;
-                }
-                Ok(())
-            }
-        }
+        Self::deserialize_reader_with_limits(reader, &mut Limits::unlimited())
+    }
 
+    #[inline]
+    fn deserialize_reader_with_limits<R: Read>(
+        reader: &mut R,
+        limits: &mut Limits,
+    ) -> Result<Self> {
         if let Some(arr) = T::array_from_reader(reader)? {
             Ok(arr)
         } else {
-            let mut result = ArrayDropGuard {
-                buffer: unsafe { MaybeUninit::uninit().assume_init() },
-                init_count: 0,
-            };
+            let mut result = ArrayDropGuard::new();
 
-            result.fill_buffer(|| T::deserialize_reader(reader))?;
+            result.fill_buffer(|idx| {
+                T::deserialize_reader_with_limits(reader, limits)
+                    .map_err(|err| prepend_path(err, &[PathSegment::Index(idx)]))
+            })?;
 
             // SAFETY: The elements up to `i` have been initialized in `fill_buffer`.
             Ok(unsafe { result.transmute_to_array() })
@@ -752,7 +982,7 @@ fn array_deserialization_doesnt_leak() {
     static DESERIALIZE_COUNT: AtomicUsize = AtomicUsize::new(0);
     static DROP_COUNT: AtomicUsize = AtomicUsize::new(0);
 
-    struct MyType(u8);
+    struct MyType(#[allow(unused)] u8);
     impl BorshDeserialize for MyType {
         fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
             let val = u8::deserialize_reader(reader)?;
@@ -805,8 +1035,13 @@ macro_rules! impl_tuple {
       {
         #[inline]
         fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+            Self::deserialize_reader_with_limits(reader, &mut Limits::unlimited())
+        }
 
-            Ok(($($name::deserialize_reader(reader)?,)+))
+        #[inline]
+        fn deserialize_reader_with_limits<R: Read>(reader: &mut R, limits: &mut Limits) -> Result<Self> {
+
+            Ok(($($name::deserialize_reader_with_limits(reader, limits)?,)+))
         }
       }
     };
@@ -841,7 +1076,15 @@ macro_rules! impl_range {
         impl<T: BorshDeserialize> BorshDeserialize for core::ops::$type<T> {
             #[inline]
             fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
-                let ($($side,)*) = <_>::deserialize_reader(reader)?;
+                Self::deserialize_reader_with_limits(reader, &mut Limits::unlimited())
+            }
+
+            #[inline]
+            fn deserialize_reader_with_limits<R: Read>(
+                reader: &mut R,
+                limits: &mut Limits,
+            ) -> Result<Self> {
+                let ($($side,)*) = <_>::deserialize_reader_with_limits(reader, limits)?;
                 Ok($make)
             }
         }
@@ -860,7 +1103,14 @@ where
     Box<T>: BorshDeserialize,
 {
     fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
-        Ok(Box::<T>::deserialize_reader(reader)?.into())
+        Self::deserialize_reader_with_limits(reader, &mut Limits::unlimited())
+    }
+
+    fn deserialize_reader_with_limits<R: Read>(
+        reader: &mut R,
+        limits: &mut Limits,
+    ) -> Result<Self> {
+        Ok(Box::<T>::deserialize_reader_with_limits(reader, limits)?.into())
     }
 }
 
@@ -870,7 +1120,14 @@ where
     Box<T>: BorshDeserialize,
 {
     fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
-        Ok(Box::<T>::deserialize_reader(reader)?.into())
+        Self::deserialize_reader_with_limits(reader, &mut Limits::unlimited())
+    }
+
+    fn deserialize_reader_with_limits<R: Read>(
+        reader: &mut R,
+        limits: &mut Limits,
+    ) -> Result<Self> {
+        Ok(Box::<T>::deserialize_reader_with_limits(reader, limits)?.into())
     }
 }
 
@@ -909,12 +1166,10 @@ impl<T: ?Sized> BorshDeserialize for PhantomData<T> {
 /// This function will return an error if the data is not fully read.
 pub fn from_slice<T: BorshDeserialize>(v: &[u8]) -> Result<T> {
     let mut v_mut = v;
-    let object = T::deserialize(&mut v_mut)?;
+    let object =
+        T::deserialize(&mut v_mut).map_err(|err| set_offset(err, v.len() - v_mut.len()))?;
     if !v_mut.is_empty() {
-        return Err(Error::new(
-            ErrorKind::InvalidData,
-            crate::de::ERROR_NOT_ALL_BYTES_READ,
-        ));
+        return Err(not_all_bytes_read(v.len() - v_mut.len()));
     }
     Ok(object)
 }
@@ -942,10 +1197,62 @@ pub fn from_slice<T: BorshDeserialize>(v: &[u8]) -> Result<T> {
 /// assert_eq!(original, decoded);
 /// ```
 pub fn from_reader<R: Read, T: BorshDeserialize>(reader: &mut R) -> Result<T> {
-    let result = T::deserialize_reader(reader)?;
+    let mut counting = CountingReader::new(reader);
+    let result =
+        T::deserialize_reader(&mut counting).map_err(|err| set_offset(err, counting.count))?;
+    let consumed = counting.count;
+    let mut buf = [0u8; 1];
+    match reader.read_exact(&mut buf) {
+        Err(f) if f.kind() == ErrorKind::UnexpectedEof => Ok(result),
+        _ => Err(not_all_bytes_read(consumed)),
+    }
+}
+
+/// Deserializes an object from a slice of bytes, enforcing limits from `options`.
+///
+/// Use it for untrusted input, to bound the memory and the stack space, which decoding may take.
+/// # Example
+/// ```
+/// use borsh::{de::DeserializeOptions, from_slice_with_options, to_vec};
+///
+/// let options = DeserializeOptions::new().max_string_len(4);
+/// let encoded = to_vec(&vec![String::from("abc"), String::from("abcde")]).unwrap();
+/// let err = from_slice_with_options::<Vec<String>>(&encoded, &options).unwrap_err();
+/// assert_eq!(err.to_string(), "String length 5 exceeds limit of 4");
+/// ```
+/// # Errors
+/// If the data is invalid or exceeds any of the limits, this function will return an error.
+/// # Note
+/// This function will return an error if the data is not fully read.
+pub fn from_slice_with_options<T: BorshDeserialize>(
+    v: &[u8],
+    options: &DeserializeOptions,
+) -> Result<T> {
+    let mut v_mut = v;
+    let mut limits = Limits::new(*options);
+    let object = T::deserialize_reader_with_limits(&mut v_mut, &mut limits)
+        .map_err(|err| set_offset(err, v.len() - v_mut.len()))?;
+    if !v_mut.is_empty() {
+        return Err(not_all_bytes_read(v.len() - v_mut.len()));
+    }
+    Ok(object)
+}
+
+/// Deserializes an object from a reader, enforcing limits from `options`.
+///
+/// See [`from_slice_with_options`].
+pub fn from_reader_with_options<R: Read, T: BorshDeserialize>(
+    reader: &mut R,
+    options: &DeserializeOptions,
+) -> Result<T> {
+    let mut counting = CountingReader::new(reader);
+    let mut limits = Limits::new(*options);
+    let result = T::deserialize_reader_with_limits(&mut counting, &mut limits)
+        .map_err(|err| set_offset(err, counting.count))?;
+    let consumed = counting.count;
     let mut buf = [0u8; 1];
     match reader.read_exact(&mut buf) {
         Err(f) if f.kind() == ErrorKind::UnexpectedEof => Ok(result),
-        _ => Err(Error::new(ErrorKind::InvalidData, ERROR_NOT_ALL_BYTES_READ)),
+        _ => Err(not_all_bytes_read(consumed)),
     }
 }
@@ -1,4 +1,8 @@
+#[cfg(not(feature = "std"))]
+use crate::__private::maybestd::boxed::Box;
+use crate::__private::maybestd::{string::String, vec::Vec};
 use crate::io::{Error, ErrorKind, Result};
+use core::fmt::{self, Write as _};
 use core::mem::size_of;
 pub const ERROR_ZST_FORBIDDEN: &str = "Collections of zero-sized types are not allowed due to deny-of-service concerns on deserialization.";
 
@@ -8,3 +12,276 @@ pub(crate) fn check_zst<T>() -> Result<()> {
     }
     Ok(())
 }
+
+/// Single step of a path to the value, which failed to deserialize.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PathSegment {
+    /// Name of a struct or an enum, rendered as `Name`.
+    Type(&'static str),
+    /// Name of a named field, rendered as `.name`.
+    Field(&'static str),
+    /// Index of an unnamed field, rendered as `.0`.
+    Position(usize),
+    /// Name of an enum variant, rendered as `::Variant`.
+    Variant(&'static str),
+    /// Index of an element of a sequence, rendered as `[3]`.
+    Index(usize),
+}
+
+/// Deserialization error, which records where in a nested value decoding failed.
+///
+/// Errors returned from [`from_slice`](crate::from_slice), [`from_reader`](crate::from_reader)
+/// and derived [`BorshDeserialize`](crate::BorshDeserialize) impls are still
+/// [`io::Error`](crate::io::Error)s, which wrap this type.
+/// It can be retrieved with [`DeserializeError::from_io_error`].
+///
+/// As the wrapper sits between the returned [`io::Error`](crate::io::Error) and the error,
+/// produced by a failed impl (e.g. a `deserialize_with` function or a manual impl),
+/// `io::Error::get_ref`, `io::Error::into_inner` and `downcast` of the returned error
+/// yield the wrapper, not the original payload. The original error is reachable with
+/// [`DeserializeError::original`] and [`DeserializeError::into_original`], and with
+/// `std` its payload is also the [`source`](std::error::Error::source) of the wrapper:
+///
+/// ```
+/// # #[cfg(feature = "std")]
+/// # {
+/// use borsh::{error::DeserializeError, io, from_slice};
+///
+/// #[derive(Debug)]
+/// struct Custom;
+///
+/// impl std::fmt::Display for Custom {
+///     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+///         f.write_str("custom")
+///     }
+/// }
+///
+/// impl std::error::Error for Custom {}
+///
+/// #[derive(Debug)]
+/// struct Rejected;
+///
+/// impl borsh::BorshDeserialize for Rejected {
+///     fn deserialize_reader<R: io::Read>(_reader: &mut R) -> io::Result<Self> {
+///         Err(io::Error::new(io::ErrorKind::InvalidData, Custom))
+///     }
+/// }
+///
+/// let err = from_slice::<Rejected>(&[]).unwrap_err();
+/// assert!(DeserializeError::original(&err).get_ref().unwrap().is::<Custom>());
+/// let payload = DeserializeError::into_original(err).into_inner().unwrap();
+/// assert!(payload.downcast::<Custom>().is_ok());
+/// # }
+/// ```
+///
+/// Regular `Display` output of the error is the message of the original error,
+/// alternate output (`{:#}`) also includes the path and the byte offset:
+///
+/// ```
+/// use borsh::{error::DeserializeError, from_slice, to_vec};
+///
+/// let bytes = to_vec(&vec![Some(1u8), Some(2u8)]).unwrap();
+/// let mut corrupted = bytes.clone();
+/// corrupted[6] = 7;
+/// let err = from_slice::<Vec<Option<u8>>>(&corrupted).unwrap_err();
+/// assert_eq!(
+///     err.to_string(),
+///     "Invalid Option representation: 7. The first byte must be 0 or 1"
+/// );
+///
+/// let details = DeserializeError::from_io_error(&err).unwrap();
+/// assert_eq!(details.path_string(), "[1]");
+/// assert_eq!(details.offset(), Some(7));
+/// assert_eq!(
+///     format!("{:#}", err),
+///     "Invalid Option representation: 7. The first byte must be 0 or 1 (at [1], offset 7)"
+/// );
+/// ```
+#[derive(Debug)]
+pub struct DeserializeError {
+    error: Error,
+    path: Vec<PathSegment>,
+    offset: Option<usize>,
+}
+
+impl DeserializeError {
+    /// Returns structured details of `error`, if it carries any.
+    pub fn from_io_error(error: &Error) -> Option<&DeserializeError> {
+        #[cfg(feature = "std")]
+        {
+            error.get_ref()?.downcast_ref()
+        }
+        #[cfg(not(feature = "std"))]
+        {
+            error.deserialize_error()
+        }
+    }
+
+    /// Returns the original error, wrapped into `error`, or `error` itself,
+    /// if it carries no details.
+    pub fn original(error: &Error) -> &Error {
+        match Self::from_io_error(error) {
+            Some(details) => &details.error,
+            None => error,
+        }
+    }
+
+    /// Consumes `error`, returning the original error, wrapped into it,
+    /// or `error` itself, if it carries no details.
+    pub fn into_original(error: Error) -> Error {
+        #[cfg(feature = "std")]
+        {
+            if Self::from_io_error(&error).is_none() {
+                return error;
+            }
+            match error
+                .into_inner()
+                .map(|inner| inner.downcast::<DeserializeError>())
+            {
+                Some(Ok(details)) => details.error,
+                _ => unreachable!("checked to be `DeserializeError` above"),
+            }
+        }
+        #[cfg(not(feature = "std"))]
+        {
+            match error.into_deserialize_error() {
+                Ok(details) => details.error,
+                Err(error) => error,
+            }
+        }
+    }
+
+    fn from_io_error_mut(error: &mut Error) -> Option<&mut DeserializeError> {
+        #[cfg(feature = "std")]
+        {
+            error.get_mut()?.downcast_mut()
+        }
+        #[cfg(not(feature = "std"))]
+        {
+            error.deserialize_error_mut()
+        }
+    }
+
+    fn into_io_error(self) -> Error {
+        #[cfg(feature = "std")]
+        {
+            Error::new(self.error.kind(), self)
+        }
+        #[cfg(not(feature = "std"))]
+        {
+            Error::from_deserialize_error(Box::new(self))
+        }
+    }
+
+    /// Wraps `error` into [`DeserializeError`], unless it already is one.
+    fn wrap(mut error: Error, f: impl FnOnce(&mut DeserializeError)) -> Error {
+        if let Some(details) = Self::from_io_error_mut(&mut error) {
+            f(details);
+            return error;
+        }
+        let mut details = DeserializeError {
+            error,
+            path: Vec::new(),
+            offset: None,
+        };
+        f(&mut details);
+        details.into_io_error()
+    }
+
+    /// The kind of the original error.
+    pub fn kind(&self) -> ErrorKind {
+        self.error.kind()
+    }
+
+    /// The original error, as it was returned by the failed impl.
+    pub fn inner(&self) -> &Error {
+        &self.error
+    }
+
+    /// Consumes `self`, returning the original error.
+    pub fn into_inner(self) -> Error {
+        self.error
+    }
+
+    /// Path to the value, which failed to deserialize, from the outermost type inwards.
+    pub fn path(&self) -> &[PathSegment] {
+        &self.path
+    }
+
+    /// Path to the value, which failed to deserialize, rendered
+    /// like `Outer.items[3].Inner::Variant.field`.
+    pub fn path_string(&self) -> String {
+        let mut result = String::new();
+        for segment in &self.path {
+            let _ = match segment {
+                PathSegment::Type(name) if result.is_empty() => write!(result, "{}", name),
+                PathSegment::Type(name) | PathSegment::Field(name) => write!(result, ".{}", name),
+                PathSegment::Position(idx) => write!(result, ".{}", idx),
+                PathSegment::Variant(name) => write!(result, "::{}", name),
+                PathSegment::Index(idx) => write!(result, "[{}]", idx),
+            };
+        }
+        result
+    }
+
+    /// Offset of the first byte of input, which was not consumed when decoding failed.
+    ///
+    /// Offset is only known for errors, returned from top-level functions like
+    /// [`from_slice`](crate::from_slice) or [`from_reader`](crate::from_reader).
+    pub fn offset(&self) -> Option<usize> {
+        self.offset
+    }
+}
+
+impl fmt::Display for DeserializeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.error, f)?;
+        if f.alternate() {
+            match (self.path.is_empty(), self.offset) {
+                (false, Some(offset)) => {
+                    write!(f, " (at {}, offset {})", self.path_string(), offset)?
+                }
+                (false, None) => write!(f, " (at {})", self.path_string())?,
+                (true, Some(offset)) => write!(f, " (at offset {})", offset)?,
+                (true, None) => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DeserializeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self.error.get_ref() {
+            Some(payload) => Some(payload),
+            None => Some(&self.error),
+        }
+    }
+}
+
+/// Prepends `segments` to the path, recorded in `error`.
+///
+/// Used by derived [`BorshDeserialize`](crate::BorshDeserialize) impls, and can be used in
+/// manual impls to report positions of nested values.
+pub fn prepend_path(error: Error, segments: &[PathSegment]) -> Error {
+    DeserializeError::wrap(error, |details| {
+        details.path.splice(0..0, segments.iter().copied());
+    })
+}
+
+/// Converts an error, returned from a `#[borsh(validate = "...")]` function of derived
+/// [`BorshDeserialize`](crate::BorshDeserialize) impls, into [`ErrorKind::InvalidData`] error
+/// with the same message.
+pub fn validation_error<E: fmt::Display>(error: E) -> Error {
+    let mut message = String::new();
+    let _ = write!(message, "{}", error);
+    Error::new(ErrorKind::InvalidData, message)
+}
+
+/// Records byte `offset` of the failure in `error`, unless some offset was already recorded.
+pub(crate) fn set_offset(error: Error, offset: usize) -> Error {
+    DeserializeError::wrap(error, |details| {
+        details.offset.get_or_insert(offset);
+    })
+}
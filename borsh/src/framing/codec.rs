@@ -0,0 +1,124 @@
+use core::marker::PhantomData;
+
+use tokio_util::bytes::{Buf, BufMut, BytesMut};
+use tokio_util::codec::{Decoder, Encoder};
+
+use super::{frame_len, frame_too_large, DEFAULT_MAX_FRAME_SIZE, LENGTH_PREFIX_SIZE};
+use crate::de::{from_slice_with_options, DeserializeOptions};
+use crate::io::{Error, Result};
+use crate::{BorshDeserialize, BorshSerialize};
+
+/// [`Encoder`] and [`Decoder`] of values of type `T`, using the same length-prefixed
+/// frames as [`FramedWriter`](super::FramedWriter) and [`FramedReader`](super::FramedReader).
+///
+/// ```
+/// use borsh::framing::BorshCodec;
+/// use tokio_util::bytes::BytesMut;
+/// use tokio_util::codec::{Decoder, Encoder};
+///
+/// let mut codec = BorshCodec::<(u8, String)>::new();
+/// let mut buf = BytesMut::new();
+/// codec.encode((1, String::from("hello")), &mut buf).unwrap();
+///
+/// let mut partial = buf.split_to(6);
+/// assert_eq!(codec.decode(&mut partial).unwrap(), None);
+/// partial.unsplit(buf);
+/// assert_eq!(codec.decode(&mut partial).unwrap(), Some((1, String::from("hello"))));
+/// ```
+pub struct BorshCodec<T> {
+    max_frame_size: u32,
+    options: DeserializeOptions,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> BorshCodec<T> {
+    pub fn new() -> Self {
+        Self {
+            max_frame_size: DEFAULT_MAX_FRAME_SIZE,
+            options: DeserializeOptions::new(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Sets maximum length of a frame, longer frames are rejected by both
+    /// the encoder and the decoder.
+    pub fn max_frame_size(mut self, max_frame_size: u32) -> Self {
+        self.max_frame_size = max_frame_size;
+        self
+    }
+
+    /// Sets limits, enforced when decoding values of frames.
+    pub fn options(mut self, options: DeserializeOptions) -> Self {
+        self.options = options;
+        self
+    }
+}
+
+impl<T> Default for BorshCodec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for BorshCodec<T> {
+    fn clone(&self) -> Self {
+        Self {
+            max_frame_size: self.max_frame_size,
+            options: self.options,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> core::fmt::Debug for BorshCodec<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("BorshCodec")
+            .field("max_frame_size", &self.max_frame_size)
+            .field("options", &self.options)
+            .finish()
+    }
+}
+
+impl<T: BorshSerialize> Encoder<&T> for BorshCodec<T> {
+    type Error = Error;
+
+    fn encode(&mut self, item: &T, dst: &mut BytesMut) -> Result<()> {
+        let len = frame_len(item, self.max_frame_size)?;
+        dst.reserve(LENGTH_PREFIX_SIZE + len as usize);
+        dst.put_u32_le(len);
+        item.serialize(&mut dst.writer())
+    }
+}
+
+impl<T: BorshSerialize> Encoder<T> for BorshCodec<T> {
+    type Error = Error;
+
+    fn encode(&mut self, item: T, dst: &mut BytesMut) -> Result<()> {
+        self.encode(&item, dst)
+    }
+}
+
+impl<T: BorshDeserialize> Decoder for BorshCodec<T> {
+    type Item = T;
+    type Error = Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<T>> {
+        if src.len() < LENGTH_PREFIX_SIZE {
+            return Ok(None);
+        }
+        let mut prefix = [0u8; LENGTH_PREFIX_SIZE];
+        prefix.copy_from_slice(&src[..LENGTH_PREFIX_SIZE]);
+        let len = u32::from_le_bytes(prefix);
+        if len > self.max_frame_size {
+            return Err(frame_too_large(len as usize, self.max_frame_size));
+        }
+        let frame_size = LENGTH_PREFIX_SIZE + len as usize;
+        if src.len() < frame_size {
+            src.reserve(frame_size - src.len());
+            return Ok(None);
+        }
+        src.advance(LENGTH_PREFIX_SIZE);
+        let frame = src.split_to(len as usize);
+        from_slice_with_options(&frame, &self.options).map(Some)
+    }
+}
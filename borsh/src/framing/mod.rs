@@ -0,0 +1,182 @@
+//! Length-prefixed framing of borsh messages, sent one after another over a stream.
+//!
+//! Each frame is a `u32` little-endian length, followed by exactly that many bytes
+//! of a borsh-serialized value. Frames, longer than the configured maximum frame size
+//! ([`DEFAULT_MAX_FRAME_SIZE`] by default), are rejected on both ends.
+//!
+//! ```
+//! use borsh::framing::{FramedReader, FramedWriter};
+//!
+//! let mut writer = FramedWriter::new(Vec::new());
+//! writer.write(&(1u8, String::from("first"))).unwrap();
+//! writer.write(&(2u8, String::from("second"))).unwrap();
+//! let stream = writer.into_inner();
+//!
+//! let mut reader = FramedReader::new(&stream[..]);
+//! assert_eq!(reader.read::<(u8, String)>().unwrap(), Some((1, String::from("first"))));
+//! assert_eq!(reader.read::<(u8, String)>().unwrap(), Some((2, String::from("second"))));
+//! assert_eq!(reader.read::<(u8, String)>().unwrap(), None);
+//! ```
+//!
+//! With **tokio-util** feature, `BorshCodec` implements the same framing as
+//! `tokio_util::codec::{Encoder, Decoder}`.
+
+use core::convert::TryFrom;
+
+use crate::__private::maybestd::{format, vec::Vec};
+use crate::de::{from_slice_with_options, DeserializeOptions};
+use crate::io::{Error, ErrorKind, Read, Result, Write};
+use crate::{object_length, BorshDeserialize, BorshSerialize};
+
+#[cfg(feature = "tokio-util")]
+mod codec;
+
+#[cfg(feature = "tokio-util")]
+pub use codec::BorshCodec;
+
+/// Maximum length of a frame, unless configured otherwise: 8 MiB.
+pub const DEFAULT_MAX_FRAME_SIZE: u32 = 8 * 1024 * 1024;
+
+const LENGTH_PREFIX_SIZE: usize = 4;
+
+fn frame_too_large(len: usize, max_frame_size: u32) -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        format!(
+            "Frame of {} bytes exceeds maximum frame size of {}",
+            len, max_frame_size
+        ),
+    )
+}
+
+/// Computes the length prefix of `value`, checking it against `max_frame_size`.
+fn frame_len<T: BorshSerialize + ?Sized>(value: &T, max_frame_size: u32) -> Result<u32> {
+    let len = object_length(value)?;
+    match u32::try_from(len) {
+        Ok(prefix) if prefix <= max_frame_size => Ok(prefix),
+        _ => Err(frame_too_large(len, max_frame_size)),
+    }
+}
+
+/// Writes values as length-prefixed frames.
+///
+/// The length prefix is computed with [`object_length`], so that values are serialized
+/// straight into the writer, without buffering.
+#[derive(Debug)]
+pub struct FramedWriter<W> {
+    writer: W,
+    max_frame_size: u32,
+}
+
+impl<W: Write> FramedWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            max_frame_size: DEFAULT_MAX_FRAME_SIZE,
+        }
+    }
+
+    /// Sets maximum length of a frame, values with longer serialized form are rejected.
+    pub fn max_frame_size(mut self, max_frame_size: u32) -> Self {
+        self.max_frame_size = max_frame_size;
+        self
+    }
+
+    /// Writes `value` as a single frame.
+    ///
+    /// Nothing is written, if the value exceeds maximum frame size.
+    pub fn write<T: BorshSerialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        let len = frame_len(value, self.max_frame_size)?;
+        self.writer.write_all(&len.to_le_bytes())?;
+        value.serialize(&mut self.writer)
+    }
+
+    pub fn flush(&mut self) -> Result<()> {
+        self.writer.flush()
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Reads values from length-prefixed frames.
+///
+/// Each frame is read into an internal buffer, reused between frames, and has to
+/// be consumed completely by the value.
+#[derive(Debug)]
+pub struct FramedReader<R> {
+    reader: R,
+    max_frame_size: u32,
+    options: DeserializeOptions,
+    buf: Vec<u8>,
+}
+
+impl<R: Read> FramedReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            max_frame_size: DEFAULT_MAX_FRAME_SIZE,
+            options: DeserializeOptions::new(),
+            buf: Vec::new(),
+        }
+    }
+
+    /// Sets maximum length of a frame, longer frames are rejected before reading their contents.
+    pub fn max_frame_size(mut self, max_frame_size: u32) -> Self {
+        self.max_frame_size = max_frame_size;
+        self
+    }
+
+    /// Sets limits, enforced when deserializing values of frames.
+    pub fn options(mut self, options: DeserializeOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Reads a single frame and deserializes a value from it.
+    ///
+    /// Returns `Ok(None)`, if the stream ends right before a frame, and fails
+    /// with [`ErrorKind::UnexpectedEof`], if it ends in the middle of one.
+    pub fn read<T: BorshDeserialize>(&mut self) -> Result<Option<T>> {
+        let mut prefix = [0u8; LENGTH_PREFIX_SIZE];
+        let mut filled = 0;
+        while filled < LENGTH_PREFIX_SIZE {
+            match self.reader.read(&mut prefix[filled..]) {
+                Ok(0) if filled == 0 => return Ok(None),
+                Ok(0) => return Err(ErrorKind::UnexpectedEof.into()),
+                Ok(read) => filled += read,
+                Err(err) if err.kind() == ErrorKind::Interrupted => {}
+                Err(err) => return Err(err),
+            }
+        }
+        let len = u32::from_le_bytes(prefix);
+        if len > self.max_frame_size {
+            return Err(frame_too_large(len as usize, self.max_frame_size));
+        }
+        self.buf.clear();
+        self.buf.resize(len as usize, 0);
+        self.reader.read_exact(&mut self.buf)?;
+        from_slice_with_options(&self.buf, &self.options).map(Some)
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
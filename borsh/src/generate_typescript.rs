@@ -0,0 +1,32 @@
+//! Generate TypeScript (de)serializers from a `BorshSchemaContainer`, serialized into a file.
+//!
+//! Usage: `generate_typescript <schema file> [<output file>]`, prints to stdout if
+//! no output file is given.
+
+use borsh::schema::BorshSchemaContainer;
+use std::fs::{self, File};
+use std::io::Write;
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let input = args
+        .next()
+        .expect("Usage: generate_typescript <schema file> [<output file>]");
+    let output = args.next();
+
+    let data = fs::read(&input).expect("Failed to read schema file");
+    let container: BorshSchemaContainer =
+        borsh::from_slice(&data).expect("Failed to deserialize BorshSchemaContainer");
+    let code = container
+        .generate_typescript()
+        .expect("Failed to generate TypeScript");
+
+    match output {
+        Some(output) => {
+            let mut file = File::create(output).expect("Failed to create file");
+            file.write_all(code.as_bytes())
+                .expect("Failed to write file");
+        }
+        None => print!("{}", code),
+    }
+}
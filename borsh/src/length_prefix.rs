@@ -0,0 +1,314 @@
+//! Collections with length prefix of custom width.
+//!
+//! Borsh prefixes `Vec`, `String`, maps and sets with their length as `u32`. Formats, which use
+//! a narrower (or wider) prefix, e.g. `u8`-prefixed "Pascal strings", can be expressed with
+//! [`LengthPrefixed<L, T>`] wrapper, where `L` is one of `u8`, `u16`, `u32` or `u64`,
+//! or with `#[borsh(length_width = ...)]` attribute on a field of derived impls:
+//!
+//! ```
+//! # #[cfg(feature = "derive")]
+//! # {
+//! use borsh::{length_prefix::LengthPrefixed, BorshDeserialize, BorshSerialize};
+//!
+//! #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+//! struct Packet {
+//!     #[borsh(length_width = 1)]
+//!     name: String,
+//!     data: LengthPrefixed<u16, Vec<u8>>,
+//! }
+//!
+//! let packet = Packet {
+//!     name: "ab".to_string(),
+//!     data: LengthPrefixed::new(vec![7]),
+//! };
+//! let encoded = borsh::to_vec(&packet).unwrap();
+//! assert_eq!(encoded, [2, b'a', b'b', 1, 0, 7]);
+//! assert_eq!(borsh::from_slice::<Packet>(&encoded).unwrap(), packet);
+//! # }
+//! ```
+//!
+//! Serialization fails, if length of the collection doesn't fit into the prefix.
+
+use core::cmp::min;
+use core::convert::TryFrom;
+use core::marker::PhantomData;
+use core::mem::size_of;
+use core::ops::{Deref, DerefMut};
+
+use crate::__private::maybestd::{
+    collections::{BTreeMap, BTreeSet, LinkedList, VecDeque},
+    format,
+    string::String,
+    vec::Vec,
+};
+use crate::de::Limits;
+use crate::io::{Error, ErrorKind, Read, Result, Write};
+use crate::{BorshDeserialize, BorshSerialize};
+
+const U32_SIZE: usize = size_of::<u32>();
+
+/// Unsigned integer types, which can be used as length prefix: `u8`, `u16`, `u32` and `u64`.
+pub trait LengthWidth:
+    Copy + BorshSerialize + BorshDeserialize + TryFrom<u32> + Into<u64> + private::Sealed
+{
+    /// Maximum length, which can be encoded with the prefix.
+    const MAX: u64;
+}
+
+macro_rules! impl_length_width {
+    ($($ty:ty),+) => {
+        $(
+            impl private::Sealed for $ty {}
+            impl LengthWidth for $ty {
+                const MAX: u64 = <$ty>::MAX as u64;
+            }
+        )+
+    };
+}
+
+impl_length_width!(u8, u16, u32, u64);
+
+/// Collections, which are prefixed by their `u32` length in standard borsh encoding.
+pub trait Collection: private::Sealed {}
+
+macro_rules! impl_collection {
+    ($($ty:ty $(: $($bounds:ident)+)?),+) => {
+        $(
+            impl$(<$($bounds),+>)? private::Sealed for $ty {}
+            impl$(<$($bounds),+>)? Collection for $ty {}
+        )+
+    };
+}
+
+impl_collection!(
+    str,
+    String,
+    [T]: T,
+    Vec<T>: T,
+    VecDeque<T>: T,
+    LinkedList<T>: T,
+    BTreeSet<T>: T,
+    BTreeMap<K, V>: K V
+);
+
+#[cfg(hash_collections)]
+impl_collection!(
+    crate::__private::maybestd::collections::HashSet<T, H>: T H,
+    crate::__private::maybestd::collections::HashMap<K, V, H>: K V H
+);
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// Writer, which replaces `u32` length prefix, written first, with an `L` one.
+struct PrefixWriter<'a, L, W> {
+    inner: &'a mut W,
+    prefix: [u8; U32_SIZE],
+    filled: usize,
+    width: PhantomData<L>,
+}
+
+impl<L: LengthWidth, W: Write> Write for PrefixWriter<'_, L, W> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        if self.filled == U32_SIZE {
+            return self.inner.write(buf);
+        }
+        let n = min(U32_SIZE - self.filled, buf.len());
+        self.prefix[self.filled..self.filled + n].copy_from_slice(&buf[..n]);
+        self.filled += n;
+        if self.filled == U32_SIZE {
+            let len = u32::from_le_bytes(self.prefix);
+            let prefix = L::try_from(len).map_err(|_| {
+                Error::new(
+                    ErrorKind::InvalidData,
+                    format!(
+                        "Collection length {} doesn't fit into {}-byte length prefix",
+                        len,
+                        size_of::<L>()
+                    ),
+                )
+            })?;
+            prefix.serialize(self.inner)?;
+        }
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Reader, which yields `u32` length prefix before the rest of `inner`.
+struct PrefixReader<'a, R> {
+    inner: &'a mut R,
+    prefix: [u8; U32_SIZE],
+    consumed: usize,
+}
+
+impl<R: Read> Read for PrefixReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        if self.consumed == U32_SIZE {
+            return self.inner.read(buf);
+        }
+        let n = min(U32_SIZE - self.consumed, buf.len());
+        buf[..n].copy_from_slice(&self.prefix[self.consumed..self.consumed + n]);
+        self.consumed += n;
+        Ok(n)
+    }
+}
+
+/// Serializes `value` with `L` length prefix; used by `#[borsh(length_width = ...)]`
+/// fields of derived impls.
+pub fn serialize<L, T, W>(value: &T, writer: &mut W) -> Result<()>
+where
+    L: LengthWidth,
+    T: Collection + BorshSerialize + ?Sized,
+    W: Write,
+{
+    let mut writer = PrefixWriter::<L, W> {
+        inner: writer,
+        prefix: [0; U32_SIZE],
+        filled: 0,
+        width: PhantomData,
+    };
+    value.serialize(&mut writer)
+}
+
+/// Deserializes collection with `L` length prefix.
+pub fn deserialize<L, T, R>(reader: &mut R) -> Result<T>
+where
+    L: LengthWidth,
+    T: Collection + BorshDeserialize,
+    R: Read,
+{
+    deserialize_with_limits::<L, T, R>(reader, &mut Limits::unlimited())
+}
+
+/// Deserializes collection with `L` length prefix, enforcing `limits`; used by
+/// `#[borsh(length_width = ...)]` fields of derived impls.
+pub fn deserialize_with_limits<L, T, R>(reader: &mut R, limits: &mut Limits) -> Result<T>
+where
+    L: LengthWidth,
+    T: Collection + BorshDeserialize,
+    R: Read,
+{
+    let len: u64 = L::deserialize_reader(reader)?.into();
+    let len = u32::try_from(len).map_err(|_| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("Collection length {} exceeds u32 range", len),
+        )
+    })?;
+    deserialize_with_len(len, reader, limits)
+}
+
+/// Deserializes collection `T`, which is prefixed by `len`, already read from `reader`.
+pub(crate) fn deserialize_with_len<T: BorshDeserialize, R: Read>(
+    len: u32,
+    reader: &mut R,
+    limits: &mut Limits,
+) -> Result<T> {
+    let mut reader = PrefixReader {
+        inner: reader,
+        prefix: len.to_le_bytes(),
+        consumed: 0,
+    };
+    T::deserialize_reader_with_limits(&mut reader, limits)
+}
+
+/// Collection `T`, prefixed by its length as `L` instead of `u32`.
+#[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct LengthPrefixed<L, T> {
+    value: T,
+    width: PhantomData<L>,
+}
+
+impl<L, T> LengthPrefixed<L, T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            value,
+            width: PhantomData,
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<L, T> From<T> for LengthPrefixed<L, T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<L, T> Deref for LengthPrefixed<L, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<L, T> DerefMut for LengthPrefixed<L, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+impl<L: LengthWidth, T: Collection + BorshSerialize> BorshSerialize for LengthPrefixed<L, T> {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        serialize::<L, T, W>(&self.value, writer)
+    }
+}
+
+impl<L: LengthWidth, T: Collection + BorshDeserialize> BorshDeserialize for LengthPrefixed<L, T> {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        deserialize::<L, T, R>(reader).map(Self::new)
+    }
+
+    fn deserialize_reader_with_limits<R: Read>(
+        reader: &mut R,
+        limits: &mut Limits,
+    ) -> Result<Self> {
+        deserialize_with_limits::<L, T, R>(reader, limits).map(Self::new)
+    }
+}
+
+#[cfg(feature = "unstable__schema")]
+mod schema {
+    use core::convert::TryFrom;
+    use core::mem::size_of;
+
+    use super::{Collection, LengthPrefixed, LengthWidth};
+    use crate::__private::maybestd::{collections::BTreeMap, format};
+    use crate::schema::{add_definition, BorshSchema, Declaration, Definition};
+
+    impl<L, T> BorshSchema for LengthPrefixed<L, T>
+    where
+        L: LengthWidth + BorshSchema,
+        T: Collection + BorshSchema,
+    {
+        fn add_definitions_recursively(definitions: &mut BTreeMap<Declaration, Definition>) {
+            T::add_definitions_recursively(definitions);
+            let definition = match definitions.get(&T::declaration()) {
+                Some(Definition::Sequence {
+                    length_range,
+                    elements,
+                    ..
+                }) => Definition::Sequence {
+                    length_width: u8::try_from(size_of::<L>()).unwrap(),
+                    length_range: *length_range.start()..=(*length_range.end()).min(L::MAX),
+                    elements: elements.clone(),
+                },
+                _ => unreachable!("collections are defined as sequences"),
+            };
+            add_definition(Self::declaration(), definition, definitions);
+        }
+
+        fn declaration() -> Declaration {
+            format!("LengthPrefixed<{}, {}>", L::declaration(), T::declaration())
+        }
+    }
+}
@@ -25,25 +25,50 @@
 ### Other features
 
 * **derive** -
-  Gates derive macros of [BorshSerialize](crate::ser::BorshSerialize) and
-  [BorshDeserialize](crate::de::BorshDeserialize) traits.
+  Gates derive macros of [BorshSerialize](crate::ser::BorshSerialize),
+  [BorshDeserialize](crate::de::BorshDeserialize) and
+  [BorshDeserializeBorrowed](crate::de::BorshDeserializeBorrowed) traits.
 * **unstable__schema** -
   Gates [BorshSchema](crate::schema::BorshSchema) trait and its derive macro.
   Gates [schema](crate::schema) module.
   This feature requires **derive** to be enabled too.
+* **schema_json** -
+  Gates transcoding of borsh bytes to and from [serde_json::Value] with a
+  [BorshSchemaContainer](crate::schema::BorshSchemaContainer), i.e. without the Rust type at hand.
+  This feature requires **unstable__schema** to be enabled too.
+* **serde** -
+  Gates [serde](crate::serde) module with a [Serializer](crate::serde::Serializer) and a
+  [Deserializer](crate::serde::Deserializer), which implement borsh format for `serde`'s data model,
+  and `#[borsh(serde)]` field attribute of derive macros, which routes a field through them.
 * **rc** -
-  Gates implementation of [BorshSerialize](crate::ser::BorshSerialize) and [BorshDeserialize](crate::de::BorshDeserialize)
+  Gates implementation of [BorshSerialize](crate::ser::BorshSerialize), [BorshDeserialize](crate::de::BorshDeserialize)
+  and [BorshSchema](crate::schema::BorshSchema) (with **unstable__schema**)
   for [`Rc<T>`](std::rc::Rc)/[`Arc<T>`](std::sync::Arc) respectively.
   In `no_std` setting `Rc`/`Arc` are pulled from `alloc` crate.
 * **hashbrown** -
   Pulls in [HashMap](std::collections::HashMap)/[HashSet](std::collections::HashSet) when no `std` is available.
   This feature is set to be mutually exclusive with **std** feature.
 * **bytes** -
-  Gates implementation of [BorshSerialize](crate::ser::BorshSerialize) and [BorshDeserialize](crate::de::BorshDeserialize)
+  Gates implementation of [BorshSerialize](crate::ser::BorshSerialize), [BorshDeserialize](crate::de::BorshDeserialize)
+  and [BorshSchema](crate::schema::BorshSchema) (with **unstable__schema**)
   for [Bytes](bytes::Bytes) and [BytesMut](bytes::BytesMut).
 * **bson** -
-  Gates implementation of [BorshSerialize](crate::ser::BorshSerialize) and [BorshDeserialize](crate::de::BorshDeserialize)
+  Gates implementation of [BorshSerialize](crate::ser::BorshSerialize), [BorshDeserialize](crate::de::BorshDeserialize)
+  and [BorshSchema](crate::schema::BorshSchema) (with **unstable__schema**)
   for [ObjectId](bson::oid::ObjectId).
+* **tokio** -
+  Gates [BorshSerializeAsync](crate::ser::BorshSerializeAsync) and
+  [BorshDeserializeAsync](crate::de::BorshDeserializeAsync) traits (and their derive macros, with **derive**)
+  and [async_io] module, which implements them over [tokio::io::AsyncRead]/[tokio::io::AsyncWrite].
+  This feature requires **std**.
+* **futures-io** -
+  Same as **tokio**, but over [futures_io::AsyncRead]/[futures_io::AsyncWrite],
+  wrapped into [FuturesIo](crate::async_io::FuturesIo).
+  This feature requires **std**.
+* **tokio-util** -
+  Gates [BorshCodec](crate::framing::BorshCodec), which implements length-prefixed
+  [framing](crate::framing) of borsh messages as `tokio_util::codec::{Encoder, Decoder}`.
+  This feature requires **std**.
 * **de_strict_order** -
   Enables check that keys, parsed during deserialization of
   [HashMap](std::collections::HashMap)/[HashSet](std::collections::HashSet) and
@@ -60,6 +85,8 @@
   Gates implementation of [BorshSerialize](crate::ser::BorshSerialize), [BorshDeserialize](crate::de::BorshDeserialize)
   and [BorshSchema](crate::schema::BorshSchema)
   for [HashMap](std::collections::HashMap)/[HashSet](std::collections::HashSet).
+* **async_io** -
+  This is a feature alias, set up in `build.rs` to be equivalent to (**tokio** OR **futures-io**).
 
 
 */
@@ -73,9 +100,18 @@ pub use borsh_derive::BorshSchema;
 
 /// Derive macro available if borsh is built with `features = ["derive"]`.
 #[cfg(feature = "derive")]
-pub use borsh_derive::{BorshDeserialize, BorshSerialize};
+pub use borsh_derive::{BorshDeserialize, BorshDeserializeBorrowed, BorshSerialize};
+#[cfg(all(feature = "derive", async_io))]
+pub use borsh_derive::{BorshDeserializeAsync, BorshSerializeAsync};
+
+/// Module is available if borsh is built with `features = ["tokio"]` or `features = ["futures-io"]`.
+#[cfg(async_io)]
+pub mod async_io;
 
+pub mod bounded;
 pub mod de;
+pub mod framing;
+pub mod length_prefix;
 
 // See `hash_collections` alias definition in build.rs
 /// Module is available if borsh is built with `features = ["unstable__schema"]`.
@@ -84,9 +120,20 @@ pub mod schema;
 #[cfg(feature = "unstable__schema")]
 pub(crate) mod schema_helpers;
 pub mod ser;
+/// Module is available if borsh is built with `features = ["serde"]`.
+#[cfg(feature = "serde")]
+pub mod serde;
+pub mod trailing;
+pub mod varint;
 
 pub use de::BorshDeserialize;
-pub use de::{from_reader, from_slice};
+#[cfg(async_io)]
+pub use de::BorshDeserializeAsync;
+pub use de::BorshDeserializeBorrowed;
+pub use de::{
+    from_reader, from_reader_with_options, from_slice, from_slice_borrowed,
+    from_slice_borrowed_with_options, from_slice_with_options,
+};
 #[cfg(feature = "unstable__schema")]
 pub use schema::BorshSchema;
 #[cfg(feature = "unstable__schema")]
@@ -95,11 +142,15 @@ pub use schema_helpers::{
 };
 pub use ser::helpers::{object_length, to_vec, to_writer};
 pub use ser::BorshSerialize;
+#[cfg(async_io)]
+pub use ser::BorshSerializeAsync;
 pub mod error;
 
 #[cfg(all(feature = "std", feature = "hashbrown"))]
 compile_error!("feature \"std\" and feature \"hashbrown\" don't make sense at the same time");
 
+mod union_bytes;
+
 #[cfg(feature = "std")]
 use std::io as io_impl;
 #[cfg(not(feature = "std"))]
@@ -118,6 +169,9 @@ pub mod io {
 
 #[doc(hidden)]
 pub mod __private {
+    pub mod union_bytes {
+        pub use crate::union_bytes::{deserialize, serialize};
+    }
 
     /// A facade around all the types we need from the `std`, and `alloc`
     /// crates. This avoids elaborate import wrangling having to happen in every
@@ -1,6 +1,7 @@
 //! Taken from https://github.com/bbqsrc/bare-io (with adjustments)
 
-use crate::__private::maybestd::string::String;
+use crate::__private::maybestd::{boxed::Box, string::String};
+use crate::error::DeserializeError;
 use core::{convert::From, fmt, result};
 
 /// A specialized [`Result`] type for I/O operations.
@@ -62,6 +63,7 @@ impl fmt::Debug for Error {
 enum Repr {
     Simple(ErrorKind),
     Custom(Custom),
+    Deserialize(Box<DeserializeError>),
 }
 
 #[derive(Debug)]
@@ -267,6 +269,7 @@ impl Error {
         match self.repr {
             Repr::Simple(..) => None,
             Repr::Custom(ref c) => Some(&c.error),
+            Repr::Deserialize(ref d) => d.inner().get_ref(),
         }
     }
 
@@ -301,6 +304,7 @@ impl Error {
         match self.repr {
             Repr::Simple(..) => None,
             Repr::Custom(c) => Some(c.error),
+            Repr::Deserialize(d) => d.into_inner().into_inner(),
         }
     }
 
@@ -326,6 +330,36 @@ impl Error {
         match self.repr {
             Repr::Custom(ref c) => c.kind,
             Repr::Simple(kind) => kind,
+            Repr::Deserialize(ref d) => d.kind(),
+        }
+    }
+
+    pub(crate) fn from_deserialize_error(error: Box<DeserializeError>) -> Error {
+        Error {
+            repr: Repr::Deserialize(error),
+        }
+    }
+
+    pub(crate) fn deserialize_error(&self) -> Option<&DeserializeError> {
+        match self.repr {
+            Repr::Deserialize(ref d) => Some(d),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn into_deserialize_error(
+        self,
+    ) -> core::result::Result<Box<DeserializeError>, Error> {
+        match self.repr {
+            Repr::Deserialize(d) => Ok(d),
+            repr => Err(Error { repr }),
+        }
+    }
+
+    pub(crate) fn deserialize_error_mut(&mut self) -> Option<&mut DeserializeError> {
+        match self.repr {
+            Repr::Deserialize(ref mut d) => Some(d),
+            _ => None,
         }
     }
 }
@@ -335,6 +369,7 @@ impl fmt::Debug for Repr {
         match *self {
             Repr::Custom(ref c) => fmt::Debug::fmt(&c, fmt),
             Repr::Simple(kind) => fmt.debug_tuple("Kind").field(&kind).finish(),
+            Repr::Deserialize(ref d) => fmt::Debug::fmt(&d, fmt),
         }
     }
 }
@@ -344,6 +379,7 @@ impl fmt::Display for Error {
         match self.repr {
             Repr::Custom(ref c) => c.error.fmt(fmt),
             Repr::Simple(kind) => write!(fmt, "{}", kind.as_str()),
+            Repr::Deserialize(ref d) => d.fmt(fmt),
         }
     }
 }
@@ -649,7 +685,7 @@ impl Write for &mut [u8] {
     #[inline]
     fn write(&mut self, data: &[u8]) -> Result<usize> {
         let amt = core::cmp::min(data.len(), self.len());
-        let (a, b) = core::mem::replace(self, &mut []).split_at_mut(amt);
+        let (a, b) = core::mem::take(self).split_at_mut(amt);
         a.copy_from_slice(&data[..amt]);
         *self = b;
         Ok(amt)
@@ -14,13 +14,16 @@
 #![allow(dead_code)] // Unclear why rust check complains on fields of `Definition` variants.
 use crate as borsh; // For `#[derive(BorshSerialize, BorshDeserialize)]`.
 use crate::__private::maybestd::{
+    borrow::{Cow, ToOwned},
     boxed::Box,
-    collections::{btree_map::Entry, BTreeMap, BTreeSet},
+    collections::{btree_map::Entry, BTreeMap, BTreeSet, LinkedList, VecDeque},
     format,
     string::{String, ToString},
     vec,
     vec::Vec,
 };
+#[cfg(feature = "rc")]
+use crate::__private::maybestd::{rc::Rc, sync::Arc};
 use crate::io::{Read, Result as IOResult, Write};
 use crate::{BorshDeserialize, BorshSchema as BorshSchemaMacro, BorshSerialize};
 use core::borrow::Borrow;
@@ -29,7 +32,13 @@ use core::marker::PhantomData;
 
 mod container_ext;
 
-pub use container_ext::{SchemaContainerValidateError, SchemaMaxSerializedSizeError};
+pub use container_ext::{FieldValues, Hexdump, Incompatibility, Span, SpanKind, Value};
+pub use container_ext::{
+    SchemaCodegenError, SchemaContainerValidateError, SchemaMaxSerializedSizeError,
+    SchemaValueError,
+};
+#[cfg(feature = "schema_json")]
+pub use container_ext::SchemaJsonError;
 
 /// The type that we use to represent the declaration of the Borsh type.
 pub type Declaration = String;
@@ -37,10 +46,11 @@ pub type Declaration = String;
 pub type VariantName = String;
 /// The type that we use for value of discriminant.
 pub type DiscriminantValue = i64;
-/// The name of the field in the struct (can be used to convert JSON to Borsh using the schema).
+/// The name of the field in the struct (can be used to convert JSON to Borsh using the schema,
+/// see `BorshSchemaContainer::json_to_borsh`, available with `features = ["schema_json"]`).
 pub type FieldName = String;
 /// The type that we use to represent the definition of the Borsh type.
-
+///
 /// Description of data encoding on the wire.
 #[derive(Clone, PartialEq, Eq, Debug, BorshSerialize, BorshDeserialize, BorshSchemaMacro)]
 pub enum Definition {
@@ -118,6 +128,19 @@ pub enum Definition {
 
     /// A structure, structurally similar to a tuple.
     Struct { fields: Fields },
+
+    /// A value of the declared type, which may be missing at the end of the input,
+    /// e.g. a field with `#[borsh(default)]`, see [`Trailing`](crate::trailing::Trailing).
+    ///
+    /// Encoded as the value, if it's present, or as nothing otherwise, so the value is
+    /// considered missing only if there are no bytes left.
+    Trailing(Declaration),
+
+    /// Another name of the aliased declaration, e.g. of a `#[borsh(transparent = "...")]` struct.
+    ///
+    /// Encoded exactly as the aliased type, which keeps its meaning, e.g. an alias of `String`
+    /// is still a string, rather than a sequence of bytes.
+    Alias(Declaration),
 }
 
 impl Definition {
@@ -258,6 +281,85 @@ pub fn add_definition(
     }
 }
 
+/// Helper method to compute fields of struct `declaration` with `#[borsh(flatten)]` fields
+/// in derived impls: `(name, declaration, true)` fields are replaced with named fields of their
+/// struct types, whose definitions are added to `flattened` beforehand.
+///
+/// Definitions from `flattened` are moved to `definitions`, except for the ones of flattened
+/// types, which aren't referenced by any other definition.
+///
+/// A flattened type, which isn't a struct with named fields, is kept as a regular field,
+/// which doesn't change the encoding. Names, which occur more than once after flattening,
+/// are kept too, and are reported by [`BorshSchemaContainer::validate`].
+pub fn flatten_fields(
+    fields: Vec<(FieldName, Declaration, bool)>,
+    mut flattened: BTreeMap<Declaration, Definition>,
+    definitions: &mut BTreeMap<Declaration, Definition>,
+) -> Fields {
+    let mut result: Vec<(FieldName, Declaration)> = vec![];
+    let mut inlined: Vec<Declaration> = vec![];
+    for (name, field_declaration, flatten) in fields {
+        match flattened.get(&field_declaration) {
+            Some(Definition::Struct {
+                fields: Fields::NamedFields(fields),
+            }) if flatten => {
+                result.extend(fields.iter().cloned());
+                inlined.push(field_declaration);
+            }
+            Some(Definition::Struct {
+                fields: Fields::Empty,
+            }) if flatten => inlined.push(field_declaration),
+            _ => result.push((name, field_declaration)),
+        }
+    }
+    for declaration in inlined {
+        let referenced = result.iter().any(|(_, field)| *field == declaration)
+            || flattened
+                .values()
+                .any(|definition| definition_references(definition, &declaration));
+        if !referenced {
+            flattened.remove(&declaration);
+        }
+    }
+    for (declaration, definition) in flattened {
+        add_definition(declaration, definition, definitions);
+    }
+    if result.is_empty() {
+        Fields::Empty
+    } else {
+        Fields::NamedFields(result)
+    }
+}
+
+/// Whether `definition` refers to `declaration` directly.
+fn definition_references(definition: &Definition, declaration: &Declaration) -> bool {
+    match definition {
+        Definition::Primitive(_) => false,
+        Definition::Sequence { elements, .. } => elements == declaration,
+        Definition::Tuple { elements } => elements.contains(declaration),
+        Definition::Enum { variants, .. } => variants
+            .iter()
+            .any(|(_, _, variant)| variant == declaration),
+        Definition::Struct { fields } => match fields {
+            Fields::NamedFields(fields) => fields.iter().any(|(_, field)| field == declaration),
+            Fields::UnnamedFields(fields) => fields.contains(declaration),
+            Fields::Empty => false,
+        },
+        Definition::Trailing(present) => present == declaration,
+        Definition::Alias(aliased) => aliased == declaration,
+    }
+}
+
+/// Helper method to add the definition of `#[borsh(transparent = "...")]` struct `declaration`
+/// in derived impls, which is an alias of `aliased` type.
+pub fn add_alias_definition(
+    declaration: Declaration,
+    aliased: &Declaration,
+    definitions: &mut BTreeMap<Declaration, Definition>,
+) {
+    add_definition(declaration, Definition::Alias(aliased.clone()), definitions);
+}
+
 /// The declaration and the definition of the type that can be used to (de)serialize Borsh without
 /// the Rust type that produced it.
 pub trait BorshSchema {
@@ -313,6 +415,47 @@ where
     }
 }
 
+impl<T> BorshSchema for Cow<'_, T>
+where
+    T: BorshSchema + ToOwned + ?Sized,
+{
+    fn add_definitions_recursively(definitions: &mut BTreeMap<Declaration, Definition>) {
+        T::add_definitions_recursively(definitions);
+    }
+
+    fn declaration() -> Declaration {
+        T::declaration()
+    }
+}
+
+#[cfg(feature = "rc")]
+impl<T> BorshSchema for Rc<T>
+where
+    T: BorshSchema + ?Sized,
+{
+    fn add_definitions_recursively(definitions: &mut BTreeMap<Declaration, Definition>) {
+        T::add_definitions_recursively(definitions);
+    }
+
+    fn declaration() -> Declaration {
+        T::declaration()
+    }
+}
+
+#[cfg(feature = "rc")]
+impl<T> BorshSchema for Arc<T>
+where
+    T: BorshSchema + ?Sized,
+{
+    fn add_definitions_recursively(definitions: &mut BTreeMap<Declaration, Definition>) {
+        T::add_definitions_recursively(definitions);
+    }
+
+    fn declaration() -> Declaration {
+        T::declaration()
+    }
+}
+
 macro_rules! impl_for_renamed_primitives {
     ($($ty: ty : $name: ident => $size: expr);+) => {
     $(
@@ -537,9 +680,129 @@ where
     }
 }
 
-/// Module is available if borsh is built with `features = ["std"]` or `features = ["hashbrown"]`.
+macro_rules! impl_for_sequence {
+    ($type: ident) => {
+        impl<T> BorshSchema for $type<T>
+        where
+            T: BorshSchema,
+        {
+            fn add_definitions_recursively(definitions: &mut BTreeMap<Declaration, Definition>) {
+                let definition = Definition::Sequence {
+                    length_width: Definition::DEFAULT_LENGTH_WIDTH,
+                    length_range: Definition::DEFAULT_LENGTH_RANGE,
+                    elements: T::declaration(),
+                };
+                add_definition(Self::declaration(), definition, definitions);
+                T::add_definitions_recursively(definitions);
+            }
+
+            fn declaration() -> Declaration {
+                format!(concat!(stringify!($type), "<{}>"), T::declaration())
+            }
+        }
+    };
+}
+
+impl_for_sequence!(VecDeque);
+impl_for_sequence!(LinkedList);
+
+#[cfg(feature = "bytes")]
+macro_rules! impl_for_bytes {
+    ($type: ty) => {
+        impl BorshSchema for $type {
+            fn add_definitions_recursively(definitions: &mut BTreeMap<Declaration, Definition>) {
+                <Vec<u8>>::add_definitions_recursively(definitions);
+            }
+
+            fn declaration() -> Declaration {
+                <Vec<u8>>::declaration()
+            }
+        }
+    };
+}
+
+#[cfg(feature = "bytes")]
+impl_for_bytes!(bytes::Bytes);
+#[cfg(feature = "bytes")]
+impl_for_bytes!(bytes::BytesMut);
+
+#[cfg(feature = "bson")]
+impl BorshSchema for bson::oid::ObjectId {
+    fn add_definitions_recursively(definitions: &mut BTreeMap<Declaration, Definition>) {
+        let fields = Fields::NamedFields(vec![("bytes".to_string(), <[u8; 12]>::declaration())]);
+        add_definition(
+            Self::declaration(),
+            Definition::Struct { fields },
+            definitions,
+        );
+        <[u8; 12]>::add_definitions_recursively(definitions);
+    }
+
+    fn declaration() -> Declaration {
+        "ObjectId".into()
+    }
+}
+
+#[cfg(feature = "std")]
+pub mod net {
+    //! Module is available if borsh is built with `features = ["std"]`.
+    //!
+    //! Module defines [BorshSchema](crate::schema::BorshSchema) implementation for
+    //! [Ipv4Addr](std::net::Ipv4Addr)/[Ipv6Addr](std::net::Ipv6Addr) and
+    //! [SocketAddr](std::net::SocketAddr)/[SocketAddrV4](std::net::SocketAddrV4)/[SocketAddrV6](std::net::SocketAddrV6).
+    use crate::BorshSchema;
+
+    use super::{add_definition, Declaration, Definition, Fields};
+    use std::collections::BTreeMap;
+    use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+
+    macro_rules! impl_for_struct {
+        ($type: ident, $($name: ident: $field: ty),+) => {
+            impl BorshSchema for $type {
+                fn add_definitions_recursively(definitions: &mut BTreeMap<Declaration, Definition>) {
+                    let fields = Fields::NamedFields(vec![
+                        $((stringify!($name).to_string(), <$field>::declaration())),+
+                    ]);
+                    add_definition(Self::declaration(), Definition::Struct { fields }, definitions);
+                    $(<$field>::add_definitions_recursively(definitions);)+
+                }
+
+                fn declaration() -> Declaration {
+                    stringify!($type).into()
+                }
+            }
+        };
+    }
+
+    impl_for_struct!(Ipv4Addr, octets: [u8; 4]);
+    impl_for_struct!(Ipv6Addr, octets: [u8; 16]);
+    impl_for_struct!(SocketAddrV4, ip: Ipv4Addr, port: u16);
+    // flow info and scope id aren't serialized
+    impl_for_struct!(SocketAddrV6, ip: Ipv6Addr, port: u16);
+
+    impl BorshSchema for SocketAddr {
+        fn add_definitions_recursively(definitions: &mut BTreeMap<Declaration, Definition>) {
+            let definition = Definition::Enum {
+                tag_width: 1,
+                variants: vec![
+                    (0, "V4".to_string(), SocketAddrV4::declaration()),
+                    (1, "V6".to_string(), SocketAddrV6::declaration()),
+                ],
+            };
+            add_definition(Self::declaration(), definition, definitions);
+            SocketAddrV4::add_definitions_recursively(definitions);
+            SocketAddrV6::add_definitions_recursively(definitions);
+        }
+
+        fn declaration() -> Declaration {
+            "SocketAddr".into()
+        }
+    }
+}
+
 #[cfg(hash_collections)]
 pub mod hashes {
+    //! Module is available if borsh is built with `features = ["std"]` or `features = ["hashbrown"]`.
     //!
     //! Module defines [BorshSchema](crate::schema::BorshSchema) implementation for
     //! [HashMap](std::collections::HashMap)/[HashSet](std::collections::HashSet).
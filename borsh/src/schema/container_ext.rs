@@ -1,8 +1,24 @@
 use super::{BorshSchemaContainer, Declaration, Definition, Fields};
 
+pub use annotate::{Hexdump, Span, SpanKind};
+pub use codegen::Error as SchemaCodegenError;
+pub use compatibility::Incompatibility;
 pub use max_size::Error as SchemaMaxSerializedSizeError;
 use max_size::{is_zero_size, ZeroSizeError};
 pub use validate::Error as SchemaContainerValidateError;
+pub use value::Error as SchemaValueError;
+/// Possible error when transcoding between JSON and borsh with a [`BorshSchemaContainer`].
+///
+/// JSON is transcoded through [`Value`], so this is the same type as [`SchemaValueError`].
+#[cfg(feature = "schema_json")]
+pub type SchemaJsonError = SchemaValueError;
+pub use value::{FieldValues, Value};
 
+mod annotate;
+mod codegen;
+mod compatibility;
+#[cfg(feature = "schema_json")]
+mod json;
 mod max_size;
 mod validate;
+mod value;
@@ -0,0 +1,379 @@
+use super::value::{
+    check_sequence_len, decode_primitive, decode_varint, definition, is_string, tag_mask, take,
+    take_uint, untagged_variant, varint_type, Error,
+};
+use super::{BorshSchemaContainer, Declaration, Definition, Fields};
+use crate::__private::maybestd::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use crate::schema::Value;
+
+use core::convert::TryFrom;
+use core::fmt;
+
+/// Number of bytes, rendered on a single line of a [`Hexdump`].
+const BYTES_PER_LINE: usize = 16;
+
+impl BorshSchemaContainer {
+    /// Splits borsh `bytes` of the container's type into ranges of primitive values,
+    /// length prefixes and enum tags they consist of, each labelled with the path of the value.
+    ///
+    /// Annotation doesn't fail: the bytes starting from a value, which couldn't be decoded,
+    /// are covered by a single [`SpanKind::Invalid`] span, and the bytes left after the value
+    /// by a [`SpanKind::Trailing`] one.  Zero-sized values aren't annotated.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use borsh::schema::{BorshSchemaContainer, Span, SpanKind};
+    ///
+    /// let schema = BorshSchemaContainer::for_type::<(u16, Option<String>)>();
+    /// let bytes = borsh::to_vec(&(7u16, Some("hi"))).unwrap();
+    /// let spans = schema.annotate(&bytes);
+    /// assert_eq!(spans.len(), 4);
+    /// assert_eq!(
+    ///     spans[2],
+    ///     Span {
+    ///         offset: 3,
+    ///         len: 4,
+    ///         path: "(u16, Option<String>).1::Some".to_string(),
+    ///         declaration: "String".to_string(),
+    ///         kind: SpanKind::Length,
+    ///     }
+    /// );
+    /// ```
+    pub fn annotate(&self, bytes: &[u8]) -> Vec<Span> {
+        let mut annotator = Annotator {
+            schema: self,
+            input: bytes,
+            buf: bytes,
+            spans: Vec::new(),
+            failed_at: None,
+        };
+        let root = self.declaration();
+        if let Err(error) = annotator.annotate(root, root, 0) {
+            let (path, declaration) = annotator.failed_at.take().unwrap_or_default();
+            let offset = annotator.offset();
+            annotator.buf = &[];
+            annotator.push(offset, path, declaration, SpanKind::Invalid(error));
+        } else {
+            let offset = annotator.offset();
+            annotator.buf = &[];
+            annotator.push(offset, String::new(), String::new(), SpanKind::Trailing);
+        }
+        annotator.spans
+    }
+
+    /// Returns an adaptor, which renders an annotated hexdump of borsh `bytes` of the
+    /// container's type with [`Display`](fmt::Display): a line per span of
+    /// [`annotate`](Self::annotate) with its offset, bytes and label, followed by
+    /// the decoded value for primitives, strings, length prefixes and enum tags.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use borsh::schema::BorshSchemaContainer;
+    ///
+    /// let schema = BorshSchemaContainer::for_type::<(u16, Option<String>)>();
+    /// let bytes = borsh::to_vec(&(7u16, Some("hi"))).unwrap();
+    /// assert_eq!(
+    ///     schema.hexdump(&bytes).to_string(),
+    ///     "\
+    /// 00000000  07 00                                            (u16, Option<String>).0: u16 = 7
+    /// 00000002  01                                               (u16, Option<String>).1: Option<String> (tag) = 1
+    /// 00000003  02 00 00 00                                      (u16, Option<String>).1::Some: String (length) = 2
+    /// 00000007  68 69                                            (u16, Option<String>).1::Some: String = \"hi\"
+    /// "
+    /// );
+    /// ```
+    pub fn hexdump<'a>(&'a self, bytes: &'a [u8]) -> Hexdump<'a> {
+        Hexdump {
+            schema: self,
+            bytes,
+        }
+    }
+}
+
+/// Range of borsh bytes, labelled with the value it belongs to,
+/// produced by [`BorshSchemaContainer::annotate`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Span {
+    /// Offset of the first byte of the range in the input.
+    pub offset: usize,
+    /// Number of bytes in the range.
+    pub len: usize,
+    /// Path of the value from the root, e.g. `Account.balances[2]` or `Option<u8>::Some`,
+    /// starting with the declaration of the container, empty for [`SpanKind::Trailing`].
+    pub path: String,
+    /// Declaration of the type of the value, empty for [`SpanKind::Trailing`].
+    pub declaration: Declaration,
+    pub kind: SpanKind,
+}
+
+/// What bytes of a [`Span`] encode.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum SpanKind {
+    /// A primitive value, or all elements of a sequence of `u8`, e.g. of a `String`.
+    Value,
+    /// Length prefix of a sequence.
+    Length,
+    /// Tag of an enum variant.
+    Tag,
+    /// The bytes starting from the value, which couldn't be decoded.
+    Invalid(Error),
+    /// The bytes left after the value of the container's type.
+    Trailing,
+}
+
+/// Annotated hexdump of borsh bytes, returned by [`BorshSchemaContainer::hexdump`].
+#[derive(Clone, Copy, Debug)]
+pub struct Hexdump<'a> {
+    schema: &'a BorshSchemaContainer,
+    bytes: &'a [u8],
+}
+
+impl fmt::Display for Hexdump<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for span in self.schema.annotate(self.bytes) {
+            let bytes = &self.bytes[span.offset..span.offset + span.len];
+            let label = match &span.kind {
+                SpanKind::Trailing => "(trailing bytes)".to_string(),
+                kind => {
+                    let mut label = format!("{}: {}", span.path, span.declaration);
+                    match kind {
+                        SpanKind::Length => label.push_str(" (length)"),
+                        SpanKind::Tag => label.push_str(" (tag)"),
+                        SpanKind::Invalid(error) => {
+                            label.push_str(&format!(" (invalid: {:?})", error))
+                        }
+                        _ => {}
+                    }
+                    if let Some(value) = display_value(&span, bytes) {
+                        label.push_str(" = ");
+                        label.push_str(&value);
+                    }
+                    label
+                }
+            };
+            let mut lines = bytes.chunks(BYTES_PER_LINE);
+            let first = lines.next().unwrap_or(&[]);
+            writeln!(f, "{:08x}  {:<47}  {}", span.offset, hex(first), label)?;
+            for (index, line) in lines.enumerate() {
+                let offset = span.offset + (index + 1) * BYTES_PER_LINE;
+                writeln!(f, "{:08x}  {}", offset, hex(line))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Decoded value of a span, if it's a number or a string.
+fn display_value(span: &Span, bytes: &[u8]) -> Option<String> {
+    match span.kind {
+        SpanKind::Length | SpanKind::Tag => {
+            let width = u8::try_from(bytes.len()).ok()?;
+            take_uint(&span.declaration, &mut &bytes[..], width)
+                .ok()
+                .map(|value| value.to_string())
+        }
+        SpanKind::Value if is_string(&span.declaration, "u8") => core::str::from_utf8(bytes)
+            .ok()
+            .map(|string| format!("{:?}", string)),
+        SpanKind::Value => match decode_primitive(&span.declaration, bytes).ok()? {
+            Value::Bool(value) => Some(value.to_string()),
+            Value::U8(value) => Some(value.to_string()),
+            Value::U16(value) => Some(value.to_string()),
+            Value::U32(value) => Some(value.to_string()),
+            Value::U64(value) => Some(value.to_string()),
+            Value::U128(value) => Some(value.to_string()),
+            Value::I8(value) => Some(value.to_string()),
+            Value::I16(value) => Some(value.to_string()),
+            Value::I32(value) => Some(value.to_string()),
+            Value::I64(value) => Some(value.to_string()),
+            Value::I128(value) => Some(value.to_string()),
+            Value::F32(value) => Some(value.to_string()),
+            Value::F64(value) => Some(value.to_string()),
+            _ => None,
+        },
+        SpanKind::Invalid(_) | SpanKind::Trailing => None,
+    }
+}
+
+struct Annotator<'a> {
+    schema: &'a BorshSchemaContainer,
+    input: &'a [u8],
+    /// the part of the input, which isn't annotated yet
+    buf: &'a [u8],
+    spans: Vec<Span>,
+    /// path and declaration of the innermost value, which couldn't be decoded
+    failed_at: Option<(String, Declaration)>,
+}
+
+impl Annotator<'_> {
+    fn offset(&self) -> usize {
+        self.input.len() - self.buf.len()
+    }
+
+    /// Adds a span from `offset` up to the not annotated part of the input.
+    fn push(&mut self, offset: usize, path: String, declaration: Declaration, kind: SpanKind) {
+        let len = self.offset() - offset;
+        if len == 0 && !matches!(kind, SpanKind::Invalid(_)) {
+            return;
+        }
+        self.spans.push(Span {
+            offset,
+            len,
+            path,
+            declaration,
+            kind,
+        });
+    }
+
+    /// Annotates a value, leaving the input at the start of the invalid part on error.
+    fn annotate(&mut self, declaration: &str, path: &str, depth: usize) -> Result<(), Error> {
+        let result = self.annotate_value(declaration, path, depth);
+        if result.is_err() && self.failed_at.is_none() {
+            self.failed_at = Some((path.to_string(), declaration.to_string()));
+        }
+        result
+    }
+
+    fn annotate_value(&mut self, declaration: &str, path: &str, depth: usize) -> Result<(), Error> {
+        let offset = self.offset();
+        match definition(declaration, self.schema, depth)? {
+            Definition::Primitive(size) => {
+                let mut buf = self.buf;
+                let bytes = take(declaration, &mut buf, usize::from(*size))?;
+                decode_primitive(declaration, bytes)?;
+                self.buf = buf;
+                self.push(offset, path.into(), declaration.into(), SpanKind::Value);
+            }
+            Definition::Sequence {
+                length_width,
+                length_range,
+                elements,
+            } => {
+                if let Some((integer, _)) = varint_type(declaration, self.schema) {
+                    let mut buf = self.buf;
+                    decode_varint(declaration, integer, &mut buf)?;
+                    self.buf = buf;
+                    self.push(offset, path.into(), declaration.into(), SpanKind::Value);
+                    return Ok(());
+                }
+                let len = if *length_width == Definition::ARRAY_LENGTH_WIDTH {
+                    if length_range.start() != length_range.end() {
+                        return Err(Error::UnknownLength(declaration.to_string()));
+                    }
+                    *length_range.start()
+                } else {
+                    let mut buf = self.buf;
+                    let len = take_uint(declaration, &mut buf, *length_width)?;
+                    if !length_range.contains(&len) {
+                        return Err(Error::InvalidData(
+                            declaration.to_string(),
+                            format!("length {} is out of range {:?}", len, length_range),
+                        ));
+                    }
+                    self.buf = buf;
+                    self.push(offset, path.into(), declaration.into(), SpanKind::Length);
+                    len
+                };
+                if elements == "u8" {
+                    let offset = self.offset();
+                    let len = usize::try_from(len)
+                        .map_err(|_| Error::UnexpectedEof(declaration.to_string()))?;
+                    let mut buf = self.buf;
+                    let bytes = take(declaration, &mut buf, len)?;
+                    if is_string(declaration, elements) {
+                        core::str::from_utf8(bytes).map_err(|err| {
+                            Error::InvalidData(declaration.to_string(), err.to_string())
+                        })?;
+                    }
+                    self.buf = buf;
+                    self.push(offset, path.into(), declaration.into(), SpanKind::Value);
+                } else {
+                    check_sequence_len(
+                        declaration,
+                        *length_width,
+                        elements,
+                        len,
+                        self.schema,
+                        self.buf,
+                    )?;
+                    for index in 0..len {
+                        let path = format!("{}[{}]", path, index);
+                        self.annotate(elements, &path, depth + 1)?;
+                    }
+                }
+            }
+            Definition::Tuple { elements } => {
+                for (index, element) in elements.iter().enumerate() {
+                    let path = format!("{}.{}", path, index);
+                    self.annotate(element, &path, depth + 1)?;
+                }
+            }
+            Definition::Enum {
+                tag_width: 0,
+                variants,
+            } => {
+                let mut buf = self.buf;
+                let (index, _) =
+                    untagged_variant(declaration, variants, self.schema, &mut buf, depth)?;
+                let path = format!("{}::{}", path, variants[index].1);
+                self.annotate(&variants[index].2, &path, depth + 1)?;
+            }
+            Definition::Enum {
+                tag_width,
+                variants,
+            } => {
+                let mut buf = self.buf;
+                let tag = take_uint(declaration, &mut buf, *tag_width)?;
+                let (_, name, variant) = variants
+                    .iter()
+                    .find(|(discriminant, _, _)| *discriminant as u64 & tag_mask(*tag_width) == tag)
+                    .ok_or_else(|| {
+                        Error::InvalidData(
+                            declaration.to_string(),
+                            format!("unexpected variant tag: {}", tag),
+                        )
+                    })?;
+                self.buf = buf;
+                self.push(offset, path.into(), declaration.into(), SpanKind::Tag);
+                let path = format!("{}::{}", path, name);
+                self.annotate(variant, &path, depth + 1)?;
+            }
+            Definition::Struct { fields } => match fields {
+                Fields::NamedFields(fields) => {
+                    for (name, field) in fields {
+                        let path = format!("{}.{}", path, name);
+                        self.annotate(field, &path, depth + 1)?;
+                    }
+                }
+                Fields::UnnamedFields(fields) => {
+                    for (index, field) in fields.iter().enumerate() {
+                        let path = format!("{}.{}", path, index);
+                        self.annotate(field, &path, depth + 1)?;
+                    }
+                }
+                Fields::Empty => {}
+            },
+            Definition::Trailing(present) => {
+                if !self.buf.is_empty() {
+                    self.annotate(present, path, depth + 1)?;
+                }
+            }
+            Definition::Alias(aliased) => self.annotate(aliased, path, depth + 1)?,
+        }
+        Ok(())
+    }
+}
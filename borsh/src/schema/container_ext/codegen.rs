@@ -0,0 +1,264 @@
+//! Shared parts of code generators, which emit (de)serializers for other
+//! languages from a [`BorshSchemaContainer`].
+
+use super::value::{collection, is_string, tag_mask};
+use super::{BorshSchemaContainer, Declaration, Definition, Fields};
+use crate::__private::maybestd::{
+    collections::{BTreeMap, BTreeSet},
+    format,
+    string::{String, ToString},
+};
+
+mod python;
+mod typescript;
+
+/// Possible error when generating code from a [`BorshSchemaContainer`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Error {
+    /// Some of the declared types were lacking definition.
+    MissingDefinition(Declaration),
+}
+
+/// Primitive type, as understood by code generators.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Primitive {
+    /// Zero-sized primitive, e.g. `()`.
+    Unit,
+    Bool,
+    Int {
+        bytes: u8,
+        signed: bool,
+        non_zero: bool,
+    },
+    Float {
+        bytes: u8,
+    },
+    /// Primitive, unknown to borsh, which is handled as raw bytes.
+    Opaque {
+        bytes: u8,
+    },
+}
+
+impl Primitive {
+    fn new(declaration: &str, size: u8) -> Self {
+        let (name, non_zero) = match declaration.strip_prefix("NonZero") {
+            Some("Usize") => ("u64".to_string(), true),
+            Some(name) => (name.to_ascii_lowercase(), true),
+            None => (declaration.to_string(), false),
+        };
+        let int = |signed| Primitive::Int {
+            bytes: size,
+            signed,
+            non_zero,
+        };
+        match (name.as_str(), size) {
+            (_, 0) => Primitive::Unit,
+            ("bool", 1) if !non_zero => Primitive::Bool,
+            ("u8", 1) | ("u16", 2) | ("u32", 4) | ("u64", 8) | ("u128", 16) => int(false),
+            ("i8", 1) | ("i16", 2) | ("i32", 4) | ("i64", 8) | ("i128", 16) => int(true),
+            ("f32", 4) | ("f64", 8) if !non_zero => Primitive::Float { bytes: size },
+            _ => Primitive::Opaque { bytes: size },
+        }
+    }
+
+    /// Name of the borsh primitive, e.g. `u32`, as used by the generated runtimes.
+    fn name(&self) -> String {
+        match self {
+            Primitive::Unit => "unit".to_string(),
+            Primitive::Bool => "bool".to_string(),
+            Primitive::Int { bytes, signed, .. } => {
+                format!("{}{}", if *signed { "i" } else { "u" }, bytes * 8)
+            }
+            Primitive::Float { bytes } => format!("f{}", bytes * 8),
+            Primitive::Opaque { .. } => "bytes".to_string(),
+        }
+    }
+}
+
+/// Kind of a `Definition::Sequence`, which affects how it's represented in other languages.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Sequence<'a> {
+    String,
+    Bytes,
+    Map {
+        key: &'a Declaration,
+        value: &'a Declaration,
+    },
+    Set,
+    List,
+}
+
+impl<'a> Sequence<'a> {
+    fn new(
+        declaration: &str,
+        elements: &'a Declaration,
+        schema: &'a BorshSchemaContainer,
+    ) -> Result<Self, Error> {
+        if is_string(declaration, elements) {
+            return Ok(Sequence::String);
+        }
+        if elements == "u8" {
+            return Ok(Sequence::Bytes);
+        }
+        let collection = collection(declaration);
+        let is_generic = |names: &[&str]| {
+            names
+                .iter()
+                .any(|name| collection.starts_with(&format!("{}<", name)))
+        };
+        if is_generic(&["BTreeMap", "HashMap"]) {
+            if let Definition::Tuple { elements } = definition(elements, schema)? {
+                if let [key, value] = elements.as_slice() {
+                    return Ok(Sequence::Map { key, value });
+                }
+            }
+        }
+        if is_generic(&["BTreeSet", "HashSet"]) {
+            return Ok(Sequence::Set);
+        }
+        Ok(Sequence::List)
+    }
+}
+
+fn definition<'a>(
+    declaration: &str,
+    schema: &'a BorshSchemaContainer,
+) -> Result<&'a Definition, Error> {
+    schema
+        .get_definition(declaration)
+        .ok_or_else(|| Error::MissingDefinition(declaration.to_string()))
+}
+
+/// Whether values of the declared type hold no data, e.g. `()` or a struct without fields.
+fn is_unit(declaration: &str, schema: &BorshSchemaContainer) -> Result<bool, Error> {
+    Ok(matches!(
+        definition(declaration, schema)?,
+        Definition::Primitive(0)
+            | Definition::Struct {
+                fields: Fields::Empty
+            }
+    ))
+}
+
+/// Whether lengths, encoded with `length_width` bytes, are to be checked against `range`.
+fn is_restricted(length_width: u8, range: &core::ops::RangeInclusive<u64>) -> bool {
+    let max = match length_width {
+        0 => return true,
+        1..=7 => (1 << (u32::from(length_width) * 8)) - 1,
+        _ => u64::MAX,
+    };
+    *range.start() != 0 || *range.end() < max
+}
+
+/// Unique identifiers in the generated code for all non-primitive declarations of a schema.
+struct Names<'a> {
+    names: BTreeMap<&'a str, String>,
+    taken: BTreeSet<String>,
+}
+
+impl<'a> Names<'a> {
+    /// Derives identifiers from declarations, avoiding `reserved` ones.
+    ///
+    /// Identifiers depend only on the set of declarations, so the generated code is deterministic.
+    fn new(schema: &'a BorshSchemaContainer, reserved: &[&str]) -> Self {
+        let mut result = Self {
+            names: BTreeMap::new(),
+            taken: reserved.iter().map(|name| name.to_string()).collect(),
+        };
+        for (declaration, definition) in schema.definitions() {
+            if let Definition::Primitive(_) = definition {
+                continue;
+            }
+            let name = result.fresh(identifier(declaration));
+            result.names.insert(declaration.as_str(), name);
+        }
+        result
+    }
+
+    /// Reserves an identifier, based on `base`, which isn't used by anything else.
+    fn fresh(&mut self, base: String) -> String {
+        let mut name = base.clone();
+        let mut suffix = 2;
+        while !self.taken.insert(name.clone()) {
+            name = format!("{}_{}", base, suffix);
+            suffix += 1;
+        }
+        name
+    }
+
+    fn get(&self, declaration: &str) -> Result<&str, Error> {
+        self.names
+            .get(declaration)
+            .map(String::as_str)
+            .ok_or_else(|| Error::MissingDefinition(declaration.to_string()))
+    }
+}
+
+/// Turns a declaration, e.g. `Option<[u8; 4]>`, into an identifier, e.g. `Option_Array_u8_4`.
+fn identifier(declaration: &str) -> String {
+    let mut result = String::new();
+    let mut separated = true;
+    for char in declaration.chars() {
+        let prefix = match char {
+            '[' => Some("Array"),
+            '(' => Some("Tuple"),
+            _ => None,
+        };
+        if let Some(prefix) = prefix {
+            if !separated {
+                result.push('_');
+            }
+            result.push_str(prefix);
+            result.push('_');
+            separated = true;
+        } else if char.is_ascii_alphanumeric() {
+            result.push(char);
+            separated = false;
+        } else if !separated {
+            result.push('_');
+            separated = true;
+        }
+    }
+    while result.ends_with('_') {
+        result.pop();
+    }
+    if result.is_empty() || result.starts_with(|char: char| char.is_ascii_digit()) {
+        result.insert(0, '_');
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{identifier, tag_mask, Primitive};
+
+    #[test]
+    fn test_identifier() {
+        assert_eq!(identifier("Option<u32>"), "Option_u32");
+        assert_eq!(identifier("[u8; 4]"), "Array_u8_4");
+        assert_eq!(
+            identifier("Vec<(u8, [String; 2])>"),
+            "Vec_Tuple_u8_Array_String_2"
+        );
+        assert_eq!(identifier("(u8,)"), "Tuple_u8");
+        assert_eq!(identifier("HashMap<u8, Vec<u8>>"), "HashMap_u8_Vec_u8");
+    }
+
+    #[test]
+    fn test_primitive() {
+        assert_eq!(Primitive::new("()", 0), Primitive::Unit);
+        assert_eq!(Primitive::new("NonZeroUsize", 8).name(), "u64");
+        assert_eq!(Primitive::new("i128", 16).name(), "i128");
+        assert_eq!(
+            Primitive::new("ObjectId", 12),
+            Primitive::Opaque { bytes: 12 }
+        );
+    }
+
+    #[test]
+    fn test_tag_mask() {
+        assert_eq!(-1i64 as u64 & tag_mask(1), 0xff);
+        assert_eq!(300 & tag_mask(2), 300);
+        assert_eq!(-1i64 as u64 & tag_mask(8), u64::MAX);
+    }
+}
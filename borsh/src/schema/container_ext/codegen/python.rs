@@ -0,0 +1,527 @@
+use super::{definition, is_restricted, is_unit, tag_mask, Error, Names, Primitive, Sequence};
+use crate::__private::maybestd::{
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use crate::schema::{BorshSchemaContainer, Declaration, Definition, Fields};
+
+/// Reader, writer and helpers, which the generated code is built upon.
+const RUNTIME: &str = include_str!("runtime.py");
+
+/// Identifiers of the runtime and imported names, which generated types may not shadow.
+const RESERVED: &[&str] = &[
+    "BorshError",
+    "BorshReader",
+    "BorshWriter",
+    "Dict",
+    "List",
+    "NoReturn",
+    "Set",
+    "Tuple",
+    "Union",
+    "dataclass",
+    "math",
+    "struct",
+];
+
+/// Python keywords, which can't be used as names of fields.
+const KEYWORDS: &[&str] = &[
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue",
+    "def", "del", "elif", "else", "except", "finally", "for", "from", "global", "if", "import",
+    "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while",
+    "with", "yield",
+];
+
+impl BorshSchemaContainer {
+    /// Generates a self-contained Python module with dataclasses and
+    /// (de)serializers for every type of the container.
+    ///
+    /// For every declaration `Decl`, which isn't a primitive, the module defines
+    /// a type `Decl`, `serialize_Decl(writer, value)` and `deserialize_Decl(reader)`,
+    /// with declarations turned into identifiers, e.g. `Option<u8>` into `Option_u8`.
+    /// Additionally, `encode(value)` and `decode(data)` are defined for the
+    /// container's type itself.  Only the standard library is required, i.e.
+    /// `struct`, `dataclasses` and `typing`.
+    ///
+    /// Structs are represented by frozen dataclasses, with unnamed fields named
+    /// `_0`, `_1` and so on.  Every enum variant gets its own dataclass, named
+    /// `Enum_Variant`, which holds the variant's data in the `value` field, if any.
+    /// Sequences of `u8` are represented by `bytes`, maps and sets by `dict` and `set`,
+    /// other sequences and tuples by `list` and `tuple`.  Elements of sets are
+    /// written in sorted order, while entries of maps are written in the order of
+    /// insertion, which is preserved when decoding.
+    ///
+    /// Output depends only on the schema, which makes it suitable for snapshot tests.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use borsh::schema::BorshSchemaContainer;
+    ///
+    /// let schema = BorshSchemaContainer::for_type::<Option<u8>>();
+    /// let code = schema.generate_python().unwrap();
+    /// assert!(code.contains("Option_u8 = Union[\"Option_u8_None\", \"Option_u8_Some\"]"));
+    /// ```
+    pub fn generate_python(&self) -> Result<String, Error> {
+        let mut names = Names::new(self, RESERVED);
+        let mut variants = BTreeMap::new();
+        for (declaration, definition) in self.definitions() {
+            if let Definition::Enum {
+                variants: definitions,
+                ..
+            } = definition
+            {
+                let name = names.get(declaration)?.to_string();
+                for (_, variant, _) in definitions {
+                    let class = names.fresh(format!("{}_{}", name, variant));
+                    variants.insert((declaration.as_str(), variant.as_str()), class);
+                }
+            }
+        }
+        let generator = Generator {
+            schema: self,
+            names,
+            variants,
+        };
+        generator.module()
+    }
+}
+
+struct Generator<'a> {
+    schema: &'a BorshSchemaContainer,
+    names: Names<'a>,
+    /// Names of the dataclasses of enum variants.
+    variants: BTreeMap<(&'a str, &'a str), String>,
+}
+
+impl Generator<'_> {
+    fn module(&self) -> Result<String, Error> {
+        let mut out = format!(
+            "# Generated by borsh from the schema of `{}`, do not edit.\n\n",
+            self.schema.declaration()
+        );
+        out.push_str(RUNTIME);
+        for (declaration, definition) in self.schema.definitions() {
+            if let Definition::Primitive(_) = definition {
+                continue;
+            }
+            self.definition(&mut out, declaration, definition)?;
+        }
+
+        let root = self.schema.declaration();
+        let ty = self.ty(root)?;
+        function(
+            &mut out,
+            &format!("encode(value: {}) -> bytes", ty),
+            vec!["writer = BorshWriter()".to_string()]
+                .into_iter()
+                .chain(self.write(root, "value")?)
+                .chain(Some("return writer.to_bytes()".to_string()))
+                .collect(),
+        );
+        function(
+            &mut out,
+            &format!("decode(data: bytes) -> {}", ty),
+            vec![
+                "reader = BorshReader(data)".to_string(),
+                format!("value = {}", self.read(root)?),
+                "reader.finish()".to_string(),
+                "return value".to_string(),
+            ],
+        );
+        Ok(out)
+    }
+
+    /// Python type of values of the declared type.
+    ///
+    /// Names of the generated types are `quoted`, when used outside of annotations.
+    fn ty_of(&self, declaration: &str, quoted: bool) -> Result<String, Error> {
+        Ok(match definition(declaration, self.schema)? {
+            Definition::Primitive(size) => match Primitive::new(declaration, *size) {
+                Primitive::Unit => "None",
+                Primitive::Bool => "bool",
+                Primitive::Int { .. } => "int",
+                Primitive::Float { .. } => "float",
+                Primitive::Opaque { .. } => "bytes",
+            }
+            .to_string(),
+            _ if quoted => format!("\"{}\"", self.names.get(declaration)?),
+            _ => self.names.get(declaration)?.to_string(),
+        })
+    }
+
+    fn ty(&self, declaration: &str) -> Result<String, Error> {
+        self.ty_of(declaration, false)
+    }
+
+    /// Statement, which writes `expr` of the declared type, unless there is nothing to write.
+    fn write(&self, declaration: &str, expr: &str) -> Result<Option<String>, Error> {
+        Ok(Some(match definition(declaration, self.schema)? {
+            Definition::Primitive(size) => match Primitive::new(declaration, *size) {
+                Primitive::Unit => return Ok(None),
+                Primitive::Opaque { bytes } => format!("writer.fixed_bytes({}, {})", bytes, expr),
+                primitive @ Primitive::Int { non_zero: true, .. } => {
+                    format!("writer.{}(non_zero({}))", primitive.name(), expr)
+                }
+                primitive => format!("writer.{}({})", primitive.name(), expr),
+            },
+            _ => format!(
+                "serialize_{}(writer, {})",
+                self.names.get(declaration)?,
+                expr
+            ),
+        }))
+    }
+
+    /// Expression, which reads a value of the declared type.
+    fn read(&self, declaration: &str) -> Result<String, Error> {
+        Ok(match definition(declaration, self.schema)? {
+            Definition::Primitive(size) => match Primitive::new(declaration, *size) {
+                Primitive::Unit => "None".to_string(),
+                Primitive::Opaque { bytes } => format!("reader.bytes({})", bytes),
+                primitive @ Primitive::Int { non_zero: true, .. } => {
+                    format!("non_zero(reader.{}())", primitive.name())
+                }
+                primitive => format!("reader.{}()", primitive.name()),
+            },
+            _ => format!("deserialize_{}(reader)", self.names.get(declaration)?),
+        })
+    }
+
+    fn definition(
+        &self,
+        out: &mut String,
+        declaration: &Declaration,
+        definition: &Definition,
+    ) -> Result<(), Error> {
+        let name = self.names.get(declaration)?;
+        let (ty, write, read) = match definition {
+            Definition::Primitive(_) => unreachable!("primitives aren't named"),
+            Definition::Sequence {
+                length_width,
+                length_range,
+                elements,
+            } => {
+                let sequence = Sequence::new(declaration, elements, self.schema)?;
+                let ty = match sequence {
+                    Sequence::String => "str".to_string(),
+                    Sequence::Bytes => "bytes".to_string(),
+                    Sequence::Map { key, value } => format!(
+                        "Dict[{}, {}]",
+                        self.ty_of(key, true)?,
+                        self.ty_of(value, true)?
+                    ),
+                    Sequence::Set => format!("Set[{}]", self.ty_of(elements, true)?),
+                    Sequence::List => format!("List[{}]", self.ty_of(elements, true)?),
+                };
+                let length = if sequence == Sequence::String {
+                    "len(data)"
+                } else {
+                    "len(value)"
+                };
+                let check = format!(
+                    "check_length({{}}, {}, {})",
+                    length_range.start(),
+                    length_range.end()
+                );
+                let restricted = is_restricted(*length_width, length_range);
+
+                let mut write = Vec::new();
+                if sequence == Sequence::String {
+                    write.push("data = value.encode(\"utf-8\")".to_string());
+                }
+                if restricted {
+                    write.push(check.replace("{}", length));
+                }
+                if *length_width != Definition::ARRAY_LENGTH_WIDTH {
+                    write.push(format!("writer.uint({}, {})", length_width, length));
+                }
+                match sequence {
+                    Sequence::String => write.push("writer.bytes(data)".to_string()),
+                    Sequence::Bytes => write.push("writer.bytes(value)".to_string()),
+                    Sequence::Map { key, value } => {
+                        let body: Vec<_> = self
+                            .write(key, "key")?
+                            .into_iter()
+                            .chain(self.write(value, "item")?)
+                            .collect();
+                        if !body.is_empty() {
+                            write.push("for key, item in value.items():".to_string());
+                            write.extend(body.into_iter().map(indent));
+                        }
+                    }
+                    Sequence::Set | Sequence::List => {
+                        if let Some(item) = self.write(elements, "item")? {
+                            // borsh expects elements of sets in ascending order
+                            write.push(if sequence == Sequence::Set {
+                                "for item in sorted(value):".to_string()
+                            } else {
+                                "for item in value:".to_string()
+                            });
+                            write.push(indent(item));
+                        }
+                    }
+                }
+
+                let mut read = Vec::new();
+                if *length_width == Definition::ARRAY_LENGTH_WIDTH {
+                    read.push(format!("length = {}", length_range.start()));
+                } else {
+                    read.push(format!("length = reader.uint({})", length_width));
+                    if restricted {
+                        read.push(check.replace("{}", "length"));
+                    }
+                }
+                match sequence {
+                    Sequence::String => {
+                        read.push("return reader.bytes(length).decode(\"utf-8\")".to_string())
+                    }
+                    Sequence::Bytes => read.push("return reader.bytes(length)".to_string()),
+                    Sequence::Map { key, value } => {
+                        read.push(format!("value: {} = {{}}", name));
+                        read.push("for _ in range(length):".to_string());
+                        read.push(indent(format!("key = {}", self.read(key)?)));
+                        read.push(indent(format!("value[key] = {}", self.read(value)?)));
+                        read.push("return value".to_string());
+                    }
+                    Sequence::Set => read.push(format!(
+                        "return {{{} for _ in range(length)}}",
+                        self.read(elements)?
+                    )),
+                    Sequence::List => read.push(format!(
+                        "return [{} for _ in range(length)]",
+                        self.read(elements)?
+                    )),
+                }
+                (format!("{} = {}\n", name, ty), write, read)
+            }
+            Definition::Tuple { elements } => {
+                let mut types = Vec::new();
+                let mut write = Vec::new();
+                let mut reads = Vec::new();
+                for (idx, element) in elements.iter().enumerate() {
+                    types.push(self.ty_of(element, true)?);
+                    write.extend(self.write(element, &format!("value[{}]", idx))?);
+                    reads.push(self.read(element)?);
+                }
+                // a trailing comma makes single-element tuples
+                let trailing = if reads.len() == 1 { "," } else { "" };
+                (
+                    format!("{} = Tuple[{}]\n", name, types.join(", ")),
+                    write,
+                    vec![format!("return ({}{})", reads.join(", "), trailing)],
+                )
+            }
+            Definition::Struct { fields } => {
+                let fields: Vec<(String, &Declaration)> = match fields {
+                    Fields::NamedFields(fields) => fields
+                        .iter()
+                        .map(|(field, declaration)| (field_name(field), declaration))
+                        .collect(),
+                    Fields::UnnamedFields(fields) => fields
+                        .iter()
+                        .enumerate()
+                        .map(|(idx, declaration)| (format!("_{}", idx), declaration))
+                        .collect(),
+                    Fields::Empty => Vec::new(),
+                };
+                let mut write = Vec::new();
+                let mut reads = Vec::new();
+                let mut attributes = Vec::new();
+                for (field, declaration) in &fields {
+                    attributes.push(format!("{}: {}", field, self.ty(declaration)?));
+                    write.extend(self.write(declaration, &format!("value.{}", field))?);
+                    reads.push(format!("{}={}", field, self.read(declaration)?));
+                }
+                (
+                    dataclass(name, attributes),
+                    write,
+                    vec![format!("return {}({})", name, reads.join(", "))],
+                )
+            }
+            Definition::Enum {
+                tag_width,
+                variants,
+            } => self.variants(declaration, name, *tag_width, variants)?,
+            Definition::Trailing(present) => (
+                format!("{} = Union[{}, None]\n", name, self.ty_of(present, true)?),
+                match self.write(present, "value")? {
+                    Some(write) => vec!["if value is not None:".to_string(), indent(write)],
+                    None => Vec::new(),
+                },
+                vec![format!(
+                    "return None if reader.at_end() else {}",
+                    self.read(present)?
+                )],
+            ),
+            // a single-member `Union` keeps the quoted name of a type, defined later, a type
+            Definition::Alias(aliased) => (
+                format!("{} = Union[{}]\n", name, self.ty_of(aliased, true)?),
+                self.write(aliased, "value")?.into_iter().collect(),
+                vec![format!("return {}", self.read(aliased)?)],
+            ),
+        };
+
+        out.push_str("\n\n");
+        out.push_str(&ty);
+        // parameters, which aren't used, are prefixed to keep linters happy
+        let param = |name: &str, body: &[String]| {
+            if body.iter().any(|line| line.contains(name)) {
+                name.to_string()
+            } else {
+                format!("_{}", name)
+            }
+        };
+        function(
+            out,
+            &format!(
+                "serialize_{}({}: BorshWriter, {}: {}) -> None",
+                name,
+                param("writer", &write),
+                param("value", &write),
+                name
+            ),
+            write,
+        );
+        function(
+            out,
+            &format!(
+                "deserialize_{}({}: BorshReader) -> {}",
+                name,
+                param("reader", &read),
+                name
+            ),
+            read,
+        );
+        Ok(())
+    }
+
+    fn variants(
+        &self,
+        declaration: &str,
+        name: &str,
+        tag_width: u8,
+        variants: &[(i64, String, Declaration)],
+    ) -> Result<(String, Vec<String>, Vec<String>), Error> {
+        if variants.is_empty() {
+            let unreachable = format!("raise BorshError(\"{} has no values\")", name);
+            return Ok((
+                format!("{} = NoReturn\n", name),
+                vec![unreachable.clone()],
+                vec![unreachable],
+            ));
+        }
+
+        let mut ty = String::new();
+        let mut classes = Vec::new();
+        let mut write = Vec::new();
+        let mut read = Vec::new();
+        if tag_width == 0 {
+            read.push("start = reader.offset".to_string());
+        } else {
+            read.push(format!("tag = reader.uint({})", tag_width));
+        }
+
+        for (idx, (discriminant, variant, payload)) in variants.iter().enumerate() {
+            let class = &self.variants[&(declaration, variant.as_str())];
+            let unit = is_unit(payload, self.schema)?;
+            let attributes = if unit {
+                Vec::new()
+            } else {
+                vec![format!("value: {}", self.ty(payload)?)]
+            };
+            ty.push_str(&dataclass(class, attributes));
+            ty.push_str("\n\n");
+            classes.push(format!("\"{}\"", class));
+
+            let tag = *discriminant as u64 & tag_mask(tag_width);
+            let keyword = if idx == 0 { "if" } else { "elif" };
+            write.push(format!("{} isinstance(value, {}):", keyword, class));
+            if tag_width != 0 {
+                write.push(indent(format!("writer.uint({}, {})", tag_width, tag)));
+            }
+            if !unit {
+                write.extend(self.write(payload, "value.value")?.map(indent));
+            }
+            if tag_width == 0 && unit {
+                write.push(indent("pass".to_string()));
+            }
+
+            let result = if unit {
+                format!("{}()", class)
+            } else {
+                format!("{}({})", class, self.read(payload)?)
+            };
+            if tag_width == 0 {
+                read.push("try:".to_string());
+                read.push(indent(format!("return {}", result)));
+                read.push("except ValueError:".to_string());
+                read.push(indent("reader.offset = start".to_string()));
+            } else {
+                read.push(format!("if tag == {}:", tag));
+                read.push(indent(format!("return {}", result)));
+            }
+        }
+
+        ty.push_str(&format!("{} = Union[{}]\n", name, classes.join(", ")));
+        write.push("else:".to_string());
+        write.push(indent(format!(
+            "raise BorshError(f\"Unexpected value of {}: {{value!r}}\")",
+            name
+        )));
+        if tag_width == 0 {
+            read.push(format!(
+                "raise BorshError(\"None of the variants of {} match\")",
+                name
+            ));
+        } else {
+            read.push(format!(
+                "raise BorshError(f\"Unexpected variant tag of {}: {{tag}}\")",
+                name
+            ));
+        }
+        Ok((ty, write, read))
+    }
+}
+
+/// Python attribute for a named field, e.g. `class_` for `r#class`.
+fn field_name(field: &str) -> String {
+    let field = field.strip_prefix("r#").unwrap_or(field);
+    if KEYWORDS.contains(&field) {
+        format!("{}_", field)
+    } else {
+        field.to_string()
+    }
+}
+
+fn indent(line: String) -> String {
+    format!("    {}", line)
+}
+
+fn dataclass(name: &str, attributes: Vec<String>) -> String {
+    let mut out = format!("@dataclass(frozen=True, order=True)\nclass {}:\n", name);
+    if attributes.is_empty() {
+        out.push_str("    pass\n");
+    }
+    for attribute in attributes {
+        out.push_str(&indent(attribute));
+        out.push('\n');
+    }
+    out
+}
+
+fn function(out: &mut String, signature: &str, body: Vec<String>) {
+    out.push_str("\n\ndef ");
+    out.push_str(signature);
+    out.push_str(":\n");
+    if body.is_empty() {
+        out.push_str("    pass\n");
+    }
+    for line in body {
+        out.push_str(&indent(line));
+        out.push('\n');
+    }
+}
@@ -0,0 +1,178 @@
+from __future__ import annotations
+
+import math
+import struct
+from dataclasses import dataclass
+from typing import Dict, List, NoReturn, Set, Tuple, Union
+
+
+class BorshError(ValueError):
+    pass
+
+
+class BorshWriter:
+    def __init__(self) -> None:
+        self._buffer = bytearray()
+
+    def _pack(self, fmt: str, value: Union[int, float]) -> None:
+        if isinstance(value, bool) or not isinstance(value, (int, float)):
+            raise BorshError(f"Expected a number, got {value!r}")
+        try:
+            self._buffer += struct.pack("<" + fmt, value)
+        except (struct.error, OverflowError) as err:
+            raise BorshError(f"{value!r} is out of range: {err}") from None
+
+    def _int(self, size: int, signed: bool, value: int) -> None:
+        if isinstance(value, bool) or not isinstance(value, int):
+            raise BorshError(f"Expected an integer, got {value!r}")
+        try:
+            self._buffer += value.to_bytes(size, "little", signed=signed)
+        except OverflowError:
+            raise BorshError(f"{value!r} is out of range") from None
+
+    def bool(self, value: bool) -> None:
+        if not isinstance(value, bool):
+            raise BorshError(f"Expected a bool, got {value!r}")
+        self._buffer.append(1 if value else 0)
+
+    def u8(self, value: int) -> None:
+        self._int(1, False, value)
+
+    def u16(self, value: int) -> None:
+        self._int(2, False, value)
+
+    def u32(self, value: int) -> None:
+        self._int(4, False, value)
+
+    def u64(self, value: int) -> None:
+        self._int(8, False, value)
+
+    def u128(self, value: int) -> None:
+        self._int(16, False, value)
+
+    def i8(self, value: int) -> None:
+        self._int(1, True, value)
+
+    def i16(self, value: int) -> None:
+        self._int(2, True, value)
+
+    def i32(self, value: int) -> None:
+        self._int(4, True, value)
+
+    def i64(self, value: int) -> None:
+        self._int(8, True, value)
+
+    def i128(self, value: int) -> None:
+        self._int(16, True, value)
+
+    def f32(self, value: float) -> None:
+        if isinstance(value, float) and math.isnan(value):
+            raise BorshError("NaN is not allowed")
+        self._pack("f", value)
+
+    def f64(self, value: float) -> None:
+        if isinstance(value, float) and math.isnan(value):
+            raise BorshError("NaN is not allowed")
+        self._pack("d", value)
+
+    def uint(self, width: int, value: int) -> None:
+        """Writes an unsigned integer `width` bytes wide, e.g. a length or an enum tag."""
+        self._int(width, False, value)
+
+    def bytes(self, value: bytes) -> None:
+        self._buffer += value
+
+    def fixed_bytes(self, size: int, value: bytes) -> None:
+        if len(value) != size:
+            raise BorshError(f"Expected {size} bytes, got {len(value)}")
+        self._buffer += value
+
+    def to_bytes(self) -> bytes:
+        return bytes(self._buffer)
+
+
+class BorshReader:
+    def __init__(self, data: bytes) -> None:
+        self._data = bytes(data)
+        self.offset = 0
+
+    def bytes(self, size: int) -> bytes:
+        end = self.offset + size
+        if end > len(self._data):
+            raise BorshError("Unexpected end of input")
+        result = self._data[self.offset:end]
+        self.offset = end
+        return result
+
+    def _int(self, size: int, signed: bool) -> int:
+        return int.from_bytes(self.bytes(size), "little", signed=signed)
+
+    def bool(self) -> bool:
+        value = self.u8()
+        if value > 1:
+            raise BorshError(f"Invalid bool representation: {value}")
+        return value == 1
+
+    def u8(self) -> int:
+        return self._int(1, False)
+
+    def u16(self) -> int:
+        return self._int(2, False)
+
+    def u32(self) -> int:
+        return self._int(4, False)
+
+    def u64(self) -> int:
+        return self._int(8, False)
+
+    def u128(self) -> int:
+        return self._int(16, False)
+
+    def i8(self) -> int:
+        return self._int(1, True)
+
+    def i16(self) -> int:
+        return self._int(2, True)
+
+    def i32(self) -> int:
+        return self._int(4, True)
+
+    def i64(self) -> int:
+        return self._int(8, True)
+
+    def i128(self) -> int:
+        return self._int(16, True)
+
+    def f32(self) -> float:
+        (value,) = struct.unpack("<f", self.bytes(4))
+        if math.isnan(value):
+            raise BorshError("NaN is not allowed")
+        return value
+
+    def f64(self) -> float:
+        (value,) = struct.unpack("<d", self.bytes(8))
+        if math.isnan(value):
+            raise BorshError("NaN is not allowed")
+        return value
+
+    def uint(self, width: int) -> int:
+        """Reads an unsigned integer `width` bytes wide, e.g. a length or an enum tag."""
+        return self._int(width, False)
+
+    def at_end(self) -> bool:
+        return self.offset == len(self._data)
+
+    def finish(self) -> None:
+        if self.offset != len(self._data):
+            raise BorshError(f"Unexpected {len(self._data) - self.offset} trailing bytes")
+
+
+def non_zero(value: int) -> int:
+    if value == 0:
+        raise BorshError("Expected a non-zero value")
+    return value
+
+
+def check_length(length: int, min_length: int, max_length: int) -> None:
+    if length < min_length or length > max_length:
+        raise BorshError(f"Length {length} is out of range {min_length}..={max_length}")
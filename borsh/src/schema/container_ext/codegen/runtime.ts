@@ -0,0 +1,269 @@
+export class BorshWriter {
+  private buffer = new Uint8Array(64);
+  private view = new DataView(this.buffer.buffer);
+  private length = 0;
+
+  private reserve(size: number): number {
+    if (this.length + size > this.buffer.length) {
+      const buffer = new Uint8Array(Math.max(this.buffer.length * 2, this.length + size));
+      buffer.set(this.buffer);
+      this.buffer = buffer;
+      this.view = new DataView(buffer.buffer);
+    }
+    const offset = this.length;
+    this.length += size;
+    return offset;
+  }
+
+  private int(value: number, min: number, max: number): number {
+    if (!Number.isInteger(value) || value < min || value > max) {
+      throw new RangeError(`Integer out of range: ${value}`);
+    }
+    return value;
+  }
+
+  private bigint(value: bigint, bits: number, signed: boolean): bigint {
+    const wrapped = signed ? BigInt.asIntN(bits, value) : BigInt.asUintN(bits, value);
+    if (wrapped !== value) {
+      throw new RangeError(`Integer out of range: ${value}`);
+    }
+    return value;
+  }
+
+  bool(value: boolean): void {
+    this.u8(value ? 1 : 0);
+  }
+
+  u8(value: number): void {
+    const checked = this.int(value, 0, 0xff);
+    const offset = this.reserve(1);
+    this.view.setUint8(offset, checked);
+  }
+
+  u16(value: number): void {
+    const checked = this.int(value, 0, 0xffff);
+    const offset = this.reserve(2);
+    this.view.setUint16(offset, checked, true);
+  }
+
+  u32(value: number): void {
+    const checked = this.int(value, 0, 0xffffffff);
+    const offset = this.reserve(4);
+    this.view.setUint32(offset, checked, true);
+  }
+
+  u64(value: bigint): void {
+    const checked = this.bigint(value, 64, false);
+    const offset = this.reserve(8);
+    this.view.setBigUint64(offset, checked, true);
+  }
+
+  u128(value: bigint): void {
+    this.bigint(value, 128, false);
+    this.u64(BigInt.asUintN(64, value));
+    this.u64(value >> 64n);
+  }
+
+  i8(value: number): void {
+    const checked = this.int(value, -0x80, 0x7f);
+    const offset = this.reserve(1);
+    this.view.setInt8(offset, checked);
+  }
+
+  i16(value: number): void {
+    const checked = this.int(value, -0x8000, 0x7fff);
+    const offset = this.reserve(2);
+    this.view.setInt16(offset, checked, true);
+  }
+
+  i32(value: number): void {
+    const checked = this.int(value, -0x80000000, 0x7fffffff);
+    const offset = this.reserve(4);
+    this.view.setInt32(offset, checked, true);
+  }
+
+  i64(value: bigint): void {
+    const checked = this.bigint(value, 64, true);
+    const offset = this.reserve(8);
+    this.view.setBigInt64(offset, checked, true);
+  }
+
+  i128(value: bigint): void {
+    this.bigint(value, 128, true);
+    this.u64(BigInt.asUintN(64, value));
+    this.i64(value >> 64n);
+  }
+
+  f32(value: number): void {
+    if (Number.isNaN(value)) {
+      throw new RangeError("NaN is not allowed");
+    }
+    const offset = this.reserve(4);
+    this.view.setFloat32(offset, value, true);
+  }
+
+  f64(value: number): void {
+    if (Number.isNaN(value)) {
+      throw new RangeError("NaN is not allowed");
+    }
+    const offset = this.reserve(8);
+    this.view.setFloat64(offset, value, true);
+  }
+
+  /** Writes an unsigned integer `width` bytes wide, e.g. a length or an enum tag. */
+  uint(width: number, value: bigint): void {
+    if (value < 0n || value >= 1n << BigInt(width * 8)) {
+      throw new RangeError(`${value} doesn't fit into ${width} bytes`);
+    }
+    const offset = this.reserve(width);
+    for (let i = 0; i < width; i++) {
+      this.buffer[offset + i] = Number((value >> BigInt(i * 8)) & 0xffn);
+    }
+  }
+
+  bytes(value: Uint8Array): void {
+    const offset = this.reserve(value.length);
+    this.buffer.set(value, offset);
+  }
+
+  fixedBytes(size: number, value: Uint8Array): void {
+    if (value.length !== size) {
+      throw new RangeError(`Expected ${size} bytes, got ${value.length}`);
+    }
+    this.bytes(value);
+  }
+
+  toBytes(): Uint8Array {
+    return this.buffer.slice(0, this.length);
+  }
+}
+
+export class BorshReader {
+  private readonly view: DataView;
+  offset = 0;
+
+  constructor(private readonly buffer: Uint8Array) {
+    this.view = new DataView(buffer.buffer, buffer.byteOffset, buffer.byteLength);
+  }
+
+  private advance(size: number): number {
+    if (this.offset + size > this.buffer.length) {
+      throw new Error("Unexpected end of input");
+    }
+    const offset = this.offset;
+    this.offset += size;
+    return offset;
+  }
+
+  bool(): boolean {
+    const value = this.u8();
+    if (value > 1) {
+      throw new Error(`Invalid bool representation: ${value}`);
+    }
+    return value === 1;
+  }
+
+  u8(): number {
+    return this.view.getUint8(this.advance(1));
+  }
+
+  u16(): number {
+    return this.view.getUint16(this.advance(2), true);
+  }
+
+  u32(): number {
+    return this.view.getUint32(this.advance(4), true);
+  }
+
+  u64(): bigint {
+    return this.view.getBigUint64(this.advance(8), true);
+  }
+
+  u128(): bigint {
+    const low = this.u64();
+    return low + (this.u64() << 64n);
+  }
+
+  i8(): number {
+    return this.view.getInt8(this.advance(1));
+  }
+
+  i16(): number {
+    return this.view.getInt16(this.advance(2), true);
+  }
+
+  i32(): number {
+    return this.view.getInt32(this.advance(4), true);
+  }
+
+  i64(): bigint {
+    return this.view.getBigInt64(this.advance(8), true);
+  }
+
+  i128(): bigint {
+    const low = this.u64();
+    return low + (this.i64() << 64n);
+  }
+
+  f32(): number {
+    const value = this.view.getFloat32(this.advance(4), true);
+    if (Number.isNaN(value)) {
+      throw new Error("NaN is not allowed");
+    }
+    return value;
+  }
+
+  f64(): number {
+    const value = this.view.getFloat64(this.advance(8), true);
+    if (Number.isNaN(value)) {
+      throw new Error("NaN is not allowed");
+    }
+    return value;
+  }
+
+  /** Reads an unsigned integer `width` bytes wide, e.g. a length or an enum tag. */
+  uint(width: number): bigint {
+    const offset = this.advance(width);
+    let value = 0n;
+    for (let i = width - 1; i >= 0; i--) {
+      value = (value << 8n) | BigInt(this.buffer[offset + i]);
+    }
+    return value;
+  }
+
+  bytes(size: number): Uint8Array {
+    const offset = this.advance(size);
+    return this.buffer.slice(offset, offset + size);
+  }
+
+  atEnd(): boolean {
+    return this.offset === this.buffer.length;
+  }
+
+  finish(): void {
+    if (this.offset !== this.buffer.length) {
+      throw new Error(`Unexpected ${this.buffer.length - this.offset} trailing bytes`);
+    }
+  }
+}
+
+function nonZero<T extends number | bigint>(value: T): T {
+  if (Number(value) === 0) {
+    throw new RangeError("Expected a non-zero value");
+  }
+  return value;
+}
+
+function checkLength(length: number, min: number, max: number): void {
+  if (length < min || length > max) {
+    throw new RangeError(`Length ${length} is out of range ${min}..=${max}`);
+  }
+}
+
+function encodeUtf8(value: string): Uint8Array {
+  return new TextEncoder().encode(value);
+}
+
+function decodeUtf8(bytes: Uint8Array): string {
+  return new TextDecoder("utf-8", { fatal: true }).decode(bytes);
+}
@@ -0,0 +1,478 @@
+use super::{definition, is_restricted, is_unit, tag_mask, Error, Names, Primitive, Sequence};
+use crate::__private::maybestd::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use crate::schema::{BorshSchemaContainer, Declaration, Definition, Fields};
+
+/// Reader, writer and helpers, which the generated code is built upon.
+const RUNTIME: &str = include_str!("runtime.ts");
+
+/// Identifiers of the runtime and TypeScript globals, used by the generated code,
+/// which generated types may not shadow.
+const RESERVED: &[&str] = &[
+    "BigInt",
+    "BorshReader",
+    "BorshWriter",
+    "DataView",
+    "Error",
+    "Map",
+    "Math",
+    "Number",
+    "RangeError",
+    "Record",
+    "Set",
+    "TextDecoder",
+    "TextEncoder",
+    "Uint8Array",
+];
+
+impl BorshSchemaContainer {
+    /// Generates a self-contained TypeScript module with type declarations and
+    /// (de)serializers for every type of the container.
+    ///
+    /// For every declaration `Decl`, which isn't a primitive, the module exports
+    /// `type Decl`, `serializeDecl(writer, value)` and `deserializeDecl(reader)`,
+    /// with declarations turned into identifiers, e.g. `Option<u8>` into `Option_u8`.
+    /// Additionally, `encode(value)` and `decode(bytes)` are exported for the
+    /// container's type itself.
+    ///
+    /// Integers wider than 32 bits are represented by `bigint`, sequences of `u8` by
+    /// `Uint8Array`, maps and sets by `Map` and `Set`, and enums by discriminated
+    /// unions of `{ kind: "Variant"; value: ... }` objects.  Entries of maps and sets
+    /// are serialized in their iteration order, so these should be populated in the
+    /// order of their keys to produce canonical borsh.
+    ///
+    /// Output depends only on the schema, which makes it suitable for snapshot tests.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use borsh::schema::BorshSchemaContainer;
+    ///
+    /// let schema = BorshSchemaContainer::for_type::<Option<u8>>();
+    /// let code = schema.generate_typescript().unwrap();
+    /// assert!(code.contains("export type Option_u8 =\n  | { kind: \"None\" }\n  | { kind: \"Some\"; value: number };"));
+    /// ```
+    pub fn generate_typescript(&self) -> Result<String, Error> {
+        let generator = Generator {
+            schema: self,
+            names: Names::new(self, RESERVED),
+        };
+        generator.module()
+    }
+}
+
+struct Generator<'a> {
+    schema: &'a BorshSchemaContainer,
+    names: Names<'a>,
+}
+
+impl Generator<'_> {
+    fn module(&self) -> Result<String, Error> {
+        let mut out = format!(
+            "// Generated by borsh from the schema of `{}`, do not edit.\n\n",
+            self.schema.declaration()
+        );
+        out.push_str(RUNTIME);
+        for (declaration, definition) in self.schema.definitions() {
+            if let Definition::Primitive(_) = definition {
+                continue;
+            }
+            out.push('\n');
+            self.definition(&mut out, declaration, definition)?;
+        }
+
+        let root = self.schema.declaration();
+        let ty = self.ty(root)?;
+        out.push('\n');
+        function(
+            &mut out,
+            &format!("encode(value: {}): Uint8Array", ty),
+            vec!["const writer = new BorshWriter();".to_string()]
+                .into_iter()
+                .chain(self.write(root, "value")?)
+                .chain(Some("return writer.toBytes();".to_string()))
+                .collect(),
+        );
+        out.push('\n');
+        function(
+            &mut out,
+            &format!("decode(bytes: Uint8Array): {}", ty),
+            vec![
+                "const reader = new BorshReader(bytes);".to_string(),
+                format!("const value = {};", self.read(root)?),
+                "reader.finish();".to_string(),
+                "return value;".to_string(),
+            ],
+        );
+        Ok(out)
+    }
+
+    /// TypeScript type of values of the declared type.
+    fn ty(&self, declaration: &str) -> Result<String, Error> {
+        Ok(match definition(declaration, self.schema)? {
+            Definition::Primitive(size) => match Primitive::new(declaration, *size) {
+                Primitive::Unit => "null",
+                Primitive::Bool => "boolean",
+                Primitive::Int { bytes, .. } if bytes <= 4 => "number",
+                Primitive::Int { .. } => "bigint",
+                Primitive::Float { .. } => "number",
+                Primitive::Opaque { .. } => "Uint8Array",
+            }
+            .to_string(),
+            _ => self.names.get(declaration)?.to_string(),
+        })
+    }
+
+    /// Statement, which writes `expr` of the declared type, unless there is nothing to write.
+    fn write(&self, declaration: &str, expr: &str) -> Result<Option<String>, Error> {
+        Ok(Some(match definition(declaration, self.schema)? {
+            Definition::Primitive(size) => match Primitive::new(declaration, *size) {
+                Primitive::Unit => return Ok(None),
+                Primitive::Opaque { bytes } => format!("writer.fixedBytes({}, {});", bytes, expr),
+                primitive @ Primitive::Int { non_zero: true, .. } => {
+                    format!("writer.{}(nonZero({}));", primitive.name(), expr)
+                }
+                primitive => format!("writer.{}({});", primitive.name(), expr),
+            },
+            _ => format!(
+                "serialize{}(writer, {});",
+                self.names.get(declaration)?,
+                expr
+            ),
+        }))
+    }
+
+    /// Expression, which reads a value of the declared type.
+    fn read(&self, declaration: &str) -> Result<String, Error> {
+        Ok(match definition(declaration, self.schema)? {
+            Definition::Primitive(size) => match Primitive::new(declaration, *size) {
+                Primitive::Unit => "null".to_string(),
+                Primitive::Opaque { bytes } => format!("reader.bytes({})", bytes),
+                primitive @ Primitive::Int { non_zero: true, .. } => {
+                    format!("nonZero(reader.{}())", primitive.name())
+                }
+                primitive => format!("reader.{}()", primitive.name()),
+            },
+            _ => format!("deserialize{}(reader)", self.names.get(declaration)?),
+        })
+    }
+
+    fn definition(
+        &self,
+        out: &mut String,
+        declaration: &Declaration,
+        definition: &Definition,
+    ) -> Result<(), Error> {
+        let name = self.names.get(declaration)?;
+        let (ty, write, read) = match definition {
+            Definition::Primitive(_) => unreachable!("primitives aren't named"),
+            Definition::Sequence {
+                length_width,
+                length_range,
+                elements,
+            } => {
+                let sequence = Sequence::new(declaration, elements, self.schema)?;
+                let ty = match sequence {
+                    Sequence::String => "string".to_string(),
+                    Sequence::Bytes => "Uint8Array".to_string(),
+                    Sequence::Map { key, value } => {
+                        format!("Map<{}, {}>", self.ty(key)?, self.ty(value)?)
+                    }
+                    Sequence::Set => format!("Set<{}>", self.ty(elements)?),
+                    Sequence::List => format!("{}[]", self.ty(elements)?),
+                };
+                let length = match sequence {
+                    Sequence::String => "bytes.length",
+                    Sequence::Map { .. } | Sequence::Set => "value.size",
+                    Sequence::Bytes | Sequence::List => "value.length",
+                };
+                let check = format!(
+                    "checkLength({{}}, {}, {});",
+                    length_range.start(),
+                    length_range.end()
+                );
+                let restricted = is_restricted(*length_width, length_range);
+
+                let mut write = Vec::new();
+                if sequence == Sequence::String {
+                    write.push("const bytes = encodeUtf8(value);".to_string());
+                }
+                if restricted {
+                    write.push(check.replace("{}", length));
+                }
+                if *length_width != Definition::ARRAY_LENGTH_WIDTH {
+                    write.push(format!(
+                        "writer.uint({}, BigInt({}));",
+                        length_width, length
+                    ));
+                }
+                match sequence {
+                    Sequence::String => write.push("writer.bytes(bytes);".to_string()),
+                    Sequence::Bytes => write.push("writer.bytes(value);".to_string()),
+                    Sequence::Map { key, value } => {
+                        write.push("for (const [key, item] of value) {".to_string());
+                        write.extend(indent(self.write(key, "key")?));
+                        write.extend(indent(self.write(value, "item")?));
+                        write.push("}".to_string());
+                    }
+                    Sequence::Set | Sequence::List => {
+                        if let Some(item) = self.write(elements, "item")? {
+                            write.push("for (const item of value) {".to_string());
+                            write.push(format!("  {}", item));
+                            write.push("}".to_string());
+                        }
+                    }
+                }
+
+                let mut read = Vec::new();
+                if *length_width == Definition::ARRAY_LENGTH_WIDTH {
+                    read.push(format!("const length = {};", length_range.start()));
+                } else {
+                    read.push(format!(
+                        "const length = Number(reader.uint({}));",
+                        length_width
+                    ));
+                    if restricted {
+                        read.push(check.replace("{}", "length"));
+                    }
+                }
+                let collect = |init: &str, add: String| {
+                    vec![
+                        format!("const value: {} = {};", name, init),
+                        "for (let i = 0; i < length; i++) {".to_string(),
+                        format!("  {}", add),
+                        "}".to_string(),
+                        "return value;".to_string(),
+                    ]
+                };
+                match sequence {
+                    Sequence::String => {
+                        read.push("return decodeUtf8(reader.bytes(length));".to_string())
+                    }
+                    Sequence::Bytes => read.push("return reader.bytes(length);".to_string()),
+                    Sequence::Map { key, value } => {
+                        let mut lines = collect(
+                            "new Map()",
+                            format!("value.set(key, {});", self.read(value)?),
+                        );
+                        lines.insert(2, format!("  const key = {};", self.read(key)?));
+                        read.extend(lines);
+                    }
+                    Sequence::Set => read.extend(collect(
+                        "new Set()",
+                        format!("value.add({});", self.read(elements)?),
+                    )),
+                    Sequence::List => read.extend(collect(
+                        "[]",
+                        format!("value.push({});", self.read(elements)?),
+                    )),
+                }
+                (format!("export type {} = {};", name, ty), write, read)
+            }
+            Definition::Tuple { elements }
+            | Definition::Struct {
+                fields: Fields::UnnamedFields(elements),
+            } => {
+                let mut types = Vec::new();
+                let mut write = Vec::new();
+                let mut reads = Vec::new();
+                for (idx, element) in elements.iter().enumerate() {
+                    types.push(self.ty(element)?);
+                    write.extend(self.write(element, &format!("value[{}]", idx))?);
+                    reads.push(self.read(element)?);
+                }
+                (
+                    format!("export type {} = [{}];", name, types.join(", ")),
+                    write,
+                    vec![format!("return [{}];", reads.join(", "))],
+                )
+            }
+            Definition::Struct {
+                fields: Fields::NamedFields(fields),
+            } => {
+                let mut ty = format!("export interface {} {{\n", name);
+                let mut write = Vec::new();
+                let mut read = vec!["return {".to_string()];
+                for (field, declaration) in fields {
+                    ty.push_str(&format!("  {}: {};\n", field, self.ty(declaration)?));
+                    write.extend(self.write(declaration, &format!("value.{}", field))?);
+                    read.push(format!("  {}: {},", field, self.read(declaration)?));
+                }
+                ty.push('}');
+                read.push("};".to_string());
+                (ty, write, read)
+            }
+            Definition::Struct {
+                fields: Fields::Empty,
+            } => (
+                format!("export type {} = Record<string, never>;", name),
+                Vec::new(),
+                vec!["return {};".to_string()],
+            ),
+            Definition::Enum {
+                tag_width,
+                variants,
+            } => {
+                if variants.is_empty() {
+                    let unreachable = format!("throw new Error(\"{} has no values\");", name);
+                    (
+                        format!("export type {} = never;", name),
+                        vec![unreachable.clone()],
+                        vec![unreachable],
+                    )
+                } else {
+                    self.variants(name, *tag_width, variants)?
+                }
+            }
+            Definition::Trailing(present) => (
+                format!("export type {} = {} | null;", name, self.ty(present)?),
+                match self.write(present, "value")? {
+                    Some(write) => vec![
+                        "if (value !== null) {".to_string(),
+                        format!("  {}", write),
+                        "}".to_string(),
+                    ],
+                    None => Vec::new(),
+                },
+                vec![format!(
+                    "return reader.atEnd() ? null : {};",
+                    self.read(present)?
+                )],
+            ),
+            Definition::Alias(aliased) => (
+                format!("export type {} = {};", name, self.ty(aliased)?),
+                self.write(aliased, "value")?.into_iter().collect(),
+                vec![format!("return {};", self.read(aliased)?)],
+            ),
+        };
+
+        out.push_str(&ty);
+        out.push_str("\n\n");
+        // parameters, which aren't used, are prefixed to keep linters happy
+        let param = |name: &str, body: &[String]| {
+            if body.iter().any(|line| line.contains(name)) {
+                name.to_string()
+            } else {
+                format!("_{}", name)
+            }
+        };
+        function(
+            out,
+            &format!(
+                "serialize{}({}: BorshWriter, {}: {}): void",
+                name,
+                param("writer", &write),
+                param("value", &write),
+                name
+            ),
+            write,
+        );
+        out.push('\n');
+        function(
+            out,
+            &format!(
+                "deserialize{}({}: BorshReader): {}",
+                name,
+                param("reader", &read),
+                name
+            ),
+            read,
+        );
+        Ok(())
+    }
+
+    fn variants(
+        &self,
+        name: &str,
+        tag_width: u8,
+        variants: &[(i64, String, Declaration)],
+    ) -> Result<(String, Vec<String>, Vec<String>), Error> {
+        let mut ty = format!("export type {} =", name);
+        let mut write = vec!["switch (value.kind) {".to_string()];
+        let mut read = Vec::new();
+        if tag_width == 0 {
+            read.push("const start = reader.offset;".to_string());
+        } else {
+            read.push(format!("const tag = reader.uint({});", tag_width));
+            read.push("switch (tag) {".to_string());
+        }
+
+        for (discriminant, variant, declaration) in variants {
+            let (object, value) = if is_unit(declaration, self.schema)? {
+                (format!("{{ kind: \"{}\" }}", variant), None)
+            } else {
+                (
+                    format!(
+                        "{{ kind: \"{}\"; value: {} }}",
+                        variant,
+                        self.ty(declaration)?
+                    ),
+                    Some(self.read(declaration)?),
+                )
+            };
+            ty.push_str(&format!("\n  | {}", object));
+
+            let tag = *discriminant as u64 & tag_mask(tag_width);
+            write.push(format!("  case \"{}\":", variant));
+            if tag_width != 0 {
+                write.push(format!("    writer.uint({}, {}n);", tag_width, tag));
+            }
+            if value.is_some() {
+                write.extend(indent(indent(self.write(declaration, "value.value")?)));
+            }
+            write.push("    break;".to_string());
+
+            let result = match value {
+                Some(value) => format!("{{ kind: \"{}\", value: {} }}", variant, value),
+                None => format!("{{ kind: \"{}\" }}", variant),
+            };
+            if tag_width == 0 {
+                read.push("try {".to_string());
+                read.push(format!("  return {};", result));
+                read.push("} catch {".to_string());
+                read.push("  reader.offset = start;".to_string());
+                read.push("}".to_string());
+            } else {
+                read.push(format!("  case {}n:", tag));
+                read.push(format!("    return {};", result));
+            }
+        }
+
+        ty.push(';');
+        write.push("}".to_string());
+        if tag_width == 0 {
+            read.push(format!(
+                "throw new Error(\"None of the variants of {} match\");",
+                name
+            ));
+        } else {
+            read.push("  default:".to_string());
+            read.push(format!(
+                "    throw new Error(`Unexpected variant tag of {}: ${{tag}}`);",
+                name
+            ));
+            read.push("}".to_string());
+        }
+        Ok((ty, write, read))
+    }
+}
+
+fn indent(line: Option<String>) -> Option<String> {
+    line.map(|line| format!("  {}", line))
+}
+
+fn function(out: &mut String, signature: &str, body: Vec<String>) {
+    out.push_str("export function ");
+    out.push_str(signature);
+    out.push_str(" {\n");
+    for line in body {
+        out.push_str("  ");
+        out.push_str(&line);
+        out.push('\n');
+    }
+    out.push_str("}\n");
+}
@@ -0,0 +1,432 @@
+use super::{BorshSchemaContainer, Declaration, Definition, Fields};
+use crate::__private::maybestd::{collections::BTreeSet, string::ToString, vec::Vec};
+use crate::schema::{DiscriminantValue, FieldName, VariantName};
+
+use core::ops::RangeInclusive;
+
+impl BorshSchemaContainer {
+    /// Checks whether values, serialized with the `old` schema, can still be
+    /// deserialized with this one, and reports every difference found.
+    ///
+    /// Both definition graphs are walked side by side, starting from their root
+    /// declarations, so types may be renamed freely.  Differences which don't
+    /// affect decoding of old data, e.g. appended enum variants, are reported as
+    /// well, see [`Incompatibility::is_breaking`].
+    ///
+    /// Values of the root type are assumed to end the input, so fields, appended
+    /// to the structs at its end as [`Trailing`](crate::trailing::Trailing), i.e.
+    /// with `#[borsh(default)]`, don't break decoding.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use borsh::schema::{BorshSchemaContainer, Incompatibility};
+    ///
+    /// let old = BorshSchemaContainer::for_type::<Option<u32>>();
+    /// let new = BorshSchemaContainer::for_type::<Option<u64>>();
+    /// assert_eq!(
+    ///     new.check_compatibility(&old),
+    ///     vec![Incompatibility::TypeChanged {
+    ///         old: "u32".to_string(),
+    ///         new: "u64".to_string(),
+    ///     }]
+    /// );
+    /// ```
+    pub fn check_compatibility(&self, old: &BorshSchemaContainer) -> Vec<Incompatibility> {
+        let mut checker = Checker {
+            old,
+            new: self,
+            visited: BTreeSet::new(),
+            found: Vec::new(),
+        };
+        checker.check(old.declaration(), self.declaration(), true);
+        checker.found
+    }
+}
+
+/// A difference between two [`BorshSchemaContainer`]s, found by
+/// [`BorshSchemaContainer::check_compatibility`].
+///
+/// Declarations are those of the new schema, unless stated otherwise.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Incompatibility {
+    /// Some of the declared types were lacking definition in either of schemas.
+    MissingDefinition(Declaration),
+    /// A type was replaced with a different one, e.g. `u32` with `u64`,
+    /// or a struct with an enum.
+    TypeChanged { old: Declaration, new: Declaration },
+    /// Number of elements of a tuple or unnamed fields of a struct has changed.
+    ElementCountChanged {
+        declaration: Declaration,
+        old: usize,
+        new: usize,
+    },
+    /// `length_width` of a sequence has changed.
+    LengthWidthChanged {
+        declaration: Declaration,
+        old: u8,
+        new: u8,
+    },
+    /// Lengths, allowed for a sequence by the old schema, aren't allowed anymore,
+    /// or length of an array has changed.
+    LengthRangeChanged {
+        declaration: Declaration,
+        old: RangeInclusive<u64>,
+        new: RangeInclusive<u64>,
+    },
+    /// `tag_width` of an enum has changed.
+    TagWidthChanged {
+        declaration: Declaration,
+        old: u8,
+        new: u8,
+    },
+    /// Named fields of a struct are the same, but their order has changed.
+    FieldsReordered(Declaration),
+    /// A named field was added to a struct.
+    FieldAdded {
+        declaration: Declaration,
+        field: FieldName,
+    },
+    /// A field, declared as [`Trailing`](crate::trailing::Trailing), was appended to
+    /// a struct at the end of the input.  Unnamed fields are named by their positions.
+    /// Doesn't break decoding.
+    TrailingFieldAdded {
+        declaration: Declaration,
+        field: FieldName,
+    },
+    /// A named field was removed from a struct.
+    FieldRemoved {
+        declaration: Declaration,
+        field: FieldName,
+    },
+    /// A variant was removed from an enum.
+    VariantRemoved {
+        declaration: Declaration,
+        variant: VariantName,
+        discriminant: DiscriminantValue,
+    },
+    /// Discriminant of an enum variant has changed.
+    DiscriminantChanged {
+        declaration: Declaration,
+        variant: VariantName,
+        old: DiscriminantValue,
+        new: DiscriminantValue,
+    },
+    /// An enum variant was renamed, keeping its discriminant.  Doesn't break decoding.
+    VariantRenamed {
+        declaration: Declaration,
+        discriminant: DiscriminantValue,
+        old: VariantName,
+        new: VariantName,
+    },
+    /// A variant with a previously unused discriminant was added to an enum.
+    /// Doesn't break decoding.
+    VariantAdded {
+        declaration: Declaration,
+        variant: VariantName,
+        discriminant: DiscriminantValue,
+    },
+}
+
+impl Incompatibility {
+    /// Whether data, serialized with the old schema, may fail to deserialize,
+    /// or deserialize into a different value with the new one.
+    pub fn is_breaking(&self) -> bool {
+        !matches!(
+            self,
+            Incompatibility::VariantAdded { .. }
+                | Incompatibility::VariantRenamed { .. }
+                | Incompatibility::TrailingFieldAdded { .. }
+        )
+    }
+}
+
+fn narrowed(old: &RangeInclusive<u64>, new: &RangeInclusive<u64>) -> bool {
+    old.start() < new.start() || old.end() > new.end()
+}
+
+struct Checker<'a> {
+    old: &'a BorshSchemaContainer,
+    new: &'a BorshSchemaContainer,
+    /// Pairs of declarations, which were compared already, and whether their values
+    /// end the input, which also stops recursion.
+    visited: BTreeSet<(&'a Declaration, &'a Declaration, bool)>,
+    found: Vec<Incompatibility>,
+}
+
+impl<'a> Checker<'a> {
+    /// Whether the declared type of the new schema is `Definition::Trailing`, e.g.
+    /// [`Trailing`](crate::trailing::Trailing), which may be missing at the end of the input.
+    fn is_trailing(&self, declaration: &str) -> bool {
+        matches!(
+            self.new.get_definition(declaration),
+            Some(Definition::Trailing(_))
+        )
+    }
+
+    /// Reports a difference, unless it was already found for a different position of the types.
+    fn report(&mut self, incompatibility: Incompatibility) {
+        if !self.found.contains(&incompatibility) {
+            self.found.push(incompatibility);
+        }
+    }
+
+    /// Compares the types, whose values end the input, if `at_end`.
+    fn check(
+        &mut self,
+        old_declaration: &'a Declaration,
+        new_declaration: &'a Declaration,
+        at_end: bool,
+    ) {
+        if !self
+            .visited
+            .insert((old_declaration, new_declaration, at_end))
+        {
+            return;
+        }
+        let (old, new) = match (
+            self.old.get_definition(old_declaration),
+            self.new.get_definition(new_declaration),
+        ) {
+            (Some(old), Some(new)) => (old, new),
+            (None, _) => {
+                return self.report(Incompatibility::MissingDefinition(old_declaration.clone()))
+            }
+            (_, None) => {
+                return self.report(Incompatibility::MissingDefinition(new_declaration.clone()))
+            }
+        };
+        // aliases are encoded as the types they refer to
+        if let Definition::Alias(aliased) = old {
+            return self.check(aliased, new_declaration, at_end);
+        }
+        if let Definition::Alias(aliased) = new {
+            return self.check(old_declaration, aliased, at_end);
+        }
+        let declaration = || new_declaration.clone();
+        let type_changed = || Incompatibility::TypeChanged {
+            old: old_declaration.clone(),
+            new: new_declaration.clone(),
+        };
+
+        match (old, new) {
+            (Definition::Trailing(old_present), Definition::Trailing(new_present)) => {
+                self.check(old_present, new_present, at_end)
+            }
+            // a field, which became trailing, is still decoded, if it's present
+            (_, Definition::Trailing(new_present)) => {
+                self.check(old_declaration, new_present, at_end)
+            }
+            (Definition::Primitive(old_size), Definition::Primitive(new_size)) => {
+                // primitives are identified by their declarations
+                if old_size != new_size || old_declaration != new_declaration {
+                    self.report(type_changed());
+                }
+            }
+            (
+                Definition::Sequence {
+                    length_width: old_width,
+                    length_range: old_range,
+                    elements: old_elements,
+                },
+                Definition::Sequence {
+                    length_width: new_width,
+                    length_range: new_range,
+                    elements: new_elements,
+                },
+            ) => {
+                if old_width != new_width {
+                    self.report(Incompatibility::LengthWidthChanged {
+                        declaration: declaration(),
+                        old: *old_width,
+                        new: *new_width,
+                    });
+                } else if narrowed(old_range, new_range)
+                    || (*new_width == Definition::ARRAY_LENGTH_WIDTH && old_range != new_range)
+                {
+                    self.report(Incompatibility::LengthRangeChanged {
+                        declaration: declaration(),
+                        old: old_range.clone(),
+                        new: new_range.clone(),
+                    });
+                }
+                self.check(old_elements, new_elements, false);
+            }
+            (
+                Definition::Tuple {
+                    elements: old_elements,
+                },
+                Definition::Tuple {
+                    elements: new_elements,
+                },
+            ) => self.check_all(new_declaration, old_elements, new_elements, at_end),
+            (
+                Definition::Enum {
+                    tag_width: old_width,
+                    variants: old_variants,
+                },
+                Definition::Enum {
+                    tag_width: new_width,
+                    variants: new_variants,
+                },
+            ) => {
+                if old_width != new_width {
+                    self.report(Incompatibility::TagWidthChanged {
+                        declaration: declaration(),
+                        old: *old_width,
+                        new: *new_width,
+                    });
+                }
+                for (old_discriminant, old_name, old_variant) in old_variants {
+                    let by_name = new_variants.iter().find(|(_, name, _)| name == old_name);
+                    let by_discriminant = new_variants
+                        .iter()
+                        .find(|(discriminant, _, _)| discriminant == old_discriminant);
+                    match (by_name, by_discriminant) {
+                        (Some((new_discriminant, _, new_variant)), _) => {
+                            if new_discriminant != old_discriminant {
+                                self.report(Incompatibility::DiscriminantChanged {
+                                    declaration: declaration(),
+                                    variant: old_name.clone(),
+                                    old: *old_discriminant,
+                                    new: *new_discriminant,
+                                });
+                            }
+                            self.check(old_variant, new_variant, at_end);
+                        }
+                        (None, Some((_, new_name, new_variant))) => {
+                            self.report(Incompatibility::VariantRenamed {
+                                declaration: declaration(),
+                                discriminant: *old_discriminant,
+                                old: old_name.clone(),
+                                new: new_name.clone(),
+                            });
+                            self.check(old_variant, new_variant, at_end);
+                        }
+                        (None, None) => self.report(Incompatibility::VariantRemoved {
+                            declaration: declaration(),
+                            variant: old_name.clone(),
+                            discriminant: *old_discriminant,
+                        }),
+                    }
+                }
+                for (new_discriminant, new_name, _) in new_variants {
+                    let existed = old_variants.iter().any(|(discriminant, name, _)| {
+                        name == new_name || discriminant == new_discriminant
+                    });
+                    if !existed {
+                        self.report(Incompatibility::VariantAdded {
+                            declaration: declaration(),
+                            variant: new_name.clone(),
+                            discriminant: *new_discriminant,
+                        });
+                    }
+                }
+            }
+            (
+                Definition::Struct { fields: old_fields },
+                Definition::Struct { fields: new_fields },
+            ) => match (old_fields, new_fields) {
+                (Fields::NamedFields(old_fields), Fields::NamedFields(new_fields)) => {
+                    self.check_named(new_declaration, old_fields, new_fields, at_end)
+                }
+                (Fields::UnnamedFields(old_fields), Fields::UnnamedFields(new_fields)) => {
+                    self.check_all(new_declaration, old_fields, new_fields, at_end)
+                }
+                (Fields::Empty, Fields::Empty) => {}
+                _ => self.report(type_changed()),
+            },
+            _ => self.report(type_changed()),
+        }
+    }
+
+    /// Compares positional elements of tuples and structs with unnamed fields.
+    fn check_all(
+        &mut self,
+        declaration: &Declaration,
+        old_elements: &'a [Declaration],
+        new_elements: &'a [Declaration],
+        at_end: bool,
+    ) {
+        let appended = new_elements.get(old_elements.len()..).unwrap_or_default();
+        if at_end
+            && !appended.is_empty()
+            && appended.iter().all(|element| self.is_trailing(element))
+        {
+            for idx in old_elements.len()..new_elements.len() {
+                self.report(Incompatibility::TrailingFieldAdded {
+                    declaration: declaration.clone(),
+                    field: idx.to_string(),
+                });
+            }
+        } else if old_elements.len() != new_elements.len() {
+            self.report(Incompatibility::ElementCountChanged {
+                declaration: declaration.clone(),
+                old: old_elements.len(),
+                new: new_elements.len(),
+            });
+        }
+        let last = old_elements.len().wrapping_sub(1);
+        for (idx, (old_element, new_element)) in old_elements.iter().zip(new_elements).enumerate() {
+            self.check(old_element, new_element, at_end && idx == last);
+        }
+    }
+
+    fn check_named(
+        &mut self,
+        declaration: &Declaration,
+        old_fields: &'a [(FieldName, Declaration)],
+        new_fields: &'a [(FieldName, Declaration)],
+        at_end: bool,
+    ) {
+        let removed = old_fields
+            .iter()
+            .filter(|(name, _)| !new_fields.iter().any(|(new_name, _)| new_name == name));
+        for (name, _) in removed {
+            self.report(Incompatibility::FieldRemoved {
+                declaration: declaration.clone(),
+                field: name.to_string(),
+            });
+        }
+        // fields, appended after all the kept ones, may be missing at the end of the input
+        let appended_from = new_fields
+            .iter()
+            .rposition(|(name, _)| old_fields.iter().any(|(old_name, _)| old_name == name))
+            .map_or(0, |idx| idx + 1);
+        let added = new_fields
+            .iter()
+            .enumerate()
+            .filter(|(_, (name, _))| !old_fields.iter().any(|(old_name, _)| old_name == name));
+        for (idx, (name, field)) in added {
+            if at_end && idx >= appended_from && self.is_trailing(field) {
+                self.report(Incompatibility::TrailingFieldAdded {
+                    declaration: declaration.clone(),
+                    field: name.to_string(),
+                });
+            } else {
+                self.report(Incompatibility::FieldAdded {
+                    declaration: declaration.clone(),
+                    field: name.to_string(),
+                });
+            }
+        }
+
+        let kept = |fields: &'a [(FieldName, Declaration)], other: &[(FieldName, Declaration)]| {
+            fields
+                .iter()
+                .filter(|(name, _)| other.iter().any(|(other_name, _)| other_name == name))
+                .map(|(name, _)| name)
+                .collect::<Vec<_>>()
+        };
+        if kept(old_fields, new_fields) != kept(new_fields, old_fields) {
+            self.report(Incompatibility::FieldsReordered(declaration.clone()));
+        }
+
+        let last = old_fields.len().wrapping_sub(1);
+        for (idx, (name, old_field)) in old_fields.iter().enumerate() {
+            if let Some((_, new_field)) = new_fields.iter().find(|(new_name, _)| new_name == name) {
+                self.check(old_field, new_field, at_end && idx == last);
+            }
+        }
+    }
+}
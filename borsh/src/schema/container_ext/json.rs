@@ -0,0 +1,290 @@
+use super::value::{definition, is_string, trailing_value, varint_type, Error, FieldValues, Value};
+use super::{BorshSchemaContainer, Definition, Fields};
+use crate::__private::maybestd::{
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use core::convert::TryFrom;
+use serde_json::{Map, Number, Value as Json};
+
+impl BorshSchemaContainer {
+    /// Decodes borsh `bytes` of the container's type into a JSON value.
+    ///
+    /// Structs with named fields are decoded into objects, tuples, sequences and
+    /// structs with unnamed fields into arrays and `String`s into strings.
+    /// Enum variants are decoded as `{"Variant": value}`, or just `"Variant"`
+    /// if the variant holds no data.  Integers wider than 64 bits are decoded
+    /// into strings, as JSON numbers can't represent them precisely.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use borsh::schema::BorshSchemaContainer;
+    /// use serde_json::json;
+    ///
+    /// let schema = BorshSchemaContainer::for_type::<(u8, Option<String>, Vec<u64>)>();
+    /// let bytes = borsh::to_vec(&(7u8, Some("hello"), vec![1u64, 2])).unwrap();
+    /// assert_eq!(
+    ///     schema.borsh_to_json(&bytes).unwrap(),
+    ///     json!([7, {"Some": "hello"}, [1, 2]])
+    /// );
+    /// ```
+    pub fn borsh_to_json(&self, bytes: &[u8]) -> Result<Json, Error> {
+        to_json(self.deserialize_value(bytes)?)
+    }
+
+    /// Encodes a JSON `value` into borsh bytes of the container's type.
+    ///
+    /// Accepts values in the format, produced by [`BorshSchemaContainer::borsh_to_json`].
+    /// Additionally, integers wider than 64 bits may be given as numbers.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use borsh::schema::BorshSchemaContainer;
+    /// use serde_json::json;
+    ///
+    /// let schema = BorshSchemaContainer::for_type::<(u8, Option<String>, Vec<u64>)>();
+    /// let bytes = schema.json_to_borsh(&json!([7, "None", [1, 2]])).unwrap();
+    /// assert_eq!(bytes, borsh::to_vec(&(7u8, None::<String>, vec![1u64, 2])).unwrap());
+    /// ```
+    pub fn json_to_borsh(&self, value: &Json) -> Result<Vec<u8>, Error> {
+        let value = from_json(self.declaration(), self, value, 0)?;
+        self.serialize_value(&value)
+    }
+}
+
+fn to_json(value: Value) -> Result<Json, Error> {
+    let float = |declaration: &str, number: f64| {
+        Number::from_f64(number).map(Json::Number).ok_or_else(|| {
+            Error::InvalidData(
+                declaration.to_string(),
+                "infinite numbers are not allowed".to_string(),
+            )
+        })
+    };
+    let all = |values: Vec<Value>| values.into_iter().map(to_json).collect::<Result<_, _>>();
+
+    let json = match value {
+        Value::Unit => Json::Null,
+        Value::Bool(value) => Json::Bool(value),
+        Value::U8(value) => Json::from(value),
+        Value::U16(value) => Json::from(value),
+        Value::U32(value) => Json::from(value),
+        Value::U64(value) => Json::from(value),
+        Value::U128(value) => Json::String(value.to_string()),
+        Value::I8(value) => Json::from(value),
+        Value::I16(value) => Json::from(value),
+        Value::I32(value) => Json::from(value),
+        Value::I64(value) => Json::from(value),
+        Value::I128(value) => Json::String(value.to_string()),
+        Value::F32(value) => float("f32", f64::from(value))?,
+        Value::F64(value) => float("f64", value)?,
+        // Primitives unknown to borsh are opaque, and represented by their bytes in hex.
+        Value::Opaque(bytes) => {
+            Json::String(bytes.iter().map(|byte| format!("{:02x}", byte)).collect())
+        }
+        Value::String(string) => Json::String(string),
+        Value::Bytes(bytes) => Json::Array(bytes.into_iter().map(Json::from).collect()),
+        Value::Sequence(values) | Value::Tuple(values) => Json::Array(all(values)?),
+        Value::Struct(FieldValues::Named(fields)) => {
+            let mut result = Map::new();
+            for (name, value) in fields {
+                result.insert(name, to_json(value)?);
+            }
+            Json::Object(result)
+        }
+        Value::Struct(FieldValues::Unnamed(values)) => Json::Array(all(values)?),
+        Value::Struct(FieldValues::Empty) => Json::Null,
+        Value::Enum { variant, value, .. } => match to_json(*value)? {
+            Json::Null => Json::String(variant),
+            value => {
+                let mut result = Map::new();
+                result.insert(variant, value);
+                Json::Object(result)
+            }
+        },
+    };
+    Ok(json)
+}
+
+/// Converts JSON `value` into a [`Value`] of the declared type.
+///
+/// Only the shape of the value is checked here, everything else is up to
+/// [`BorshSchemaContainer::serialize_value`].
+fn from_json(
+    declaration: &str,
+    schema: &BorshSchemaContainer,
+    value: &Json,
+    depth: usize,
+) -> Result<Value, Error> {
+    let invalid = |reason: String| Error::InvalidValue(declaration.to_string(), reason);
+    let all = |elements: &[String], items: &[Json]| {
+        elements
+            .iter()
+            .zip(items)
+            .map(|(element, item)| from_json(element, schema, item, depth + 1))
+            .collect::<Result<Vec<_>, _>>()
+    };
+    let array_of = |len: usize| match value {
+        Json::Array(items) if items.len() == len => Ok(items),
+        _ => Err(invalid(format!(
+            "expected an array of {} elements, got {}",
+            len, value
+        ))),
+    };
+
+    let variant_of = || match value {
+        Json::String(name) => Ok((name, &Json::Null)),
+        Json::Object(object) if object.len() == 1 => Ok(object.iter().next().unwrap()),
+        _ => Err(invalid(format!(
+            "expected a variant name or a single-key object, got {}",
+            value
+        ))),
+    };
+
+    match definition(declaration, schema, depth)? {
+        Definition::Primitive(size) => from_json_primitive(declaration, *size, value),
+        Definition::Sequence { elements, .. } => {
+            if let Some((integer, size)) = varint_type(declaration, schema) {
+                return from_json_primitive(integer, size, value);
+            }
+            match value {
+                Json::String(string) if is_string(declaration, elements) => {
+                    Ok(Value::String(string.clone()))
+                }
+                Json::Array(items) => items
+                    .iter()
+                    .map(|item| from_json(elements, schema, item, depth + 1))
+                    .collect::<Result<_, _>>()
+                    .map(Value::Sequence),
+                _ => Err(invalid(format!("expected an array, got {}", value))),
+            }
+        }
+        Definition::Tuple { elements } => {
+            Ok(Value::Tuple(all(elements, array_of(elements.len())?)?))
+        }
+        Definition::Enum { variants, .. } => {
+            let (name, payload) = variant_of()?;
+            let (discriminant, _, variant) = variants
+                .iter()
+                .find(|(_, variant_name, _)| variant_name == name)
+                .ok_or_else(|| invalid(format!("unknown variant `{}`", name)))?;
+            Ok(Value::Enum {
+                discriminant: *discriminant,
+                variant: name.clone(),
+                value: Box::new(from_json(variant, schema, payload, depth + 1)?),
+            })
+        }
+        Definition::Struct { fields } => match fields {
+            Fields::NamedFields(fields) => {
+                let object = match value {
+                    Json::Object(object) => object,
+                    _ => return Err(invalid(format!("expected an object, got {}", value))),
+                };
+                if let Some(key) = object
+                    .keys()
+                    .find(|key| !fields.iter().any(|(name, _)| name == *key))
+                {
+                    return Err(invalid(format!("unknown field `{}`", key)));
+                }
+                let mut result = Vec::with_capacity(fields.len());
+                for (name, field) in fields {
+                    let item = object
+                        .get(name)
+                        .ok_or_else(|| invalid(format!("missing field `{}`", name)))?;
+                    result.push((name.clone(), from_json(field, schema, item, depth + 1)?));
+                }
+                Ok(Value::Struct(FieldValues::Named(result)))
+            }
+            Fields::UnnamedFields(fields) => Ok(Value::Struct(FieldValues::Unnamed(all(
+                fields,
+                array_of(fields.len())?,
+            )?))),
+            Fields::Empty => match value {
+                Json::Null => Ok(Value::Struct(FieldValues::Empty)),
+                Json::Array(items) if items.is_empty() => Ok(Value::Struct(FieldValues::Empty)),
+                Json::Object(object) if object.is_empty() => Ok(Value::Struct(FieldValues::Empty)),
+                _ => Err(invalid(format!("expected null, got {}", value))),
+            },
+        },
+        Definition::Trailing(present) => match variant_of()? {
+            (name, payload) if name == "Some" => Ok(trailing_value(Some(from_json(
+                present,
+                schema,
+                payload,
+                depth + 1,
+            )?))),
+            (name, Json::Null) if name == "None" => Ok(trailing_value(None)),
+            (name, _) => Err(invalid(format!("unknown variant `{}`", name))),
+        },
+        Definition::Alias(aliased) => from_json(aliased, schema, value, depth + 1),
+    }
+}
+
+fn from_json_primitive(declaration: &str, size: u8, value: &Json) -> Result<Value, Error> {
+    let invalid = || {
+        Error::InvalidValue(
+            declaration.to_string(),
+            format!("{} is not a valid {}", value, declaration),
+        )
+    };
+    // Wide integers are represented by strings, but numbers are accepted too.
+    let u128_value = || match value {
+        Json::String(string) => string.parse::<u128>().ok(),
+        _ => value.as_u64().map(u128::from),
+    };
+    let i128_value = || match value {
+        Json::String(string) => string.parse::<i128>().ok(),
+        _ => value.as_i64().map(i128::from),
+    };
+    macro_rules! int {
+        ($variant:ident, $ty:ty, $wide:expr) => {{
+            let number = $wide.ok_or_else(invalid)?;
+            Value::$variant(<$ty>::try_from(number).map_err(|_| invalid())?)
+        }};
+    }
+
+    let result = match (declaration, size) {
+        (_, 0) if value.is_null() => Value::Unit,
+        (_, 0) => return Err(invalid()),
+        ("bool", 1) => Value::Bool(value.as_bool().ok_or_else(invalid)?),
+        ("u8", 1) | ("NonZeroU8", 1) => int!(U8, u8, u128_value()),
+        ("u16", 2) | ("NonZeroU16", 2) => int!(U16, u16, u128_value()),
+        ("u32", 4) | ("NonZeroU32", 4) => int!(U32, u32, u128_value()),
+        ("u64", 8) | ("NonZeroU64", 8) | ("NonZeroUsize", 8) => int!(U64, u64, u128_value()),
+        ("u128", 16) | ("NonZeroU128", 16) => int!(U128, u128, u128_value()),
+        ("i8", 1) | ("NonZeroI8", 1) => int!(I8, i8, i128_value()),
+        ("i16", 2) | ("NonZeroI16", 2) => int!(I16, i16, i128_value()),
+        ("i32", 4) | ("NonZeroI32", 4) => int!(I32, i32, i128_value()),
+        ("i64", 8) | ("NonZeroI64", 8) => int!(I64, i64, i128_value()),
+        ("i128", 16) | ("NonZeroI128", 16) => int!(I128, i128, i128_value()),
+        ("f32", 4) => {
+            let number = value.as_f64().ok_or_else(invalid)? as f32;
+            if !number.is_finite() {
+                return Err(invalid());
+            }
+            Value::F32(number)
+        }
+        ("f64", 8) => Value::F64(value.as_f64().ok_or_else(invalid)?),
+        _ => {
+            let hex = value.as_str().ok_or_else(invalid)?;
+            if hex.len() != usize::from(size) * 2 {
+                return Err(invalid());
+            }
+            let bytes = (0..hex.len())
+                .step_by(2)
+                .map(|idx| {
+                    hex.get(idx..idx + 2)
+                        .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                        .ok_or_else(invalid)
+                })
+                .collect::<Result<_, _>>()?;
+            Value::Opaque(bytes)
+        }
+    };
+    Ok(result)
+}
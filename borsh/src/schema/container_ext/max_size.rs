@@ -1,6 +1,7 @@
 use super::{BorshSchemaContainer, Declaration, Definition, Fields};
 use crate::__private::maybestd::{string::ToString, vec::Vec};
 
+use core::fmt;
 use core::num::NonZeroUsize;
 
 /// NonZeroUsize of value one.
@@ -59,6 +60,18 @@ pub enum Error {
     MissingDefinition(Declaration),
 }
 
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Overflow => f.write_str("maximum size overflows `usize`"),
+            Self::Recursive => f.write_str("maximum size of a recursive type is unbounded"),
+            Self::MissingDefinition(declaration) => {
+                write!(f, "missing definition of `{}`", declaration)
+            }
+        }
+    }
+}
+
 /// Implementation of [`BorshSchema::max_serialized_size`].
 fn max_serialized_size_impl<'a>(
     count: NonZeroUsize,
@@ -94,7 +107,7 @@ fn max_serialized_size_impl<'a>(
         mul(count, sum)
     }
 
-    if stack.iter().any(|dec| *dec == declaration) {
+    if stack.contains(&declaration) {
         return Err(Error::Recursive);
     }
     stack.push(declaration);
@@ -103,9 +116,7 @@ fn max_serialized_size_impl<'a>(
         Ok(Definition::Primitive(size)) => match size {
             0 => Ok(0),
             size => {
-                let count_sizes = usize::try_from(*size)
-                    .ok()
-                    .and_then(|size| size.checked_mul(count.get()));
+                let count_sizes = usize::from(*size).checked_mul(count.get());
                 count_sizes.ok_or(Error::Overflow)
             }
         },
@@ -146,6 +157,10 @@ fn max_serialized_size_impl<'a>(
             Fields::UnnamedFields(fields) => tuple(count, fields, schema, stack),
             Fields::Empty => Ok(0),
         },
+        Ok(Definition::Trailing(present)) => {
+            max_serialized_size_impl(count, present, schema, stack)
+        }
+        Ok(Definition::Alias(aliased)) => max_serialized_size_impl(count, aliased, schema, stack),
 
         Err(declaration) => Err(Error::MissingDefinition(declaration.to_string())),
     }?;
@@ -207,7 +222,7 @@ fn is_zero_size_impl<'a>(
         Ok(true)
     }
 
-    if stack.iter().any(|dec| *dec == declaration) {
+    if stack.contains(&declaration) {
         return Err(ZeroSizeError::Recursive);
     }
     stack.push(declaration);
@@ -253,6 +268,8 @@ fn is_zero_size_impl<'a>(
             }
             Fields::Empty => true,
         },
+        Ok(Definition::Trailing(present)) => is_zero_size_impl(present.as_str(), schema, stack)?,
+        Ok(Definition::Alias(aliased)) => is_zero_size_impl(aliased.as_str(), schema, stack)?,
 
         Err(declaration) => {
             return Err(ZeroSizeError::MissingDefinition(declaration.into()));
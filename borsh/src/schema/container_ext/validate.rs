@@ -1,6 +1,8 @@
 use super::{is_zero_size, ZeroSizeError};
 use super::{BorshSchemaContainer, Declaration, Definition, Fields};
-use crate::__private::maybestd::{string::ToString, vec::Vec};
+use crate::__private::maybestd::{string::ToString, vec, vec::Vec};
+use crate::schema::FieldName;
+use core::fmt;
 
 impl BorshSchemaContainer {
     /// Validates container for violation of any well-known rules with
@@ -39,6 +41,45 @@ pub enum Error {
     MissingDefinition(Declaration),
     /// A Sequence defined with an empty length range.
     EmptyLengthRange(Declaration),
+    /// A struct has more than one field with the same name, e.g. after `#[borsh(flatten)]`.
+    DuplicateField(Declaration, FieldName),
+    /// An alias refers to itself, possibly through other aliases, instead of a type.
+    AliasCycle(Declaration),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ZSTSequence(declaration) => write!(
+                f,
+                "`{}` is a sequence of zero-sized elements of dynamic length",
+                declaration
+            ),
+            Self::TagTooWide(declaration) => {
+                write!(f, "tag of `{}` is wider than 8 bytes", declaration)
+            }
+            Self::TagTooNarrow(declaration) => {
+                write!(f, "length of `{}` doesn't fit into its tag", declaration)
+            }
+            Self::TagNotPowerOfTwo(declaration) => write!(
+                f,
+                "length tag of `{}` isn't 0, 1, 2, 4 or 8 bytes wide",
+                declaration
+            ),
+            Self::MissingDefinition(declaration) => {
+                write!(f, "missing definition of `{}`", declaration)
+            }
+            Self::EmptyLengthRange(declaration) => {
+                write!(f, "`{}` has an empty length range", declaration)
+            }
+            Self::DuplicateField(declaration, name) => {
+                write!(f, "`{}` has more than one `{}` field", declaration, name)
+            }
+            Self::AliasCycle(declaration) => {
+                write!(f, "alias `{}` refers to itself", declaration)
+            }
+        }
+    }
 }
 
 fn check_length_width(declaration: &Declaration, width: u8, max: u64) -> Result<(), Error> {
@@ -65,7 +106,7 @@ fn validate_impl<'a>(
             return Err(Error::MissingDefinition(declaration.to_string()));
         }
     };
-    if stack.iter().any(|dec| *dec == declaration) {
+    if stack.contains(&declaration) {
         return Ok(());
     }
     stack.push(declaration);
@@ -121,7 +162,13 @@ fn validate_impl<'a>(
         }
         Definition::Struct { fields } => match fields {
             Fields::NamedFields(fields) => {
-                for (_field_name, field_type) in fields {
+                for (index, (field_name, field_type)) in fields.iter().enumerate() {
+                    if fields[..index].iter().any(|(other, _)| other == field_name) {
+                        return Err(Error::DuplicateField(
+                            declaration.clone(),
+                            field_name.clone(),
+                        ));
+                    }
                     validate_impl(field_type, schema, stack)?;
                 }
             }
@@ -132,6 +179,19 @@ fn validate_impl<'a>(
             }
             Fields::Empty => {}
         },
+        Definition::Trailing(present) => validate_impl(present, schema, stack)?,
+        Definition::Alias(aliased) => {
+            let mut aliases = vec![declaration];
+            let mut target = aliased;
+            while let Some(Definition::Alias(next)) = schema.get_definition(target) {
+                if aliases.contains(&target) {
+                    return Err(Error::AliasCycle(declaration.clone()));
+                }
+                aliases.push(target);
+                target = next;
+            }
+            validate_impl(aliased, schema, stack)?;
+        }
     };
     stack.pop();
     Ok(())
@@ -0,0 +1,780 @@
+use crate::__private::maybestd::{
+    boxed::Box,
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use crate::error::ERROR_ZST_FORBIDDEN;
+use crate::schema::{
+    BorshSchemaContainer, Declaration, Definition, DiscriminantValue, FieldName, Fields,
+    VariantName,
+};
+use crate::varint::{VarI64, VarU32, VarU64};
+use crate::{BorshSchema, BorshSerialize};
+
+use core::convert::TryFrom;
+use core::fmt;
+
+use super::is_zero_size;
+
+/// Maximum nesting of values, decoded or encoded with the schema.
+///
+/// Protects from stack overflow on recursive schemas, as the input can't be trusted.
+const MAX_DEPTH: usize = 256;
+
+/// Dynamic representation of a value of any type, described by a [`BorshSchemaContainer`].
+///
+/// Values are produced by [`BorshSchemaContainer::deserialize_value`], and can be
+/// serialized back with [`BorshSchemaContainer::serialize_value`] into exactly the same bytes.
+///
+/// # Example
+///
+/// ```
+/// use borsh::schema::{BorshSchemaContainer, FieldValues, Value};
+///
+/// let schema = BorshSchemaContainer::for_type::<(u16, Option<String>)>();
+/// let bytes = borsh::to_vec(&(7u16, Some("seven"))).unwrap();
+/// let value = schema.deserialize_value(&bytes).unwrap();
+/// assert_eq!(
+///     value,
+///     Value::Tuple(vec![
+///         Value::U16(7),
+///         Value::Enum {
+///             discriminant: 1,
+///             variant: "Some".to_string(),
+///             value: Box::new(Value::String("seven".to_string())),
+///         },
+///     ])
+/// );
+/// assert_eq!(schema.serialize_value(&value).unwrap(), bytes);
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub enum Value {
+    /// Value of a zero-sized primitive, e.g. `()`.
+    Unit,
+    Bool(bool),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    U128(u128),
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    I128(i128),
+    F32(f32),
+    F64(f64),
+    /// Value of a primitive, unknown to borsh, as its raw bytes.
+    Opaque(Vec<u8>),
+    /// Value of the `String` sequence.
+    String(String),
+    /// Value of a sequence of `u8`.
+    Bytes(Vec<u8>),
+    /// Value of a sequence of any other elements.
+    Sequence(Vec<Value>),
+    Tuple(Vec<Value>),
+    Struct(FieldValues),
+    Enum {
+        discriminant: DiscriminantValue,
+        variant: VariantName,
+        value: Box<Value>,
+    },
+}
+
+/// Values of the fields of a [`Value::Struct`], structurally identical to [`Fields`].
+#[derive(Clone, PartialEq, Debug)]
+pub enum FieldValues {
+    Named(Vec<(FieldName, Value)>),
+    Unnamed(Vec<Value>),
+    Empty,
+}
+
+impl BorshSchemaContainer {
+    /// Deserializes borsh `bytes` of the container's type into a dynamic [`Value`].
+    ///
+    /// Values of the `NonZero*` types are represented by their underlying integers,
+    /// and values of `Definition::Trailing` as [`Value::Enum`] of `Option`.
+    pub fn deserialize_value(&self, bytes: &[u8]) -> Result<Value, Error> {
+        let mut buf = bytes;
+        let value = decode(self.declaration(), self, &mut buf, 0)?;
+        if !buf.is_empty() {
+            return Err(Error::TrailingBytes(buf.len()));
+        }
+        Ok(value)
+    }
+
+    /// Serializes a dynamic [`Value`] of the container's type into borsh bytes.
+    pub fn serialize_value(&self, value: &Value) -> Result<Vec<u8>, Error> {
+        let mut out = Vec::new();
+        encode(self.declaration(), self, value, &mut out, 0)?;
+        Ok(out)
+    }
+}
+
+/// Possible error when converting between borsh and dynamic values
+/// with a [`BorshSchemaContainer`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Error {
+    /// Some of the declared types were lacking definition.
+    MissingDefinition(Declaration),
+    /// The input ended before a value of the declared type was decoded.
+    UnexpectedEof(Declaration),
+    /// The input wasn't fully consumed by the decoded value, holds number of bytes left.
+    TrailingBytes(usize),
+    /// The input isn't a valid encoding of the declared type,
+    /// e.g. it has an unknown enum tag or a `bool` other than 0 or 1.
+    InvalidData(Declaration, String),
+    /// Given value doesn't match the declared type.
+    InvalidValue(Declaration, String),
+    /// Length of the declared sequence can't be determined from the schema alone,
+    /// i.e. it's neither prefixed with its length nor has a fixed length.
+    UnknownLength(Declaration),
+    /// Values are nested deeper than supported.
+    TooDeep(Declaration),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingDefinition(declaration) => {
+                write!(f, "missing definition of `{}`", declaration)
+            }
+            Self::UnexpectedEof(declaration) => {
+                write!(f, "unexpected end of input in `{}`", declaration)
+            }
+            Self::TrailingBytes(len) => write!(f, "{} bytes left after the value", len),
+            Self::InvalidData(declaration, reason) => {
+                write!(f, "invalid data of `{}`: {}", declaration, reason)
+            }
+            Self::InvalidValue(declaration, reason) => {
+                write!(f, "invalid value of `{}`: {}", declaration, reason)
+            }
+            Self::UnknownLength(declaration) => {
+                write!(f, "length of `{}` isn't known from the schema", declaration)
+            }
+            Self::TooDeep(declaration) => write!(f, "`{}` is nested too deep", declaration),
+        }
+    }
+}
+
+pub(super) fn definition<'a>(
+    declaration: &str,
+    schema: &'a BorshSchemaContainer,
+    depth: usize,
+) -> Result<&'a Definition, Error> {
+    if depth > MAX_DEPTH {
+        return Err(Error::TooDeep(declaration.to_string()));
+    }
+    schema
+        .get_definition(declaration)
+        .ok_or_else(|| Error::MissingDefinition(declaration.to_string()))
+}
+
+/// Declaration of the collection, which is re-encoded by `declaration`, e.g. `String`
+/// of `LengthPrefixed<u8, String>`, or `declaration` itself, if it isn't a [`crate::length_prefix`] type.
+pub(super) fn collection(declaration: &str) -> &str {
+    declaration
+        .strip_prefix("LengthPrefixed<")
+        .and_then(|rest| rest.strip_suffix('>'))
+        .and_then(|rest| rest.split_once(", "))
+        .map_or(declaration, |(_, inner)| collection(inner))
+}
+
+/// Whether a `Sequence` is a string, either `String` or `BoundedString<MAX>`,
+/// possibly with another length prefix, e.g. `LengthPrefixed<u8, String>`.
+pub(super) fn is_string(declaration: &str, elements: &str) -> bool {
+    let collection = collection(declaration);
+    (collection == "String" || collection.starts_with("BoundedString<")) && elements == "u8"
+}
+
+/// Declaration and size of the integer of a `Sequence`, which is one of [`crate::varint`] types.
+///
+/// Such sequences have no length prefix, their length is determined by the high bits of their bytes,
+/// which the schema can't express.  So varints are recognised by both the declaration and the exact
+/// definition of the library types, and not by the name alone, while aliases of them refer to these.
+pub(super) fn varint_type(
+    declaration: &str,
+    schema: &BorshSchemaContainer,
+) -> Option<(&'static str, u8)> {
+    fn is<T: BorshSchema>(declaration: &str, schema: &BorshSchemaContainer) -> bool {
+        if declaration != T::declaration() {
+            return false;
+        }
+        let mut definitions = BTreeMap::new();
+        T::add_definitions_recursively(&mut definitions);
+        schema.get_definition(declaration) == definitions.get(declaration)
+    }
+
+    if is::<VarU32>(declaration, schema) {
+        Some(("u32", 4))
+    } else if is::<VarU64>(declaration, schema) {
+        Some(("u64", 8))
+    } else if is::<VarI64>(declaration, schema) {
+        Some(("i64", 8))
+    } else {
+        None
+    }
+}
+
+/// Decodes a varint of the `integer` type, returned by [`varint_type`].
+pub(super) fn decode_varint(
+    declaration: &str,
+    integer: &str,
+    buf: &mut &[u8],
+) -> Result<Value, Error> {
+    let len = buf
+        .iter()
+        .position(|byte| byte & 0x80 == 0)
+        .ok_or_else(|| Error::UnexpectedEof(declaration.to_string()))?;
+    let bytes = take(declaration, buf, len + 1)?;
+    let invalid =
+        |err: crate::io::Error| Error::InvalidData(declaration.to_string(), err.to_string());
+    Ok(match integer {
+        "u32" => Value::U32(crate::from_slice::<VarU32>(bytes).map_err(invalid)?.0),
+        "u64" => Value::U64(crate::from_slice::<VarU64>(bytes).map_err(invalid)?.0),
+        _ => Value::I64(crate::from_slice::<VarI64>(bytes).map_err(invalid)?.0),
+    })
+}
+
+/// Encodes a varint of the `integer` type, returned by [`varint_type`].
+fn encode_varint(
+    declaration: &str,
+    integer: &str,
+    value: &Value,
+    out: &mut Vec<u8>,
+) -> Result<(), Error> {
+    let written = match (integer, value) {
+        ("u32", Value::U32(value)) => VarU32(*value).serialize(out),
+        ("u64", Value::U64(value)) => VarU64(*value).serialize(out),
+        ("i64", Value::I64(value)) => VarI64(*value).serialize(out),
+        _ => {
+            return Err(Error::InvalidValue(
+                declaration.to_string(),
+                format!("{:?} is not a valid {}", value, declaration),
+            ))
+        }
+    };
+    written.map_err(|err| Error::InvalidValue(declaration.to_string(), err.to_string()))
+}
+
+/// Checks that `len` elements of a sequence may be left in `buf`, before decoding them,
+/// so that a length, declared in the input, can't make decoding spin without consuming it:
+/// elements, which take up some bytes, can't outnumber the bytes left, and zero-sized ones
+/// are only allowed in arrays of fixed length.
+pub(super) fn check_sequence_len(
+    declaration: &str,
+    length_width: u8,
+    elements: &Declaration,
+    len: u64,
+    schema: &BorshSchemaContainer,
+    buf: &[u8],
+) -> Result<(), Error> {
+    if is_zero_size(elements, schema) == Ok(true) {
+        if length_width != Definition::ARRAY_LENGTH_WIDTH {
+            return Err(Error::InvalidData(
+                declaration.to_string(),
+                ERROR_ZST_FORBIDDEN.to_string(),
+            ));
+        }
+    } else if len > buf.len() as u64 {
+        return Err(Error::UnexpectedEof(declaration.to_string()));
+    }
+    Ok(())
+}
+
+/// Value of a `Definition::Trailing`, represented as `Option`: either `Some` variant
+/// of the present value, or `None` variant of [`Value::Unit`], if it's missing.
+pub(super) fn trailing_value(present: Option<Value>) -> Value {
+    let (discriminant, variant, value) = match present {
+        Some(value) => (1, "Some", value),
+        None => (0, "None", Value::Unit),
+    };
+    Value::Enum {
+        discriminant,
+        variant: variant.to_string(),
+        value: Box::new(value),
+    }
+}
+
+/// Mask of the meaningful bits of enum discriminant, encoded with `tag_width` bytes.
+pub(super) fn tag_mask(tag_width: u8) -> u64 {
+    match tag_width {
+        0 => 0,
+        1..=7 => (1 << (u32::from(tag_width) * 8)) - 1,
+        _ => u64::MAX,
+    }
+}
+
+pub(super) fn take<'a>(
+    declaration: &str,
+    buf: &mut &'a [u8],
+    len: usize,
+) -> Result<&'a [u8], Error> {
+    if buf.len() < len {
+        return Err(Error::UnexpectedEof(declaration.to_string()));
+    }
+    let (head, tail) = buf.split_at(len);
+    *buf = tail;
+    Ok(head)
+}
+
+/// Reads a little-endian unsigned integer `width` bytes wide.
+pub(super) fn take_uint(declaration: &str, buf: &mut &[u8], width: u8) -> Result<u64, Error> {
+    if width > 8 {
+        return Err(Error::InvalidData(
+            declaration.to_string(),
+            format!("unsupported width of {} bytes", width),
+        ));
+    }
+    let bytes = take(declaration, buf, usize::from(width))?;
+    let mut le = [0u8; 8];
+    le[..bytes.len()].copy_from_slice(bytes);
+    Ok(u64::from_le_bytes(le))
+}
+
+/// Writes a little-endian unsigned integer `width` bytes wide.
+fn put_uint(declaration: &str, out: &mut Vec<u8>, value: u64, width: u8) -> Result<(), Error> {
+    if width > 8 || value & !tag_mask(width) != 0 {
+        return Err(Error::InvalidValue(
+            declaration.to_string(),
+            format!("{} doesn't fit into {} bytes", value, width),
+        ));
+    }
+    out.extend_from_slice(&value.to_le_bytes()[..usize::from(width)]);
+    Ok(())
+}
+
+/// Decodes the only variant of an untagged enum, which matches `buf`, and returns its index.
+///
+/// Variants, which decode from no bytes, e.g. `()`, only match at the end of the input,
+/// as otherwise they would match anywhere. Input, which matches several variants,
+/// is rejected as ambiguous instead of guessing one of them.
+pub(super) fn untagged_variant(
+    declaration: &str,
+    variants: &[(DiscriminantValue, VariantName, Declaration)],
+    schema: &BorshSchemaContainer,
+    buf: &mut &[u8],
+    depth: usize,
+) -> Result<(usize, Value), Error> {
+    let mut matched: Option<(usize, Value, &[u8])> = None;
+    for (index, (_, name, variant)) in variants.iter().enumerate() {
+        let mut attempt = *buf;
+        let value = match decode(variant, schema, &mut attempt, depth + 1) {
+            Ok(value) => value,
+            Err(_) => continue,
+        };
+        if attempt.len() == buf.len() && !buf.is_empty() {
+            continue;
+        }
+        if let Some((other, _, _)) = &matched {
+            return Err(Error::InvalidData(
+                declaration.to_string(),
+                format!(
+                    "ambiguous input, matching both {} and {} variants",
+                    variants[*other].1, name
+                ),
+            ));
+        }
+        matched = Some((index, value, attempt));
+    }
+    let (index, value, rest) = matched.ok_or_else(|| {
+        Error::InvalidData(
+            declaration.to_string(),
+            "none of the variants match".to_string(),
+        )
+    })?;
+    *buf = rest;
+    Ok((index, value))
+}
+
+fn decode(
+    declaration: &str,
+    schema: &BorshSchemaContainer,
+    buf: &mut &[u8],
+    depth: usize,
+) -> Result<Value, Error> {
+    let decode_all = |elements: &[Declaration], buf: &mut &[u8]| {
+        elements
+            .iter()
+            .map(|element| decode(element, schema, buf, depth + 1))
+            .collect::<Result<Vec<_>, _>>()
+    };
+
+    match definition(declaration, schema, depth)? {
+        Definition::Primitive(size) => {
+            let bytes = take(declaration, buf, usize::from(*size))?;
+            decode_primitive(declaration, bytes)
+        }
+        Definition::Sequence {
+            length_width,
+            length_range,
+            elements,
+        } => decode_sequence(
+            declaration,
+            *length_width,
+            length_range,
+            elements,
+            schema,
+            buf,
+            depth,
+        ),
+        Definition::Tuple { elements } => Ok(Value::Tuple(decode_all(elements, buf)?)),
+        Definition::Enum {
+            tag_width: 0,
+            variants,
+        } => {
+            let (index, value) = untagged_variant(declaration, variants, schema, buf, depth)?;
+            let (discriminant, name, _) = &variants[index];
+            Ok(Value::Enum {
+                discriminant: *discriminant,
+                variant: name.clone(),
+                value: Box::new(value),
+            })
+        }
+        Definition::Enum {
+            tag_width,
+            variants,
+        } => {
+            let tag = take_uint(declaration, buf, *tag_width)?;
+            let mask = tag_mask(*tag_width);
+            let (discriminant, name, variant) = variants
+                .iter()
+                .find(|(discriminant, _, _)| *discriminant as u64 & mask == tag)
+                .ok_or_else(|| {
+                    Error::InvalidData(
+                        declaration.to_string(),
+                        format!("unexpected variant tag: {}", tag),
+                    )
+                })?;
+            Ok(Value::Enum {
+                discriminant: *discriminant,
+                variant: name.clone(),
+                value: Box::new(decode(variant, schema, buf, depth + 1)?),
+            })
+        }
+        Definition::Struct { fields } => {
+            let values = match fields {
+                Fields::NamedFields(fields) => {
+                    let mut result = Vec::with_capacity(fields.len());
+                    for (name, field) in fields {
+                        result.push((name.clone(), decode(field, schema, buf, depth + 1)?));
+                    }
+                    FieldValues::Named(result)
+                }
+                Fields::UnnamedFields(fields) => FieldValues::Unnamed(decode_all(fields, buf)?),
+                Fields::Empty => FieldValues::Empty,
+            };
+            Ok(Value::Struct(values))
+        }
+        Definition::Trailing(present) => {
+            if buf.is_empty() {
+                return Ok(trailing_value(None));
+            }
+            let value = decode(present, schema, buf, depth + 1)?;
+            Ok(trailing_value(Some(value)))
+        }
+        Definition::Alias(aliased) => decode(aliased, schema, buf, depth + 1),
+    }
+}
+
+/// Decodes a value of `Definition::Sequence`, kept apart from [`decode`] to keep
+/// the stack frames of deeply nested values small.
+fn decode_sequence(
+    declaration: &str,
+    length_width: u8,
+    length_range: &core::ops::RangeInclusive<u64>,
+    elements: &Declaration,
+    schema: &BorshSchemaContainer,
+    buf: &mut &[u8],
+    depth: usize,
+) -> Result<Value, Error> {
+    if let Some((integer, _)) = varint_type(declaration, schema) {
+        return decode_varint(declaration, integer, buf);
+    }
+    let len = if length_width == Definition::ARRAY_LENGTH_WIDTH {
+        if length_range.start() != length_range.end() {
+            return Err(Error::UnknownLength(declaration.to_string()));
+        }
+        *length_range.start()
+    } else {
+        let len = take_uint(declaration, buf, length_width)?;
+        if !length_range.contains(&len) {
+            return Err(Error::InvalidData(
+                declaration.to_string(),
+                format!("length {} is out of range {:?}", len, length_range),
+            ));
+        }
+        len
+    };
+    if elements == "u8" {
+        let len =
+            usize::try_from(len).map_err(|_| Error::UnexpectedEof(declaration.to_string()))?;
+        let bytes = take(declaration, buf, len)?;
+        if !is_string(declaration, elements) {
+            return Ok(Value::Bytes(bytes.to_vec()));
+        }
+        return match core::str::from_utf8(bytes) {
+            Ok(string) => Ok(Value::String(string.to_string())),
+            Err(err) => Err(Error::InvalidData(declaration.to_string(), err.to_string())),
+        };
+    }
+    check_sequence_len(declaration, length_width, elements, len, schema, buf)?;
+    let mut result = Vec::new();
+    for _ in 0..len {
+        result.push(decode(elements, schema, buf, depth + 1)?);
+    }
+    Ok(Value::Sequence(result))
+}
+
+pub(super) fn decode_primitive(declaration: &str, bytes: &[u8]) -> Result<Value, Error> {
+    macro_rules! int {
+        ($ty:ty) => {{
+            let mut le = [0u8; core::mem::size_of::<$ty>()];
+            le.copy_from_slice(bytes);
+            <$ty>::from_le_bytes(le)
+        }};
+    }
+    let invalid = |reason: &str| Err(Error::InvalidData(declaration.to_string(), reason.into()));
+    let nonzero = |value: Value| {
+        if bytes.iter().all(|byte| *byte == 0) {
+            invalid("expected a non-zero value")
+        } else {
+            Ok(value)
+        }
+    };
+
+    let value = match (declaration, bytes.len()) {
+        (_, 0) => Value::Unit,
+        ("bool", 1) => match bytes[0] {
+            0 => Value::Bool(false),
+            1 => Value::Bool(true),
+            _ => return invalid("bool must be 0 or 1"),
+        },
+        ("u8", 1) => Value::U8(bytes[0]),
+        ("u16", 2) => Value::U16(int!(u16)),
+        ("u32", 4) => Value::U32(int!(u32)),
+        ("u64", 8) => Value::U64(int!(u64)),
+        ("u128", 16) => Value::U128(int!(u128)),
+        ("i8", 1) => Value::I8(int!(i8)),
+        ("i16", 2) => Value::I16(int!(i16)),
+        ("i32", 4) => Value::I32(int!(i32)),
+        ("i64", 8) => Value::I64(int!(i64)),
+        ("i128", 16) => Value::I128(int!(i128)),
+        ("f32", 4) if f32::from_bits(int!(u32)).is_nan() => return invalid("NaN is not allowed"),
+        ("f32", 4) => Value::F32(f32::from_bits(int!(u32))),
+        ("f64", 8) if f64::from_bits(int!(u64)).is_nan() => return invalid("NaN is not allowed"),
+        ("f64", 8) => Value::F64(f64::from_bits(int!(u64))),
+        ("NonZeroU8", 1) => nonzero(Value::U8(bytes[0]))?,
+        ("NonZeroU16", 2) => nonzero(Value::U16(int!(u16)))?,
+        ("NonZeroU32", 4) => nonzero(Value::U32(int!(u32)))?,
+        ("NonZeroU64", 8) | ("NonZeroUsize", 8) => nonzero(Value::U64(int!(u64)))?,
+        ("NonZeroU128", 16) => nonzero(Value::U128(int!(u128)))?,
+        ("NonZeroI8", 1) => nonzero(Value::I8(int!(i8)))?,
+        ("NonZeroI16", 2) => nonzero(Value::I16(int!(i16)))?,
+        ("NonZeroI32", 4) => nonzero(Value::I32(int!(i32)))?,
+        ("NonZeroI64", 8) => nonzero(Value::I64(int!(i64)))?,
+        ("NonZeroI128", 16) => nonzero(Value::I128(int!(i128)))?,
+        _ => Value::Opaque(bytes.to_vec()),
+    };
+    Ok(value)
+}
+
+fn encode(
+    declaration: &str,
+    schema: &BorshSchemaContainer,
+    value: &Value,
+    out: &mut Vec<u8>,
+    depth: usize,
+) -> Result<(), Error> {
+    let invalid = |reason: String| Error::InvalidValue(declaration.to_string(), reason);
+    let mismatch = || invalid(format!("unexpected value {:?}", value));
+
+    match definition(declaration, schema, depth)? {
+        Definition::Primitive(size) => encode_primitive(declaration, *size, value, out),
+        Definition::Sequence {
+            length_width,
+            length_range,
+            elements,
+        } => {
+            if let Some((integer, _)) = varint_type(declaration, schema) {
+                return encode_varint(declaration, integer, value, out);
+            }
+            let len = match value {
+                Value::String(string) if is_string(declaration, elements) => string.len(),
+                Value::Bytes(bytes) if elements == "u8" => bytes.len(),
+                Value::Sequence(items) => items.len(),
+                _ => return Err(mismatch()),
+            } as u64;
+            if !length_range.contains(&len) {
+                return Err(invalid(format!(
+                    "length {} is out of range {:?}",
+                    len, length_range
+                )));
+            }
+            if *length_width != Definition::ARRAY_LENGTH_WIDTH {
+                put_uint(declaration, out, len, *length_width)?;
+            } else if length_range.start() != length_range.end() {
+                return Err(Error::UnknownLength(declaration.to_string()));
+            }
+            match value {
+                Value::String(string) => out.extend_from_slice(string.as_bytes()),
+                Value::Bytes(bytes) => out.extend_from_slice(bytes),
+                Value::Sequence(items) => {
+                    for item in items {
+                        encode(elements, schema, item, out, depth + 1)?;
+                    }
+                }
+                _ => unreachable!("value kind was checked above"),
+            }
+            Ok(())
+        }
+        Definition::Tuple { elements } => match value {
+            Value::Tuple(items) if items.len() == elements.len() => {
+                for (element, item) in elements.iter().zip(items) {
+                    encode(element, schema, item, out, depth + 1)?;
+                }
+                Ok(())
+            }
+            _ => Err(mismatch()),
+        },
+        Definition::Enum {
+            tag_width,
+            variants,
+        } => {
+            let (discriminant, variant, payload) = match value {
+                Value::Enum {
+                    discriminant,
+                    variant,
+                    value,
+                } => (discriminant, variant, value),
+                _ => return Err(mismatch()),
+            };
+            let (_, _, declaration_of_variant) = variants
+                .iter()
+                .find(|(d, name, _)| d == discriminant && name == variant)
+                .ok_or_else(|| {
+                    invalid(format!(
+                        "unknown variant `{}` with discriminant {}",
+                        variant, discriminant
+                    ))
+                })?;
+            if *tag_width != 0 {
+                let tag = *discriminant as u64 & tag_mask(*tag_width);
+                put_uint(declaration, out, tag, *tag_width)?;
+            }
+            encode(declaration_of_variant, schema, payload, out, depth + 1)
+        }
+        Definition::Struct { fields } => match (fields, value) {
+            (Fields::NamedFields(fields), Value::Struct(FieldValues::Named(values))) => {
+                if let Some((key, _)) = values
+                    .iter()
+                    .find(|(key, _)| !fields.iter().any(|(name, _)| name == key))
+                {
+                    return Err(invalid(format!("unknown field `{}`", key)));
+                }
+                for (name, field) in fields {
+                    let (_, item) = values
+                        .iter()
+                        .find(|(key, _)| key == name)
+                        .ok_or_else(|| invalid(format!("missing field `{}`", name)))?;
+                    encode(field, schema, item, out, depth + 1)?;
+                }
+                Ok(())
+            }
+            (Fields::UnnamedFields(fields), Value::Struct(FieldValues::Unnamed(values)))
+                if fields.len() == values.len() =>
+            {
+                for (field, item) in fields.iter().zip(values) {
+                    encode(field, schema, item, out, depth + 1)?;
+                }
+                Ok(())
+            }
+            (Fields::Empty, Value::Struct(FieldValues::Empty)) => Ok(()),
+            _ => Err(mismatch()),
+        },
+        Definition::Trailing(present) => match value {
+            Value::Enum { variant, value, .. } if variant == "Some" => {
+                encode(present, schema, value, out, depth + 1)
+            }
+            Value::Enum { variant, value, .. } if variant == "None" && **value == Value::Unit => {
+                Ok(())
+            }
+            _ => Err(mismatch()),
+        },
+        Definition::Alias(aliased) => encode(aliased, schema, value, out, depth + 1),
+    }
+}
+
+fn encode_primitive(
+    declaration: &str,
+    size: u8,
+    value: &Value,
+    out: &mut Vec<u8>,
+) -> Result<(), Error> {
+    let invalid = || {
+        Error::InvalidValue(
+            declaration.to_string(),
+            format!("{:?} is not a valid {}", value, declaration),
+        )
+    };
+    let start = out.len();
+    match (declaration, size, value) {
+        (_, 0, Value::Unit) => {}
+        ("bool", 1, Value::Bool(value)) => out.push(u8::from(*value)),
+        ("u8", 1, Value::U8(value)) => out.push(*value),
+        ("u16", 2, Value::U16(value)) => out.extend_from_slice(&value.to_le_bytes()),
+        ("u32", 4, Value::U32(value)) => out.extend_from_slice(&value.to_le_bytes()),
+        ("u64", 8, Value::U64(value)) => out.extend_from_slice(&value.to_le_bytes()),
+        ("u128", 16, Value::U128(value)) => out.extend_from_slice(&value.to_le_bytes()),
+        ("i8", 1, Value::I8(value)) => out.extend_from_slice(&value.to_le_bytes()),
+        ("i16", 2, Value::I16(value)) => out.extend_from_slice(&value.to_le_bytes()),
+        ("i32", 4, Value::I32(value)) => out.extend_from_slice(&value.to_le_bytes()),
+        ("i64", 8, Value::I64(value)) => out.extend_from_slice(&value.to_le_bytes()),
+        ("i128", 16, Value::I128(value)) => out.extend_from_slice(&value.to_le_bytes()),
+        ("f32", 4, Value::F32(value)) if !value.is_nan() => {
+            out.extend_from_slice(&value.to_le_bytes())
+        }
+        ("f64", 8, Value::F64(value)) if !value.is_nan() => {
+            out.extend_from_slice(&value.to_le_bytes())
+        }
+        ("NonZeroU8", 1, Value::U8(value)) if *value != 0 => out.push(*value),
+        ("NonZeroU16", 2, Value::U16(value)) if *value != 0 => {
+            out.extend_from_slice(&value.to_le_bytes())
+        }
+        ("NonZeroU32", 4, Value::U32(value)) if *value != 0 => {
+            out.extend_from_slice(&value.to_le_bytes())
+        }
+        ("NonZeroU64", 8, Value::U64(value)) | ("NonZeroUsize", 8, Value::U64(value))
+            if *value != 0 =>
+        {
+            out.extend_from_slice(&value.to_le_bytes())
+        }
+        ("NonZeroU128", 16, Value::U128(value)) if *value != 0 => {
+            out.extend_from_slice(&value.to_le_bytes())
+        }
+        ("NonZeroI8", 1, Value::I8(value)) if *value != 0 => {
+            out.extend_from_slice(&value.to_le_bytes())
+        }
+        ("NonZeroI16", 2, Value::I16(value)) if *value != 0 => {
+            out.extend_from_slice(&value.to_le_bytes())
+        }
+        ("NonZeroI32", 4, Value::I32(value)) if *value != 0 => {
+            out.extend_from_slice(&value.to_le_bytes())
+        }
+        ("NonZeroI64", 8, Value::I64(value)) if *value != 0 => {
+            out.extend_from_slice(&value.to_le_bytes())
+        }
+        ("NonZeroI128", 16, Value::I128(value)) if *value != 0 => {
+            out.extend_from_slice(&value.to_le_bytes())
+        }
+        (_, _, Value::Opaque(bytes)) if bytes.len() == usize::from(size) => {
+            out.extend_from_slice(bytes)
+        }
+        _ => return Err(invalid()),
+    }
+    // a known declaration of unexpected size is rejected by now
+    debug_assert_eq!(out.len() - start, usize::from(size));
+    Ok(())
+}
@@ -0,0 +1,559 @@
+use core::convert::TryFrom;
+use core::marker::PhantomData;
+
+use crate::__private::maybestd::{
+    borrow::{Cow, ToOwned},
+    boxed::Box,
+    collections::{BTreeMap, BTreeSet, LinkedList, VecDeque},
+    string::String,
+    vec::Vec,
+};
+use crate::async_io::{AsyncWrite, BoxFuture};
+use crate::error::check_zst;
+use crate::io::{ErrorKind, Result};
+use crate::BorshSerialize;
+
+#[cfg(feature = "rc")]
+use crate::__private::maybestd::sync::Arc;
+
+/// A data-structure that can be serialized into binary format by NBOR, writing
+/// to an [`AsyncWrite`] sink.
+///
+/// Produces the same bytes as [`BorshSerialize`].  Futures are boxed, see
+/// [`BoxFuture`], and need to be `Send`, which is why the trait requires `Sync`.
+///
+/// ```
+/// use borsh::BorshSerializeAsync;
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let (mut client, mut server) = tokio::io::duplex(64);
+/// (42u32, "hello").serialize_async(&mut client).await.unwrap();
+/// # });
+/// ```
+pub trait BorshSerializeAsync: Sync {
+    fn serialize_async<'a, W: AsyncWrite + ?Sized>(
+        &'a self,
+        writer: &'a mut W,
+    ) -> BoxFuture<'a, Result<()>>;
+
+    #[inline]
+    #[doc(hidden)]
+    fn u8_slice(slice: &[Self]) -> Option<&[u8]>
+    where
+        Self: Sized,
+    {
+        let _ = slice;
+        None
+    }
+}
+
+/// Writes length prefix of a sequence.
+async fn serialize_len<W: AsyncWrite + ?Sized>(len: usize, writer: &mut W) -> Result<()> {
+    let len = u32::try_from(len).map_err(|_| ErrorKind::InvalidData)?;
+    writer.write_all(&len.to_le_bytes()).await
+}
+
+/// Helper method that is used to serialize a slice of data (without the length marker).
+async fn serialize_slice<T: BorshSerializeAsync, W: AsyncWrite + ?Sized>(
+    data: &[T],
+    writer: &mut W,
+) -> Result<()> {
+    if let Some(u8_slice) = T::u8_slice(data) {
+        writer.write_all(u8_slice).await?;
+    } else {
+        for item in data {
+            item.serialize_async(writer).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Implements the trait for types of bounded size with [`BorshSerialize`], which
+/// serializes them into a buffer on stack.
+macro_rules! impl_via_buffer {
+    ($($type:ty => $max_size:expr),+ $(,)?) => {
+        $(
+        impl BorshSerializeAsync for $type {
+            #[inline]
+            fn serialize_async<'a, W: AsyncWrite + ?Sized>(
+                &'a self,
+                writer: &'a mut W,
+            ) -> BoxFuture<'a, Result<()>> {
+                Box::pin(async move {
+                    let mut buf = [0u8; $max_size];
+                    let mut unused = &mut buf[..];
+                    BorshSerialize::serialize(self, &mut unused)?;
+                    let len = $max_size - unused.len();
+                    writer.write_all(&buf[..len]).await
+                })
+            }
+        }
+        )+
+    };
+}
+
+impl_via_buffer!(
+    i8 => 1,
+    i16 => 2,
+    i32 => 4,
+    i64 => 8,
+    i128 => 16,
+    u16 => 2,
+    u32 => 4,
+    u64 => 8,
+    u128 => 16,
+    isize => 8,
+    usize => 8,
+    core::num::NonZeroI8 => 1,
+    core::num::NonZeroI16 => 2,
+    core::num::NonZeroI32 => 4,
+    core::num::NonZeroI64 => 8,
+    core::num::NonZeroI128 => 16,
+    core::num::NonZeroU8 => 1,
+    core::num::NonZeroU16 => 2,
+    core::num::NonZeroU32 => 4,
+    core::num::NonZeroU64 => 8,
+    core::num::NonZeroU128 => 16,
+    core::num::NonZeroUsize => 8,
+    f32 => 4,
+    f64 => 8,
+    bool => 1,
+    std::net::SocketAddr => 19,
+    std::net::SocketAddrV4 => 6,
+    std::net::SocketAddrV6 => 18,
+    std::net::Ipv4Addr => 4,
+    std::net::Ipv6Addr => 16,
+);
+
+#[cfg(feature = "bson")]
+impl_via_buffer!(bson::oid::ObjectId => 12);
+
+impl BorshSerializeAsync for u8 {
+    #[inline]
+    fn serialize_async<'a, W: AsyncWrite + ?Sized>(
+        &'a self,
+        writer: &'a mut W,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(writer.write_all(core::slice::from_ref(self)))
+    }
+
+    #[inline]
+    #[doc(hidden)]
+    fn u8_slice(slice: &[Self]) -> Option<&[u8]> {
+        Some(slice)
+    }
+}
+
+impl<T> BorshSerializeAsync for Option<T>
+where
+    T: BorshSerializeAsync,
+{
+    #[inline]
+    fn serialize_async<'a, W: AsyncWrite + ?Sized>(
+        &'a self,
+        writer: &'a mut W,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            match self {
+                None => writer.write_all(&[0]).await,
+                Some(value) => {
+                    writer.write_all(&[1]).await?;
+                    value.serialize_async(writer).await
+                }
+            }
+        })
+    }
+}
+
+impl<T, E> BorshSerializeAsync for core::result::Result<T, E>
+where
+    T: BorshSerializeAsync,
+    E: BorshSerializeAsync,
+{
+    #[inline]
+    fn serialize_async<'a, W: AsyncWrite + ?Sized>(
+        &'a self,
+        writer: &'a mut W,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            match self {
+                Err(e) => {
+                    writer.write_all(&[0]).await?;
+                    e.serialize_async(writer).await
+                }
+                Ok(v) => {
+                    writer.write_all(&[1]).await?;
+                    v.serialize_async(writer).await
+                }
+            }
+        })
+    }
+}
+
+impl BorshSerializeAsync for str {
+    #[inline]
+    fn serialize_async<'a, W: AsyncWrite + ?Sized>(
+        &'a self,
+        writer: &'a mut W,
+    ) -> BoxFuture<'a, Result<()>> {
+        self.as_bytes().serialize_async(writer)
+    }
+}
+
+impl BorshSerializeAsync for String {
+    #[inline]
+    fn serialize_async<'a, W: AsyncWrite + ?Sized>(
+        &'a self,
+        writer: &'a mut W,
+    ) -> BoxFuture<'a, Result<()>> {
+        self.as_bytes().serialize_async(writer)
+    }
+}
+
+impl<T> BorshSerializeAsync for [T]
+where
+    T: BorshSerializeAsync,
+{
+    #[inline]
+    fn serialize_async<'a, W: AsyncWrite + ?Sized>(
+        &'a self,
+        writer: &'a mut W,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            serialize_len(self.len(), writer).await?;
+            serialize_slice(self, writer).await
+        })
+    }
+}
+
+impl<T: BorshSerializeAsync + ?Sized> BorshSerializeAsync for &T {
+    #[inline]
+    fn serialize_async<'a, W: AsyncWrite + ?Sized>(
+        &'a self,
+        writer: &'a mut W,
+    ) -> BoxFuture<'a, Result<()>> {
+        (**self).serialize_async(writer)
+    }
+}
+
+impl<T> BorshSerializeAsync for Cow<'_, T>
+where
+    T: BorshSerializeAsync + ToOwned + ?Sized,
+    T::Owned: Sync,
+{
+    #[inline]
+    fn serialize_async<'a, W: AsyncWrite + ?Sized>(
+        &'a self,
+        writer: &'a mut W,
+    ) -> BoxFuture<'a, Result<()>> {
+        self.as_ref().serialize_async(writer)
+    }
+}
+
+impl<T> BorshSerializeAsync for Vec<T>
+where
+    T: BorshSerializeAsync,
+{
+    #[inline]
+    fn serialize_async<'a, W: AsyncWrite + ?Sized>(
+        &'a self,
+        writer: &'a mut W,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            check_zst::<T>()?;
+
+            self.as_slice().serialize_async(writer).await
+        })
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl BorshSerializeAsync for bytes::Bytes {
+    #[inline]
+    fn serialize_async<'a, W: AsyncWrite + ?Sized>(
+        &'a self,
+        writer: &'a mut W,
+    ) -> BoxFuture<'a, Result<()>> {
+        self.as_ref().serialize_async(writer)
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl BorshSerializeAsync for bytes::BytesMut {
+    #[inline]
+    fn serialize_async<'a, W: AsyncWrite + ?Sized>(
+        &'a self,
+        writer: &'a mut W,
+    ) -> BoxFuture<'a, Result<()>> {
+        self.as_ref().serialize_async(writer)
+    }
+}
+
+impl<T> BorshSerializeAsync for VecDeque<T>
+where
+    T: BorshSerializeAsync,
+{
+    #[inline]
+    fn serialize_async<'a, W: AsyncWrite + ?Sized>(
+        &'a self,
+        writer: &'a mut W,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            check_zst::<T>()?;
+
+            serialize_len(self.len(), writer).await?;
+            let slices = self.as_slices();
+            serialize_slice(slices.0, writer).await?;
+            serialize_slice(slices.1, writer).await
+        })
+    }
+}
+
+impl<T> BorshSerializeAsync for LinkedList<T>
+where
+    T: BorshSerializeAsync,
+{
+    #[inline]
+    fn serialize_async<'a, W: AsyncWrite + ?Sized>(
+        &'a self,
+        writer: &'a mut W,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            check_zst::<T>()?;
+
+            serialize_len(self.len(), writer).await?;
+            for item in self {
+                item.serialize_async(writer).await?;
+            }
+            Ok(())
+        })
+    }
+}
+
+mod hashes {
+    use core::hash::BuildHasher;
+
+    use super::{serialize_len, BorshSerializeAsync};
+    use crate::__private::maybestd::{
+        boxed::Box,
+        collections::{HashMap, HashSet},
+        vec::Vec,
+    };
+    use crate::async_io::{AsyncWrite, BoxFuture};
+    use crate::error::check_zst;
+    use crate::io::Result;
+
+    impl<K, V, H> BorshSerializeAsync for HashMap<K, V, H>
+    where
+        K: BorshSerializeAsync + Ord,
+        V: BorshSerializeAsync,
+        H: BuildHasher + Sync,
+    {
+        #[inline]
+        fn serialize_async<'a, W: AsyncWrite + ?Sized>(
+            &'a self,
+            writer: &'a mut W,
+        ) -> BoxFuture<'a, Result<()>> {
+            Box::pin(async move {
+                check_zst::<K>()?;
+
+                let mut vec = self.iter().collect::<Vec<_>>();
+                vec.sort_by_key(|(a, _)| *a);
+                serialize_len(vec.len(), writer).await?;
+                for (key, value) in vec {
+                    key.serialize_async(writer).await?;
+                    value.serialize_async(writer).await?;
+                }
+                Ok(())
+            })
+        }
+    }
+
+    impl<T, H> BorshSerializeAsync for HashSet<T, H>
+    where
+        T: BorshSerializeAsync + Ord,
+        H: BuildHasher + Sync,
+    {
+        #[inline]
+        fn serialize_async<'a, W: AsyncWrite + ?Sized>(
+            &'a self,
+            writer: &'a mut W,
+        ) -> BoxFuture<'a, Result<()>> {
+            Box::pin(async move {
+                check_zst::<T>()?;
+
+                let mut vec = self.iter().collect::<Vec<_>>();
+                vec.sort();
+                serialize_len(vec.len(), writer).await?;
+                for item in vec {
+                    item.serialize_async(writer).await?;
+                }
+                Ok(())
+            })
+        }
+    }
+}
+
+impl<K, V> BorshSerializeAsync for BTreeMap<K, V>
+where
+    K: BorshSerializeAsync,
+    V: BorshSerializeAsync,
+{
+    #[inline]
+    fn serialize_async<'a, W: AsyncWrite + ?Sized>(
+        &'a self,
+        writer: &'a mut W,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            check_zst::<K>()?;
+
+            serialize_len(self.len(), writer).await?;
+            for (key, value) in self {
+                key.serialize_async(writer).await?;
+                value.serialize_async(writer).await?;
+            }
+            Ok(())
+        })
+    }
+}
+
+impl<T> BorshSerializeAsync for BTreeSet<T>
+where
+    T: BorshSerializeAsync,
+{
+    #[inline]
+    fn serialize_async<'a, W: AsyncWrite + ?Sized>(
+        &'a self,
+        writer: &'a mut W,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            check_zst::<T>()?;
+
+            serialize_len(self.len(), writer).await?;
+            for item in self {
+                item.serialize_async(writer).await?;
+            }
+            Ok(())
+        })
+    }
+}
+
+impl<T: BorshSerializeAsync + ?Sized> BorshSerializeAsync for Box<T> {
+    fn serialize_async<'a, W: AsyncWrite + ?Sized>(
+        &'a self,
+        writer: &'a mut W,
+    ) -> BoxFuture<'a, Result<()>> {
+        self.as_ref().serialize_async(writer)
+    }
+}
+
+impl<T, const N: usize> BorshSerializeAsync for [T; N]
+where
+    T: BorshSerializeAsync,
+{
+    #[inline]
+    fn serialize_async<'a, W: AsyncWrite + ?Sized>(
+        &'a self,
+        writer: &'a mut W,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(serialize_slice(self, writer))
+    }
+}
+
+macro_rules! impl_tuple {
+    (@unit $name:ty) => {
+        impl BorshSerializeAsync for $name {
+            #[inline]
+            fn serialize_async<'a, W: AsyncWrite + ?Sized>(
+                &'a self,
+                _writer: &'a mut W,
+            ) -> BoxFuture<'a, Result<()>> {
+                Box::pin(async { Ok(()) })
+            }
+        }
+    };
+
+    ($($idx:tt $name:ident)+) => {
+      impl<$($name),+> BorshSerializeAsync for ($($name,)+)
+      where $($name: BorshSerializeAsync,)+
+      {
+        #[inline]
+        fn serialize_async<'a, W: AsyncWrite + ?Sized>(
+            &'a self,
+            writer: &'a mut W,
+        ) -> BoxFuture<'a, Result<()>> {
+            Box::pin(async move {
+                $(self.$idx.serialize_async(writer).await?;)+
+                Ok(())
+            })
+        }
+      }
+    };
+}
+
+impl_tuple!(@unit ());
+impl_tuple!(@unit core::ops::RangeFull);
+
+impl_tuple!(0 T0);
+impl_tuple!(0 T0 1 T1);
+impl_tuple!(0 T0 1 T1 2 T2);
+impl_tuple!(0 T0 1 T1 2 T2 3 T3);
+impl_tuple!(0 T0 1 T1 2 T2 3 T3 4 T4);
+impl_tuple!(0 T0 1 T1 2 T2 3 T3 4 T4 5 T5);
+impl_tuple!(0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6);
+impl_tuple!(0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7);
+impl_tuple!(0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8);
+impl_tuple!(0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9);
+impl_tuple!(0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10);
+impl_tuple!(0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11);
+impl_tuple!(0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11 12 T12);
+impl_tuple!(0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11 12 T12 13 T13);
+impl_tuple!(0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11 12 T12 13 T13 14 T14);
+impl_tuple!(0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11 12 T12 13 T13 14 T14 15 T15);
+impl_tuple!(0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11 12 T12 13 T13 14 T14 15 T15 16 T16);
+impl_tuple!(0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11 12 T12 13 T13 14 T14 15 T15 16 T16 17 T17);
+impl_tuple!(0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11 12 T12 13 T13 14 T14 15 T15 16 T16 17 T17 18 T18);
+impl_tuple!(0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11 12 T12 13 T13 14 T14 15 T15 16 T16 17 T17 18 T18 19 T19);
+
+macro_rules! impl_range {
+    ($type:ident, $this:ident, $($field:expr),*) => {
+        impl<T: BorshSerializeAsync> BorshSerializeAsync for core::ops::$type<T> {
+            #[inline]
+            fn serialize_async<'a, W: AsyncWrite + ?Sized>(
+                &'a self,
+                writer: &'a mut W,
+            ) -> BoxFuture<'a, Result<()>> {
+                Box::pin(async move {
+                    let $this = self;
+                    $( $field.serialize_async(writer).await?; )*
+                    Ok(())
+                })
+            }
+        }
+    };
+}
+
+impl_range!(Range, this, &this.start, &this.end);
+impl_range!(RangeInclusive, this, this.start(), this.end());
+impl_range!(RangeFrom, this, &this.start);
+impl_range!(RangeTo, this, &this.end);
+impl_range!(RangeToInclusive, this, &this.end);
+
+#[cfg(feature = "rc")]
+impl<T: BorshSerializeAsync + Send + ?Sized> BorshSerializeAsync for Arc<T> {
+    fn serialize_async<'a, W: AsyncWrite + ?Sized>(
+        &'a self,
+        writer: &'a mut W,
+    ) -> BoxFuture<'a, Result<()>> {
+        (**self).serialize_async(writer)
+    }
+}
+
+impl<T: Sync + ?Sized> BorshSerializeAsync for PhantomData<T> {
+    fn serialize_async<'a, W: AsyncWrite + ?Sized>(
+        &'a self,
+        _: &'a mut W,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async { Ok(()) })
+    }
+}
@@ -14,8 +14,13 @@ use crate::io::{Error, ErrorKind, Result, Write};
 #[cfg(feature = "rc")]
 use crate::__private::maybestd::{rc::Rc, sync::Arc};
 
+#[cfg(async_io)]
+mod asynchronous;
 pub(crate) mod helpers;
 
+#[cfg(async_io)]
+pub use asynchronous::BorshSerializeAsync;
+
 const FLOAT_NAN_ERR: &str = "For portability reasons we do not allow to serialize NaNs.";
 
 /// A data-structure that can be serialized into binary format by NBOR.
@@ -324,9 +329,9 @@ where
     }
 }
 
-/// Module is available if borsh is built with `features = ["std"]` or `features = ["hashbrown"]`.
 #[cfg(hash_collections)]
 pub mod hashes {
+    //! Module is available if borsh is built with `features = ["std"]` or `features = ["hashbrown"]`.
     //!
     //! Module defines [BorshSerialize](crate::ser::BorshSerialize) implementation for
     //! [HashMap](std::collections::HashMap)/[HashSet](std::collections::HashSet).
@@ -352,7 +357,7 @@ pub mod hashes {
             check_zst::<K>()?;
 
             let mut vec = self.iter().collect::<Vec<_>>();
-            vec.sort_by(|(a, _), (b, _)| a.cmp(b));
+            vec.sort_by_key(|(a, _)| *a);
             u32::try_from(vec.len())
                 .map_err(|_| ErrorKind::InvalidData)?
                 .serialize(writer)?;
@@ -0,0 +1,1190 @@
+//! Borsh format for [serde](::serde)'s data model.
+//!
+//! [`Serializer`] and [`Deserializer`] let types, which implement `serde::Serialize` and
+//! `serde::Deserialize`, be encoded with borsh, byte-compatible with derived
+//! [`BorshSerialize`]/[`BorshDeserialize`] impls of equivalent types:
+//! * primitives, strings and byte buffers are encoded with borsh impls of the corresponding types;
+//! * `Option` is a `u8` tag `0` or `1`, followed by the value if present;
+//! * unit, unit structs and unit variants are encoded as nothing;
+//! * tuples and structs are their fields, concatenated;
+//! * sequences and maps are prefixed with `u32` length, so their length has to be known upfront;
+//! * map entries are sorted by their keys, as borsh impls of `HashMap` do, in the order of
+//!   `Ord` of std types and derived `Ord` impls, which is recovered from the serialized keys;
+//!   elements of sequences are written in iteration order, so e.g. `HashSet` isn't sorted;
+//! * enums are a `u8` tag, the index of the variant, followed by its fields.
+//!
+//! Borsh isn't self-describing, so `deserialize_any` and `deserialize_ignored_any`
+//! are not supported, e.g. `#[serde(flatten)]` or untagged enums can't be deserialized.
+//!
+//! Fields of derived borsh impls can be routed through serde with `#[borsh(serde)]`:
+//!
+//! ```
+//! # #[cfg(feature = "derive")]
+//! # {
+//! use borsh::{BorshDeserialize, BorshSerialize};
+//!
+//! #[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug)]
+//! struct Point {
+//!     x: i32,
+//!     y: i32,
+//! }
+//!
+//! #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+//! struct Shape {
+//!     #[borsh(serde)]
+//!     origin: Point,
+//!     points: Vec<u8>,
+//! }
+//!
+//! let shape = Shape { origin: Point { x: 1, y: -1 }, points: vec![] };
+//! let encoded = borsh::to_vec(&shape).unwrap();
+//! assert_eq!(encoded, borsh::to_vec(&(1i32, -1i32, Vec::<u8>::new())).unwrap());
+//! assert_eq!(borsh::from_slice::<Shape>(&encoded).unwrap(), shape);
+//! # }
+//! ```
+
+use core::convert::TryFrom;
+use core::fmt;
+
+use ::serde::de::{self, DeserializeOwned, DeserializeSeed, IntoDeserializer, Visitor};
+use ::serde::ser::{self, Serialize};
+
+use crate::__private::maybestd::{format, string::String, string::ToString, vec::Vec};
+use crate::de::{DeserializeOptions, Limits};
+use crate::error::{set_offset, ERROR_ZST_FORBIDDEN};
+use crate::io::{self, ErrorKind, Read, Write};
+use crate::{BorshDeserialize, BorshSerialize};
+
+const ERROR_UNKNOWN_LENGTH: &str = "Length of sequences and maps has to be known upfront";
+const ERROR_NOT_SELF_DESCRIBING: &str =
+    "Borsh isn't a self-describing format, the type has to be known to deserialize it";
+
+/// Error of [`Serializer`] and [`Deserializer`], convertible to and from [`io::Error`].
+#[derive(Debug)]
+pub struct Error(io::Error);
+
+impl Error {
+    fn invalid_data<T: Into<String>>(msg: T) -> Self {
+        Error(io::Error::new(ErrorKind::InvalidData, msg.into()))
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl ser::StdError for Error {}
+
+impl ser::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error::invalid_data(msg.to_string())
+    }
+}
+
+impl de::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error::invalid_data(msg.to_string())
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Error(error)
+    }
+}
+
+impl From<Error> for io::Error {
+    fn from(error: Error) -> Self {
+        error.0
+    }
+}
+
+/// Serializes `value` with [`Serializer`]; used by `#[borsh(serde)]` fields of derived impls.
+pub fn serialize<T: Serialize + ?Sized, W: Write>(value: &T, writer: &mut W) -> io::Result<()> {
+    value
+        .serialize(&mut Serializer::new(writer))
+        .map_err(Into::into)
+}
+
+/// Deserializes a value with [`Deserializer`].
+pub fn deserialize<T: DeserializeOwned, R: Read>(reader: &mut R) -> io::Result<T> {
+    T::deserialize(&mut Deserializer::new(reader)).map_err(Into::into)
+}
+
+/// Deserializes a value with [`Deserializer`], enforcing `limits`;
+/// used by `#[borsh(serde)]` fields of derived impls.
+pub fn deserialize_with_limits<T: DeserializeOwned, R: Read>(
+    reader: &mut R,
+    limits: &mut Limits,
+) -> io::Result<T> {
+    let mut deserializer = Deserializer {
+        reader,
+        count: 0,
+        limits: limits.clone(),
+    };
+    let result = T::deserialize(&mut deserializer);
+    *limits = deserializer.limits;
+    result.map_err(Into::into)
+}
+
+/// Serializes `value` with [`Serializer`] into a vector of bytes.
+pub fn to_vec<T: Serialize + ?Sized>(value: &T) -> io::Result<Vec<u8>> {
+    let mut result = Vec::new();
+    serialize(value, &mut result)?;
+    Ok(result)
+}
+
+/// Deserializes a value with [`Deserializer`] from a slice of bytes,
+/// which have to be read completely.
+pub fn from_slice<T: DeserializeOwned>(v: &[u8]) -> io::Result<T> {
+    let mut deserializer = Deserializer::new(v);
+    let object = T::deserialize(&mut deserializer)
+        .map_err(|err| set_offset(err.into(), deserializer.count))?;
+    if !deserializer.reader.is_empty() {
+        return Err(crate::de::not_all_bytes_read(deserializer.count));
+    }
+    Ok(object)
+}
+
+/// Serde serializer, which writes values in borsh format.
+pub struct Serializer<W> {
+    writer: W,
+    count: usize,
+}
+
+impl<W: Write> Serializer<W> {
+    /// Creates a serializer, writing into `writer`.
+    pub fn new(writer: W) -> Self {
+        Self { writer, count: 0 }
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    fn write<T: BorshSerialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let mut writer = Counting {
+            inner: &mut self.writer,
+            count: &mut self.count,
+        };
+        value.serialize(&mut writer).map_err(Error)
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        let mut writer = Counting {
+            inner: &mut self.writer,
+            count: &mut self.count,
+        };
+        writer.write_all(bytes).map_err(Error)
+    }
+
+    fn write_length(&mut self, len: Option<usize>) -> Result<(), Error> {
+        let len = len.ok_or_else(|| Error::invalid_data(ERROR_UNKNOWN_LENGTH))?;
+        let len = u32::try_from(len).map_err(|_| Error(ErrorKind::InvalidData.into()))?;
+        self.write(&len)
+    }
+
+    fn write_tag(&mut self, variant_index: u32) -> Result<(), Error> {
+        let tag = u8::try_from(variant_index).map_err(|_| {
+            Error::invalid_data(format!(
+                "Variant index {} doesn't fit into `u8` tag",
+                variant_index
+            ))
+        })?;
+        self.write(&tag)
+    }
+}
+
+/// Reader or writer adaptor, which counts bytes passed through it.
+struct Counting<'a, T> {
+    inner: T,
+    count: &'a mut usize,
+}
+
+impl<W: Write> Write for Counting<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        *self.count += written;
+        Ok(written)
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.inner.write_all(buf)?;
+        *self.count += buf.len();
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<R: Read> Read for Counting<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        *self.count += read;
+        Ok(read)
+    }
+
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        self.inner.read_exact(buf)?;
+        *self.count += buf.len();
+        Ok(())
+    }
+}
+
+macro_rules! serialize_borsh {
+    ($($method:ident($ty:ty),)*) => {
+        $(
+            fn $method(self, v: $ty) -> Result<(), Error> {
+                self.write(&v)
+            }
+        )*
+    };
+}
+
+impl<'a, W: Write> ser::Serializer for &'a mut Serializer<W> {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Compound<'a, W>;
+    type SerializeTuple = Compound<'a, W>;
+    type SerializeTupleStruct = Compound<'a, W>;
+    type SerializeTupleVariant = Compound<'a, W>;
+    type SerializeMap = Compound<'a, W>;
+    type SerializeStruct = Compound<'a, W>;
+    type SerializeStructVariant = Compound<'a, W>;
+
+    serialize_borsh! {
+        serialize_bool(bool),
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_i128(i128),
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_u128(u128),
+        serialize_f32(f32),
+        serialize_f64(f64),
+        serialize_str(&str),
+        serialize_bytes(&[u8]),
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), Error> {
+        self.write(&u32::from(v))
+    }
+
+    fn serialize_none(self) -> Result<(), Error> {
+        self.write(&0u8)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), Error> {
+        self.write(&1u8)?;
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<(), Error> {
+        self.write_tag(variant_index)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.write_tag(variant_index)?;
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Compound<'a, W>, Error> {
+        self.write_length(len)?;
+        Ok(Compound::new(self, true))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Compound<'a, W>, Error> {
+        Ok(Compound::new(self, false))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Compound<'a, W>, Error> {
+        Ok(Compound::new(self, false))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Compound<'a, W>, Error> {
+        self.write_tag(variant_index)?;
+        Ok(Compound::new(self, false))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Compound<'a, W>, Error> {
+        self.write_length(len)?;
+        Ok(Compound {
+            entries: Some(Vec::new()),
+            ..Compound::new(self, true)
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Compound<'a, W>, Error> {
+        Ok(Compound::new(self, false))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Compound<'a, W>, Error> {
+        self.write_tag(variant_index)?;
+        Ok(Compound::new(self, false))
+    }
+
+    fn collect_str<T: fmt::Display + ?Sized>(self, value: &T) -> Result<(), Error> {
+        self.write(value.to_string().as_str())
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+/// Serializer of fields and elements of tuples, structs, sequences and maps.
+#[doc(hidden)]
+pub struct Compound<'a, W> {
+    ser: &'a mut Serializer<W>,
+    /// Elements of sequences and maps, unlike fields, have to take up some bytes,
+    /// so that their number can't be inflated for free on deserialization.
+    forbid_zero_size: bool,
+    /// Entries of a map, buffered to be written in the order of their keys:
+    /// [`OrderKey`] of the key and the serialized entry.
+    entries: Option<Vec<(Vec<u8>, Vec<u8>)>>,
+}
+
+impl<'a, W: Write> Compound<'a, W> {
+    fn new(ser: &'a mut Serializer<W>, forbid_zero_size: bool) -> Self {
+        Self {
+            ser,
+            forbid_zero_size,
+            entries: None,
+        }
+    }
+
+    fn element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let start = self.ser.count;
+        value.serialize(&mut *self.ser)?;
+        if self.forbid_zero_size && self.ser.count == start {
+            return Err(Error::invalid_data(ERROR_ZST_FORBIDDEN));
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write> ser::SerializeSeq for Compound<'_, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl<W: Write> ser::SerializeTuple for Compound<'_, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl<W: Write> ser::SerializeTupleStruct for Compound<'_, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl<W: Write> ser::SerializeTupleVariant for Compound<'_, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl<W: Write> ser::SerializeMap for Compound<'_, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        let mut order_key = OrderKey(Vec::new());
+        key.serialize(&mut order_key)?;
+        let mut entry = Serializer::new(Vec::new());
+        key.serialize(&mut entry)?;
+        let entries = self.entries.as_mut().expect("only maps serialize keys");
+        entries.push((order_key.0, entry.writer));
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let entries = self.entries.as_mut().expect("only maps serialize values");
+        let (_, entry) = entries
+            .last_mut()
+            .ok_or_else(|| Error::invalid_data("Map value is serialized before its key"))?;
+        let mut entry = Serializer::new(entry);
+        value.serialize(&mut entry)?;
+        if self.forbid_zero_size && entry.writer.is_empty() {
+            return Err(Error::invalid_data(ERROR_ZST_FORBIDDEN));
+        }
+        Ok(())
+    }
+
+    fn end(self) -> Result<(), Error> {
+        let mut entries = self.entries.expect("only maps are ended as maps");
+        entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+        for (_, entry) in entries {
+            self.ser.write_bytes(&entry)?;
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write> ser::SerializeStruct for Compound<'_, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        _key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl<W: Write> ser::SerializeStructVariant for Compound<'_, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        _key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// Serializer of map keys into bytes, which compare in the same order as the keys:
+/// integers are big-endian, with the sign bit flipped for signed ones, strings, bytes,
+/// sequences and maps have every element prefixed with `1` and are terminated with `0`,
+/// and enums are prefixed with their big-endian variant index.
+///
+/// This is the order of `Ord` impls of std types and of derived ones, in which
+/// borsh impls of `HashMap` write their entries.
+struct OrderKey(Vec<u8>);
+
+impl OrderKey {
+    fn variant(&mut self, variant_index: u32) {
+        self.0.extend_from_slice(&variant_index.to_be_bytes());
+    }
+}
+
+macro_rules! order_key_int {
+    ($($method:ident($ty:ty),)*) => {
+        $(
+            fn $method(self, v: $ty) -> Result<(), Error> {
+                self.0.extend_from_slice(&v.to_be_bytes());
+                Ok(())
+            }
+        )*
+    };
+}
+
+macro_rules! order_key_signed_int {
+    ($($method:ident($ty:ty, $unsigned:ty),)*) => {
+        $(
+            fn $method(self, v: $ty) -> Result<(), Error> {
+                let flipped = (v as $unsigned) ^ (1 << (<$unsigned>::BITS - 1));
+                self.0.extend_from_slice(&flipped.to_be_bytes());
+                Ok(())
+            }
+        )*
+    };
+}
+
+impl<'a> ser::Serializer for &'a mut OrderKey {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = OrderKeyCompound<'a>;
+    type SerializeTuple = OrderKeyCompound<'a>;
+    type SerializeTupleStruct = OrderKeyCompound<'a>;
+    type SerializeTupleVariant = OrderKeyCompound<'a>;
+    type SerializeMap = OrderKeyCompound<'a>;
+    type SerializeStruct = OrderKeyCompound<'a>;
+    type SerializeStructVariant = OrderKeyCompound<'a>;
+
+    order_key_int! {
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_u128(u128),
+    }
+
+    order_key_signed_int! {
+        serialize_i8(i8, u8),
+        serialize_i16(i16, u16),
+        serialize_i32(i32, u32),
+        serialize_i64(i64, u64),
+        serialize_i128(i128, u128),
+    }
+
+    fn serialize_bool(self, v: bool) -> Result<(), Error> {
+        self.serialize_u8(u8::from(v))
+    }
+
+    // floats are ordered as by `total_cmp`
+    fn serialize_f32(self, v: f32) -> Result<(), Error> {
+        let bits = v.to_bits() as i32;
+        self.serialize_i32(bits ^ (((bits >> 31) as u32) >> 1) as i32)
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<(), Error> {
+        let bits = v.to_bits() as i64;
+        self.serialize_i64(bits ^ (((bits >> 63) as u64) >> 1) as i64)
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), Error> {
+        self.serialize_u32(u32::from(v))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), Error> {
+        self.serialize_bytes(v.as_bytes())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), Error> {
+        for byte in v {
+            self.0.extend_from_slice(&[1, *byte]);
+        }
+        self.0.push(0);
+        Ok(())
+    }
+
+    fn serialize_none(self) -> Result<(), Error> {
+        self.0.push(0);
+        Ok(())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), Error> {
+        self.0.push(1);
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<(), Error> {
+        self.variant(variant_index);
+        Ok(())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.variant(variant_index);
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<OrderKeyCompound<'a>, Error> {
+        Ok(OrderKeyCompound {
+            key: self,
+            terminated: true,
+        })
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<OrderKeyCompound<'a>, Error> {
+        Ok(OrderKeyCompound {
+            key: self,
+            terminated: false,
+        })
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<OrderKeyCompound<'a>, Error> {
+        self.serialize_tuple(len)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        len: usize,
+    ) -> Result<OrderKeyCompound<'a>, Error> {
+        self.variant(variant_index);
+        self.serialize_tuple(len)
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<OrderKeyCompound<'a>, Error> {
+        self.serialize_seq(len)
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<OrderKeyCompound<'a>, Error> {
+        self.serialize_tuple(len)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        len: usize,
+    ) -> Result<OrderKeyCompound<'a>, Error> {
+        self.variant(variant_index);
+        self.serialize_tuple(len)
+    }
+
+    fn collect_str<T: fmt::Display + ?Sized>(self, value: &T) -> Result<(), Error> {
+        self.serialize_str(&value.to_string())
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+/// Serializer of fields and elements of [`OrderKey`]s.
+struct OrderKeyCompound<'a> {
+    key: &'a mut OrderKey,
+    /// Whether the number of elements may vary, so every element is prefixed with `1`,
+    /// and the end is marked with `0`, to order shorter sequences before longer ones.
+    terminated: bool,
+}
+
+impl OrderKeyCompound<'_> {
+    fn element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        if self.terminated {
+            self.key.0.push(1);
+        }
+        value.serialize(&mut *self.key)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        if self.terminated {
+            self.key.0.push(0);
+        }
+        Ok(())
+    }
+}
+
+impl ser::SerializeSeq for OrderKeyCompound<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        OrderKeyCompound::end(self)
+    }
+}
+
+impl ser::SerializeTuple for OrderKeyCompound<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        OrderKeyCompound::end(self)
+    }
+}
+
+impl ser::SerializeTupleStruct for OrderKeyCompound<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        OrderKeyCompound::end(self)
+    }
+}
+
+impl ser::SerializeTupleVariant for OrderKeyCompound<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        OrderKeyCompound::end(self)
+    }
+}
+
+impl ser::SerializeMap for OrderKeyCompound<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        self.element(key)
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        value.serialize(&mut *self.key)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        OrderKeyCompound::end(self)
+    }
+}
+
+impl ser::SerializeStruct for OrderKeyCompound<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        _key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        OrderKeyCompound::end(self)
+    }
+}
+
+impl ser::SerializeStructVariant for OrderKeyCompound<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        _key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        OrderKeyCompound::end(self)
+    }
+}
+
+/// Serde deserializer, which reads values in borsh format.
+///
+/// Sequences, maps, structs and enums count towards [`DeserializeOptions::max_depth`],
+/// and lengths of sequences and maps are checked against
+/// [`DeserializeOptions::max_collection_len`].
+pub struct Deserializer<R> {
+    reader: R,
+    count: usize,
+    limits: Limits,
+}
+
+impl<R: Read> Deserializer<R> {
+    /// Creates a deserializer, reading from `reader`.
+    pub fn new(reader: R) -> Self {
+        Self::with_options(reader, &DeserializeOptions::new())
+    }
+
+    /// Creates a deserializer, reading from `reader` and enforcing limits from `options`.
+    pub fn with_options(reader: R, options: &DeserializeOptions) -> Self {
+        Self {
+            reader,
+            count: 0,
+            limits: Limits::new(*options),
+        }
+    }
+
+    /// Returns the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    fn read<T: BorshDeserialize>(&mut self) -> Result<T, Error> {
+        let mut reader = Counting {
+            inner: &mut self.reader,
+            count: &mut self.count,
+        };
+        T::deserialize_reader_with_limits(&mut reader, &mut self.limits).map_err(Error)
+    }
+
+    fn read_length(&mut self) -> Result<usize, Error> {
+        let len = self.read::<u32>()?;
+        // sizes of elements are unknown here, so only the length is checked
+        self.limits.reserve_collection::<()>(len).map_err(Error)?;
+        usize::try_from(len).map_err(|_| Error(ErrorKind::InvalidData.into()))
+    }
+
+    /// Runs `f` for a nested value, accounting for its depth.
+    fn nested<T>(&mut self, f: impl FnOnce(&mut Self) -> Result<T, Error>) -> Result<T, Error> {
+        self.limits.enter().map_err(Error)?;
+        let result = f(self)?;
+        self.limits.exit();
+        Ok(result)
+    }
+}
+
+macro_rules! deserialize_borsh {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                visitor.$visit(self.read()?)
+            }
+        )*
+    };
+}
+
+impl<'de, R: Read> de::Deserializer<'de> for &mut Deserializer<R> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Error> {
+        Err(Error::invalid_data(ERROR_NOT_SELF_DESCRIBING))
+    }
+
+    deserialize_borsh! {
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let code = self.read::<u32>()?;
+        let v = char::from_u32(code)
+            .ok_or_else(|| Error::invalid_data(format!("Invalid char code point: {}", code)))?;
+        visitor.visit_char(v)
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_string(self.read::<String>()?)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_string(self.read::<String>()?)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_byte_buf(self.read::<Vec<u8>>()?)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_byte_buf(self.read::<Vec<u8>>()?)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.read::<u8>()? {
+            0 => visitor.visit_none(),
+            1 => visitor.visit_some(self),
+            flag => Err(Error::invalid_data(format!(
+                "Invalid Option representation: {}. The first byte must be 0 or 1",
+                flag
+            ))),
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let len = self.read_length()?;
+        self.nested(|de| visitor.visit_seq(Access::new(de, len, true)))
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_seq(Access::new(self, len, false))
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.nested(|de| visitor.visit_seq(Access::new(de, len, false)))
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let len = self.read_length()?;
+        self.nested(|de| visitor.visit_map(Access::new(de, len, true)))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.nested(|de| visitor.visit_seq(Access::new(de, fields.len(), false)))
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.nested(|de| visitor.visit_enum(de))
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Error> {
+        Err(Error::invalid_data(ERROR_NOT_SELF_DESCRIBING))
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Error> {
+        Err(Error::invalid_data(ERROR_NOT_SELF_DESCRIBING))
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+impl<'de, R: Read> de::EnumAccess<'de> for &mut Deserializer<R> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self), Error> {
+        let tag = self.read::<u8>()?;
+        let variant =
+            seed.deserialize(IntoDeserializer::<Error>::into_deserializer(u32::from(tag)))?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de, R: Read> de::VariantAccess<'de> for &mut Deserializer<R> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_seq(Access::new(self, len, false))
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_seq(Access::new(self, fields.len(), false))
+    }
+}
+
+/// Deserializer of a known number of fields, elements or entries.
+struct Access<'a, R> {
+    de: &'a mut Deserializer<R>,
+    len: usize,
+    /// See [`Compound::forbid_zero_size`].
+    forbid_zero_size: bool,
+}
+
+impl<'a, R: Read> Access<'a, R> {
+    fn new(de: &'a mut Deserializer<R>, len: usize, forbid_zero_size: bool) -> Self {
+        Self {
+            de,
+            len,
+            forbid_zero_size,
+        }
+    }
+
+    fn check_consumed(&self, start: usize) -> Result<(), Error> {
+        if self.forbid_zero_size && self.de.count == start {
+            return Err(Error::invalid_data(ERROR_ZST_FORBIDDEN));
+        }
+        Ok(())
+    }
+}
+
+impl<'de, R: Read> de::SeqAccess<'de> for Access<'_, R> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Error> {
+        if self.len == 0 {
+            return Ok(None);
+        }
+        self.len -= 1;
+        let start = self.de.count;
+        let value = seed.deserialize(&mut *self.de)?;
+        self.check_consumed(start)?;
+        Ok(Some(value))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.len)
+    }
+}
+
+impl<'de, R: Read> de::MapAccess<'de> for Access<'_, R> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        if self.len == 0 {
+            return Ok(None);
+        }
+        self.len -= 1;
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        seed.deserialize(&mut *self.de)
+    }
+
+    fn next_entry_seed<K: DeserializeSeed<'de>, V: DeserializeSeed<'de>>(
+        &mut self,
+        kseed: K,
+        vseed: V,
+    ) -> Result<Option<(K::Value, V::Value)>, Error> {
+        let start = self.de.count;
+        let entry = match self.next_key_seed(kseed)? {
+            Some(key) => (key, self.next_value_seed(vseed)?),
+            None => return Ok(None),
+        };
+        self.check_consumed(start)?;
+        Ok(Some(entry))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.len)
+    }
+}
@@ -0,0 +1,151 @@
+//! Trailing fields, which may be missing from the end of the input.
+//!
+//! Appending a field to a stored struct makes values, serialized before the change, fail
+//! to deserialize with "Unexpected length of input". Fields, annotated with `#[borsh(default)]`
+//! or `#[borsh(default = "path")]` in derived impls, are set to `Default::default()` or to
+//! the result of `path()` instead, if the input ends right before them. Such fields have to
+//! follow all the other (non-skipped) fields of the struct or enum variant:
+//!
+//! ```
+//! # #[cfg(feature = "derive")]
+//! # {
+//! use borsh::{BorshDeserialize, BorshSerialize};
+//!
+//! #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+//! struct Account {
+//!     balance: u64,
+//!     #[borsh(default)]
+//!     nonce: u32,
+//!     #[borsh(default = "default_limit")]
+//!     limit: u64,
+//! }
+//!
+//! fn default_limit() -> u64 {
+//!     1000
+//! }
+//!
+//! // serialized before `nonce` and `limit` were added
+//! let old = borsh::to_vec(&7u64).unwrap();
+//! assert_eq!(
+//!     borsh::from_slice::<Account>(&old).unwrap(),
+//!     Account { balance: 7, nonce: 0, limit: 1000 }
+//! );
+//! # }
+//! ```
+//!
+//! Only the end of the input is detected, so a struct with trailing fields can be evolved
+//! this way only as long as its values are stored last, e.g. as the root of the input.
+//! In schemas such fields are declared as [`Trailing<T>`], which is defined as `Definition::Trailing`
+//! of `T`.
+
+use crate::de::{BorshDeserializeBorrowed, Limits};
+use crate::io::{ErrorKind, Read, Result, Write};
+use crate::{BorshDeserialize, BorshSerialize};
+
+/// Reader, which yields a byte, peeked from `inner`, before the rest of it.
+pub struct PeekedReader<'a, R> {
+    inner: &'a mut R,
+    peeked: Option<u8>,
+}
+
+impl<R: Read> Read for PeekedReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        match (self.peeked, buf.first_mut()) {
+            (Some(byte), Some(first)) => {
+                *first = byte;
+                self.peeked = None;
+                Ok(1)
+            }
+            _ => self.inner.read(buf),
+        }
+    }
+}
+
+/// Reads one byte, or returns `None` at the end of the input.
+fn peek<R: Read>(reader: &mut R) -> Result<Option<u8>> {
+    let mut byte = [0u8];
+    loop {
+        match reader.read(&mut byte) {
+            Ok(0) => return Ok(None),
+            Ok(_) => return Ok(Some(byte[0])),
+            Err(err) if err.kind() == ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// Deserializes a value with `deserialize`, or returns `default()`, if the input has ended;
+/// used by `#[borsh(default)]` fields of derived impls.
+pub fn deserialize_or_else<T, R, D, F>(reader: &mut R, default: D, deserialize: F) -> Result<T>
+where
+    R: Read,
+    D: FnOnce() -> T,
+    F: FnOnce(&mut PeekedReader<'_, R>) -> Result<T>,
+{
+    match peek(reader)? {
+        None => Ok(default()),
+        Some(byte) => deserialize(&mut PeekedReader {
+            inner: reader,
+            peeked: Some(byte),
+        }),
+    }
+}
+
+/// Value, which is either present, or missing at the end of the input.
+///
+/// `None` is serialized as no bytes at all, so it can only be the last value of the input.
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Trailing<T>(pub Option<T>);
+
+impl<T: BorshSerialize> BorshSerialize for Trailing<T> {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        match &self.0 {
+            Some(value) => value.serialize(writer),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<T: BorshDeserialize> BorshDeserialize for Trailing<T> {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        Self::deserialize_reader_with_limits(reader, &mut Limits::unlimited())
+    }
+
+    fn deserialize_reader_with_limits<R: Read>(
+        reader: &mut R,
+        limits: &mut Limits,
+    ) -> Result<Self> {
+        deserialize_or_else(reader, Option::default, |reader| {
+            T::deserialize_reader_with_limits(reader, limits).map(Some)
+        })
+        .map(Self)
+    }
+}
+
+impl<'de, T: BorshDeserializeBorrowed<'de>> BorshDeserializeBorrowed<'de> for Trailing<T> {
+    fn deserialize_borrowed(buf: &mut &'de [u8]) -> Result<Self> {
+        if buf.is_empty() {
+            return Ok(Self(None));
+        }
+        T::deserialize_borrowed(buf).map(|value| Self(Some(value)))
+    }
+}
+
+#[cfg(feature = "unstable__schema")]
+mod schema {
+    use super::Trailing;
+    use crate::__private::maybestd::{collections::BTreeMap, format};
+    use crate::schema::{add_definition, BorshSchema, Declaration, Definition};
+
+    impl<T: BorshSchema> BorshSchema for Trailing<T> {
+        fn add_definitions_recursively(definitions: &mut BTreeMap<Declaration, Definition>) {
+            let definition = Definition::Trailing(T::declaration());
+            add_definition(Self::declaration(), definition, definitions);
+            T::add_definitions_recursively(definitions);
+        }
+
+        fn declaration() -> Declaration {
+            format!("Trailing<{}>", T::declaration())
+        }
+    }
+}
@@ -0,0 +1,42 @@
+//! Helpers of derived impls for unions with `#[borsh(unsafe_union_bytes)]`, which are
+//! (de)serialized as `size_of::<T>()` raw bytes.
+//!
+//! By putting the attribute on a union, its author asserts, that all bytes of
+//! every value of the union are initialized, e.g. that it has no padding, and that
+//! any bit pattern is a valid value of the union.
+
+use core::mem::{size_of, MaybeUninit};
+use core::slice;
+
+use crate::de::unexpected_eof_to_unexpected_length_of_input;
+use crate::io::{Read, Result, Write};
+
+/// Writes raw bytes of `value`.
+///
+/// # Safety
+///
+/// All `size_of::<T>()` bytes of `value` have to be initialized, i.e. `T` may not
+/// have padding bytes or uninitialized fields.
+pub unsafe fn serialize<T: Copy, W: Write + ?Sized>(value: &T, writer: &mut W) -> Result<()> {
+    // SAFETY: `value` is a valid reference to `size_of::<T>()` bytes, which are
+    // initialized as guaranteed by the caller.
+    let bytes = unsafe { slice::from_raw_parts(value as *const T as *const u8, size_of::<T>()) };
+    writer.write_all(bytes)
+}
+
+/// Reads `size_of::<T>()` raw bytes of a value of `T`.
+///
+/// # Safety
+///
+/// Any bit pattern of `size_of::<T>()` bytes has to be a valid value of `T`, e.g.
+/// `T` may not be or contain `bool`, `char`, references or `NonZero*` integers.
+pub unsafe fn deserialize<T: Copy, R: Read + ?Sized>(reader: &mut R) -> Result<T> {
+    let mut value = MaybeUninit::<T>::zeroed();
+    // SAFETY: zeroed memory of `value` is initialized and is exclusively borrowed here.
+    let bytes = unsafe { slice::from_raw_parts_mut(value.as_mut_ptr() as *mut u8, size_of::<T>()) };
+    reader
+        .read_exact(bytes)
+        .map_err(unexpected_eof_to_unexpected_length_of_input)?;
+    // SAFETY: any bit pattern is a valid value of `T`, as guaranteed by the caller.
+    Ok(unsafe { value.assume_init() })
+}
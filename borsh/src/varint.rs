@@ -0,0 +1,201 @@
+//! Variable-length integers, encoded as [LEB128](https://en.wikipedia.org/wiki/LEB128).
+//!
+//! Each byte holds 7 bits of the value, starting from the least significant ones,
+//! and its high bit is set if more bytes follow. Small values thus take a single byte,
+//! while a `u64` takes up to 10 bytes. Signed [`VarI64`] is zigzag-encoded first, so that
+//! values close to zero are short regardless of their sign.
+//!
+//! Only the shortest encoding of a value is accepted by deserialization, so that
+//! every value has exactly one representation, like with the rest of borsh.
+//!
+//! The wrappers can be used as field types directly, or plain integer fields can be
+//! annotated with `#[borsh(varint)]` in derived impls:
+//!
+//! ```
+//! # #[cfg(feature = "derive")]
+//! # {
+//! use borsh::{varint::VarU64, BorshDeserialize, BorshSerialize};
+//!
+//! #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+//! struct Counters {
+//!     #[borsh(varint)]
+//!     hits: u64,
+//!     misses: VarU64,
+//! }
+//!
+//! let counters = Counters { hits: 5, misses: VarU64(300) };
+//! let encoded = borsh::to_vec(&counters).unwrap();
+//! assert_eq!(encoded, [5, 0xac, 0x02]);
+//! assert_eq!(borsh::from_slice::<Counters>(&encoded).unwrap(), counters);
+//! # }
+//! ```
+
+use crate::de::BorshDeserializeBorrowed;
+use crate::io::{Error, ErrorKind, Read, Result, Write};
+use crate::{BorshDeserialize, BorshSerialize};
+
+const ERROR_OVERLONG: &str = "Overlong varint encoding";
+const ERROR_OVERFLOW: &str = "Varint is out of range";
+
+/// Integer types, which can be (de)serialized as varints with `#[borsh(varint)]`.
+pub trait VarInt: Copy {
+    /// Wrapper, which implements the encoding of the type.
+    type Encoded: BorshSerialize + BorshDeserialize + From<Self> + Into<Self>;
+}
+
+/// Serializes `value` as a varint; used by `#[borsh(varint)]` fields of derived impls.
+pub fn serialize<T: VarInt, W: Write>(value: &T, writer: &mut W) -> Result<()> {
+    T::Encoded::from(*value).serialize(writer)
+}
+
+/// Deserializes a varint; used by `#[borsh(varint)]` fields of derived impls.
+pub fn deserialize<T: VarInt, R: Read>(reader: &mut R) -> Result<T> {
+    T::Encoded::deserialize_reader(reader).map(Into::into)
+}
+
+fn write_unsigned<W: Write>(mut value: u64, writer: &mut W) -> Result<()> {
+    // 10 bytes of 7 bits fit any `u64`
+    let mut buf = [0u8; 10];
+    let mut len = 0;
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            buf[len] = byte;
+            len += 1;
+            break;
+        }
+        buf[len] = byte | 0x80;
+        len += 1;
+    }
+    writer.write_all(&buf[..len])
+}
+
+/// Reads a varint, which has to fit into `bits` bits.
+fn read_unsigned<R: Read>(reader: &mut R, bits: u32) -> Result<u64> {
+    let mut value = 0u64;
+    let mut shift = 0;
+    loop {
+        let byte = u8::deserialize_reader(reader)?;
+        let payload = u64::from(byte & 0x7f);
+        if shift + 7 > bits && payload >> (bits - shift) != 0 {
+            return Err(Error::new(ErrorKind::InvalidData, ERROR_OVERFLOW));
+        }
+        value |= payload << shift;
+        if byte & 0x80 == 0 {
+            if byte == 0 && shift > 0 {
+                return Err(Error::new(ErrorKind::InvalidData, ERROR_OVERLONG));
+            }
+            return Ok(value);
+        }
+        shift += 7;
+        if shift >= bits {
+            return Err(Error::new(ErrorKind::InvalidData, ERROR_OVERFLOW));
+        }
+    }
+}
+
+/// `u32`, encoded in 1 to 5 bytes.
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct VarU32(pub u32);
+
+/// `u64`, encoded in 1 to 10 bytes.
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct VarU64(pub u64);
+
+/// `i64`, zigzag-encoded in 1 to 10 bytes: 0, -1, 1, -2, ... are encoded as 0, 1, 2, 3, ...
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct VarI64(pub i64);
+
+impl BorshSerialize for VarU32 {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        write_unsigned(u64::from(self.0), writer)
+    }
+}
+
+impl BorshDeserialize for VarU32 {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        read_unsigned(reader, u32::BITS).map(|value| Self(value as u32))
+    }
+}
+
+impl BorshSerialize for VarU64 {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        write_unsigned(self.0, writer)
+    }
+}
+
+impl BorshDeserialize for VarU64 {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        read_unsigned(reader, u64::BITS).map(Self)
+    }
+}
+
+impl BorshSerialize for VarI64 {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        let zigzag = ((self.0 << 1) ^ (self.0 >> 63)) as u64;
+        write_unsigned(zigzag, writer)
+    }
+}
+
+impl BorshDeserialize for VarI64 {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        let zigzag = read_unsigned(reader, u64::BITS)?;
+        Ok(Self((zigzag >> 1) as i64 ^ -((zigzag & 1) as i64)))
+    }
+}
+
+macro_rules! impl_wrapper {
+    ($wrapper:ident: $ty:ty, $max_len:expr) => {
+        impl From<$ty> for $wrapper {
+            fn from(value: $ty) -> Self {
+                Self(value)
+            }
+        }
+
+        impl From<$wrapper> for $ty {
+            fn from(value: $wrapper) -> Self {
+                value.0
+            }
+        }
+
+        impl VarInt for $ty {
+            type Encoded = $wrapper;
+        }
+
+        impl<'de> BorshDeserializeBorrowed<'de> for $wrapper {
+            fn deserialize_borrowed(buf: &mut &'de [u8]) -> Result<Self> {
+                Self::deserialize(buf)
+            }
+        }
+
+        #[cfg(feature = "unstable__schema")]
+        impl crate::BorshSchema for $wrapper {
+            fn add_definitions_recursively(
+                definitions: &mut crate::__private::maybestd::collections::BTreeMap<
+                    crate::schema::Declaration,
+                    crate::schema::Definition,
+                >,
+            ) {
+                // Length isn't known upfront, so schema containers recognise varints by their
+                // declarations together with this exact definition, and decode them into plain
+                // integers.
+                let definition = crate::schema::Definition::Sequence {
+                    length_width: crate::schema::Definition::ARRAY_LENGTH_WIDTH,
+                    length_range: 1..=$max_len,
+                    elements: <u8 as crate::BorshSchema>::declaration(),
+                };
+                crate::schema::add_definition(Self::declaration(), definition, definitions);
+                <u8 as crate::BorshSchema>::add_definitions_recursively(definitions);
+            }
+
+            fn declaration() -> crate::schema::Declaration {
+                stringify!($wrapper).into()
+            }
+        }
+    };
+}
+
+impl_wrapper!(VarU32: u32, 5);
+impl_wrapper!(VarU64: u64, 10);
+impl_wrapper!(VarI64: i64, 10);
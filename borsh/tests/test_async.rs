@@ -0,0 +1,252 @@
+#![cfg(feature = "tokio")]
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Debug;
+
+use borsh::{
+    de::{DeserializeOptions, Limits},
+    error::DeserializeError,
+    to_vec, BorshDeserialize, BorshDeserializeAsync, BorshSerialize, BorshSerializeAsync,
+};
+
+async fn check_roundtrip<T>(value: &T)
+where
+    T: BorshSerialize + BorshSerializeAsync + BorshDeserializeAsync + PartialEq + Debug,
+{
+    let mut buf = Vec::new();
+    value.serialize_async(&mut buf).await.unwrap();
+    assert_eq!(buf, to_vec(value).unwrap());
+
+    let mut reader = &buf[..];
+    let actual = T::deserialize_async(&mut reader).await.unwrap();
+    assert_eq!(&actual, value);
+    assert!(reader.is_empty());
+}
+
+#[tokio::test]
+async fn test_builtin_roundtrip() {
+    check_roundtrip(&0x1234_5678u32).await;
+    check_roundtrip(&-3i128).await;
+    check_roundtrip(&1.5f64).await;
+    check_roundtrip(&true).await;
+    check_roundtrip(&String::from("hello")).await;
+    check_roundtrip(&vec![1u8, 2, 3]).await;
+    check_roundtrip(&vec![vec![1u64], vec![], vec![2, 3]]).await;
+    check_roundtrip(&[7u8; 33]).await;
+    check_roundtrip(&[String::from("a"), String::from("b")]).await;
+    check_roundtrip(&Some((1u16, String::from("x")))).await;
+    check_roundtrip(&Result::<u8, String>::Err(String::from("err"))).await;
+    check_roundtrip(&Box::new(5u32)).await;
+    check_roundtrip(&(1u8..=9)).await;
+    check_roundtrip(&"127.0.0.1:8080".parse::<std::net::SocketAddr>().unwrap()).await;
+
+    let map: HashMap<String, u32> = [("b", 2), ("a", 1), ("c", 3)]
+        .iter()
+        .map(|(k, v)| (k.to_string(), *v))
+        .collect();
+    check_roundtrip(&map).await;
+    let set: HashSet<u64> = [5, 3, 8, 1].iter().copied().collect();
+    check_roundtrip(&set).await;
+    let btree: BTreeMap<u8, Vec<u8>> = [(2, vec![2]), (1, vec![])].iter().cloned().collect();
+    check_roundtrip(&btree).await;
+}
+
+#[tokio::test]
+async fn test_large_bytes_roundtrip() {
+    let value: Vec<u8> = (0..3 * 1024 * 1024).map(|i| i as u8).collect();
+    check_roundtrip(&value).await;
+}
+
+#[tokio::test]
+async fn test_unexpected_eof() {
+    let buf = to_vec(&String::from("hello")).unwrap();
+    let mut reader = &buf[..buf.len() - 1];
+    let err = String::deserialize_async(&mut reader).await.unwrap_err();
+    assert_eq!(err.to_string(), "Unexpected length of input");
+}
+
+#[tokio::test]
+async fn test_duplex_stream() {
+    let (mut client, mut server) = tokio::io::duplex(16);
+    let values = vec![String::from("one"), "two".repeat(20), String::new()];
+    let expected = values.clone();
+    let writer = tokio::spawn(async move {
+        for value in &values {
+            value.serialize_async(&mut client).await.unwrap();
+        }
+    });
+    for value in &expected {
+        assert_eq!(
+            &String::deserialize_async(&mut server).await.unwrap(),
+            value
+        );
+    }
+    writer.await.unwrap();
+}
+
+#[tokio::test]
+async fn test_limits() {
+    let buf = to_vec(&vec![1u32, 2, 3, 4]).unwrap();
+    let mut limits = Limits::new(DeserializeOptions::new().max_collection_len(3));
+    let err = <Vec<u32>>::deserialize_async_with_limits(&mut &buf[..], &mut limits)
+        .await
+        .unwrap_err();
+    assert_eq!(err.to_string(), "Collection length 4 exceeds limit of 3");
+
+    let nested = to_vec(&vec![vec![vec![1u8]]]).unwrap();
+    let mut limits = Limits::new(DeserializeOptions::new().max_depth(1));
+    let err = <Vec<Vec<Vec<u8>>>>::deserialize_async_with_limits(&mut &nested[..], &mut limits)
+        .await
+        .unwrap_err();
+    assert_eq!(err.to_string(), "Maximum depth of nested values exceeded");
+    let details = DeserializeError::from_io_error(&err).unwrap();
+    assert_eq!(details.path_string(), "[0]");
+}
+
+#[cfg(feature = "futures-io")]
+#[test]
+fn test_futures_io() {
+    use borsh::async_io::FuturesIo;
+
+    futures::executor::block_on(async {
+        let mut writer = FuturesIo(futures::io::Cursor::new(Vec::new()));
+        (1u64, String::from("abc"))
+            .serialize_async(&mut writer)
+            .await
+            .unwrap();
+        let buf = writer.0.into_inner();
+        assert_eq!(buf, to_vec(&(1u64, String::from("abc"))).unwrap());
+
+        let mut reader = FuturesIo(futures::io::Cursor::new(buf));
+        let value = <(u64, String)>::deserialize_async(&mut reader)
+            .await
+            .unwrap();
+        assert_eq!(value, (1, String::from("abc")));
+    });
+}
+
+#[cfg(feature = "derive")]
+mod derived {
+    use super::*;
+
+    #[derive(
+        BorshSerialize,
+        BorshDeserialize,
+        BorshSerializeAsync,
+        BorshDeserializeAsync,
+        PartialEq,
+        Debug,
+        Clone,
+    )]
+    struct Header<T> {
+        id: u32,
+        name: String,
+        #[borsh(skip)]
+        cached: Option<u8>,
+        payload: T,
+    }
+
+    #[derive(
+        BorshSerialize,
+        BorshDeserialize,
+        BorshSerializeAsync,
+        BorshDeserializeAsync,
+        PartialEq,
+        Debug,
+    )]
+    enum Tree {
+        Leaf(u32),
+        Node { children: Vec<Tree>, label: String },
+        Empty,
+    }
+
+    #[derive(
+        BorshSerialize,
+        BorshDeserialize,
+        BorshSerializeAsync,
+        BorshDeserializeAsync,
+        PartialEq,
+        Debug,
+    )]
+    #[borsh(use_discriminant = true)]
+    enum Discriminant {
+        A = 10,
+        B = 20,
+    }
+
+    #[derive(
+        BorshSerialize,
+        BorshDeserialize,
+        BorshSerializeAsync,
+        BorshDeserializeAsync,
+        PartialEq,
+        Debug,
+    )]
+    struct Unit;
+
+    #[tokio::test]
+    async fn test_derived_roundtrip() {
+        check_roundtrip(&Header {
+            id: 7,
+            name: String::from("header"),
+            cached: None,
+            payload: vec![1u16, 2],
+        })
+        .await;
+        check_roundtrip(&Tree::Node {
+            children: vec![
+                Tree::Leaf(1),
+                Tree::Empty,
+                Tree::Node {
+                    children: vec![Tree::Leaf(2)],
+                    label: String::from("inner"),
+                },
+            ],
+            label: String::from("root"),
+        })
+        .await;
+        check_roundtrip(&Discriminant::B).await;
+        check_roundtrip(&Unit).await;
+    }
+
+    #[tokio::test]
+    async fn test_derived_skip_field_is_default() {
+        let value = Header {
+            id: 1,
+            name: String::new(),
+            cached: Some(3),
+            payload: (),
+        };
+        let mut buf = Vec::new();
+        value.serialize_async(&mut buf).await.unwrap();
+        let actual = <Header<()>>::deserialize_async(&mut &buf[..])
+            .await
+            .unwrap();
+        assert_eq!(
+            actual,
+            Header {
+                cached: None,
+                ..value
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_derived_error_path() {
+        let buf = to_vec(&Tree::Node {
+            children: vec![Tree::Leaf(1)],
+            label: String::from("root"),
+        })
+        .unwrap();
+        let err = Tree::deserialize_async(&mut &buf[..buf.len() - 2])
+            .await
+            .unwrap_err();
+        let err = DeserializeError::from_io_error(&err).unwrap();
+        assert_eq!(err.path_string(), "Tree::Node.label");
+
+        let err = Tree::deserialize_async(&mut &[9u8][..]).await.unwrap_err();
+        assert_eq!(err.to_string(), "Unexpected variant tag: 9");
+        let err = DeserializeError::from_io_error(&err).unwrap();
+        assert_eq!(err.path_string(), "Tree");
+    }
+}
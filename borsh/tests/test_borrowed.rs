@@ -0,0 +1,212 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+#[cfg(not(feature = "std"))]
+use alloc::{
+    borrow::Cow,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+#[cfg(feature = "std")]
+use std::borrow::Cow;
+
+use borsh::{from_slice, from_slice_borrowed, to_vec, BorshDeserializeBorrowed};
+
+#[test]
+fn test_borrowed_str_and_bytes() {
+    let buf = to_vec(&(String::from("hello"), vec![1u8, 2, 3])).unwrap();
+    let (s, bytes): (&str, &[u8]) = from_slice_borrowed(&buf).unwrap();
+    assert_eq!(s, "hello");
+    assert_eq!(bytes, &[1, 2, 3]);
+    // both values point into the input buffer
+    assert_eq!(s.as_ptr(), buf[4..].as_ptr());
+    assert_eq!(bytes.as_ptr(), buf[13..].as_ptr());
+}
+
+#[test]
+fn test_borrowed_cow() {
+    let buf = to_vec(&(String::from("hello"), vec![1u8, 2, 3])).unwrap();
+    let (s, bytes): (Cow<str>, Cow<[u8]>) = from_slice_borrowed(&buf).unwrap();
+    assert!(matches!(s, Cow::Borrowed("hello")));
+    assert!(matches!(bytes, Cow::Borrowed(&[1, 2, 3])));
+}
+
+#[test]
+fn test_borrowed_nested() {
+    let value: Vec<Option<String>> = vec![Some("a".into()), None, Some("bc".into())];
+    let buf = to_vec(&value).unwrap();
+    let actual: Vec<Option<&str>> = from_slice_borrowed(&buf).unwrap();
+    assert_eq!(actual, vec![Some("a"), None, Some("bc")]);
+}
+
+#[test]
+fn test_borrowed_array() {
+    let buf = to_vec(&["a".to_string(), "bc".to_string(), "d".to_string()]).unwrap();
+    let actual: [&str; 3] = from_slice_borrowed(&buf).unwrap();
+    assert_eq!(actual, ["a", "bc", "d"]);
+    assert_eq!(actual[1].as_ptr(), buf[9..].as_ptr());
+
+    let err = <[Cow<str>; 3]>::try_from_slice_borrowed(&buf[..12]).unwrap_err();
+    assert_eq!(err.to_string(), "Unexpected length of input");
+}
+
+#[test]
+fn test_borrowed_errors() {
+    let buf = to_vec(&vec![0xffu8, 0xfe]).unwrap();
+    assert_eq!(
+        <&str>::try_from_slice_borrowed(&buf)
+            .unwrap_err()
+            .to_string(),
+        "invalid utf-8 sequence of 1 bytes from index 0"
+    );
+    assert_eq!(
+        <&[u8]>::try_from_slice_borrowed(&buf[..5])
+            .unwrap_err()
+            .to_string(),
+        "Unexpected length of input"
+    );
+    let mut extra = buf.clone();
+    extra.push(0);
+    assert_eq!(
+        <&[u8]>::try_from_slice_borrowed(&extra)
+            .unwrap_err()
+            .to_string(),
+        "Not all bytes read"
+    );
+}
+
+#[cfg(feature = "derive")]
+mod derive {
+    #[cfg(not(feature = "std"))]
+    use alloc::{
+        borrow::Cow,
+        string::{String, ToString},
+        vec,
+        vec::Vec,
+    };
+    #[cfg(feature = "std")]
+    use std::borrow::Cow;
+
+    use borsh::{
+        from_slice, from_slice_borrowed, to_vec, BorshDeserialize, BorshDeserializeBorrowed,
+        BorshSerialize,
+    };
+
+    #[derive(BorshSerialize, BorshDeserialize)]
+    struct Owned {
+        id: u64,
+        name: String,
+        blob: Vec<u8>,
+        tags: Vec<String>,
+        kind: OwnedKind,
+    }
+
+    #[derive(BorshSerialize, BorshDeserialize)]
+    enum OwnedKind {
+        Empty,
+        Named { label: String },
+        Raw(Vec<u8>, u16),
+    }
+
+    #[derive(BorshDeserializeBorrowed, PartialEq, Debug)]
+    struct Borrowed<'a> {
+        id: u64,
+        name: &'a str,
+        blob: Cow<'a, [u8]>,
+        tags: Vec<Cow<'a, str>>,
+        kind: BorrowedKind<'a>,
+    }
+
+    #[derive(BorshDeserializeBorrowed, PartialEq, Debug)]
+    enum BorrowedKind<'a> {
+        Empty,
+        Named { label: &'a str },
+        Raw(&'a [u8], u16),
+    }
+
+    #[derive(BorshDeserializeBorrowed, PartialEq, Debug)]
+    #[borsh(init = init)]
+    struct Generic<'a, T> {
+        value: T,
+        name: &'a str,
+        #[borsh(skip)]
+        len: usize,
+    }
+
+    impl<'a, T> Generic<'a, T> {
+        fn init(&mut self) {
+            self.len = self.name.len();
+        }
+    }
+
+    #[test]
+    fn test_derived_struct() {
+        let owned = Owned {
+            id: 7,
+            name: "account".into(),
+            blob: vec![0xde, 0xad, 0xbe, 0xef],
+            tags: vec!["x".into(), "yz".into()],
+            kind: OwnedKind::Named {
+                label: "label".into(),
+            },
+        };
+        let buf = to_vec(&owned).unwrap();
+        let borrowed = from_slice_borrowed::<Borrowed>(&buf).unwrap();
+        assert_eq!(
+            borrowed,
+            Borrowed {
+                id: 7,
+                name: "account",
+                blob: Cow::Borrowed(&[0xde, 0xad, 0xbe, 0xef]),
+                tags: vec![Cow::Borrowed("x"), Cow::Borrowed("yz")],
+                kind: BorrowedKind::Named { label: "label" },
+            }
+        );
+    }
+
+    #[test]
+    fn test_derived_enum() {
+        let buf = to_vec(&OwnedKind::Raw(vec![1, 2], 3)).unwrap();
+        assert_eq!(
+            BorrowedKind::try_from_slice_borrowed(&buf).unwrap(),
+            BorrowedKind::Raw(&[1, 2], 3)
+        );
+        let buf = to_vec(&OwnedKind::Empty).unwrap();
+        assert_eq!(
+            BorrowedKind::try_from_slice_borrowed(&buf).unwrap(),
+            BorrowedKind::Empty
+        );
+        assert_eq!(
+            BorrowedKind::try_from_slice_borrowed(&[3])
+                .unwrap_err()
+                .to_string(),
+            "Unexpected variant tag: 3"
+        );
+        let _ = from_slice::<OwnedKind>(&buf).unwrap();
+    }
+
+    #[test]
+    fn test_derived_generic_with_init() {
+        let buf = to_vec(&(42u32, String::from("four"))).unwrap();
+        let actual = from_slice_borrowed::<Generic<u32>>(&buf).unwrap();
+        assert_eq!(
+            actual,
+            Generic {
+                value: 42,
+                name: "four",
+                len: 4,
+            }
+        );
+    }
+}
+
+#[test]
+fn test_borrowed_matches_owned() {
+    let buf = to_vec(&(1u8, String::from("same"))).unwrap();
+    let owned: (u8, String) = from_slice(&buf).unwrap();
+    let borrowed: (u8, &str) = from_slice_borrowed(&buf).unwrap();
+    assert_eq!(owned.0, borrowed.0);
+    assert_eq!(owned.1, borrowed.1);
+}
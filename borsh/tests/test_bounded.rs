@@ -0,0 +1,137 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+#[cfg(not(feature = "std"))]
+use alloc::{string::ToString, vec, vec::Vec};
+
+use core::convert::TryFrom;
+
+use borsh::bounded::{BoundedBytes, BoundedString, BoundedVec, LengthOutOfBounds};
+use borsh::de::DeserializeOptions;
+use borsh::{from_slice, from_slice_with_options, to_vec};
+
+#[test]
+fn test_bounded_roundtrip() {
+    let name = BoundedString::<8>::try_from("abc".to_string()).unwrap();
+    let encoded = to_vec(&name).unwrap();
+    assert_eq!(encoded, to_vec(&"abc".to_string()).unwrap());
+    assert_eq!(from_slice::<BoundedString<8>>(&encoded).unwrap(), name);
+    assert_eq!(name.as_str(), "abc");
+
+    let values = BoundedVec::<u16, 1, 3>::try_from(vec![1, 2]).unwrap();
+    let encoded = to_vec(&values).unwrap();
+    assert_eq!(encoded, [2, 0, 0, 0, 1, 0, 2, 0]);
+    assert_eq!(
+        from_slice::<BoundedVec<u16, 1, 3>>(&encoded).unwrap(),
+        values
+    );
+    assert_eq!(values.into_inner(), vec![1, 2]);
+
+    let bytes = BoundedBytes::<4>::try_from(vec![1, 2, 3, 4]).unwrap();
+    let encoded = to_vec(&bytes).unwrap();
+    assert_eq!(encoded, [4, 0, 0, 0, 1, 2, 3, 4]);
+    assert_eq!(from_slice::<BoundedBytes<4>>(&encoded).unwrap(), bytes);
+    assert_eq!(bytes.len(), 4);
+}
+
+#[test]
+fn test_bounded_construction_errors() {
+    assert_eq!(
+        BoundedVec::<u8, 1, 3>::try_from(Vec::new()),
+        Err(LengthOutOfBounds {
+            len: 0,
+            min: 1,
+            max: 3
+        })
+    );
+    let err = BoundedBytes::<2>::try_from(vec![0; 3]).unwrap_err();
+    assert_eq!(err.to_string(), "Length 3 is out of bounds 0..=2");
+    assert!(BoundedString::<2>::try_from("abc".to_string()).is_err());
+}
+
+#[test]
+fn test_bounded_deserialization_errors() {
+    let err = from_slice::<BoundedVec<u8, 1, 3>>(&[0, 0, 0, 0]).unwrap_err();
+    assert_eq!(err.to_string(), "Length 0 is out of bounds 1..=3");
+
+    // the length is rejected before the elements are read
+    let err = from_slice::<BoundedBytes<16>>(&[0xff, 0xff, 0xff, 0xff]).unwrap_err();
+    assert_eq!(err.to_string(), "Length 4294967295 is out of bounds 0..=16");
+
+    let err = from_slice::<BoundedString<4>>(&to_vec("hello").unwrap()).unwrap_err();
+    assert_eq!(err.to_string(), "Length 5 is out of bounds 0..=4");
+
+    let err = from_slice::<BoundedString<4>>(&[1, 0, 0, 0, 0xff]).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "invalid utf-8 sequence of 1 bytes from index 0"
+    );
+
+    let options = DeserializeOptions::new().max_string_len(2);
+    let err =
+        from_slice_with_options::<BoundedString<4>>(&to_vec("abc").unwrap(), &options).unwrap_err();
+    assert_eq!(err.to_string(), "String length 3 exceeds limit of 2");
+
+    let nested: Vec<Vec<u8>> = vec![vec![1], vec![2, 3, 4]];
+    let err =
+        from_slice::<BoundedVec<BoundedBytes<2>, 0, 4>>(&to_vec(&nested).unwrap()).unwrap_err();
+    assert_eq!(err.to_string(), "Length 3 is out of bounds 0..=2");
+}
+
+#[cfg(feature = "unstable__schema")]
+mod schema {
+    #[cfg(not(feature = "std"))]
+    use alloc::{string::ToString, vec::Vec};
+
+    use borsh::bounded::{BoundedBytes, BoundedString, BoundedVec};
+    use borsh::schema::{BorshSchemaContainer, Definition, SchemaMaxSerializedSizeError};
+    use borsh::{max_serialized_size, BorshSchema};
+
+    #[derive(BorshSchema)]
+    #[allow(unused)]
+    struct Message {
+        topic: BoundedString<32>,
+        chunks: BoundedVec<BoundedBytes<16>, 1, 4>,
+    }
+
+    #[test]
+    fn test_bounded_schema() {
+        let container = BorshSchemaContainer::for_type::<Message>();
+        assert_eq!(
+            container
+                .get_definition("BoundedVec<BoundedBytes<16>, 1, 4>")
+                .unwrap(),
+            &Definition::Sequence {
+                length_width: Definition::DEFAULT_LENGTH_WIDTH,
+                length_range: 1..=4,
+                elements: "BoundedBytes<16>".to_string(),
+            }
+        );
+        assert_eq!(
+            container.get_definition("BoundedString<32>").unwrap(),
+            &Definition::Sequence {
+                length_width: Definition::DEFAULT_LENGTH_WIDTH,
+                length_range: 0..=32,
+                elements: "u8".to_string(),
+            }
+        );
+        assert_eq!(container.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_bounded_max_serialized_size() {
+        assert_eq!(
+            max_serialized_size::<Vec<Vec<u8>>>(),
+            Err(SchemaMaxSerializedSizeError::Overflow)
+        );
+        assert_eq!(
+            max_serialized_size::<BoundedVec<BoundedBytes<16>, 0, 4>>(),
+            Ok(4 + 4 * (4 + 16))
+        );
+        assert_eq!(
+            max_serialized_size::<Message>(),
+            Ok(4 + 32 + 4 + 4 * (4 + 16))
+        );
+    }
+}
@@ -235,3 +235,187 @@ fn test_zero_on_nonzero_integer_missing_byte() {
         ERROR_UNEXPECTED_LENGTH_OF_INPUT
     );
 }
+
+#[cfg(feature = "derive")]
+mod path {
+    use borsh::error::{DeserializeError, PathSegment};
+    use borsh::{from_reader, from_slice, to_vec, BorshDeserialize, BorshSerialize};
+
+    #[cfg(not(feature = "std"))]
+    use alloc::{
+        format,
+        string::{String, ToString},
+        vec,
+        vec::Vec,
+    };
+
+    #[derive(BorshSerialize, BorshDeserialize, Debug)]
+    struct Outer {
+        id: u8,
+        items: Vec<Inner>,
+    }
+
+    #[derive(BorshSerialize, BorshDeserialize, Debug)]
+    enum Inner {
+        Unit,
+        Variant { flag: bool, field: Option<u16> },
+        Tuple(u8, String),
+    }
+
+    fn outer() -> Outer {
+        Outer {
+            id: 1,
+            items: vec![
+                Inner::Unit,
+                Inner::Tuple(2, "x".into()),
+                Inner::Variant {
+                    flag: true,
+                    field: None,
+                },
+                Inner::Variant {
+                    flag: false,
+                    field: Some(7),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_error_path_of_nested_field() {
+        let mut bytes = to_vec(&outer()).unwrap();
+        // `Option` tag of `items[3].field`
+        let option_tag = bytes.len() - 3;
+        bytes[option_tag] = 2;
+
+        let err = from_slice::<Outer>(&bytes).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid Option representation: 2. The first byte must be 0 or 1"
+        );
+        assert_eq!(err.kind(), borsh::io::ErrorKind::InvalidData);
+
+        let details = DeserializeError::from_io_error(&err).unwrap();
+        assert_eq!(
+            details.path(),
+            &[
+                PathSegment::Type("Outer"),
+                PathSegment::Field("items"),
+                PathSegment::Index(3),
+                PathSegment::Type("Inner"),
+                PathSegment::Variant("Variant"),
+                PathSegment::Field("field"),
+            ]
+        );
+        assert_eq!(details.path_string(), "Outer.items[3].Inner::Variant.field");
+        assert_eq!(details.offset(), Some(option_tag + 1));
+        assert_eq!(
+            format!("{:#}", err),
+            format!(
+                "Invalid Option representation: 2. The first byte must be 0 or 1 \
+                 (at Outer.items[3].Inner::Variant.field, offset {})",
+                option_tag + 1
+            )
+        );
+    }
+
+    #[test]
+    fn test_error_path_of_unnamed_field_and_tag() {
+        let mut bytes = to_vec(&outer()).unwrap();
+        // length of `items[1].1` string
+        bytes[8] = 0xff;
+        let err = from_slice::<Outer>(&bytes).unwrap_err();
+        let details = DeserializeError::from_io_error(&err).unwrap();
+        assert_eq!(details.path_string(), "Outer.items[1].Inner::Tuple.1");
+
+        let mut bytes = to_vec(&outer()).unwrap();
+        // tag of `items[2]`
+        bytes[13] = 9;
+        let err = from_slice::<Outer>(&bytes).unwrap_err();
+        assert_eq!(err.to_string(), "Unexpected variant tag: 9");
+        let details = DeserializeError::from_io_error(&err).unwrap();
+        assert_eq!(details.path_string(), "Outer.items[2].Inner");
+        assert_eq!(details.offset(), Some(14));
+    }
+
+    #[test]
+    fn test_error_path_from_reader() {
+        let bytes = to_vec(&outer()).unwrap();
+        let truncated: Vec<u8> = bytes[..bytes.len() - 1].to_vec();
+        let err = from_reader::<_, Outer>(&mut truncated.as_slice()).unwrap_err();
+        assert_eq!(err.to_string(), "Unexpected length of input");
+        let details = DeserializeError::from_io_error(&err).unwrap();
+        assert_eq!(details.path_string(), "Outer.items[3].Inner::Variant.field");
+        assert_eq!(details.offset(), Some(truncated.len() - 1));
+    }
+
+    #[test]
+    fn test_error_offset_of_extra_bytes() {
+        let mut bytes = to_vec(&outer()).unwrap();
+        let value_len = bytes.len();
+        bytes.extend_from_slice(&[0, 0]);
+
+        let err = from_slice::<Outer>(&bytes).unwrap_err();
+        assert_eq!(err.to_string(), "Not all bytes read");
+        let details = DeserializeError::from_io_error(&err).unwrap();
+        assert_eq!(details.offset(), Some(value_len));
+        assert!(details.path().is_empty());
+
+        let err = from_reader::<_, Outer>(&mut bytes.as_slice()).unwrap_err();
+        let details = DeserializeError::from_io_error(&err).unwrap();
+        assert_eq!(details.offset(), Some(value_len));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_original_error_is_reachable() {
+        use std::error::Error as _;
+
+        #[derive(Debug, PartialEq)]
+        struct Custom(u8);
+
+        impl std::fmt::Display for Custom {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "custom {}", self.0)
+            }
+        }
+
+        impl std::error::Error for Custom {}
+
+        fn reject<R: borsh::io::Read>(_reader: &mut R) -> borsh::io::Result<u8> {
+            Err(borsh::io::Error::new(
+                borsh::io::ErrorKind::InvalidData,
+                Custom(3),
+            ))
+        }
+
+        #[derive(BorshDeserialize, Debug)]
+        struct WithCustom {
+            #[borsh(deserialize_with = "reject")]
+            _field: u8,
+        }
+
+        let err = from_slice::<WithCustom>(&[0]).unwrap_err();
+        assert_eq!(err.to_string(), "custom 3");
+        let details = DeserializeError::from_io_error(&err).unwrap();
+        assert_eq!(details.path_string(), "WithCustom._field");
+        assert_eq!(
+            details.source().unwrap().downcast_ref::<Custom>(),
+            Some(&Custom(3))
+        );
+        assert_eq!(
+            DeserializeError::original(&err)
+                .get_ref()
+                .unwrap()
+                .downcast_ref::<Custom>(),
+            Some(&Custom(3))
+        );
+        let payload = DeserializeError::into_original(err).into_inner().unwrap();
+        assert_eq!(*payload.downcast::<Custom>().unwrap(), Custom(3));
+
+        let plain = borsh::io::Error::new(borsh::io::ErrorKind::Other, Custom(4));
+        assert!(DeserializeError::into_original(plain)
+            .get_ref()
+            .unwrap()
+            .is::<Custom>());
+    }
+}
@@ -36,7 +36,7 @@ enum XYNoDiscriminant {
 
 #[test]
 fn test_discriminant_serde_no_unit_type() {
-    let values = vec![XY::A, XY::B, XY::C, XY::E, XY::D(12, 14), XY::F(35325423)];
+    let values = [XY::A, XY::B, XY::C, XY::E, XY::D(12, 14), XY::F(35325423)];
     let expected_discriminants = [0u8, 20, 21, 10, 22, 11];
 
     for (ind, value) in values.iter().enumerate() {
@@ -48,7 +48,7 @@ fn test_discriminant_serde_no_unit_type() {
 
 #[test]
 fn test_discriminant_serde_no_unit_type_no_use_discriminant() {
-    let values = vec![
+    let values = [
         XYNoDiscriminant::A,
         XYNoDiscriminant::B,
         XYNoDiscriminant::C,
@@ -112,7 +112,7 @@ enum XNoDiscriminant {
 
 #[test]
 fn test_discriminant_serde_no_use_discriminant() {
-    let values = vec![
+    let values = [
         XNoDiscriminant::A,
         XNoDiscriminant::B,
         XNoDiscriminant::C,
@@ -127,11 +127,13 @@ fn test_discriminant_serde_no_use_discriminant() {
         assert_eq!(from_slice::<XNoDiscriminant>(&data).unwrap(), values[index]);
     }
 }
+#[allow(unused)]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
 struct D {
     x: u64,
 }
 
+#[allow(unused)]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
 enum C {
     C1,
@@ -176,7 +178,7 @@ fn test_deserialize_invalid_discriminant() {
 
 #[test]
 fn test_discriminant_serde() {
-    let values = vec![X::A, X::B, X::C, X::D, X::E, X::F];
+    let values = [X::A, X::B, X::C, X::D, X::E, X::F];
     let expected_discriminants = [0u8, 20, 21, 22, 10, 11];
     for (index, value) in values.iter().enumerate() {
         let data = to_vec(value).unwrap();
@@ -0,0 +1,120 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![cfg(feature = "derive")]
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+#[cfg(not(feature = "std"))]
+use alloc::{string::ToString, vec, vec::Vec};
+
+use borsh::{from_slice, to_vec, BorshDeserialize, BorshSerialize};
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+#[borsh(tag_width = 2, use_discriminant = true)]
+#[repr(u16)]
+enum Opcode {
+    Nop = 0,
+    Push(u64) = 0x100,
+    Call { target: u32 },
+    Halt = 0xffff,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+#[borsh(tag_width = 4)]
+enum Wide {
+    A,
+    B(u8),
+}
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+#[borsh(tag_width = 8)]
+enum Widest {
+    A(u8),
+}
+
+#[test]
+fn test_wide_tags() {
+    assert_eq!(to_vec(&Opcode::Nop).unwrap(), vec![0, 0]);
+    assert_eq!(
+        to_vec(&Opcode::Push(7)).unwrap(),
+        vec![0x00, 0x01, 7, 0, 0, 0, 0, 0, 0, 0]
+    );
+    assert_eq!(
+        to_vec(&Opcode::Call { target: 1 }).unwrap(),
+        vec![0x01, 0x01, 1, 0, 0, 0]
+    );
+    assert_eq!(to_vec(&Opcode::Halt).unwrap(), vec![0xff, 0xff]);
+    assert_eq!(to_vec(&Wide::B(3)).unwrap(), vec![1, 0, 0, 0, 3]);
+    assert_eq!(
+        to_vec(&Widest::A(3)).unwrap(),
+        vec![0, 0, 0, 0, 0, 0, 0, 0, 3]
+    );
+
+    let values = vec![
+        Opcode::Nop,
+        Opcode::Push(u64::MAX),
+        Opcode::Call { target: 9 },
+        Opcode::Halt,
+    ];
+    let encoded = to_vec(&values).unwrap();
+    assert_eq!(from_slice::<Vec<Opcode>>(&encoded).unwrap(), values);
+    assert_eq!(from_slice::<Wide>(&[0, 0, 0, 0]).unwrap(), Wide::A);
+}
+
+#[test]
+fn test_wide_tag_errors() {
+    let err = from_slice::<Opcode>(&[0x00, 0x02]).unwrap_err();
+    assert_eq!(err.to_string(), "Unexpected variant tag: 512");
+    let err = from_slice::<Wide>(&[0, 0, 0]).unwrap_err();
+    assert_eq!(err.to_string(), "Unexpected length of input");
+}
+
+#[test]
+fn test_wide_tag_enum_ext() {
+    use borsh::de::EnumExt;
+
+    assert_eq!(
+        Wide::deserialize_variant(&mut &[3][..], 1).unwrap(),
+        Wide::B(3)
+    );
+    assert_eq!(
+        Opcode::deserialize_variant(&mut &[][..], 0).unwrap(),
+        Opcode::Nop
+    );
+    let err = Opcode::deserialize_variant(&mut &[][..], 1).unwrap_err();
+    assert_eq!(err.to_string(), "Unexpected variant tag: 1");
+}
+
+#[cfg(feature = "unstable__schema")]
+mod schema {
+    #[cfg(not(feature = "std"))]
+    use alloc::{string::ToString, vec::Vec};
+
+    use borsh::schema::{BorshSchemaContainer, Definition};
+
+    #[derive(borsh::BorshSchema)]
+    #[borsh(tag_width = 2, use_discriminant = true)]
+    #[repr(u16)]
+    #[allow(unused)]
+    enum Opcode {
+        Nop = 0,
+        Push(u64) = 0x100,
+        Call { target: u32 },
+    }
+
+    #[test]
+    fn test_tag_width_schema() {
+        let container = BorshSchemaContainer::for_type::<Opcode>();
+        match container.get_definition("Opcode").unwrap() {
+            Definition::Enum {
+                tag_width,
+                variants,
+            } => {
+                assert_eq!(*tag_width, 2);
+                let discriminants: Vec<i64> = variants.iter().map(|(d, _, _)| *d).collect();
+                assert_eq!(discriminants, [0, 0x100, 0x101]);
+            }
+            other => panic!("unexpected definition {:?}", other),
+        }
+        assert_eq!(container.max_serialized_size(), Ok(10));
+    }
+}
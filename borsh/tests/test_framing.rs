@@ -0,0 +1,166 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+#[cfg(not(feature = "std"))]
+use alloc::{
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use borsh::{
+    de::DeserializeOptions,
+    framing::{FramedReader, FramedWriter},
+    io::ErrorKind,
+    to_vec,
+};
+
+#[test]
+fn test_frames_roundtrip() {
+    let mut writer = FramedWriter::new(Vec::new());
+    writer.write(&vec![1u32, 2, 3]).unwrap();
+    writer.write(&Vec::<u32>::new()).unwrap();
+    writer.write(&vec![4u32]).unwrap();
+    let stream = writer.into_inner();
+
+    let mut expected = Vec::new();
+    for value in [vec![1u32, 2, 3], vec![], vec![4]].iter() {
+        let encoded = to_vec(value).unwrap();
+        expected.extend_from_slice(&(encoded.len() as u32).to_le_bytes());
+        expected.extend_from_slice(&encoded);
+    }
+    assert_eq!(stream, expected);
+
+    let mut reader = FramedReader::new(&stream[..]);
+    assert_eq!(reader.read::<Vec<u32>>().unwrap(), Some(vec![1, 2, 3]));
+    assert_eq!(reader.read::<Vec<u32>>().unwrap(), Some(vec![]));
+    assert_eq!(reader.read::<Vec<u32>>().unwrap(), Some(vec![4]));
+    assert_eq!(reader.read::<Vec<u32>>().unwrap(), None);
+}
+
+#[test]
+fn test_truncated_stream() {
+    let mut writer = FramedWriter::new(Vec::new());
+    writer.write("hello").unwrap();
+    let stream = writer.into_inner();
+
+    let err = FramedReader::new(&stream[..2])
+        .read::<String>()
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    let err = FramedReader::new(&stream[..stream.len() - 1])
+        .read::<String>()
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn test_frame_not_consumed_completely() {
+    let mut writer = FramedWriter::new(Vec::new());
+    writer.write(&(1u8, 2u8)).unwrap();
+    let stream = writer.into_inner();
+
+    let err = FramedReader::new(&stream[..]).read::<u8>().unwrap_err();
+    assert_eq!(err.to_string(), "Not all bytes read");
+}
+
+#[test]
+fn test_max_frame_size() {
+    let mut writer = FramedWriter::new(Vec::new()).max_frame_size(8);
+    let err = writer.write(&[0u8; 9]).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Frame of 9 bytes exceeds maximum frame size of 8"
+    );
+    assert!(writer.get_ref().is_empty());
+    writer.write(&[0u8; 8]).unwrap();
+
+    let mut stream = u32::MAX.to_le_bytes().to_vec();
+    stream.extend_from_slice(&[0u8; 16]);
+    let err = FramedReader::new(&stream[..])
+        .max_frame_size(16)
+        .read::<Vec<u8>>()
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Frame of 4294967295 bytes exceeds maximum frame size of 16"
+    );
+}
+
+#[test]
+fn test_deserialize_options() {
+    let mut writer = FramedWriter::new(Vec::new());
+    writer.write(&vec![1u8, 2, 3, 4]).unwrap();
+    let stream = writer.into_inner();
+
+    let err = FramedReader::new(&stream[..])
+        .options(DeserializeOptions::new().max_collection_len(3))
+        .read::<Vec<u8>>()
+        .unwrap_err();
+    assert_eq!(err.to_string(), "Collection length 4 exceeds limit of 3");
+}
+
+#[cfg(feature = "tokio-util")]
+mod codec {
+    use borsh::framing::{BorshCodec, FramedWriter};
+    use tokio_util::bytes::BytesMut;
+    use tokio_util::codec::{Decoder, Encoder};
+
+    #[test]
+    fn test_codec_matches_framed_writer() {
+        let mut codec = BorshCodec::<(u16, String)>::new();
+        let mut buf = BytesMut::new();
+        codec.encode((1, String::from("one")), &mut buf).unwrap();
+        codec.encode(&(2, String::from("two")), &mut buf).unwrap();
+
+        let mut writer = FramedWriter::new(Vec::new());
+        writer.write(&(1u16, "one")).unwrap();
+        writer.write(&(2u16, "two")).unwrap();
+        assert_eq!(&buf[..], &writer.into_inner()[..]);
+
+        assert_eq!(
+            codec.decode(&mut buf).unwrap(),
+            Some((1, String::from("one")))
+        );
+        assert_eq!(
+            codec.decode(&mut buf).unwrap(),
+            Some((2, String::from("two")))
+        );
+        assert_eq!(codec.decode(&mut buf).unwrap(), None);
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn test_codec_partial_frames() {
+        let mut codec = BorshCodec::<Vec<u8>>::new();
+        let mut encoded = BytesMut::new();
+        codec.encode(vec![7u8; 100], &mut encoded).unwrap();
+
+        let mut buf = BytesMut::new();
+        for chunk in encoded.chunks(7) {
+            assert_eq!(codec.decode(&mut buf).unwrap(), None);
+            buf.extend_from_slice(chunk);
+        }
+        assert_eq!(codec.decode(&mut buf).unwrap(), Some(vec![7u8; 100]));
+    }
+
+    #[test]
+    fn test_codec_max_frame_size() {
+        let mut codec = BorshCodec::<Vec<u8>>::new().max_frame_size(10);
+        let mut buf = BytesMut::new();
+        let err = codec.encode(vec![0u8; 7], &mut buf).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Frame of 11 bytes exceeds maximum frame size of 10"
+        );
+        assert!(buf.is_empty());
+
+        buf.extend_from_slice(&11u32.to_le_bytes());
+        let err = codec.decode(&mut buf).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Frame of 11 bytes exceeds maximum frame size of 10"
+        );
+    }
+}
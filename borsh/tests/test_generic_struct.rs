@@ -41,9 +41,11 @@ enum B<F, G> {
     Y(G),
 }
 
+#[allow(unused)]
 #[derive(BorshSerialize, BorshDeserialize, Debug)]
 struct TupleA<T>(T, u32);
 
+#[allow(unused)]
 #[derive(BorshSerialize, BorshDeserialize, Debug)]
 struct NamedA<T> {
     a: T,
@@ -61,6 +63,7 @@ struct C<T: Ord + Hash + Eq, U> {
 
 /// `T: PartialOrd` is injected here via field bound to avoid having this restriction on
 /// the struct itself
+#[allow(unused)]
 #[cfg(hash_collections)]
 #[derive(BorshSerialize)]
 struct C1<T, U> {
@@ -83,30 +86,36 @@ struct C2<T, U> {
 }
 
 /// `T: Ord` bound is required for `BorshDeserialize` derive to be successful
+#[allow(unused)]
 #[derive(BorshSerialize, BorshDeserialize)]
 struct D<T: Ord, U> {
     a: String,
     b: BTreeMap<T, U>,
 }
 
+#[allow(unused)]
 #[cfg(hash_collections)]
 #[derive(BorshSerialize)]
 struct G<K, V, U>(#[borsh(skip)] HashMap<K, V>, U);
 
+#[allow(unused)]
 #[cfg(hash_collections)]
 #[derive(BorshDeserialize)]
 struct G1<K, V, U>(#[borsh(skip)] HashMap<K, V>, U);
 
+#[allow(unused)]
 #[cfg(hash_collections)]
 #[derive(BorshDeserialize)]
 struct G2<K: Ord + Hash + Eq, V, U>(HashMap<K, V>, #[borsh(skip)] U);
 
 /// implicit derived `core::default::Default` bounds on `K` and `V` are dropped by empty bound
 /// specified, as `HashMap` hash its own `Default` implementation
+#[allow(unused)]
 #[cfg(hash_collections)]
 #[derive(BorshDeserialize)]
 struct G3<K, V, U>(#[borsh(skip, bound(deserialize = ""))] HashMap<K, V>, U);
 
+#[allow(unused)]
 #[cfg(hash_collections)]
 #[derive(BorshSerialize, BorshDeserialize)]
 struct H<K: Ord, V, U> {
@@ -117,12 +126,14 @@ struct H<K: Ord, V, U> {
 }
 
 /// `T: Ord` bound is required for `BorshDeserialize` derive to be successful
+#[allow(unused)]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
 enum E<T: Ord, U, G> {
     X { f: BTreeMap<T, U> },
     Y(G),
 }
 
+#[allow(unused)]
 #[cfg(hash_collections)]
 #[derive(BorshSerialize, BorshDeserialize, Debug)]
 enum I1<K, V, U> {
@@ -135,6 +146,7 @@ enum I1<K, V, U> {
     C(K, Vec<U>),
 }
 
+#[allow(unused)]
 #[cfg(hash_collections)]
 #[derive(BorshSerialize, BorshDeserialize, Debug)]
 enum I2<K: Ord + Eq + Hash, V, U> {
@@ -144,6 +156,7 @@ enum I2<K: Ord + Eq + Hash, V, U> {
 
 trait TraitName {
     type Associated;
+    #[allow(unused)]
     fn method(&self);
 }
 
@@ -0,0 +1,206 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+#[cfg(not(feature = "std"))]
+use alloc::{
+    collections::BTreeMap,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+#[cfg(feature = "std")]
+use std::collections::BTreeMap;
+
+use borsh::de::DeserializeOptions;
+use borsh::length_prefix::{self, LengthPrefixed};
+use borsh::{from_slice, from_slice_with_options, to_vec};
+
+#[test]
+fn test_length_prefixed() {
+    let name: LengthPrefixed<u8, String> = LengthPrefixed::new("abc".to_string());
+    assert_eq!(to_vec(&name).unwrap(), [3, b'a', b'b', b'c']);
+
+    let values: LengthPrefixed<u16, Vec<u32>> = vec![1, 2].into();
+    let encoded = to_vec(&values).unwrap();
+    assert_eq!(encoded, [2, 0, 1, 0, 0, 0, 2, 0, 0, 0]);
+    assert_eq!(
+        from_slice::<LengthPrefixed<u16, Vec<u32>>>(&encoded).unwrap(),
+        values
+    );
+
+    let mut map = LengthPrefixed::<u64, BTreeMap<u8, String>>::default();
+    map.insert(1, "x".to_string());
+    let encoded = to_vec(&map).unwrap();
+    assert_eq!(encoded, [1, 0, 0, 0, 0, 0, 0, 0, 1, 1, 0, 0, 0, b'x']);
+    assert_eq!(from_slice::<LengthPrefixed<u64, _>>(&encoded).unwrap(), map);
+
+    // nested collections keep their standard prefixes
+    let nested: LengthPrefixed<u8, Vec<String>> = vec!["y".to_string()].into();
+    let encoded = to_vec(&nested).unwrap();
+    assert_eq!(encoded, [1, 1, 0, 0, 0, b'y']);
+    assert_eq!(
+        from_slice::<LengthPrefixed<u8, _>>(&encoded).unwrap(),
+        nested
+    );
+}
+
+#[test]
+fn test_length_prefix_errors() {
+    let long: LengthPrefixed<u8, Vec<u8>> = vec![0; 256].into();
+    let err = to_vec(&long).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Collection length 256 doesn't fit into 1-byte length prefix"
+    );
+    let mut buf = Vec::new();
+    assert!(length_prefix::serialize::<u8, _, _>(&[0u8; 255][..], &mut buf).is_ok());
+    assert_eq!(buf.len(), 256);
+
+    let err = from_slice::<LengthPrefixed<u64, Vec<u8>>>(&[0, 0, 0, 0, 1, 0, 0, 0]).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Collection length 4294967296 exceeds u32 range"
+    );
+
+    let err = from_slice::<LengthPrefixed<u8, String>>(&[3, b'a']).unwrap_err();
+    assert_eq!(err.to_string(), "Unexpected length of input");
+
+    let options = DeserializeOptions::new().max_string_len(2);
+    let err =
+        from_slice_with_options::<LengthPrefixed<u8, String>>(&[3, b'a', b'b', b'c'], &options)
+            .unwrap_err();
+    assert_eq!(err.to_string(), "String length 3 exceeds limit of 2");
+}
+
+#[cfg(feature = "derive")]
+mod derive {
+    #[cfg(not(feature = "std"))]
+    use alloc::{
+        string::{String, ToString},
+        vec,
+        vec::Vec,
+    };
+
+    use borsh::{
+        from_slice, from_slice_borrowed, to_vec, BorshDeserialize, BorshDeserializeBorrowed,
+        BorshSerialize,
+    };
+
+    /// packet with `u8`-prefixed "Pascal strings"
+    #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+    struct Packet {
+        #[borsh(length_width = 1)]
+        name: String,
+        #[borsh(length = "u16")]
+        payload: Vec<u8>,
+        tail: Vec<u8>,
+    }
+
+    #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+    enum Message {
+        Text(#[borsh(length = "u8")] String),
+    }
+
+    #[derive(BorshSerialize, BorshDeserializeBorrowed, PartialEq, Debug)]
+    struct Record<'a> {
+        key: &'a str,
+        #[borsh(length_width = 1)]
+        tags: Vec<String>,
+    }
+
+    #[test]
+    fn test_length_width_attribute() {
+        let packet = Packet {
+            name: "ab".to_string(),
+            payload: vec![9],
+            tail: vec![],
+        };
+        let encoded = to_vec(&packet).unwrap();
+        assert_eq!(encoded, [2, b'a', b'b', 1, 0, 9, 0, 0, 0, 0]);
+        assert_eq!(from_slice::<Packet>(&encoded).unwrap(), packet);
+
+        let message = Message::Text("hi".to_string());
+        let encoded = to_vec(&message).unwrap();
+        assert_eq!(encoded, [0, 2, b'h', b'i']);
+        assert_eq!(from_slice::<Message>(&encoded).unwrap(), message);
+
+        let record = Record {
+            key: "k",
+            tags: vec!["t".to_string()],
+        };
+        let encoded = to_vec(&record).unwrap();
+        assert_eq!(encoded, [1, 0, 0, 0, b'k', 1, 1, 0, 0, 0, b't']);
+        assert_eq!(from_slice_borrowed::<Record>(&encoded).unwrap(), record);
+    }
+
+    #[test]
+    fn test_length_width_overflow() {
+        let packet = Packet {
+            name: "x".repeat(300),
+            payload: vec![],
+            tail: vec![],
+        };
+        let err = to_vec(&packet).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Collection length 300 doesn't fit into 1-byte length prefix"
+        );
+    }
+}
+
+#[cfg(feature = "unstable__schema")]
+mod schema {
+    #[cfg(not(feature = "std"))]
+    use alloc::{
+        string::{String, ToString},
+        vec,
+        vec::Vec,
+    };
+
+    use borsh::length_prefix::LengthPrefixed;
+    use borsh::schema::{BorshSchemaContainer, Definition, Fields};
+    use borsh::{max_serialized_size, BorshSchema};
+
+    #[derive(BorshSchema)]
+    #[allow(unused)]
+    struct Packet {
+        #[borsh(length_width = 1)]
+        name: String,
+        #[borsh(length = "u16")]
+        payload: Vec<u8>,
+    }
+
+    #[test]
+    fn test_length_width_schema() {
+        let container = BorshSchemaContainer::for_type::<Packet>();
+        assert_eq!(
+            container.get_definition("Packet").unwrap(),
+            &Definition::Struct {
+                fields: Fields::NamedFields(vec![
+                    ("name".to_string(), "LengthPrefixed<u8, String>".to_string()),
+                    (
+                        "payload".to_string(),
+                        "LengthPrefixed<u16, Vec<u8>>".to_string()
+                    ),
+                ]),
+            }
+        );
+        assert_eq!(
+            container
+                .get_definition("LengthPrefixed<u8, String>")
+                .unwrap(),
+            &Definition::Sequence {
+                length_width: 1,
+                length_range: 0..=255,
+                elements: "u8".to_string(),
+            }
+        );
+        assert_eq!(container.validate(), Ok(()));
+        assert_eq!(max_serialized_size::<Packet>(), Ok(1 + 255 + 2 + 65535));
+        assert_eq!(
+            max_serialized_size::<LengthPrefixed<u8, Vec<u16>>>(),
+            Ok(1 + 255 * 2)
+        );
+    }
+}
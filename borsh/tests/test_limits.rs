@@ -0,0 +1,311 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+#[cfg(not(feature = "std"))]
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use borsh::{
+    de::DeserializeOptions, error::DeserializeError, from_reader_with_options, from_slice,
+    from_slice_borrowed_with_options, from_slice_with_options, to_vec,
+};
+
+#[test]
+fn test_unlimited_options_match_from_slice() {
+    let value = vec![vec![String::from("a"), String::from("bc")], vec![]];
+    let encoded = to_vec(&value).unwrap();
+    let actual: Vec<Vec<String>> =
+        from_slice_with_options(&encoded, &DeserializeOptions::new()).unwrap();
+    assert_eq!(actual, from_slice::<Vec<Vec<String>>>(&encoded).unwrap());
+}
+
+#[test]
+fn test_max_collection_len() {
+    let options = DeserializeOptions::new().max_collection_len(3);
+    let encoded = to_vec(&vec![1u16, 2, 3]).unwrap();
+    assert!(from_slice_with_options::<Vec<u16>>(&encoded, &options).is_ok());
+
+    let encoded = to_vec(&vec![vec![0u8; 2], vec![0u8; 4]]).unwrap();
+    let err = from_slice_with_options::<Vec<Vec<u8>>>(&encoded, &options).unwrap_err();
+    assert_eq!(err.to_string(), "Collection length 4 exceeds limit of 3");
+    let details = DeserializeError::from_io_error(&err).unwrap();
+    assert_eq!(details.path_string(), "[1]");
+}
+
+#[test]
+fn test_max_collection_len_is_checked_before_reading_elements() {
+    // declares `u32::MAX` elements, but contains none
+    let encoded = [0xff, 0xff, 0xff, 0xff];
+    let options = DeserializeOptions::new().max_collection_len(1024);
+    let err = from_slice_with_options::<Vec<u64>>(&encoded, &options).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Collection length 4294967295 exceeds limit of 1024"
+    );
+}
+
+#[test]
+fn test_max_string_len() {
+    let options = DeserializeOptions::new().max_string_len(5);
+    let encoded = to_vec(&(String::from("hello"), String::from("world!"))).unwrap();
+    let err = from_slice_with_options::<(String, String)>(&encoded, &options).unwrap_err();
+    assert_eq!(err.to_string(), "String length 6 exceeds limit of 5");
+}
+
+#[test]
+fn test_max_allocation() {
+    let value = vec![vec![0u8; 40], vec![0u8; 40]];
+    let encoded = to_vec(&value).unwrap();
+    // outer vector takes `2 * size_of::<Vec<u8>>()` on its own
+    let outer = 2 * core::mem::size_of::<Vec<u8>>();
+
+    let options = DeserializeOptions::new().max_allocation(outer + 80);
+    assert_eq!(
+        from_slice_with_options::<Vec<Vec<u8>>>(&encoded, &options).unwrap(),
+        value
+    );
+
+    let options = DeserializeOptions::new().max_allocation(outer + 79);
+    let err = from_slice_with_options::<Vec<Vec<u8>>>(&encoded, &options).unwrap_err();
+    assert_eq!(err.to_string(), "Maximum total allocation exceeded");
+    let details = DeserializeError::from_io_error(&err).unwrap();
+    assert_eq!(details.path_string(), "[1]");
+}
+
+#[test]
+fn test_max_depth_of_collections() {
+    let encoded = to_vec(&vec![vec![vec![1u32]]]).unwrap();
+    let options = DeserializeOptions::new().max_depth(2);
+    assert!(from_slice_with_options::<Vec<Vec<Vec<u32>>>>(&encoded, &options).is_err());
+    let options = DeserializeOptions::new().max_depth(3);
+    assert!(from_slice_with_options::<Vec<Vec<Vec<u32>>>>(&encoded, &options).is_ok());
+}
+
+#[test]
+fn test_from_reader_with_options() {
+    let encoded = to_vec(&String::from("too long")).unwrap();
+    let options = DeserializeOptions::new().max_string_len(4);
+    let err = from_reader_with_options::<_, String>(&mut encoded.as_slice(), &options).unwrap_err();
+    assert_eq!(err.to_string(), "String length 8 exceeds limit of 4");
+    let details = DeserializeError::from_io_error(&err).unwrap();
+    assert_eq!(details.offset(), Some(4));
+}
+
+#[test]
+fn test_boxes_count_towards_limits() {
+    let encoded = to_vec(&Some(Box::new(Some(Box::new(7u64))))).unwrap();
+    let options = DeserializeOptions::new().max_depth(1);
+    let err =
+        from_slice_with_options::<Option<Box<Option<Box<u64>>>>>(&encoded, &options).unwrap_err();
+    assert_eq!(err.to_string(), "Maximum depth of nested values exceeded");
+
+    let options = DeserializeOptions::new().max_allocation(8);
+    assert!(from_slice_with_options::<Option<Box<Option<Box<u64>>>>>(&encoded, &options).is_err());
+}
+
+#[test]
+fn test_borrowed_limits() {
+    let value = vec![vec!["a", "bc"], vec!["def"]];
+    let encoded = to_vec(&value).unwrap();
+    let options = DeserializeOptions::new().max_collection_len(2).max_depth(2);
+    assert_eq!(
+        from_slice_borrowed_with_options::<Vec<Vec<&str>>>(&encoded, &options).unwrap(),
+        value
+    );
+
+    let options = DeserializeOptions::new().max_collection_len(1);
+    let err = from_slice_borrowed_with_options::<Vec<Vec<&str>>>(&encoded, &options).unwrap_err();
+    assert_eq!(err.to_string(), "Collection length 2 exceeds limit of 1");
+
+    let options = DeserializeOptions::new().max_depth(1);
+    let err = from_slice_borrowed_with_options::<Vec<Vec<&str>>>(&encoded, &options).unwrap_err();
+    assert_eq!(err.to_string(), "Maximum depth of nested values exceeded");
+    let details = DeserializeError::from_io_error(&err).unwrap();
+    assert_eq!(details.path_string(), "[0]");
+
+    // borrowed slices don't allocate, owned strings do
+    let encoded = to_vec(&(vec![1u8; 8], "abcdefgh", String::from("abcdefgh"))).unwrap();
+    let options = DeserializeOptions::new().max_allocation(16);
+    assert!(from_slice_borrowed_with_options::<(Vec<u8>, &str, String)>(&encoded, &options).is_ok());
+    let options = DeserializeOptions::new().max_allocation(15);
+    let err = from_slice_borrowed_with_options::<(Vec<u8>, &str, String)>(&encoded, &options)
+        .unwrap_err();
+    assert_eq!(err.to_string(), "Maximum total allocation exceeded");
+}
+
+#[cfg(feature = "derive")]
+mod derive {
+    #[cfg(not(feature = "std"))]
+    use alloc::{
+        boxed::Box,
+        string::{String, ToString},
+        vec,
+        vec::Vec,
+    };
+
+    use borsh::{
+        de::DeserializeOptions, error::DeserializeError, from_slice, from_slice_with_options,
+        to_vec, BorshDeserialize, BorshSerialize,
+    };
+
+    #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+    struct Rec(Option<Box<Rec>>);
+
+    #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+    enum Tree {
+        Leaf(u8),
+        Node { children: Vec<Tree> },
+    }
+
+    #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+    struct Message {
+        name: String,
+        tree: Tree,
+    }
+
+    fn nested(depth: usize) -> Rec {
+        let mut rec = Rec(None);
+        for _ in 0..depth {
+            rec = Rec(Some(Box::new(rec)));
+        }
+        rec
+    }
+
+    #[test]
+    fn test_recursive_struct_depth() {
+        let encoded = to_vec(&nested(10)).unwrap();
+        // every level takes a `Rec` and a `Box`
+        let options = DeserializeOptions::new().max_depth(21);
+        assert_eq!(
+            from_slice_with_options::<Rec>(&encoded, &options).unwrap(),
+            nested(10)
+        );
+        let options = DeserializeOptions::new().max_depth(20);
+        let err = from_slice_with_options::<Rec>(&encoded, &options).unwrap_err();
+        assert_eq!(err.to_string(), "Maximum depth of nested values exceeded");
+    }
+
+    #[test]
+    fn test_hostile_recursive_input_does_not_overflow_stack() {
+        // `Some(Box::new(...))` repeated a million times, as would be sent by an attacker
+        let encoded = vec![1u8; 1_000_000];
+        let options = DeserializeOptions::new().max_depth(64);
+        let err = from_slice_with_options::<Rec>(&encoded, &options).unwrap_err();
+        assert_eq!(err.to_string(), "Maximum depth of nested values exceeded");
+        let details = DeserializeError::from_io_error(&err).unwrap();
+        assert_eq!(details.offset(), Some(32));
+    }
+
+    #[test]
+    fn test_derived_enum_limits() {
+        let message = Message {
+            name: "tree".to_string(),
+            tree: Tree::Node {
+                children: vec![
+                    Tree::Leaf(1),
+                    Tree::Node {
+                        children: vec![Tree::Leaf(2), Tree::Leaf(3)],
+                    },
+                ],
+            },
+        };
+        let encoded = to_vec(&message).unwrap();
+        assert_eq!(from_slice::<Message>(&encoded).unwrap(), message);
+
+        let options = DeserializeOptions::new().max_collection_len(2).max_depth(6);
+        assert_eq!(
+            from_slice_with_options::<Message>(&encoded, &options).unwrap(),
+            message
+        );
+
+        let options = DeserializeOptions::new().max_depth(5);
+        let err = from_slice_with_options::<Message>(&encoded, &options).unwrap_err();
+        assert_eq!(err.to_string(), "Maximum depth of nested values exceeded");
+        let details = DeserializeError::from_io_error(&err).unwrap();
+        assert_eq!(
+            details.path_string(),
+            "Message.tree.Tree::Node.children[1].Tree::Node.children[0]"
+        );
+
+        let options = DeserializeOptions::new().max_string_len(3);
+        let err = from_slice_with_options::<Message>(&encoded, &options).unwrap_err();
+        assert_eq!(err.to_string(), "String length 4 exceeds limit of 3");
+        let details = DeserializeError::from_io_error(&err).unwrap();
+        assert_eq!(details.path_string(), "Message.name");
+    }
+
+    fn deserialize_vec<R: borsh::io::Read>(reader: &mut R) -> borsh::io::Result<Vec<u8>> {
+        Vec::<u8>::deserialize_reader(reader)
+    }
+
+    #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+    struct Exempt {
+        #[borsh(deserialize_with = "deserialize_vec")]
+        custom: Vec<u8>,
+        #[borsh(varint)]
+        varint: u64,
+        plain: Vec<u8>,
+    }
+
+    #[test]
+    fn test_deserialize_with_and_varint_fields_are_exempt() {
+        let value = Exempt {
+            custom: vec![1, 2, 3, 4],
+            varint: 1 << 40,
+            plain: vec![5, 6],
+        };
+        let encoded = to_vec(&value).unwrap();
+        // `custom` isn't passed the limits, so only `plain` counts towards them
+        let options = DeserializeOptions::new()
+            .max_collection_len(2)
+            .max_allocation(2);
+        assert_eq!(
+            from_slice_with_options::<Exempt>(&encoded, &options).unwrap(),
+            value
+        );
+
+        let options = DeserializeOptions::new().max_collection_len(1);
+        let err = from_slice_with_options::<Exempt>(&encoded, &options).unwrap_err();
+        let details = DeserializeError::from_io_error(&err).unwrap();
+        assert_eq!(details.path_string(), "Exempt.plain");
+    }
+
+    #[derive(borsh::BorshDeserializeBorrowed, BorshSerialize, PartialEq, Debug)]
+    struct Borrowed<'a> {
+        name: &'a str,
+        children: Vec<Option<Box<Borrowed<'a>>>>,
+    }
+
+    #[test]
+    fn test_derived_borrowed_limits() {
+        let value = Borrowed {
+            name: "root",
+            children: vec![Some(Box::new(Borrowed {
+                name: "leaf",
+                children: vec![None],
+            }))],
+        };
+        let encoded = to_vec(&value).unwrap();
+        assert_eq!(
+            borsh::from_slice_borrowed_with_options::<Borrowed>(
+                &encoded,
+                &DeserializeOptions::new().max_depth(5)
+            )
+            .unwrap(),
+            value
+        );
+        let err = borsh::from_slice_borrowed_with_options::<Borrowed>(
+            &encoded,
+            &DeserializeOptions::new().max_depth(4),
+        )
+        .unwrap_err();
+        assert_eq!(err.to_string(), "Maximum depth of nested values exceeded");
+        let details = DeserializeError::from_io_error(&err).unwrap();
+        assert_eq!(details.path_string(), "Borrowed.children[0].Borrowed.children");
+    }
+}
@@ -18,9 +18,9 @@ macro_rules! test_primitive {
 
 test_primitive!(test_isize_neg, -100isize, isize);
 test_primitive!(test_isize_pos, 100isize, isize);
-test_primitive!(test_isize_min, isize::min_value(), isize);
-test_primitive!(test_isize_max, isize::max_value(), isize);
+test_primitive!(test_isize_min, isize::MIN, isize);
+test_primitive!(test_isize_max, isize::MAX, isize);
 
 test_primitive!(test_usize, 100usize, usize);
-test_primitive!(test_usize_min, usize::min_value(), usize);
-test_primitive!(test_usize_max, usize::max_value(), usize);
+test_primitive!(test_usize_min, usize::MIN, usize);
+test_primitive!(test_usize_max, usize::MAX, usize);
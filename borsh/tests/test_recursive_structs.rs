@@ -16,6 +16,7 @@ extern crate alloc;
 use alloc::{boxed::Box, string::String, vec::Vec};
 
 #[cfg(hash_collections)]
+#[allow(unused)]
 #[derive(BorshSerialize, BorshDeserialize)]
 struct CRec<U: Ord + Hash + Eq> {
     a: String,
@@ -24,6 +25,7 @@ struct CRec<U: Ord + Hash + Eq> {
 
 //  `impl<T, U> BorshDeserialize for Box<T>` pulls in => `ToOwned`
 // => pulls in at least `Clone`
+#[allow(unused)]
 #[derive(Clone, BorshSerialize, BorshDeserialize)]
 struct CRecA {
     a: String,
@@ -37,6 +39,7 @@ struct CRecB {
 }
 
 #[cfg(hash_collections)]
+#[allow(unused)]
 #[derive(BorshSerialize, BorshDeserialize)]
 struct CRecC {
     a: String,
@@ -0,0 +1,160 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![cfg(feature = "unstable__schema")]
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+#[cfg(not(feature = "std"))]
+use alloc::{
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use borsh::schema::*;
+use borsh::{to_vec, BorshSchema, BorshSerialize};
+
+#[derive(BorshSchema, BorshSerialize)]
+struct Point(i16, i16);
+
+#[derive(BorshSchema, BorshSerialize)]
+#[borsh(untagged)]
+#[allow(unused)]
+enum Shape {
+    Dot(Point),
+    Line(Point, Point),
+}
+
+#[derive(BorshSchema, BorshSerialize)]
+struct Drawing {
+    name: String,
+    id: [u8; 2],
+    points: Vec<Point>,
+    flags: (bool, ()),
+    shape: Shape,
+    layer: Option<u32>,
+}
+
+fn span(offset: usize, len: usize, path: &str, declaration: &str, kind: SpanKind) -> Span {
+    Span {
+        offset,
+        len,
+        path: path.to_string(),
+        declaration: declaration.to_string(),
+        kind,
+    }
+}
+
+fn drawing() -> Vec<u8> {
+    to_vec(&Drawing {
+        name: "ab".to_string(),
+        id: [1, 2],
+        points: vec![Point(3, -3)],
+        flags: (true, ()),
+        shape: Shape::Dot(Point(4, 5)),
+        layer: None,
+    })
+    .unwrap()
+}
+
+#[test]
+fn test_annotate_all_definitions() {
+    let schema = BorshSchemaContainer::for_type::<Drawing>();
+    assert_eq!(
+        schema.annotate(&drawing()),
+        vec![
+            span(0, 4, "Drawing.name", "String", SpanKind::Length),
+            span(4, 2, "Drawing.name", "String", SpanKind::Value),
+            span(6, 2, "Drawing.id", "[u8; 2]", SpanKind::Value),
+            span(8, 4, "Drawing.points", "Vec<Point>", SpanKind::Length),
+            span(12, 2, "Drawing.points[0].0", "i16", SpanKind::Value),
+            span(14, 2, "Drawing.points[0].1", "i16", SpanKind::Value),
+            span(16, 1, "Drawing.flags.0", "bool", SpanKind::Value),
+            span(17, 2, "Drawing.shape::Dot.0.0", "i16", SpanKind::Value),
+            span(19, 2, "Drawing.shape::Dot.0.1", "i16", SpanKind::Value),
+            span(21, 1, "Drawing.layer", "Option<u32>", SpanKind::Tag),
+        ]
+    );
+}
+
+#[test]
+fn test_annotate_invalid_and_trailing() {
+    let schema = BorshSchemaContainer::for_type::<Drawing>();
+
+    let mut bytes = drawing();
+    bytes[16] = 2;
+    let spans = schema.annotate(&bytes);
+    assert_eq!(spans.len(), 7);
+    assert_eq!(
+        spans[6],
+        span(
+            16,
+            6,
+            "Drawing.flags.0",
+            "bool",
+            SpanKind::Invalid(SchemaValueError::InvalidData(
+                "bool".to_string(),
+                "bool must be 0 or 1".to_string()
+            ))
+        )
+    );
+
+    let bytes = drawing();
+    let spans = schema.annotate(&bytes[..20]);
+    assert_eq!(
+        spans.last().unwrap(),
+        &span(
+            17,
+            3,
+            "Drawing.shape",
+            "Shape",
+            SpanKind::Invalid(SchemaValueError::InvalidData(
+                "Shape".to_string(),
+                "none of the variants match".to_string()
+            ))
+        )
+    );
+
+    let spans = schema.annotate(&bytes[..21]);
+    assert_eq!(
+        spans.last().unwrap(),
+        &span(
+            21,
+            0,
+            "Drawing.layer",
+            "Option<u32>",
+            SpanKind::Invalid(SchemaValueError::UnexpectedEof("Option<u32>".to_string()))
+        )
+    );
+
+    let mut bytes = drawing();
+    bytes.extend([7, 7]);
+    let spans = schema.annotate(&bytes);
+    assert_eq!(
+        spans.last().unwrap(),
+        &span(22, 2, "", "", SpanKind::Trailing)
+    );
+}
+
+#[test]
+fn test_hexdump() {
+    let schema = BorshSchemaContainer::for_type::<(Vec<u8>, u64)>();
+    let bytes = to_vec(&((0..20).collect::<Vec<u8>>(), 42u64)).unwrap();
+    assert_eq!(
+        schema.hexdump(&bytes[..30]).to_string(),
+        "\
+00000000  14 00 00 00                                      (Vec<u8>, u64).0: Vec<u8> (length) = 20
+00000004  00 01 02 03 04 05 06 07 08 09 0a 0b 0c 0d 0e 0f  (Vec<u8>, u64).0: Vec<u8>
+00000014  10 11 12 13
+00000018  2a 00 00 00 00 00                                (Vec<u8>, u64).1: u64 (invalid: UnexpectedEof(\"u64\"))
+"
+    );
+
+    let schema = BorshSchemaContainer::for_type::<u8>();
+    assert_eq!(
+        schema.hexdump(&[1, 2, 3]).to_string(),
+        "\
+00000000  01                                               u8: u8 = 1
+00000001  02 03                                            (trailing bytes)
+"
+    );
+}
@@ -0,0 +1,237 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![cfg(feature = "unstable__schema")]
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, string::String, string::ToString, vec, vec::Vec};
+
+use borsh::schema::*;
+use borsh::BorshSchema;
+
+fn check<Old: BorshSchema, New: BorshSchema>() -> Vec<Incompatibility> {
+    BorshSchemaContainer::for_type::<New>()
+        .check_compatibility(&BorshSchemaContainer::for_type::<Old>())
+}
+
+#[allow(unused)]
+mod v1 {
+    use super::*;
+
+    #[derive(BorshSchema)]
+    pub struct Account {
+        pub id: u64,
+        pub name: String,
+        pub kind: Kind,
+    }
+
+    #[derive(BorshSchema)]
+    pub enum Kind {
+        Basic,
+        Premium(u8),
+    }
+
+    #[derive(BorshSchema)]
+    pub struct List(pub Option<Box<List>>);
+}
+
+#[allow(unused)]
+mod v2 {
+    use super::*;
+
+    // renamed types are compatible as long as their definitions are
+    #[derive(BorshSchema)]
+    pub struct Customer {
+        pub id: u64,
+        pub name: String,
+        pub kind: Kind,
+    }
+
+    #[derive(BorshSchema)]
+    pub enum Kind {
+        Basic,
+        Premium(u8),
+        Business { seats: u32 },
+    }
+
+    #[derive(BorshSchema)]
+    pub struct List(pub Option<Box<List>>);
+}
+
+#[test]
+fn identical_schemas() {
+    assert_eq!(check::<v1::Account, v1::Account>(), vec![]);
+    assert_eq!(check::<v1::List, v2::List>(), vec![]);
+    assert_eq!(check::<Vec<u8>, Vec<u8>>(), vec![]);
+}
+
+#[test]
+fn appended_variant_is_safe() {
+    let found = check::<v1::Account, v2::Customer>();
+    assert_eq!(
+        found,
+        vec![Incompatibility::VariantAdded {
+            declaration: "Kind".to_string(),
+            variant: "Business".to_string(),
+            discriminant: 2,
+        }]
+    );
+    assert!(!found[0].is_breaking());
+}
+
+#[test]
+fn type_changes() {
+    assert_eq!(
+        check::<(u8, u32), (u8, i32)>(),
+        vec![Incompatibility::TypeChanged {
+            old: "u32".to_string(),
+            new: "i32".to_string(),
+        }]
+    );
+    assert_eq!(
+        check::<(u8, u32), (u8, u32, u32)>(),
+        vec![Incompatibility::ElementCountChanged {
+            declaration: "(u8, u32, u32)".to_string(),
+            old: 2,
+            new: 3,
+        }]
+    );
+    assert_eq!(
+        check::<Vec<u8>, [u8; 4]>(),
+        vec![Incompatibility::LengthWidthChanged {
+            declaration: "[u8; 4]".to_string(),
+            old: 4,
+            new: 0,
+        }]
+    );
+    assert_eq!(
+        check::<[u8; 4], [u8; 5]>(),
+        vec![Incompatibility::LengthRangeChanged {
+            declaration: "[u8; 5]".to_string(),
+            old: 4..=4,
+            new: 5..=5,
+        }]
+    );
+    assert!(check::<u8, Option<u8>>()
+        .iter()
+        .all(Incompatibility::is_breaking));
+}
+
+#[allow(unused)]
+mod fields {
+    use super::*;
+
+    #[derive(BorshSchema)]
+    pub struct Original {
+        pub a: u8,
+        pub b: u16,
+        pub c: u32,
+    }
+
+    #[derive(BorshSchema)]
+    pub struct Reordered {
+        pub b: u16,
+        pub a: u8,
+        pub c: u32,
+    }
+
+    #[derive(BorshSchema)]
+    pub struct Changed {
+        pub a: u8,
+        pub c: u32,
+        pub d: u64,
+    }
+}
+
+#[test]
+fn field_changes() {
+    assert_eq!(
+        check::<fields::Original, fields::Reordered>(),
+        vec![Incompatibility::FieldsReordered("Reordered".to_string())]
+    );
+    assert_eq!(
+        check::<fields::Original, fields::Changed>(),
+        vec![
+            Incompatibility::FieldRemoved {
+                declaration: "Changed".to_string(),
+                field: "b".to_string(),
+            },
+            Incompatibility::FieldAdded {
+                declaration: "Changed".to_string(),
+                field: "d".to_string(),
+            },
+        ]
+    );
+}
+
+#[allow(unused)]
+mod variants {
+    use super::*;
+
+    #[derive(BorshSchema)]
+    pub enum Original {
+        A,
+        B(u8),
+        C,
+    }
+
+    #[derive(BorshSchema)]
+    #[borsh(use_discriminant = true)]
+    #[repr(u8)]
+    pub enum Renumbered {
+        A = 0,
+        B(u8) = 5,
+        Z = 2,
+    }
+
+    #[derive(BorshSchema)]
+    pub enum Removed {
+        A,
+    }
+}
+
+#[test]
+fn variant_changes() {
+    assert_eq!(
+        check::<variants::Original, variants::Renumbered>(),
+        vec![
+            Incompatibility::DiscriminantChanged {
+                declaration: "Renumbered".to_string(),
+                variant: "B".to_string(),
+                old: 1,
+                new: 5,
+            },
+            Incompatibility::VariantRenamed {
+                declaration: "Renumbered".to_string(),
+                discriminant: 2,
+                old: "C".to_string(),
+                new: "Z".to_string(),
+            },
+        ]
+    );
+    assert_eq!(
+        check::<variants::Original, variants::Removed>(),
+        vec![
+            Incompatibility::VariantRemoved {
+                declaration: "Removed".to_string(),
+                variant: "B".to_string(),
+                discriminant: 1,
+            },
+            Incompatibility::VariantRemoved {
+                declaration: "Removed".to_string(),
+                variant: "C".to_string(),
+                discriminant: 2,
+            },
+        ]
+    );
+}
+
+#[test]
+fn missing_definition() {
+    let old = BorshSchemaContainer::for_type::<u8>();
+    let new = BorshSchemaContainer::new("Unknown".to_string(), Default::default());
+    assert_eq!(
+        new.check_compatibility(&old),
+        vec![Incompatibility::MissingDefinition("Unknown".to_string())]
+    );
+}
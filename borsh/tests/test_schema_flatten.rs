@@ -0,0 +1,173 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![cfg(feature = "unstable__schema")]
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+#[cfg(not(feature = "std"))]
+use alloc::{format, string::ToString, vec, vec::Vec};
+
+use borsh::schema::{BorshSchemaContainer, Definition, Fields, SchemaContainerValidateError};
+use borsh::{to_vec, BorshSchema, BorshSerialize};
+
+#[derive(BorshSchema, BorshSerialize)]
+struct Header {
+    id: u32,
+    flags: u8,
+}
+
+#[derive(BorshSchema, BorshSerialize)]
+struct Message {
+    kind: u8,
+    #[borsh(flatten)]
+    header: Header,
+    body: Vec<u8>,
+}
+
+#[derive(BorshSchema)]
+#[allow(unused)]
+struct Nested<T> {
+    #[borsh(flatten)]
+    message: Message,
+    extra: T,
+}
+
+#[derive(BorshSchema)]
+#[allow(unused)]
+enum Envelope {
+    Plain {
+        #[borsh(flatten)]
+        header: Header,
+    },
+    Empty,
+}
+
+fn named(fields: &[(&str, &str)]) -> Definition {
+    Definition::Struct {
+        fields: Fields::NamedFields(
+            fields
+                .iter()
+                .map(|(name, declaration)| (name.to_string(), declaration.to_string()))
+                .collect(),
+        ),
+    }
+}
+
+#[test]
+fn test_flatten_schema() {
+    let container = BorshSchemaContainer::for_type::<Message>();
+    assert_eq!(
+        container.get_definition("Message").unwrap(),
+        &named(&[
+            ("kind", "u8"),
+            ("id", "u32"),
+            ("flags", "u8"),
+            ("body", "Vec<u8>"),
+        ])
+    );
+    assert_eq!(container.validate(), Ok(()));
+
+    // the byte encoding isn't affected
+    let message = Message {
+        kind: 1,
+        header: Header { id: 2, flags: 3 },
+        body: vec![4],
+    };
+    let encoded = to_vec(&message).unwrap();
+    assert_eq!(encoded, [1, 2, 0, 0, 0, 3, 1, 0, 0, 0, 4]);
+    assert!(container.deserialize_value(&encoded).is_ok());
+}
+
+#[test]
+fn test_flatten_nested_and_generic() {
+    let container = BorshSchemaContainer::for_type::<Nested<u16>>();
+    assert_eq!(
+        container.get_definition("Nested<u16>").unwrap(),
+        &named(&[
+            ("kind", "u8"),
+            ("id", "u32"),
+            ("flags", "u8"),
+            ("body", "Vec<u8>"),
+            ("extra", "u16"),
+        ])
+    );
+    assert_eq!(container.validate(), Ok(()));
+
+    let container = BorshSchemaContainer::for_type::<Envelope>();
+    assert_eq!(
+        container.get_definition("EnvelopePlain").unwrap(),
+        &named(&[("id", "u32"), ("flags", "u8")])
+    );
+}
+
+#[derive(BorshSchema)]
+#[allow(unused)]
+struct Clashing {
+    id: u64,
+    #[borsh(flatten)]
+    header: Header,
+}
+
+#[derive(BorshSchema)]
+#[allow(unused)]
+struct Pair(u8, u16);
+
+#[derive(BorshSchema)]
+#[allow(unused)]
+struct NotNamed {
+    #[borsh(flatten)]
+    inner: Pair,
+}
+
+#[test]
+fn test_flatten_name_clash() {
+    let container = BorshSchemaContainer::for_type::<Clashing>();
+    assert_eq!(
+        container.get_definition("Clashing").unwrap(),
+        &named(&[("id", "u64"), ("id", "u32"), ("flags", "u8")])
+    );
+    assert_eq!(
+        container.validate(),
+        Err(SchemaContainerValidateError::DuplicateField(
+            "Clashing".to_string(),
+            "id".to_string()
+        ))
+    );
+}
+
+#[test]
+fn test_flatten_not_named() {
+    // kept as a regular field, as the encoding is the same
+    let container = BorshSchemaContainer::for_type::<NotNamed>();
+    assert_eq!(
+        container.get_definition("NotNamed").unwrap(),
+        &named(&[("inner", "Pair")])
+    );
+    assert!(container.get_definition("Pair").is_some());
+    assert_eq!(container.validate(), Ok(()));
+}
+
+#[derive(BorshSchema)]
+#[allow(unused)]
+struct Reused {
+    #[borsh(flatten)]
+    header: Header,
+    copy: Header,
+}
+
+#[test]
+fn test_flattened_definitions() {
+    let container = BorshSchemaContainer::for_type::<Message>();
+    let declarations: Vec<_> = container
+        .definitions()
+        .map(|(name, _)| name.clone())
+        .collect();
+    assert_eq!(declarations, ["Message", "Vec<u8>", "u32", "u8"]);
+
+    let container = BorshSchemaContainer::for_type::<Reused>();
+    assert_eq!(
+        container.get_definition("Reused").unwrap(),
+        &named(&[("id", "u32"), ("flags", "u8"), ("copy", "Header")])
+    );
+    assert!(container.get_definition("Header").is_some());
+    assert_eq!(container.validate(), Ok(()));
+}
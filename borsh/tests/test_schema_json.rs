@@ -0,0 +1,317 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![cfg(feature = "schema_json")]
+
+#[cfg(feature = "std")]
+use std::collections::BTreeMap;
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+#[cfg(not(feature = "std"))]
+use alloc::{
+    boxed::Box,
+    collections::BTreeMap,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use borsh::schema::*;
+use borsh::{to_vec, BorshSchema, BorshSerialize};
+use serde_json::{json, Value};
+
+#[track_caller]
+fn roundtrip<T: BorshSchema + BorshSerialize>(value: &T, expected: Value) {
+    let schema = BorshSchemaContainer::for_type::<T>();
+    let bytes = to_vec(value).unwrap();
+    let actual = schema.borsh_to_json(&bytes).unwrap();
+    assert_eq!(actual, expected);
+    assert_eq!(schema.json_to_borsh(&actual).unwrap(), bytes);
+}
+
+#[test]
+fn primitives() {
+    roundtrip(&true, json!(true));
+    roundtrip(&255u8, json!(255));
+    roundtrip(&-3i16, json!(-3));
+    roundtrip(&u64::MAX, json!(u64::MAX));
+    roundtrip(&i64::MIN, json!(i64::MIN));
+    roundtrip(&u128::MAX, json!(u128::MAX.to_string()));
+    roundtrip(&i128::MIN, json!(i128::MIN.to_string()));
+    roundtrip(&1.5f32, json!(1.5));
+    roundtrip(&-0.25f64, json!(-0.25));
+    roundtrip(&(), json!(null));
+    roundtrip(&core::num::NonZeroU32::new(5).unwrap(), json!(5));
+    roundtrip(&String::from("hello"), json!("hello"));
+}
+
+#[test]
+fn wide_integers_from_numbers() {
+    let schema = BorshSchemaContainer::for_type::<(u128, i128)>();
+    assert_eq!(
+        schema.json_to_borsh(&json!([1, -1])).unwrap(),
+        to_vec(&(1u128, -1i128)).unwrap()
+    );
+}
+
+#[test]
+fn sequences_and_tuples() {
+    roundtrip(&vec![1u16, 2, 3], json!([1, 2, 3]));
+    roundtrip(&[7u8; 3], json!([7, 7, 7]));
+    roundtrip(&(1u8, String::from("a"), false), json!([1, "a", false]));
+    let mut map = BTreeMap::new();
+    map.insert(String::from("a"), 1u32);
+    map.insert(String::from("b"), 2u32);
+    roundtrip(&map, json!([["a", 1], ["b", 2]]));
+}
+
+#[test]
+fn enums() {
+    roundtrip(&Some(3u8), json!({"Some": 3}));
+    roundtrip(&None::<u8>, json!("None"));
+    roundtrip(&Ok::<u8, String>(1), json!({"Ok": 1}));
+    roundtrip(&Err::<u8, String>("bad".into()), json!({"Err": "bad"}));
+
+    let schema = BorshSchemaContainer::for_type::<Option<u8>>();
+    assert_eq!(
+        schema.json_to_borsh(&json!({"None": null})).unwrap(),
+        to_vec(&None::<u8>).unwrap()
+    );
+}
+
+#[test]
+fn derived_types() {
+    #[derive(BorshSchema, BorshSerialize)]
+    struct Account {
+        id: u64,
+        name: String,
+        balance: u128,
+        kind: Kind,
+        tags: Vec<Tag>,
+    }
+
+    #[derive(BorshSchema, BorshSerialize)]
+    enum Kind {
+        Basic,
+        Premium { level: u8 },
+        Linked(Box<Account>),
+    }
+
+    #[derive(BorshSchema, BorshSerialize)]
+    struct Tag(String, u8);
+
+    let account = Account {
+        id: 1,
+        name: "alice".into(),
+        balance: 10,
+        kind: Kind::Linked(Box::new(Account {
+            id: 2,
+            name: "bob".into(),
+            balance: 0,
+            kind: Kind::Premium { level: 3 },
+            tags: vec![],
+        })),
+        tags: vec![Tag("x".into(), 1)],
+    };
+    roundtrip(
+        &account,
+        json!({
+            "id": 1,
+            "name": "alice",
+            "balance": "10",
+            "kind": {"Linked": [{
+                "id": 2,
+                "name": "bob",
+                "balance": "0",
+                "kind": {"Premium": {"level": 3}},
+                "tags": [],
+            }]},
+            "tags": [["x", 1]],
+        }),
+    );
+    roundtrip(&Kind::Basic, json!("Basic"));
+}
+
+#[test]
+fn length_prefixed_strings() {
+    #[derive(BorshSchema, BorshSerialize)]
+    struct Packet {
+        #[borsh(length = "u8")]
+        name: String,
+        #[borsh(length_width = 2)]
+        labels: Vec<String>,
+    }
+
+    roundtrip(
+        &Packet {
+            name: "ab".into(),
+            labels: vec!["x".into()],
+        },
+        json!({"name": "ab", "labels": ["x"]}),
+    );
+}
+
+fn custom_schema(definition: Definition) -> BorshSchemaContainer {
+    let mut definitions = BTreeMap::new();
+    definitions.insert("Custom".to_string(), definition);
+    definitions.insert("u8".to_string(), Definition::Primitive(1));
+    definitions.insert("u16".to_string(), Definition::Primitive(2));
+    definitions.insert("()".to_string(), Definition::Primitive(0));
+    BorshSchemaContainer::new("Custom".to_string(), definitions)
+}
+
+#[test]
+fn custom_length_width() {
+    let schema = custom_schema(Definition::Sequence {
+        length_width: 1,
+        length_range: 0..=3,
+        elements: "u16".to_string(),
+    });
+    assert_eq!(
+        schema.borsh_to_json(&[2, 1, 0, 2, 0]).unwrap(),
+        json!([1, 2])
+    );
+    assert_eq!(
+        schema.json_to_borsh(&json!([1, 2])).unwrap(),
+        vec![2, 1, 0, 2, 0]
+    );
+    assert!(matches!(
+        schema.borsh_to_json(&[4, 0, 0, 0, 0, 0, 0, 0, 0]),
+        Err(SchemaJsonError::InvalidData(..))
+    ));
+    assert!(matches!(
+        schema.json_to_borsh(&json!([1, 2, 3, 4])),
+        Err(SchemaJsonError::InvalidValue(..))
+    ));
+}
+
+#[test]
+fn untagged_sequence_of_unknown_length() {
+    let schema = custom_schema(Definition::Sequence {
+        length_width: 0,
+        length_range: 1..=5,
+        elements: "u8".to_string(),
+    });
+    assert_eq!(
+        schema.borsh_to_json(&[1]),
+        Err(SchemaJsonError::UnknownLength("Custom".to_string()))
+    );
+}
+
+#[test]
+fn wide_and_untagged_enums() {
+    let schema = custom_schema(Definition::Enum {
+        tag_width: 2,
+        variants: vec![
+            (0, "Nothing".to_string(), "()".to_string()),
+            (300, "Byte".to_string(), "u8".to_string()),
+        ],
+    });
+    assert_eq!(
+        schema.borsh_to_json(&[44, 1, 7]).unwrap(),
+        json!({"Byte": 7})
+    );
+    assert_eq!(
+        schema.json_to_borsh(&json!({"Byte": 7})).unwrap(),
+        vec![44, 1, 7]
+    );
+
+    let schema = custom_schema(Definition::Enum {
+        tag_width: 0,
+        variants: vec![
+            (0, "Short".to_string(), "u8".to_string()),
+            (1, "Long".to_string(), "u16".to_string()),
+        ],
+    });
+    assert_eq!(schema.borsh_to_json(&[7]).unwrap(), json!({"Short": 7}));
+    assert_eq!(
+        schema.json_to_borsh(&json!({"Long": 258})).unwrap(),
+        vec![2, 1]
+    );
+}
+
+#[test]
+fn decode_errors() {
+    let schema = BorshSchemaContainer::for_type::<(bool, u32)>();
+    assert_eq!(
+        schema.borsh_to_json(&[1, 0, 0]),
+        Err(SchemaJsonError::UnexpectedEof("u32".to_string()))
+    );
+    assert_eq!(
+        schema.borsh_to_json(&[1, 0, 0, 0, 0, 9]),
+        Err(SchemaJsonError::TrailingBytes(1))
+    );
+    assert!(matches!(
+        schema.borsh_to_json(&[2, 0, 0, 0, 0]),
+        Err(SchemaJsonError::InvalidData(declaration, _)) if declaration == "bool"
+    ));
+
+    let schema = BorshSchemaContainer::for_type::<Option<u8>>();
+    assert!(matches!(
+        schema.borsh_to_json(&[2, 0]),
+        Err(SchemaJsonError::InvalidData(..))
+    ));
+
+    let schema = BorshSchemaContainer::for_type::<String>();
+    assert!(matches!(
+        schema.borsh_to_json(&[2, 0, 0, 0, 0xff, 0xfe]),
+        Err(SchemaJsonError::InvalidData(..))
+    ));
+}
+
+#[test]
+fn encode_errors() {
+    #[allow(unused)]
+    #[derive(BorshSchema)]
+    struct Point {
+        x: i8,
+        y: i8,
+    }
+
+    let schema = BorshSchemaContainer::for_type::<Point>();
+    assert_eq!(
+        schema.json_to_borsh(&json!({"x": 1, "y": -1})).unwrap(),
+        vec![1, 255]
+    );
+    for invalid in [
+        json!({"x": 1}),
+        json!({"x": 1, "y": 2, "z": 3}),
+        json!({"x": 1, "y": 200}),
+        json!([1, 2]),
+    ] {
+        assert!(matches!(
+            schema.json_to_borsh(&invalid),
+            Err(SchemaJsonError::InvalidValue(..))
+        ));
+    }
+
+    let schema = BorshSchemaContainer::for_type::<Option<u8>>();
+    assert!(matches!(
+        schema.json_to_borsh(&json!("Maybe")),
+        Err(SchemaJsonError::InvalidValue(..))
+    ));
+}
+
+#[test]
+fn recursive_schema_is_bounded() {
+    let mut definitions = BTreeMap::new();
+    definitions.insert(
+        "Rec".to_string(),
+        Definition::Struct {
+            fields: Fields::UnnamedFields(vec!["Rec".to_string()]),
+        },
+    );
+    let schema = BorshSchemaContainer::new("Rec".to_string(), definitions);
+    assert_eq!(
+        schema.borsh_to_json(&[]),
+        Err(SchemaJsonError::TooDeep("Rec".to_string()))
+    );
+}
+
+#[test]
+fn missing_definition() {
+    let schema = BorshSchemaContainer::new("Unknown".to_string(), BTreeMap::new());
+    assert_eq!(
+        schema.borsh_to_json(&[]),
+        Err(SchemaJsonError::MissingDefinition("Unknown".to_string()))
+    );
+}
@@ -0,0 +1,186 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![cfg(feature = "unstable__schema")]
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+#[cfg(not(feature = "std"))]
+use alloc::{
+    borrow::Cow,
+    boxed::Box,
+    collections::{BTreeMap, BTreeSet, LinkedList, VecDeque},
+    string::{String, ToString},
+    vec,
+};
+#[cfg(feature = "std")]
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet, LinkedList, VecDeque},
+};
+
+use core::marker::PhantomData;
+use core::num::{NonZeroI32, NonZeroU8, NonZeroUsize};
+
+use borsh::schema::{BorshSchemaContainer, Definition, Fields, SpanKind};
+use borsh::{to_vec, BorshDeserialize, BorshSchema, BorshSerialize};
+
+const MAX_LEN: usize = u32::MAX as usize;
+
+/// Every type with both `BorshSerialize` and `BorshDeserialize` impls has to implement
+/// `BorshSchema`, which describes its encoding: `value` is checked to be decoded by the schema
+/// without errors or trailing bytes, and the type to have `max_size` as its maximum size.
+#[track_caller]
+fn check<T: BorshSerialize + BorshDeserialize + BorshSchema>(value: T, max_size: usize) {
+    let schema = BorshSchemaContainer::for_type::<T>();
+    assert_eq!(schema.validate(), Ok(()), "{}", T::declaration());
+    assert_eq!(
+        schema.max_serialized_size(),
+        Ok(max_size),
+        "{}",
+        T::declaration()
+    );
+    let bytes = to_vec(&value).unwrap();
+    assert!(bytes.len() <= max_size, "{}", T::declaration());
+    let spans = schema.annotate(&bytes);
+    for span in spans {
+        assert!(
+            !matches!(span.kind, SpanKind::Invalid(_) | SpanKind::Trailing),
+            "{}: {:?}",
+            T::declaration(),
+            span
+        );
+    }
+}
+
+#[test]
+fn test_core_types() {
+    check(true, 1);
+    check(1u8, 1);
+    check(-1i16, 2);
+    check(1u32, 4);
+    check(-1i64, 8);
+    check(1u128, 16);
+    check(-1i128, 16);
+    check(1usize, 8);
+    check(-1isize, 8);
+    check(0.5f32, 4);
+    check(0.5f64, 8);
+    check(NonZeroU8::new(1).unwrap(), 1);
+    check(NonZeroI32::new(-1).unwrap(), 4);
+    check(NonZeroUsize::new(1).unwrap(), 8);
+    check((), 0);
+    check((1u8, 2u16, 3u32), 7);
+    check([1u8, 2, 3], 3);
+    check(Some(1u8), 2);
+    check(Ok::<u8, String>(1), 1 + 4 + MAX_LEN);
+    check(Err::<u8, String>("error".to_string()), 1 + 4 + MAX_LEN);
+    check(PhantomData::<u64>, 0);
+    check(.., 0);
+    check(1u8..2, 2);
+    check(1u8..=2, 2);
+    check(1u8.., 1);
+    check(..2u8, 1);
+    check(..=2u8, 1);
+}
+
+#[test]
+fn test_alloc_types() {
+    check("string".to_string(), 4 + MAX_LEN);
+    check(Box::new(1u16), 2);
+    check(vec![1u8, 2], 4 + MAX_LEN);
+    check(Cow::<str>::Borrowed("cow"), 4 + MAX_LEN);
+    check(Cow::<[u16]>::Owned(vec![1, 2]), 4 + 2 * MAX_LEN);
+    check(VecDeque::from(vec![1u32, 2]), 4 + 4 * MAX_LEN);
+    check(
+        vec![1u64, 2].into_iter().collect::<LinkedList<_>>(),
+        4 + 8 * MAX_LEN,
+    );
+    check(
+        vec![1u8, 2].into_iter().collect::<BTreeSet<_>>(),
+        4 + MAX_LEN,
+    );
+    check(
+        vec![(1u8, 2u32)].into_iter().collect::<BTreeMap<_, _>>(),
+        4 + 5 * MAX_LEN,
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_std_types() {
+    use std::collections::{HashMap, HashSet};
+    use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+
+    check(
+        vec![1u8, 2].into_iter().collect::<HashSet<_>>(),
+        4 + MAX_LEN,
+    );
+    check(
+        vec![(1u8, 2u8)].into_iter().collect::<HashMap<_, _>>(),
+        4 + 2 * MAX_LEN,
+    );
+
+    let v4 = SocketAddrV4::new(Ipv4Addr::new(127, 0, 0, 1), 80);
+    let v6 = SocketAddrV6::new(Ipv6Addr::LOCALHOST, 443, 0, 0);
+    check(Ipv4Addr::new(127, 0, 0, 1), 4);
+    check(Ipv6Addr::LOCALHOST, 16);
+    check(v4, 6);
+    check(v6, 18);
+    check(SocketAddr::V4(v4), 19);
+    check(SocketAddr::V6(v6), 19);
+}
+
+#[cfg(feature = "rc")]
+#[test]
+fn test_rc_types() {
+    #[cfg(not(feature = "std"))]
+    use alloc::{rc::Rc, sync::Arc};
+    #[cfg(feature = "std")]
+    use std::{rc::Rc, sync::Arc};
+
+    check(Rc::new(1u8), 1);
+    check(Arc::new("arc".to_string()), 4 + MAX_LEN);
+}
+
+#[test]
+fn test_third_party_types() {
+    check(bytes::Bytes::from_static(b"bytes"), 4 + MAX_LEN);
+    check(bytes::BytesMut::from(&b"bytes"[..]), 4 + MAX_LEN);
+    check(bson::oid::ObjectId::from_bytes([7; 12]), 12);
+}
+
+#[derive(BorshSchema, BorshSerialize, BorshDeserialize)]
+struct Collections<'a> {
+    queue: VecDeque<u8>,
+    list: LinkedList<u16>,
+    name: Cow<'a, str>,
+    payload: bytes::Bytes,
+    id: bson::oid::ObjectId,
+}
+
+#[test]
+fn test_derive() {
+    let container = BorshSchemaContainer::for_type::<Collections>();
+    assert_eq!(container.validate(), Ok(()));
+    assert_eq!(
+        container.get_definition("Collections").unwrap(),
+        &Definition::Struct {
+            fields: Fields::NamedFields(vec![
+                ("queue".to_string(), "VecDeque<u8>".to_string()),
+                ("list".to_string(), "LinkedList<u16>".to_string()),
+                ("name".to_string(), "String".to_string()),
+                ("payload".to_string(), "Vec<u8>".to_string()),
+                ("id".to_string(), "ObjectId".to_string()),
+            ]),
+        }
+    );
+    check(
+        Collections {
+            queue: VecDeque::from(vec![1]),
+            list: LinkedList::new(),
+            name: Cow::Borrowed("name"),
+            payload: bytes::Bytes::from_static(b"payload"),
+            id: bson::oid::ObjectId::from_bytes([1; 12]),
+        },
+        (4 + MAX_LEN) + (4 + 2 * MAX_LEN) + (4 + MAX_LEN) + (4 + MAX_LEN) + 12,
+    );
+}
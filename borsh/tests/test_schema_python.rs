@@ -0,0 +1,195 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![cfg(feature = "unstable__schema")]
+
+#[cfg(feature = "std")]
+use std::collections::{BTreeMap, BTreeSet};
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+#[cfg(not(feature = "std"))]
+use alloc::{
+    boxed::Box,
+    collections::{BTreeMap, BTreeSet},
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use borsh::schema::*;
+use borsh::BorshSchema;
+
+/// Generated code without the runtime, which is the same for every schema.
+fn generated(schema: &BorshSchemaContainer) -> String {
+    let code = schema.generate_python().unwrap();
+    let runtime_end =
+        "raise BorshError(f\"Length {length} is out of range {min_length}..={max_length}\")\n";
+    let (_, generated) = code.split_once(runtime_end).unwrap();
+    generated.to_string()
+}
+
+#[allow(unused)]
+#[derive(BorshSchema)]
+struct Account {
+    id: u64,
+    name: String,
+    balance: u128,
+    key: [u8; 32],
+    owners: [u16; 2],
+    delegate: Option<Box<Account>>,
+    status: Result<Status, String>,
+    limits: BTreeMap<String, u32>,
+    tags: BTreeSet<Tag>,
+    nonce: core::num::NonZeroU32,
+    point: (i8, f64),
+    data: Vec<u8>,
+    empty: Empty,
+}
+
+#[allow(unused)]
+#[derive(BorshSchema)]
+#[borsh(use_discriminant = true)]
+enum Status {
+    Active = 1,
+    Frozen = 10,
+    Closed = 20,
+}
+
+#[allow(unused)]
+#[derive(BorshSchema)]
+struct Tag(String, bool);
+
+#[allow(unused)]
+#[derive(BorshSchema)]
+struct Empty;
+
+#[test]
+fn full_module() {
+    let schema = BorshSchemaContainer::for_type::<Tag>();
+    insta::assert_snapshot!(schema.generate_python().unwrap());
+}
+
+#[test]
+fn derived_types() {
+    let schema = BorshSchemaContainer::for_type::<Account>();
+    insta::assert_snapshot!(generated(&schema));
+}
+
+#[test]
+fn enum_with_fields() {
+    #[allow(unused)]
+    #[derive(BorshSchema)]
+    enum Message {
+        Ping,
+        Text(String),
+        Move { x: i32, y: i32 },
+    }
+
+    let schema = BorshSchemaContainer::for_type::<Message>();
+    insta::assert_snapshot!(generated(&schema));
+}
+
+#[test]
+fn length_prefixed_fields() {
+    #[allow(unused)]
+    #[derive(BorshSchema)]
+    struct Packet {
+        #[borsh(length = "u8")]
+        name: String,
+        #[borsh(length = "u16")]
+        limits: BTreeMap<String, u32>,
+    }
+
+    let schema = BorshSchemaContainer::for_type::<Packet>();
+    insta::assert_snapshot!(generated(&schema));
+}
+
+#[test]
+fn transparent_alias() {
+    #[allow(unused)]
+    #[derive(BorshSchema)]
+    #[borsh(transparent = "Name")]
+    struct Name(String);
+
+    let schema = BorshSchemaContainer::for_type::<Name>();
+    insta::assert_snapshot!(generated(&schema));
+}
+
+#[test]
+fn trailing_fields() {
+    #[allow(unused)]
+    #[derive(BorshSchema)]
+    struct Account {
+        balance: u64,
+        #[borsh(default)]
+        nonce: u32,
+    }
+
+    let schema = BorshSchemaContainer::for_type::<Account>();
+    insta::assert_snapshot!(generated(&schema));
+}
+
+#[test]
+fn custom_widths() {
+    let mut definitions = BTreeMap::new();
+    definitions.insert(
+        "Custom".to_string(),
+        Definition::Enum {
+            tag_width: 2,
+            variants: vec![
+                (-1, "Short".to_string(), "ShortList".to_string()),
+                (300, "Long".to_string(), "u64".to_string()),
+            ],
+        },
+    );
+    definitions.insert(
+        "ShortList".to_string(),
+        Definition::Sequence {
+            length_width: 1,
+            length_range: 1..=8,
+            elements: "u16".to_string(),
+        },
+    );
+    definitions.insert(
+        "Either".to_string(),
+        Definition::Enum {
+            tag_width: 0,
+            variants: vec![
+                (0, "Small".to_string(), "u8".to_string()),
+                (1, "Custom".to_string(), "Custom".to_string()),
+            ],
+        },
+    );
+    definitions.insert("u8".to_string(), Definition::Primitive(1));
+    definitions.insert("u16".to_string(), Definition::Primitive(2));
+    definitions.insert("u64".to_string(), Definition::Primitive(8));
+    let schema = BorshSchemaContainer::new("Either".to_string(), definitions);
+    insta::assert_snapshot!(generated(&schema));
+}
+
+#[test]
+fn primitive_root() {
+    let schema = BorshSchemaContainer::for_type::<u8>();
+    insta::assert_snapshot!(generated(&schema));
+}
+
+#[test]
+fn is_deterministic() {
+    let schema = BorshSchemaContainer::for_type::<Account>();
+    assert_eq!(schema.generate_python(), schema.clone().generate_python());
+}
+
+#[test]
+fn missing_definition() {
+    let mut definitions = BTreeMap::new();
+    definitions.insert(
+        "Wrapper".to_string(),
+        Definition::Struct {
+            fields: Fields::UnnamedFields(vec!["Unknown".to_string()]),
+        },
+    );
+    let schema = BorshSchemaContainer::new("Wrapper".to_string(), definitions);
+    assert_eq!(
+        schema.generate_python(),
+        Err(SchemaCodegenError::MissingDefinition("Unknown".to_string()))
+    );
+}
@@ -130,6 +130,7 @@ pub fn wrapper_struct() {
 
 #[test]
 pub fn tuple_struct() {
+    #[allow(unused)]
     #[derive(borsh::BorshSchema)]
     struct A(u64, String);
     assert_eq!("A".to_string(), A::declaration());
@@ -242,6 +243,7 @@ fn common_map() -> BTreeMap<String, Definition> {
 pub fn generic_associated_item() {
     trait TraitName {
         type Associated;
+        #[allow(unused)]
         fn method(&self);
     }
 
@@ -274,6 +276,7 @@ pub fn generic_associated_item() {
 pub fn generic_associated_item2() {
     trait TraitName {
         type Associated;
+        #[allow(unused)]
         fn method(&self);
     }
 
@@ -307,6 +310,7 @@ pub fn generic_associated_item2() {
 pub fn generic_associated_item3() {
     trait TraitName {
         type Associated;
+        #[allow(unused)]
         fn method(&self);
     }
 
@@ -0,0 +1,109 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![cfg(feature = "unstable__schema")]
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, string::ToString, vec, vec::Vec};
+
+use core::marker::PhantomData;
+
+use borsh::schema::{BorshSchemaContainer, Definition, Fields, Value};
+use borsh::{from_slice, to_vec, BorshDeserialize, BorshSchema, BorshSerialize};
+
+#[derive(BorshSchema, BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+#[borsh(transparent)]
+struct AccountId(String);
+
+#[derive(BorshSchema, BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+#[borsh(transparent = "Balance")]
+struct Balance {
+    #[borsh(varint)]
+    amount: u64,
+}
+
+#[derive(BorshSchema)]
+#[borsh(transparent)]
+#[allow(unused)]
+struct Ids<T> {
+    ids: Vec<T>,
+    #[borsh(skip)]
+    marker: PhantomData<T>,
+}
+
+#[derive(BorshSchema)]
+#[allow(unused)]
+struct Account {
+    id: AccountId,
+    balance: Balance,
+    friends: Ids<AccountId>,
+}
+
+#[test]
+fn test_transparent_reuses_declaration() {
+    assert_eq!(AccountId::declaration(), String::declaration());
+    assert_eq!(Ids::<u8>::declaration(), "Vec<u8>");
+    assert_eq!(
+        BorshSchemaContainer::for_type::<AccountId>(),
+        BorshSchemaContainer::for_type::<String>()
+    );
+
+    let container = BorshSchemaContainer::for_type::<Account>();
+    assert_eq!(
+        container.get_definition("Account").unwrap(),
+        &Definition::Struct {
+            fields: Fields::NamedFields(vec![
+                ("id".to_string(), "String".to_string()),
+                ("balance".to_string(), "Balance".to_string()),
+                ("friends".to_string(), "Vec<String>".to_string()),
+            ]),
+        }
+    );
+    assert!(container.get_definition("AccountId").is_none());
+    assert_eq!(container.validate(), Ok(()));
+}
+
+#[test]
+fn test_transparent_alias() {
+    assert_eq!(Balance::declaration(), "Balance");
+    let container = BorshSchemaContainer::for_type::<Balance>();
+    assert_eq!(
+        container.get_definition("Balance").unwrap(),
+        &Definition::Alias(<u64 as borsh::varint::VarInt>::Encoded::declaration())
+    );
+    assert_eq!(container.validate(), Ok(()));
+
+    let balance = Balance { amount: 300 };
+    let encoded = to_vec(&balance).unwrap();
+    assert_eq!(encoded, [0xac, 0x02]);
+    assert_eq!(from_slice::<Balance>(&encoded).unwrap(), balance);
+    assert_eq!(
+        container.deserialize_value(&encoded).unwrap(),
+        Value::U64(300)
+    );
+}
+
+#[test]
+fn test_transparent_alias_keeps_meaning() {
+    #[derive(BorshSchema, BorshSerialize)]
+    #[borsh(transparent = "Name")]
+    struct Name(String);
+
+    let container = BorshSchemaContainer::for_type::<Name>();
+    let encoded = to_vec(&Name("ab".to_string())).unwrap();
+    let value = container.deserialize_value(&encoded).unwrap();
+    assert_eq!(value, Value::String("ab".to_string()));
+    assert_eq!(container.serialize_value(&value).unwrap(), encoded);
+    assert_eq!(
+        container.check_compatibility(&BorshSchemaContainer::for_type::<String>()),
+        vec![]
+    );
+}
+
+#[test]
+fn test_transparent_encoding() {
+    let id = AccountId("alice".to_string());
+    let encoded = to_vec(&id).unwrap();
+    assert_eq!(encoded, to_vec(&"alice".to_string()).unwrap());
+    assert_eq!(from_slice::<AccountId>(&encoded).unwrap(), id);
+}
@@ -35,16 +35,20 @@ fn validate_for_derived_types() {
         _bar: [u8; 15],
     }
 
+    #[allow(unused)]
     #[derive(BorshSchema)]
     pub struct Unnamed(usize, [u8; 15]);
 
+    #[allow(unused)]
     #[derive(BorshSchema)]
     struct Recursive(Option<Box<Recursive>>);
 
+    #[allow(unused)]
     #[derive(BorshSchema)]
     struct RecursiveSequence(Vec<RecursiveSequence>);
 
     // thankfully, this one cannot be constructed
+    #[allow(unused)]
     #[derive(BorshSchema)]
     struct RecursiveArray(Box<[RecursiveArray; 3]>);
 
@@ -97,3 +101,15 @@ fn max_serialized_size_bound_vec() {
     test_ok::<BoundVec<1, { u8::MAX as u64 }>>();
     test_ok::<BoundVec<0, { u16::MAX as u64 }>>();
 }
+
+#[test]
+fn validate_for_alias_cycles() {
+    let mut definitions = BTreeMap::new();
+    definitions.insert("A".to_string(), Definition::Alias("B".to_string()));
+    definitions.insert("B".to_string(), Definition::Alias("A".to_string()));
+    let schema = BorshSchemaContainer::new("A".to_string(), definitions);
+    assert_eq!(
+        schema.validate(),
+        Err(SchemaContainerValidateError::AliasCycle("A".to_string()))
+    );
+}
@@ -0,0 +1,310 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![cfg(feature = "unstable__schema")]
+
+#[cfg(feature = "std")]
+use std::collections::BTreeMap;
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+#[cfg(not(feature = "std"))]
+use alloc::{
+    boxed::Box,
+    collections::BTreeMap,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use borsh::schema::*;
+use borsh::{to_vec, BorshSchema, BorshSerialize};
+
+#[track_caller]
+fn roundtrip<T: BorshSchema + BorshSerialize>(value: &T, expected: Value) {
+    let schema = BorshSchemaContainer::for_type::<T>();
+    let bytes = to_vec(value).unwrap();
+    let actual = schema.deserialize_value(&bytes).unwrap();
+    assert_eq!(actual, expected);
+    assert_eq!(schema.serialize_value(&actual).unwrap(), bytes);
+}
+
+fn variant(discriminant: DiscriminantValue, name: &str, value: Value) -> Value {
+    Value::Enum {
+        discriminant,
+        variant: name.to_string(),
+        value: Box::new(value),
+    }
+}
+
+#[test]
+fn primitives() {
+    roundtrip(&true, Value::Bool(true));
+    roundtrip(&255u8, Value::U8(255));
+    roundtrip(&-3i16, Value::I16(-3));
+    roundtrip(&7u32, Value::U32(7));
+    roundtrip(&i64::MIN, Value::I64(i64::MIN));
+    roundtrip(&u128::MAX, Value::U128(u128::MAX));
+    roundtrip(&i128::MIN, Value::I128(i128::MIN));
+    roundtrip(&1.5f32, Value::F32(1.5));
+    roundtrip(&f64::INFINITY, Value::F64(f64::INFINITY));
+    roundtrip(&(), Value::Unit);
+    roundtrip(&core::num::NonZeroU16::new(5).unwrap(), Value::U16(5));
+    roundtrip(&String::from("hello"), Value::String("hello".to_string()));
+}
+
+#[test]
+fn sequences_and_tuples() {
+    roundtrip(&vec![1u8, 2, 3], Value::Bytes(vec![1, 2, 3]));
+    roundtrip(&[7u8; 2], Value::Bytes(vec![7, 7]));
+    roundtrip(
+        &vec![1u16, 2],
+        Value::Sequence(vec![Value::U16(1), Value::U16(2)]),
+    );
+    roundtrip(
+        &(1u8, false),
+        Value::Tuple(vec![Value::U8(1), Value::Bool(false)]),
+    );
+
+    let mut map = BTreeMap::new();
+    map.insert(String::from("a"), 1u32);
+    roundtrip(
+        &map,
+        Value::Sequence(vec![Value::Tuple(vec![
+            Value::String("a".to_string()),
+            Value::U32(1),
+        ])]),
+    );
+
+    // bytes may be given as a sequence of `u8` values too
+    let schema = BorshSchemaContainer::for_type::<Vec<u8>>();
+    assert_eq!(
+        schema
+            .serialize_value(&Value::Sequence(vec![Value::U8(4), Value::U8(2)]))
+            .unwrap(),
+        to_vec(&vec![4u8, 2]).unwrap()
+    );
+}
+
+#[test]
+fn derived_types() {
+    #[derive(BorshSchema, BorshSerialize)]
+    struct Account {
+        id: u64,
+        kind: Kind,
+        tags: Vec<Tag>,
+    }
+
+    #[derive(BorshSchema, BorshSerialize)]
+    enum Kind {
+        Basic,
+        Premium { level: u8 },
+    }
+
+    #[derive(BorshSchema, BorshSerialize)]
+    struct Tag(String);
+
+    roundtrip(
+        &Account {
+            id: 1,
+            kind: Kind::Premium { level: 3 },
+            tags: vec![Tag("x".into())],
+        },
+        Value::Struct(FieldValues::Named(vec![
+            ("id".to_string(), Value::U64(1)),
+            (
+                "kind".to_string(),
+                variant(
+                    1,
+                    "Premium",
+                    Value::Struct(FieldValues::Named(vec![(
+                        "level".to_string(),
+                        Value::U8(3),
+                    )])),
+                ),
+            ),
+            (
+                "tags".to_string(),
+                Value::Sequence(vec![Value::Struct(FieldValues::Unnamed(vec![
+                    Value::String("x".to_string()),
+                ]))]),
+            ),
+        ])),
+    );
+    roundtrip(
+        &Kind::Basic,
+        variant(0, "Basic", Value::Struct(FieldValues::Empty)),
+    );
+}
+
+#[test]
+fn opaque_primitives() {
+    let mut definitions = BTreeMap::new();
+    definitions.insert("Hash".to_string(), Definition::Primitive(4));
+    let schema = BorshSchemaContainer::new("Hash".to_string(), definitions);
+    let value = schema.deserialize_value(&[1, 2, 3, 4]).unwrap();
+    assert_eq!(value, Value::Opaque(vec![1, 2, 3, 4]));
+    assert_eq!(schema.serialize_value(&value).unwrap(), vec![1, 2, 3, 4]);
+    assert!(matches!(
+        schema.serialize_value(&Value::Opaque(vec![1])),
+        Err(SchemaValueError::InvalidValue(..))
+    ));
+}
+
+#[test]
+fn decode_errors() {
+    let schema = BorshSchemaContainer::for_type::<(bool, u32)>();
+    assert_eq!(
+        schema.deserialize_value(&[1, 0, 0]),
+        Err(SchemaValueError::UnexpectedEof("u32".to_string()))
+    );
+    assert_eq!(
+        schema.deserialize_value(&[1, 0, 0, 0, 0, 9]),
+        Err(SchemaValueError::TrailingBytes(1))
+    );
+    assert!(matches!(
+        schema.deserialize_value(&[2, 0, 0, 0, 0]),
+        Err(SchemaValueError::InvalidData(declaration, _)) if declaration == "bool"
+    ));
+
+    let schema = BorshSchemaContainer::for_type::<f32>();
+    assert!(matches!(
+        schema.deserialize_value(&f32::NAN.to_le_bytes()),
+        Err(SchemaValueError::InvalidData(..))
+    ));
+
+    let schema = BorshSchemaContainer::for_type::<core::num::NonZeroU8>();
+    assert!(matches!(
+        schema.deserialize_value(&[0]),
+        Err(SchemaValueError::InvalidData(..))
+    ));
+}
+
+#[test]
+fn sequence_length_is_bounded_by_input() {
+    let mut definitions = BTreeMap::new();
+    definitions.insert(
+        "Units".to_string(),
+        Definition::Sequence {
+            length_width: 8,
+            length_range: 0..=u64::MAX,
+            elements: "()".to_string(),
+        },
+    );
+    definitions.insert("()".to_string(), Definition::Primitive(0));
+    let schema = BorshSchemaContainer::new("Units".to_string(), definitions);
+    let bytes = u64::MAX.to_le_bytes();
+    assert_eq!(
+        schema.deserialize_value(&bytes),
+        Err(SchemaValueError::InvalidData(
+            "Units".to_string(),
+            borsh::error::ERROR_ZST_FORBIDDEN.to_string()
+        ))
+    );
+    assert!(matches!(
+        schema.annotate(&bytes).last().unwrap().kind,
+        SpanKind::Invalid(SchemaValueError::InvalidData(..))
+    ));
+
+    let schema = BorshSchemaContainer::for_type::<Vec<u16>>();
+    assert_eq!(
+        schema.deserialize_value(&[255, 255, 255, 255, 1, 0]),
+        Err(SchemaValueError::UnexpectedEof("Vec<u16>".to_string()))
+    );
+
+    // zero-sized elements of fixed arrays don't depend on the input
+    let schema = BorshSchemaContainer::for_type::<[(); 3]>();
+    assert_eq!(
+        schema.deserialize_value(&[]),
+        Ok(Value::Sequence(vec![Value::Unit; 3]))
+    );
+}
+
+#[test]
+fn varints_are_recognised_by_definition() {
+    roundtrip(&borsh::varint::VarU32(300), Value::U32(300));
+    roundtrip(&borsh::varint::VarI64(-2), Value::I64(-2));
+
+    // a type, which is only named as a varint, is decoded as defined
+    let mut definitions = BTreeMap::new();
+    definitions.insert(
+        "VarU32".to_string(),
+        Definition::Sequence {
+            length_width: Definition::DEFAULT_LENGTH_WIDTH,
+            length_range: Definition::DEFAULT_LENGTH_RANGE,
+            elements: "u8".to_string(),
+        },
+    );
+    definitions.insert("u8".to_string(), Definition::Primitive(1));
+    let schema = BorshSchemaContainer::new("VarU32".to_string(), definitions);
+    let bytes = to_vec(&vec![0xacu8, 0x02]).unwrap();
+    let value = schema.deserialize_value(&bytes).unwrap();
+    assert_eq!(value, Value::Bytes(vec![0xac, 0x02]));
+    assert_eq!(schema.serialize_value(&value).unwrap(), bytes);
+}
+
+#[test]
+fn encode_errors() {
+    #[allow(unused)]
+    #[derive(BorshSchema)]
+    struct Point {
+        x: i8,
+        y: i8,
+    }
+
+    let schema = BorshSchemaContainer::for_type::<Point>();
+    let field = |name: &str, value: i8| (name.to_string(), Value::I8(value));
+    assert_eq!(
+        schema
+            .serialize_value(&Value::Struct(FieldValues::Named(vec![
+                field("y", -1),
+                field("x", 1),
+            ])))
+            .unwrap(),
+        vec![1, 255]
+    );
+    for invalid in [
+        Value::Struct(FieldValues::Named(vec![field("x", 1)])),
+        Value::Struct(FieldValues::Named(vec![
+            field("x", 1),
+            field("y", 2),
+            field("z", 3),
+        ])),
+        Value::Struct(FieldValues::Named(vec![
+            field("x", 1),
+            ("y".to_string(), Value::U8(2)),
+        ])),
+        Value::Tuple(vec![Value::I8(1), Value::I8(2)]),
+    ] {
+        assert!(matches!(
+            schema.serialize_value(&invalid),
+            Err(SchemaValueError::InvalidValue(..))
+        ));
+    }
+
+    let schema = BorshSchemaContainer::for_type::<Option<u8>>();
+    assert!(matches!(
+        schema.serialize_value(&variant(1, "None", Value::Unit)),
+        Err(SchemaValueError::InvalidValue(..))
+    ));
+
+    let schema = BorshSchemaContainer::for_type::<core::num::NonZeroU8>();
+    assert!(matches!(
+        schema.serialize_value(&Value::U8(0)),
+        Err(SchemaValueError::InvalidValue(..))
+    ));
+}
+
+#[test]
+fn recursive_schema_is_bounded() {
+    let mut definitions = BTreeMap::new();
+    definitions.insert(
+        "Rec".to_string(),
+        Definition::Struct {
+            fields: Fields::UnnamedFields(vec!["Rec".to_string()]),
+        },
+    );
+    let schema = BorshSchemaContainer::new("Rec".to_string(), definitions);
+    assert_eq!(
+        schema.deserialize_value(&[]),
+        Err(SchemaValueError::TooDeep("Rec".to_string()))
+    );
+}
@@ -0,0 +1,286 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![cfg(all(feature = "serde", feature = "derive"))]
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+#[cfg(not(feature = "std"))]
+use alloc::{
+    collections::BTreeMap,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+#[cfg(feature = "std")]
+use std::collections::BTreeMap;
+
+use borsh::error::ERROR_ZST_FORBIDDEN;
+use borsh::{from_slice, to_vec, BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize, Serializer};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+enum Kind {
+    Empty,
+    Named(String),
+    Pair(u16, i64),
+    Point { x: i32, y: i32 },
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Unit;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Record {
+    id: u64,
+    name: String,
+    tags: Vec<String>,
+    kinds: Vec<Kind>,
+    score: Option<f32>,
+    missing: Option<u128>,
+    flags: BTreeMap<u8, bool>,
+    pair: (i8, bool),
+    unit: Unit,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+enum BorshKind {
+    Empty,
+    Named(String),
+    Pair(u16, i64),
+    Point { x: i32, y: i32 },
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+struct BorshRecord {
+    id: u64,
+    name: String,
+    tags: Vec<String>,
+    kinds: Vec<BorshKind>,
+    score: Option<f32>,
+    missing: Option<u128>,
+    flags: BTreeMap<u8, bool>,
+    pair: (i8, bool),
+    unit: (),
+}
+
+fn record() -> Record {
+    Record {
+        id: 42,
+        name: "record".to_string(),
+        tags: vec!["a".to_string(), "bc".to_string()],
+        kinds: vec![
+            Kind::Empty,
+            Kind::Named("kind".to_string()),
+            Kind::Pair(7, -7),
+            Kind::Point { x: 1, y: -1 },
+        ],
+        score: Some(0.5),
+        missing: None,
+        flags: vec![(1, true), (0, false)].into_iter().collect(),
+        pair: (-1, true),
+        unit: Unit,
+    }
+}
+
+fn borsh_record() -> BorshRecord {
+    BorshRecord {
+        id: 42,
+        name: "record".to_string(),
+        tags: vec!["a".to_string(), "bc".to_string()],
+        kinds: vec![
+            BorshKind::Empty,
+            BorshKind::Named("kind".to_string()),
+            BorshKind::Pair(7, -7),
+            BorshKind::Point { x: 1, y: -1 },
+        ],
+        score: Some(0.5),
+        missing: None,
+        flags: vec![(1, true), (0, false)].into_iter().collect(),
+        pair: (-1, true),
+        unit: (),
+    }
+}
+
+#[test]
+fn test_matches_borsh_derive() {
+    let encoded = borsh::serde::to_vec(&record()).unwrap();
+    assert_eq!(encoded, to_vec(&borsh_record()).unwrap());
+    assert_eq!(
+        borsh::serde::from_slice::<Record>(&encoded).unwrap(),
+        record()
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_hash_map_order() {
+    use std::collections::HashMap;
+
+    let names: HashMap<String, u16> = ["b", "aa", "", "ab", "ba", "a"]
+        .iter()
+        .enumerate()
+        .map(|(index, name)| (name.to_string(), index as u16))
+        .collect();
+    assert_eq!(
+        borsh::serde::to_vec(&names).unwrap(),
+        to_vec(&names).unwrap()
+    );
+
+    let numbers: HashMap<(i32, Option<u16>), bool> = vec![
+        ((-1, None), true),
+        ((256, Some(1)), false),
+        ((1, Some(256)), true),
+        ((i32::MIN, Some(0)), false),
+        ((1, None), true),
+        ((0, Some(2)), false),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(
+        borsh::serde::to_vec(&numbers).unwrap(),
+        to_vec(&numbers).unwrap()
+    );
+}
+
+#[test]
+fn test_primitives() {
+    assert_eq!(borsh::serde::to_vec(&'a').unwrap(), to_vec(&97u32).unwrap());
+    assert_eq!(
+        borsh::serde::from_slice::<char>(&[97, 0, 0, 0]).unwrap(),
+        'a'
+    );
+    assert_eq!(
+        borsh::serde::from_slice::<char>(&[0, 0xd8, 0, 0])
+            .unwrap_err()
+            .to_string(),
+        "Invalid char code point: 55296"
+    );
+    assert_eq!(
+        borsh::serde::from_slice::<Option<u8>>(&[2])
+            .unwrap_err()
+            .to_string(),
+        "Invalid Option representation: 2. The first byte must be 0 or 1"
+    );
+    assert_eq!(
+        borsh::serde::from_slice::<u8>(&[1, 2])
+            .unwrap_err()
+            .to_string(),
+        "Not all bytes read"
+    );
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+struct Shape {
+    name: String,
+    #[borsh(serde)]
+    origin: Point,
+    #[borsh(serde)]
+    points: Vec<Point>,
+}
+
+#[test]
+fn test_serde_field() {
+    let shape = Shape {
+        name: "line".to_string(),
+        origin: Point { x: 1, y: 2 },
+        points: vec![Point { x: 3, y: 4 }],
+    };
+    let encoded = to_vec(&shape).unwrap();
+    assert_eq!(
+        encoded,
+        to_vec(&("line".to_string(), (1i32, 2i32), vec![(3i32, 4i32)])).unwrap()
+    );
+    assert_eq!(from_slice::<Shape>(&encoded).unwrap(), shape);
+    assert_eq!(
+        from_slice::<Shape>(&encoded[..encoded.len() - 1])
+            .unwrap_err()
+            .to_string(),
+        "Unexpected length of input"
+    );
+}
+
+#[test]
+fn test_serde_field_limits() {
+    use borsh::de::DeserializeOptions;
+    use borsh::error::DeserializeError;
+    use borsh::from_slice_with_options;
+
+    let shape = Shape {
+        name: "triangle".to_string(),
+        origin: Point { x: 0, y: 0 },
+        points: vec![Point { x: 1, y: 0 }, Point { x: 0, y: 1 }, Point { x: 1, y: 1 }],
+    };
+    let encoded = to_vec(&shape).unwrap();
+    // `Shape`, the sequence of points and a `Point` in it
+    let options = DeserializeOptions::new().max_collection_len(3).max_depth(3);
+    assert_eq!(
+        from_slice_with_options::<Shape>(&encoded, &options).unwrap(),
+        shape
+    );
+
+    let options = DeserializeOptions::new().max_collection_len(2);
+    let err = from_slice_with_options::<Shape>(&encoded, &options).unwrap_err();
+    assert_eq!(err.to_string(), "Collection length 3 exceeds limit of 2");
+    let details = DeserializeError::from_io_error(&err).unwrap();
+    assert_eq!(details.path_string(), "Shape.points");
+
+    let options = DeserializeOptions::new().max_depth(2);
+    let err = from_slice_with_options::<Shape>(&encoded, &options).unwrap_err();
+    assert_eq!(err.to_string(), "Maximum depth of nested values exceeded");
+
+    let options = DeserializeOptions::new().max_string_len(4);
+    let mut deserializer = borsh::serde::Deserializer::with_options(&encoded[..], &options);
+    let err = <String as Deserialize>::deserialize(&mut deserializer).unwrap_err();
+    assert_eq!(err.to_string(), "String length 8 exceeds limit of 4");
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(untagged)]
+enum Untagged {
+    Number(u8),
+}
+
+struct Filtered(Vec<u8>);
+
+impl Serialize for Filtered {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.0.iter().filter(|value| **value > 0))
+    }
+}
+
+#[test]
+fn test_unsupported() {
+    assert_eq!(
+        borsh::serde::from_slice::<Untagged>(&[1])
+            .unwrap_err()
+            .to_string(),
+        "Borsh isn't a self-describing format, the type has to be known to deserialize it"
+    );
+    assert_eq!(
+        borsh::serde::to_vec(&Filtered(vec![0, 1]))
+            .unwrap_err()
+            .to_string(),
+        "Length of sequences and maps has to be known upfront"
+    );
+}
+
+#[test]
+fn test_zero_sized_elements() {
+    assert_eq!(
+        borsh::serde::to_vec(&vec![Unit, Unit])
+            .unwrap_err()
+            .to_string(),
+        ERROR_ZST_FORBIDDEN
+    );
+    assert_eq!(borsh::serde::to_vec(&Vec::<Unit>::new()).unwrap(), [0; 4]);
+    assert!(borsh::serde::from_slice::<Vec<Unit>>(&[255, 255, 255, 255])
+        .unwrap_err()
+        .to_string()
+        .starts_with(ERROR_ZST_FORBIDDEN));
+    assert_eq!(borsh::serde::to_vec(&(Unit, Unit)).unwrap(), [0u8; 0]);
+}
@@ -0,0 +1,341 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+#[cfg(not(feature = "std"))]
+use alloc::string::ToString;
+
+use borsh::trailing::Trailing;
+use borsh::{from_slice, from_slice_borrowed, to_vec};
+
+#[test]
+fn test_trailing_type() {
+    assert!(to_vec(&Trailing::<u32>(None)).unwrap().is_empty());
+    assert_eq!(to_vec(&Trailing(Some(1u16))).unwrap(), [1, 0]);
+    assert_eq!(from_slice::<Trailing<u16>>(&[]).unwrap(), Trailing(None));
+    assert_eq!(
+        from_slice::<Trailing<u16>>(&[2, 0]).unwrap(),
+        Trailing(Some(2))
+    );
+    assert_eq!(
+        from_slice_borrowed::<(u8, Trailing<&str>)>(&[7]).unwrap(),
+        (7, Trailing(None))
+    );
+
+    let err = from_slice::<Trailing<u16>>(&[2]).unwrap_err();
+    assert_eq!(err.to_string(), "Unexpected length of input");
+}
+
+#[cfg(feature = "derive")]
+mod derive {
+    #[cfg(not(feature = "std"))]
+    use alloc::{
+        format,
+        string::{String, ToString},
+        vec,
+        vec::Vec,
+    };
+
+    use borsh::{
+        from_slice, from_slice_borrowed, to_vec, BorshDeserialize, BorshDeserializeBorrowed,
+        BorshSerialize,
+    };
+
+    #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+    struct AccountV1 {
+        balance: u64,
+    }
+
+    #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+    struct AccountV2 {
+        balance: u64,
+        #[borsh(default)]
+        nonce: u32,
+        #[borsh(skip)]
+        cached: bool,
+        #[borsh(default = "default_tags", length = "u8")]
+        tags: Vec<String>,
+    }
+
+    fn default_tags() -> Vec<String> {
+        vec!["new".to_string()]
+    }
+
+    #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+    enum Event {
+        Deposit(u64, #[borsh(default)] Option<u64>),
+    }
+
+    #[derive(BorshSerialize, BorshDeserializeBorrowed, PartialEq, Debug)]
+    struct Record<'a> {
+        key: &'a str,
+        #[borsh(default)]
+        value: &'a [u8],
+    }
+
+    #[test]
+    fn test_missing_trailing_fields() {
+        let old = to_vec(&AccountV1 { balance: 5 }).unwrap();
+        assert_eq!(
+            from_slice::<AccountV2>(&old).unwrap(),
+            AccountV2 {
+                balance: 5,
+                nonce: 0,
+                cached: false,
+                tags: vec!["new".to_string()],
+            }
+        );
+
+        let mut partial = old.clone();
+        partial.extend_from_slice(&7u32.to_le_bytes());
+        assert_eq!(
+            from_slice::<AccountV2>(&partial).unwrap(),
+            AccountV2 {
+                balance: 5,
+                nonce: 7,
+                cached: false,
+                tags: vec!["new".to_string()],
+            }
+        );
+
+        let account = AccountV2 {
+            balance: 1,
+            nonce: 2,
+            cached: false,
+            tags: vec![],
+        };
+        let encoded = to_vec(&account).unwrap();
+        assert_eq!(encoded, [1, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0]);
+        assert_eq!(from_slice::<AccountV2>(&encoded).unwrap(), account);
+
+        let event = from_slice::<Event>(&[0, 3, 0, 0, 0, 0, 0, 0, 0]).unwrap();
+        assert_eq!(event, Event::Deposit(3, None));
+
+        let record = from_slice_borrowed::<Record>(&[1, 0, 0, 0, b'k']).unwrap();
+        assert_eq!(
+            record,
+            Record {
+                key: "k",
+                value: &[]
+            }
+        );
+    }
+
+    #[test]
+    fn test_strictness_is_kept() {
+        // a field, which is only partially present, is still an error
+        let err = from_slice::<AccountV2>(&[5, 0, 0, 0, 0, 0, 0, 0, 7, 0]).unwrap_err();
+        assert_eq!(
+            format!("{:#}", err),
+            "Unexpected length of input (at AccountV2.nonce, offset 10)"
+        );
+
+        let mut encoded = to_vec(&AccountV2 {
+            balance: 1,
+            nonce: 2,
+            cached: true,
+            tags: vec![],
+        })
+        .unwrap();
+        encoded.push(0);
+        let err = from_slice::<AccountV2>(&encoded).unwrap_err();
+        assert_eq!(err.to_string(), "Not all bytes read");
+
+        // only the end of the input is detected
+        let old = to_vec(&vec![AccountV1 { balance: 1 }, AccountV1 { balance: 2 }]).unwrap();
+        assert!(from_slice::<Vec<AccountV2>>(&old).is_err());
+    }
+}
+
+#[cfg(feature = "unstable__schema")]
+mod schema {
+    #[cfg(not(feature = "std"))]
+    use alloc::{string::ToString, vec, vec::Vec};
+
+    #[cfg(feature = "std")]
+    use std::collections::BTreeMap;
+
+    #[cfg(not(feature = "std"))]
+    use alloc::collections::BTreeMap;
+
+    use borsh::schema::{
+        add_definition, BorshSchemaContainer, Declaration, Definition, FieldValues, Fields,
+        Incompatibility, SchemaValueError, Value,
+    };
+    use borsh::trailing::Trailing;
+    use borsh::{max_serialized_size, to_vec, BorshSchema, BorshSerialize};
+
+    #[derive(BorshSchema, BorshSerialize)]
+    struct AccountV1 {
+        balance: u64,
+    }
+
+    #[derive(BorshSchema)]
+    #[allow(unused)]
+    struct AccountV2 {
+        balance: u64,
+        #[borsh(default)]
+        nonce: u32,
+    }
+
+    #[derive(BorshSchema)]
+    #[allow(unused)]
+    struct UnnamedV1(u64);
+
+    #[derive(BorshSchema)]
+    #[allow(unused)]
+    struct UnnamedV2(u64, #[borsh(default)] u32);
+
+    fn check<Old: BorshSchema, New: BorshSchema>() -> Vec<Incompatibility> {
+        BorshSchemaContainer::for_type::<New>()
+            .check_compatibility(&BorshSchemaContainer::for_type::<Old>())
+    }
+
+    #[test]
+    fn test_trailing_schema() {
+        let container = BorshSchemaContainer::for_type::<AccountV2>();
+        assert_eq!(
+            container.get_definition("AccountV2").unwrap(),
+            &Definition::Struct {
+                fields: Fields::NamedFields(vec![
+                    ("balance".to_string(), "u64".to_string()),
+                    ("nonce".to_string(), "Trailing<u32>".to_string()),
+                ]),
+            }
+        );
+        assert_eq!(
+            container.get_definition("Trailing<u32>").unwrap(),
+            &Definition::Trailing("u32".to_string())
+        );
+        assert_eq!(container.validate(), Ok(()));
+        assert_eq!(max_serialized_size::<AccountV2>(), Ok(12));
+
+        let old = to_vec(&AccountV1 { balance: 1 }).unwrap();
+        let value = container.deserialize_value(&old).unwrap();
+        assert_eq!(
+            value,
+            Value::Struct(FieldValues::Named(vec![
+                ("balance".to_string(), Value::U64(1)),
+                (
+                    "nonce".to_string(),
+                    Value::Enum {
+                        discriminant: 0,
+                        variant: "None".to_string(),
+                        value: Value::Unit.into(),
+                    }
+                ),
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_trailing_value_at_end_of_input() {
+        let container = BorshSchemaContainer::for_type::<AccountV2>();
+        let value = container
+            .deserialize_value(&[1, 0, 0, 0, 0, 0, 0, 0, 7, 0, 0, 0])
+            .unwrap();
+        assert_eq!(
+            value,
+            Value::Struct(FieldValues::Named(vec![
+                ("balance".to_string(), Value::U64(1)),
+                (
+                    "nonce".to_string(),
+                    Value::Enum {
+                        discriminant: 1,
+                        variant: "Some".to_string(),
+                        value: Value::U32(7).into(),
+                    }
+                ),
+            ]))
+        );
+
+        // the value is missing only at the end of the input
+        let container = BorshSchemaContainer::for_type::<(Trailing<u32>, u8)>();
+        assert_eq!(
+            container.deserialize_value(&[5]),
+            Err(SchemaValueError::UnexpectedEof("u32".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_trailing_compatibility() {
+        let found = check::<AccountV1, AccountV2>();
+        assert_eq!(
+            found,
+            vec![Incompatibility::TrailingFieldAdded {
+                declaration: "AccountV2".to_string(),
+                field: "nonce".to_string(),
+            }]
+        );
+        assert!(!found[0].is_breaking());
+
+        assert_eq!(
+            check::<UnnamedV1, UnnamedV2>(),
+            vec![Incompatibility::TrailingFieldAdded {
+                declaration: "UnnamedV2".to_string(),
+                field: "1".to_string(),
+            }]
+        );
+
+        // values, which don't end the input, always have the field
+        assert_eq!(
+            check::<Vec<AccountV1>, Vec<AccountV2>>(),
+            vec![Incompatibility::FieldAdded {
+                declaration: "AccountV2".to_string(),
+                field: "nonce".to_string(),
+            }]
+        );
+        assert_eq!(
+            check::<(AccountV1, u8), (AccountV2, u8)>(),
+            vec![Incompatibility::FieldAdded {
+                declaration: "AccountV2".to_string(),
+                field: "nonce".to_string(),
+            }]
+        );
+        assert_eq!(
+            check::<(u8, AccountV1), (u8, AccountV2)>(),
+            vec![Incompatibility::TrailingFieldAdded {
+                declaration: "AccountV2".to_string(),
+                field: "nonce".to_string(),
+            }]
+        );
+    }
+
+    /// Declared as `Trailing<u32>`, but defined as a struct, which is always present.
+    #[allow(unused)]
+    struct NotTrailing(u32);
+
+    impl BorshSchema for NotTrailing {
+        fn add_definitions_recursively(definitions: &mut BTreeMap<Declaration, Definition>) {
+            let fields = Fields::UnnamedFields(vec![u32::declaration()]);
+            add_definition(
+                Self::declaration(),
+                Definition::Struct { fields },
+                definitions,
+            );
+            u32::add_definitions_recursively(definitions);
+        }
+
+        fn declaration() -> Declaration {
+            "Trailing<u32>".to_string()
+        }
+    }
+
+    #[derive(BorshSchema)]
+    #[allow(unused)]
+    struct AccountV3 {
+        balance: u64,
+        nonce: NotTrailing,
+    }
+
+    #[test]
+    fn test_trailing_is_recognised_by_definition() {
+        assert_eq!(
+            check::<AccountV1, AccountV3>(),
+            vec![Incompatibility::FieldAdded {
+                declaration: "AccountV3".to_string(),
+                field: "nonce".to_string(),
+            }]
+        );
+    }
+}
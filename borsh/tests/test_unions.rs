@@ -0,0 +1,98 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![cfg(feature = "derive")]
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+#[cfg(not(feature = "std"))]
+use alloc::string::ToString;
+
+use borsh::{error::DeserializeError, from_slice, to_vec, BorshDeserialize, BorshSerialize};
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy)]
+#[borsh(unsafe_union_bytes)]
+#[repr(C)]
+union Word {
+    value: u32,
+    bytes: [u8; 4],
+}
+
+#[test]
+fn test_union_bytes() {
+    let word = Word {
+        value: 0x0403_0201u32.to_le(),
+    };
+    let encoded = to_vec(&word).unwrap();
+    assert_eq!(encoded, [1, 2, 3, 4]);
+
+    let decoded = from_slice::<Word>(&encoded).unwrap();
+    assert_eq!(unsafe { decoded.bytes }, [1, 2, 3, 4]);
+}
+
+#[test]
+fn test_union_bytes_unexpected_length() {
+    let err = from_slice::<Word>(&[1, 2, 3]).err().unwrap();
+    assert_eq!(err.to_string(), "Unexpected length of input");
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy)]
+#[repr(C)]
+struct Number {
+    kind: u8,
+    value: u32,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy)]
+#[repr(C)]
+struct Text {
+    kind: u8,
+    data: [u8; 3],
+}
+
+/// all fields start with the same `kind` byte, as unions of C libraries often do
+#[derive(BorshSerialize, BorshDeserialize)]
+#[borsh(unsafe_union_discriminant = "Event::active_field")]
+#[repr(C)]
+union Event {
+    kind: u8,
+    number: Number,
+    text: Text,
+}
+
+impl Event {
+    fn active_field(&self) -> u8 {
+        match unsafe { self.kind } {
+            1 => 1,
+            2 => 2,
+            _ => 0,
+        }
+    }
+}
+
+#[test]
+fn test_union_discriminant() {
+    let event = Event {
+        number: Number { kind: 1, value: 7 },
+    };
+    let encoded = to_vec(&event).unwrap();
+    assert_eq!(encoded, [1, 1, 7, 0, 0, 0]);
+    let decoded = from_slice::<Event>(&encoded).unwrap();
+    assert_eq!(unsafe { decoded.number.value }, 7);
+
+    let event = Event { kind: 5 };
+    assert_eq!(to_vec(&event).unwrap(), [0, 5]);
+
+    let decoded = from_slice::<Event>(&[2, 2, 9, 8, 7]).unwrap();
+    assert_eq!(unsafe { decoded.text.data }, [9, 8, 7]);
+}
+
+#[test]
+fn test_union_discriminant_errors() {
+    let err = from_slice::<Event>(&[3, 0]).err().unwrap();
+    assert_eq!(err.to_string(), "Unexpected union field index: 3");
+    let details = DeserializeError::from_io_error(&err).unwrap();
+    assert_eq!(details.path_string(), "Event");
+
+    let err = from_slice::<Event>(&[2, 2, 9, 8]).err().unwrap();
+    let details = DeserializeError::from_io_error(&err).unwrap();
+    assert_eq!(details.path_string(), "Event.text.Text.data");
+}
@@ -0,0 +1,169 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![cfg(feature = "derive")]
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+#[cfg(not(feature = "std"))]
+use alloc::{
+    string::{String, ToString},
+    vec,
+};
+
+use core::convert::TryFrom;
+
+use borsh::{
+    de::EnumExt, error::DeserializeError, from_slice, to_vec, BorshDeserialize, BorshSerialize,
+};
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+#[borsh(untagged)]
+enum Payload {
+    Ping,
+    Transfer { to: u64, amount: u64 },
+    Memo(String),
+}
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+struct Message {
+    kind: u8,
+    sequence: u32,
+    #[borsh(discriminant_from = "kind")]
+    payload: Payload,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, PartialEq, Debug)]
+struct Kind(u16);
+
+impl TryFrom<Kind> for u8 {
+    type Error = core::num::TryFromIntError;
+
+    fn try_from(kind: Kind) -> Result<u8, Self::Error> {
+        u8::try_from(kind.0)
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+struct WideMessage {
+    kind: Kind,
+    #[borsh(discriminant_from = "kind")]
+    payload: Payload,
+}
+
+#[test]
+fn test_untagged_serialization() {
+    assert!(to_vec(&Payload::Ping).unwrap().is_empty());
+    assert_eq!(
+        to_vec(&Payload::Memo("hi".to_string())).unwrap(),
+        vec![2, 0, 0, 0, b'h', b'i']
+    );
+}
+
+#[test]
+fn test_discriminant_from_sibling() {
+    let message = Message {
+        kind: 1,
+        sequence: 7,
+        payload: Payload::Transfer { to: 3, amount: 100 },
+    };
+    let encoded = to_vec(&message).unwrap();
+    assert_eq!(encoded.len(), 1 + 4 + 8 + 8);
+    assert_eq!(from_slice::<Message>(&encoded).unwrap(), message);
+
+    let message = Message {
+        kind: 2,
+        sequence: 8,
+        payload: Payload::Memo("memo".to_string()),
+    };
+    let encoded = to_vec(&message).unwrap();
+    assert_eq!(from_slice::<Message>(&encoded).unwrap(), message);
+}
+
+#[test]
+fn test_discriminant_from_errors() {
+    let err = from_slice::<Message>(&[5, 0, 0, 0, 0]).unwrap_err();
+    assert_eq!(err.to_string(), "Unexpected variant tag: 5");
+    let details = DeserializeError::from_io_error(&err).unwrap();
+    assert_eq!(details.path_string(), "Message.payload.Payload");
+
+    let err = from_slice::<WideMessage>(&[0, 1]).unwrap_err();
+    assert_eq!(err.to_string(), "Variant tag is out of range");
+    let details = DeserializeError::from_io_error(&err).unwrap();
+    assert_eq!(details.path_string(), "WideMessage.payload");
+
+    let message = WideMessage {
+        kind: Kind(0),
+        payload: Payload::Ping,
+    };
+    assert_eq!(
+        from_slice::<WideMessage>(&to_vec(&message).unwrap()).unwrap(),
+        message
+    );
+}
+
+#[test]
+fn test_external_tag() {
+    let encoded = to_vec(&Payload::Memo("x".to_string())).unwrap();
+    let payload = Payload::deserialize_variant(&mut &encoded[..], 2).unwrap();
+    assert_eq!(payload, Payload::Memo("x".to_string()));
+}
+
+#[cfg(feature = "unstable__schema")]
+mod schema {
+    #[cfg(not(feature = "std"))]
+    use alloc::{boxed::Box, string::ToString, vec};
+
+    use borsh::schema::{BorshSchemaContainer, Definition, FieldValues, SchemaValueError, Value};
+
+    #[derive(borsh::BorshSchema)]
+    #[borsh(untagged)]
+    #[allow(unused)]
+    enum SchemaPayload {
+        Ping,
+        Memo(u32),
+    }
+
+    #[test]
+    fn test_untagged_schema() {
+        let container = BorshSchemaContainer::for_type::<SchemaPayload>();
+        match container.get_definition("SchemaPayload").unwrap() {
+            Definition::Enum { tag_width, .. } => assert_eq!(*tag_width, 0),
+            other => panic!("unexpected definition {:?}", other),
+        }
+    }
+
+    #[derive(borsh::BorshSchema)]
+    #[borsh(untagged)]
+    #[allow(unused)]
+    enum Number {
+        Short(u8),
+        Long(u16),
+    }
+
+    #[test]
+    fn test_untagged_value() {
+        let container = BorshSchemaContainer::for_type::<SchemaPayload>();
+        let value = container.deserialize_value(&[7, 0, 0, 0]).unwrap();
+        assert_eq!(
+            value,
+            Value::Enum {
+                discriminant: 1,
+                variant: "Memo".to_string(),
+                value: Box::new(Value::Struct(FieldValues::Unnamed(vec![Value::U32(7)]))),
+            }
+        );
+        assert_eq!(container.serialize_value(&value).unwrap(), [7, 0, 0, 0]);
+        match container.deserialize_value(&[]).unwrap() {
+            Value::Enum { variant, .. } => assert_eq!(variant, "Ping"),
+            other => panic!("unexpected value {:?}", other),
+        }
+
+        let container = BorshSchemaContainer::for_type::<Number>();
+        assert_eq!(
+            container.deserialize_value(&[1, 2]),
+            Err(SchemaValueError::InvalidData(
+                "Number".to_string(),
+                "ambiguous input, matching both Short and Long variants".to_string()
+            ))
+        );
+    }
+}
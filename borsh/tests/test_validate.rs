@@ -0,0 +1,153 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![cfg(feature = "derive")]
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+#[cfg(not(feature = "std"))]
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use borsh::error::DeserializeError;
+use borsh::{from_slice, from_slice_borrowed, to_vec, BorshDeserialize, BorshDeserializeBorrowed};
+use borsh::{io::ErrorKind, BorshSerialize};
+
+fn non_empty(value: &str) -> Result<(), &'static str> {
+    if value.is_empty() {
+        return Err("must not be empty");
+    }
+    Ok(())
+}
+
+fn at_most_100(value: &u8) -> Result<(), String> {
+    if *value > 100 {
+        return Err(format!("{} is greater than 100", value));
+    }
+    Ok(())
+}
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+#[borsh(validate = "Range::check", init = init)]
+struct Range {
+    #[borsh(validate = "non_empty")]
+    name: String,
+    #[borsh(validate = "at_most_100")]
+    start: u8,
+    end: u8,
+    #[borsh(skip)]
+    len: u8,
+}
+
+impl Range {
+    fn check(&self) -> Result<(), &'static str> {
+        if self.start > self.end {
+            return Err("start is after end");
+        }
+        Ok(())
+    }
+
+    fn init(&mut self) {
+        // only called on valid values
+        self.len = self.end - self.start;
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+enum Command {
+    Seek(#[borsh(validate = "at_most_100")] u8),
+    Stop,
+}
+
+#[derive(BorshSerialize, BorshDeserializeBorrowed, PartialEq, Debug)]
+#[borsh(validate = "Token::check")]
+struct Token<'a> {
+    text: &'a str,
+}
+
+impl Token<'_> {
+    fn check(&self) -> Result<(), &'static str> {
+        if self.text.contains(' ') {
+            return Err("whitespace in token");
+        }
+        Ok(())
+    }
+}
+
+fn encode_range(name: &str, start: u8, end: u8) -> Vec<u8> {
+    to_vec(&Range {
+        name: name.to_string(),
+        start,
+        end,
+        len: 0,
+    })
+    .unwrap()
+}
+
+#[test]
+fn test_valid_values() {
+    assert_eq!(
+        from_slice::<Range>(&encode_range("a", 1, 5)).unwrap(),
+        Range {
+            name: "a".to_string(),
+            start: 1,
+            end: 5,
+            len: 4,
+        }
+    );
+    assert_eq!(
+        from_slice::<Command>(&to_vec(&Command::Seek(7)).unwrap()).unwrap(),
+        Command::Seek(7)
+    );
+    let encoded = to_vec(&Token { text: "abc" }).unwrap();
+    assert_eq!(
+        from_slice_borrowed::<Token>(&encoded).unwrap(),
+        Token { text: "abc" }
+    );
+}
+
+#[test]
+fn test_field_validation() {
+    let err = from_slice::<Range>(&encode_range("", 1, 5)).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert_eq!(
+        format!("{:#}", err),
+        "must not be empty (at Range.name, offset 4)"
+    );
+
+    let err = from_slice::<Range>(&encode_range("a", 101, 105)).unwrap_err();
+    assert_eq!(err.to_string(), "101 is greater than 100");
+    let details = DeserializeError::from_io_error(&err).unwrap();
+    assert_eq!(details.path_string(), "Range.start");
+
+    let err = from_slice::<Command>(&[0, 200]).unwrap_err();
+    assert_eq!(
+        format!("{:#}", err),
+        "200 is greater than 100 (at Command::Seek.0, offset 2)"
+    );
+}
+
+#[test]
+fn test_item_validation() {
+    let err = from_slice::<Range>(&encode_range("a", 5, 1)).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert_eq!(
+        format!("{:#}", err),
+        "start is after end (at Range, offset 7)"
+    );
+
+    let encoded = to_vec(&Token { text: "a b" }).unwrap();
+    let err = from_slice_borrowed::<Token>(&encoded).unwrap_err();
+    assert_eq!(err.to_string(), "whitespace in token");
+
+    // nested values are validated too
+    let ranges = [encode_range("a", 1, 2), encode_range("b", 3, 2)];
+    let mut encoded = to_vec(&2u32).unwrap();
+    encoded.extend(ranges.concat());
+    let err = from_slice::<Vec<Range>>(&encoded).unwrap_err();
+    assert_eq!(
+        DeserializeError::from_io_error(&err).unwrap().path_string(),
+        "[1].Range"
+    );
+}
@@ -0,0 +1,229 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+#[cfg(not(feature = "std"))]
+use alloc::{string::ToString, vec};
+
+use borsh::varint::{VarI64, VarU32, VarU64};
+use borsh::{from_slice, to_vec};
+
+#[test]
+fn test_unsigned_encoding() {
+    let cases: &[(u64, &[u8])] = &[
+        (0, &[0x00]),
+        (1, &[0x01]),
+        (127, &[0x7f]),
+        (128, &[0x80, 0x01]),
+        (300, &[0xac, 0x02]),
+        (16_383, &[0xff, 0x7f]),
+        (16_384, &[0x80, 0x80, 0x01]),
+        (
+            u64::MAX,
+            &[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01],
+        ),
+    ];
+    for (value, bytes) in cases {
+        assert_eq!(to_vec(&VarU64(*value)).unwrap(), *bytes);
+        assert_eq!(from_slice::<VarU64>(bytes).unwrap(), VarU64(*value));
+    }
+
+    assert_eq!(
+        to_vec(&VarU32(u32::MAX)).unwrap(),
+        vec![0xff, 0xff, 0xff, 0xff, 0x0f]
+    );
+    assert_eq!(
+        from_slice::<VarU32>(&[0xff, 0xff, 0xff, 0xff, 0x0f]).unwrap(),
+        VarU32(u32::MAX)
+    );
+}
+
+#[test]
+fn test_zigzag_encoding() {
+    let cases: &[(i64, &[u8])] = &[
+        (0, &[0x00]),
+        (-1, &[0x01]),
+        (1, &[0x02]),
+        (-2, &[0x03]),
+        (63, &[0x7e]),
+        (-64, &[0x7f]),
+        (64, &[0x80, 0x01]),
+    ];
+    for (value, bytes) in cases {
+        assert_eq!(to_vec(&VarI64(*value)).unwrap(), *bytes);
+        assert_eq!(from_slice::<VarI64>(bytes).unwrap(), VarI64(*value));
+    }
+    for value in [i64::MIN, i64::MIN + 1, i64::MAX] {
+        let encoded = to_vec(&VarI64(value)).unwrap();
+        assert_eq!(encoded.len(), 10);
+        assert_eq!(from_slice::<VarI64>(&encoded).unwrap(), VarI64(value));
+    }
+}
+
+#[test]
+fn test_invalid_encoding() {
+    let err = from_slice::<VarU64>(&[0x80, 0x00]).unwrap_err();
+    assert_eq!(err.to_string(), "Overlong varint encoding");
+
+    let err = from_slice::<VarU32>(&[0xff, 0xff, 0xff, 0xff, 0x1f]).unwrap_err();
+    assert_eq!(err.to_string(), "Varint is out of range");
+    let err = from_slice::<VarU32>(&[0x80, 0x80, 0x80, 0x80, 0x80, 0x01]).unwrap_err();
+    assert_eq!(err.to_string(), "Varint is out of range");
+    let err = from_slice::<VarU64>(&[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x02])
+        .unwrap_err();
+    assert_eq!(err.to_string(), "Varint is out of range");
+
+    let err = from_slice::<VarU64>(&[0x80]).unwrap_err();
+    assert_eq!(err.to_string(), "Unexpected length of input");
+}
+
+#[cfg(feature = "derive")]
+mod derive {
+    #[cfg(not(feature = "std"))]
+    use alloc::{vec, vec::Vec};
+
+    use borsh::varint::VarU32;
+    use borsh::{
+        from_slice, from_slice_borrowed, to_vec, BorshDeserialize, BorshDeserializeBorrowed,
+        BorshSerialize,
+    };
+
+    #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+    struct Counters {
+        #[borsh(varint)]
+        hits: u64,
+        #[borsh(varint)]
+        delta: i64,
+        #[borsh(varint)]
+        total: u32,
+        misses: VarU32,
+    }
+
+    #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+    enum Event {
+        Tick(#[borsh(varint)] u64),
+        Moved {
+            #[borsh(varint)]
+            dx: i64,
+        },
+    }
+
+    #[derive(BorshSerialize, BorshDeserializeBorrowed, PartialEq, Debug)]
+    struct Record<'a> {
+        name: &'a str,
+        #[borsh(varint)]
+        count: u64,
+    }
+
+    #[test]
+    fn test_varint_attribute() {
+        let counters = Counters {
+            hits: 300,
+            delta: -2,
+            total: 1,
+            misses: VarU32(0),
+        };
+        let encoded = to_vec(&counters).unwrap();
+        assert_eq!(encoded, [0xac, 0x02, 0x03, 0x01, 0x00]);
+        assert_eq!(from_slice::<Counters>(&encoded).unwrap(), counters);
+
+        let events = vec![Event::Tick(5), Event::Moved { dx: -1 }];
+        let encoded = to_vec(&events).unwrap();
+        assert_eq!(encoded, [2, 0, 0, 0, 0, 5, 1, 1]);
+        assert_eq!(from_slice::<Vec<Event>>(&encoded).unwrap(), events);
+
+        let record = Record {
+            name: "a",
+            count: 128,
+        };
+        let encoded = to_vec(&record).unwrap();
+        assert_eq!(encoded, [1, 0, 0, 0, b'a', 0x80, 0x01]);
+        assert_eq!(from_slice_borrowed::<Record>(&encoded).unwrap(), record);
+    }
+}
+
+#[cfg(feature = "unstable__schema")]
+mod schema {
+    #[cfg(not(feature = "std"))]
+    use alloc::{string::ToString, vec};
+
+    use borsh::schema::{
+        BorshSchemaContainer, Definition, FieldValues, Fields, SchemaValueError, Value,
+    };
+    use borsh::varint::{VarI64, VarU32, VarU64};
+    use borsh::{max_serialized_size, to_vec, BorshSchema, BorshSerialize};
+
+    #[derive(BorshSchema, BorshSerialize)]
+    #[allow(unused)]
+    struct Counters {
+        #[borsh(varint)]
+        hits: u64,
+        misses: VarU32,
+    }
+
+    #[test]
+    fn test_varint_schema() {
+        let container = BorshSchemaContainer::for_type::<VarU64>();
+        assert_eq!(
+            container.get_definition("VarU64").unwrap(),
+            &Definition::Sequence {
+                length_width: Definition::ARRAY_LENGTH_WIDTH,
+                length_range: 1..=10,
+                elements: "u8".to_string(),
+            }
+        );
+        assert_eq!(max_serialized_size::<VarU32>(), Ok(5));
+        assert_eq!(max_serialized_size::<VarU64>(), Ok(10));
+        assert_eq!(max_serialized_size::<VarI64>(), Ok(10));
+
+        let container = BorshSchemaContainer::for_type::<Counters>();
+        assert_eq!(
+            container.get_definition("Counters").unwrap(),
+            &Definition::Struct {
+                fields: Fields::NamedFields(vec![
+                    ("hits".to_string(), "VarU64".to_string()),
+                    ("misses".to_string(), "VarU32".to_string()),
+                ]),
+            }
+        );
+        assert_eq!(container.validate(), Ok(()));
+        assert_eq!(max_serialized_size::<Counters>(), Ok(15));
+    }
+
+    #[test]
+    fn test_varint_value() {
+        let container = BorshSchemaContainer::for_type::<Counters>();
+        let encoded = to_vec(&Counters {
+            hits: 300,
+            misses: VarU32(5),
+        })
+        .unwrap();
+        let value = container.deserialize_value(&encoded).unwrap();
+        assert_eq!(
+            value,
+            Value::Struct(FieldValues::Named(vec![
+                ("hits".to_string(), Value::U64(300)),
+                ("misses".to_string(), Value::U32(5)),
+            ]))
+        );
+        assert_eq!(container.serialize_value(&value).unwrap(), encoded);
+
+        let container = BorshSchemaContainer::for_type::<VarI64>();
+        let value = container.deserialize_value(&[0x03]).unwrap();
+        assert_eq!(value, Value::I64(-2));
+        assert_eq!(container.serialize_value(&value).unwrap(), [0x03]);
+
+        let container = BorshSchemaContainer::for_type::<VarU64>();
+        assert_eq!(
+            container.deserialize_value(&[0x80, 0x00]),
+            Err(SchemaValueError::InvalidData(
+                "VarU64".to_string(),
+                "Overlong varint encoding".to_string()
+            ))
+        );
+        assert_eq!(
+            container.deserialize_value(&[0x80]),
+            Err(SchemaValueError::UnexpectedEof("VarU64".to_string()))
+        );
+    }
+}
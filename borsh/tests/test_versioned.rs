@@ -0,0 +1,165 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![cfg(feature = "derive")]
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+#[cfg(not(feature = "std"))]
+use alloc::{
+    format,
+    string::{String, ToString},
+};
+
+use borsh::{from_slice, to_vec, BorshDeserialize, BorshSerialize};
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+struct AccountV1 {
+    balance: u32,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+struct AccountV2 {
+    balance: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+#[borsh(version = 3, migrate_from(2 = AccountV2, 1 = AccountV1), init = on_read)]
+struct Account {
+    balance: u64,
+    owner: String,
+    #[borsh(skip)]
+    reads: u8,
+}
+
+impl Account {
+    fn on_read(&mut self) {
+        self.reads += 1;
+    }
+}
+
+impl From<AccountV1> for Account {
+    fn from(old: AccountV1) -> Self {
+        Self {
+            balance: old.balance.into(),
+            owner: "unknown".to_string(),
+            reads: 0,
+        }
+    }
+}
+
+impl From<AccountV2> for Account {
+    fn from(old: AccountV2) -> Self {
+        Self {
+            balance: old.balance,
+            owner: "unknown".to_string(),
+            reads: 0,
+        }
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+#[borsh(version = 0)]
+struct Point(u8, u8);
+
+#[test]
+fn test_versioned_roundtrip() {
+    let account = Account {
+        balance: 7,
+        owner: "a".to_string(),
+        reads: 0,
+    };
+    let encoded = to_vec(&account).unwrap();
+    assert_eq!(encoded, [3, 7, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, b'a']);
+    assert_eq!(
+        from_slice::<Account>(&encoded).unwrap(),
+        Account {
+            reads: 1,
+            ..account
+        }
+    );
+
+    assert_eq!(to_vec(&Point(1, 2)).unwrap(), [0, 1, 2]);
+    assert_eq!(from_slice::<Point>(&[0, 1, 2]).unwrap(), Point(1, 2));
+}
+
+#[test]
+fn test_versioned_migration() {
+    let mut v1 = to_vec(&1u8).unwrap();
+    v1.extend(to_vec(&AccountV1 { balance: 5 }).unwrap());
+    let mut v2 = to_vec(&2u8).unwrap();
+    v2.extend(to_vec(&AccountV2 { balance: 6 }).unwrap());
+
+    // `init` is called on migrated values too
+    assert_eq!(
+        from_slice::<Account>(&v1).unwrap(),
+        Account {
+            balance: 5,
+            owner: "unknown".to_string(),
+            reads: 1,
+        }
+    );
+    assert_eq!(from_slice::<Account>(&v2).unwrap().balance, 6);
+}
+
+#[test]
+fn test_versioned_errors() {
+    let err = from_slice::<Account>(&[4, 0, 0, 0, 0]).unwrap_err();
+    assert_eq!(
+        format!("{:#}", err),
+        "Unsupported version: 4 (at Account, offset 1)"
+    );
+
+    let err = from_slice::<Account>(&[1, 0, 0]).unwrap_err();
+    assert!(format!("{:#}", err)
+        .starts_with("Unexpected length of input (at Account.AccountV1.balance, offset"));
+
+    let err = from_slice::<Account>(&[]).unwrap_err();
+    assert_eq!(err.to_string(), "Unexpected length of input");
+}
+
+#[cfg(feature = "unstable__schema")]
+mod schema {
+    #[cfg(not(feature = "std"))]
+    use alloc::{string::ToString, vec};
+
+    use borsh::schema::{BorshSchemaContainer, Definition, Fields};
+    use borsh::{to_vec, BorshSchema, BorshSerialize};
+
+    #[derive(BorshSchema)]
+    #[allow(unused)]
+    struct ConfigV1 {
+        limit: u32,
+    }
+
+    #[derive(BorshSchema, BorshSerialize)]
+    #[borsh(version = 2, migrate_from(1 = ConfigV1))]
+    struct Config {
+        limit: u64,
+    }
+
+    #[test]
+    fn test_versioned_schema() {
+        let container = BorshSchemaContainer::for_type::<Config>();
+        assert_eq!(container.declaration(), "Config");
+        assert_eq!(
+            container.get_definition("Config").unwrap(),
+            &Definition::Enum {
+                tag_width: 1,
+                variants: vec![
+                    (1, "V1".to_string(), "ConfigV1".to_string()),
+                    (2, "V2".to_string(), "ConfigV2".to_string()),
+                ],
+            }
+        );
+        assert_eq!(
+            container.get_definition("ConfigV2").unwrap(),
+            &Definition::Struct {
+                fields: Fields::NamedFields(vec![("limit".to_string(), "u64".to_string())]),
+            }
+        );
+        assert!(container.get_definition("ConfigV1").is_some());
+        assert_eq!(container.validate(), Ok(()));
+
+        let encoded = to_vec(&Config { limit: 3 }).unwrap();
+        assert!(container.deserialize_value(&encoded).is_ok());
+    }
+}